@@ -1,4 +1,6 @@
 // This crate was essentially pulled out verbatim from main `zed` crate to avoid having to run RustEmbed macro whenever zed has to be rebuilt. It saves a second or two on an incremental build.
+use std::path::Path;
+
 use anyhow::anyhow;
 
 use gpui::{AppContext, AssetSource, Result, SharedString};
@@ -18,9 +20,18 @@ pub struct Assets;
 
 impl AssetSource for Assets {
     fn load(&self, path: &str) -> Result<Option<std::borrow::Cow<'static, [u8]>>> {
-        Self::get(path)
-            .map(|f| Some(f.data))
-            .ok_or_else(|| anyhow!("could not find asset at path \"{}\"", path))
+        if let Some(file) = Self::get(path) {
+            return Ok(Some(file.data));
+        }
+
+        // User-provided assets, such as the SVGs of icon themes, are referenced by absolute path.
+        if Path::new(path).is_absolute() {
+            if let Ok(data) = std::fs::read(path) {
+                return Ok(Some(data.into()));
+            }
+        }
+
+        Err(anyhow!("could not find asset at path \"{}\"", path))
     }
 
     fn list(&self, path: &str) -> Result<Vec<SharedString>> {
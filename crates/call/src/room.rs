@@ -952,6 +952,12 @@ impl Room {
                         .map(|(user_id, participant)| (*user_id, participant.participant_index))
                         .collect();
                     user_store.set_participant_indices(participant_indices_by_user_id, cx);
+                    let participant_roles_by_user_id = this
+                        .remote_participants
+                        .iter()
+                        .map(|(user_id, participant)| (*user_id, participant.role))
+                        .collect();
+                    user_store.set_participant_roles(participant_roles_by_user_id, cx);
                 });
 
                 this.check_invariants();
@@ -13,6 +13,7 @@ pub enum CliRequest {
     Open {
         paths: Vec<String>,
         urls: Vec<String>,
+        diff_paths: Vec<[String; 2]>,
         wait: bool,
         open_new_workspace: Option<bool>,
         dev_server_token: Option<String>,
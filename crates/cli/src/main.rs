@@ -44,6 +44,10 @@ struct Args {
     /// Use `path:line:row` syntax to open a file at a specific location.
     /// Non-existing paths and directories will ignore `:line:row` suffix.
     paths_with_position: Vec<String>,
+    /// Open a diff of two files: `--diff <OLD_PATH> <NEW_PATH>`.
+    /// Can be given multiple times.
+    #[arg(long, action = clap::ArgAction::Append, num_args = 2, value_names = ["OLD_PATH", "NEW_PATH"])]
+    diff: Vec<String>,
     /// Print Zed's version and the app path.
     #[arg(short, long)]
     version: bool,
@@ -146,6 +150,16 @@ fn main() -> Result<()> {
         }
     }
 
+    let mut diff_paths = vec![];
+    for diff_pair in args.diff.chunks(2) {
+        let canonicalize = |path: &str| {
+            fs::canonicalize(path)
+                .map(|path| path.display().to_string())
+                .with_context(|| format!("Diffing {path}"))
+        };
+        diff_paths.push([canonicalize(&diff_pair[0])?, canonicalize(&diff_pair[1])?]);
+    }
+
     let sender: JoinHandle<anyhow::Result<()>> = thread::spawn({
         let exit_status = exit_status.clone();
         move || {
@@ -155,6 +169,7 @@ fn main() -> Result<()> {
             tx.send(CliRequest::Open {
                 paths,
                 urls,
+                diff_paths,
                 wait: args.wait,
                 open_new_workspace,
                 dev_server_token: args.dev_server_token,
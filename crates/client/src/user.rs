@@ -92,6 +92,7 @@ pub struct UserStore {
     users: HashMap<u64, Arc<User>>,
     by_github_login: HashMap<String, u64>,
     participant_indices: HashMap<u64, ParticipantIndex>,
+    participant_roles: HashMap<u64, proto::ChannelRole>,
     update_contacts_tx: mpsc::UnboundedSender<UpdateContacts>,
     current_plan: Option<proto::Plan>,
     current_user: watch::Receiver<Option<Arc<User>>>,
@@ -156,6 +157,7 @@ impl UserStore {
             contacts: Default::default(),
             incoming_contact_requests: Default::default(),
             participant_indices: Default::default(),
+            participant_roles: Default::default(),
             outgoing_contact_requests: Default::default(),
             invite_info: None,
             client: Arc::downgrade(&client),
@@ -774,6 +776,21 @@ impl UserStore {
         &self.participant_indices
     }
 
+    pub fn set_participant_roles(
+        &mut self,
+        participant_roles: HashMap<u64, proto::ChannelRole>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if participant_roles != self.participant_roles {
+            self.participant_roles = participant_roles;
+            cx.notify();
+        }
+    }
+
+    pub fn participant_roles(&self) -> &HashMap<u64, proto::ChannelRole> {
+        &self.participant_roles
+    }
+
     pub fn participant_names(
         &self,
         user_ids: impl Iterator<Item = u64>,
@@ -1,8 +1,8 @@
 use crate::{db::ChannelId, tests::TestServer};
 use call::ActiveCall;
 use chrono::Utc;
-use editor::Editor;
-use gpui::{BackgroundExecutor, TestAppContext};
+use editor::{Anchor, Editor};
+use gpui::{BackgroundExecutor, TestAppContext, View, VisualTestContext};
 use rpc::proto;
 
 #[gpui::test]
@@ -111,6 +111,25 @@ async fn test_channel_guest_promotion(cx_a: &mut TestAppContext, cx_b: &mut Test
         .await
         .is_err());
 
+    // A sees B's cursor in 1.txt labeled as read-only
+    let worktree_id = project_a.read_with(cx_a, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let (workspace_a, cx_a) = client_a.build_workspace(&project_a, cx_a);
+    let editor_a = workspace_a
+        .update(cx_a, |workspace, cx| {
+            workspace.open_path((worktree_id, "1.txt"), None, true, cx)
+        })
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+    cx_a.run_until_parked();
+    assert_eq!(
+        remote_cursor_labels(&editor_a, cx_a),
+        ["user_b (read-only)"]
+    );
+
     // B is promoted
     active_call_a
         .update(cx_a, |call, cx| {
@@ -129,6 +148,7 @@ async fn test_channel_guest_promotion(cx_a: &mut TestAppContext, cx_b: &mut Test
     // project and buffers are now editable
     assert!(project_b.read_with(cx_b, |project, _| !project.is_read_only()));
     assert!(editor_b.update(cx_b, |editor, cx| !editor.read_only(cx)));
+    assert_eq!(remote_cursor_labels(&editor_a, cx_a), ["user_b"]);
 
     // B sees themselves as muted, and can unmute.
     assert!(room_b.read_with(cx_b, |room, _| room.can_use_microphone()));
@@ -159,6 +179,42 @@ async fn test_channel_guest_promotion(cx_a: &mut TestAppContext, cx_b: &mut Test
         .update(cx_b, |room, cx| room.share_microphone(cx))
         .await
         .is_err());
+    assert_eq!(
+        remote_cursor_labels(&editor_a, cx_a),
+        ["user_b (read-only)"]
+    );
+
+    // Talkers can speak but not edit, so their cursors are labeled too
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.room().unwrap().update(cx, |room, cx| {
+                room.set_participant_role(
+                    client_b.user_id().unwrap(),
+                    proto::ChannelRole::Talker,
+                    cx,
+                )
+            })
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    assert!(room_b.read_with(cx_b, |room, _| room.can_use_microphone()));
+    assert_eq!(
+        remote_cursor_labels(&editor_a, cx_a),
+        ["user_b (read-only)"]
+    );
+}
+
+/// The names shown next to the other participants' cursors in `editor`.
+fn remote_cursor_labels(editor: &View<Editor>, cx: &mut VisualTestContext) -> Vec<String> {
+    editor.update(cx, |editor, cx| {
+        let snapshot = editor.snapshot(cx);
+        let range = Anchor::min()..Anchor::max();
+        snapshot
+            .remote_selections_in_range(&range, editor.collaboration_hub().unwrap(), cx)
+            .filter_map(|selection| Some(selection.user_name?.to_string()))
+            .collect()
+    })
 }
 
 #[gpui::test]
@@ -21,7 +21,7 @@ use workspace::{
     dock::{test::TestPanel, DockPosition},
     item::{test::TestItem, ItemHandle as _},
     shared_screen::SharedScreen,
-    SplitDirection, Workspace,
+    FollowMode, FollowSettings, SplitDirection, Workspace,
 };
 
 use super::TestClient;
@@ -2150,3 +2150,44 @@ async fn test_following_while_deactivated(cx_a: &mut TestAppContext, cx_b: &mut
         assert_eq!(editor.tab_description(0, cx).unwrap(), "2.js");
     });
 }
+
+#[gpui::test]
+async fn test_following_viewport_only(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let (_server, client_a, client_b, channel) = TestServer::start2(cx_a, cx_b).await;
+    cx_b.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<FollowSettings>(cx, |settings| {
+                settings.mode = Some(FollowMode::Viewport);
+            });
+        });
+    });
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    join_channel(channel, &client_a, cx_a).await.unwrap();
+    share_workspace(&workspace_a, cx_a).await.unwrap();
+
+    // a opens 1.txt
+    cx_a.simulate_keystrokes("cmd-p 1 enter");
+    cx_a.run_until_parked();
+
+    // b joins channel and is following a
+    join_channel(channel, &client_b, cx_b).await.unwrap();
+    cx_b.run_until_parked();
+    let (workspace_b, cx_b) = client_b.active_workspace(cx_b);
+    workspace_b.update(cx_b, |workspace, cx| {
+        let editor = workspace.active_item(cx).unwrap();
+        assert_eq!(editor.tab_description(0, cx).unwrap(), "1.txt");
+    });
+
+    // a opens a different file, b keeps showing the followed one
+    cx_a.simulate_keystrokes("cmd-p 2 enter");
+    cx_a.run_until_parked();
+    workspace_b.update(cx_b, |workspace, cx| {
+        let editor = workspace.active_item(cx).unwrap();
+        assert_eq!(editor.tab_description(0, cx).unwrap(), "1.txt");
+        assert_eq!(
+            workspace.leader_for_pane(workspace.active_pane()),
+            client_a.peer_id()
+        );
+    });
+}
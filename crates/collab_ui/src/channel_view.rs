@@ -632,4 +632,8 @@ impl CollaborationHub for ChannelBufferCollaborationHub {
             .read(cx)
             .participant_names(user_ids, cx)
     }
+
+    fn user_roles<'a>(&self, cx: &'a AppContext) -> &'a HashMap<u64, proto::ChannelRole> {
+        self.0.read(cx).user_store().read(cx).participant_roles()
+    }
 }
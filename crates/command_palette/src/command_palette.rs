@@ -90,6 +90,7 @@ impl CommandPalette {
                 Some(Command {
                     name: humanize_action_name(action.name()),
                     action,
+                    intercepted_query: None,
                 })
             })
             .collect();
@@ -146,6 +147,8 @@ pub struct CommandPaletteDelegate {
 struct Command {
     name: String,
     action: Box<dyn Action>,
+    /// The query this command was produced from by the [`CommandPaletteInterceptor`].
+    intercepted_query: Option<String>,
 }
 
 impl Clone for Command {
@@ -153,6 +156,7 @@ impl Clone for Command {
         Self {
             name: self.name.clone(),
             action: self.action.boxed_clone(),
+            intercepted_query: self.intercepted_query.clone(),
         }
     }
 }
@@ -195,13 +199,15 @@ impl CommandPaletteDelegate {
 
         let mut intercept_result = CommandPaletteInterceptor::try_global(cx)
             .and_then(|interceptor| interceptor.intercept(&query, cx));
+        let mut intercepted_query = intercept_result.is_some().then(|| query.clone());
 
         if parse_zed_link(&query, cx).is_some() {
             intercept_result = Some(CommandInterceptResult {
                 action: OpenZedUrl { url: query.clone() }.boxed_clone(),
                 string: query.clone(),
                 positions: vec![],
-            })
+            });
+            intercepted_query = None;
         }
 
         if let Some(CommandInterceptResult {
@@ -219,6 +225,7 @@ impl CommandPaletteDelegate {
             commands.push(Command {
                 name: string.clone(),
                 action,
+                intercepted_query,
             });
             matches.insert(
                 0,
@@ -385,6 +392,9 @@ impl PickerDelegate for CommandPaletteDelegate {
         let action = command.action;
         cx.focus(&self.previous_focus_handle);
         self.dismissed(cx);
+        if let Some(query) = command.intercepted_query {
+            CommandPaletteInterceptor::confirmed(&query, cx);
+        }
         cx.dispatch_action(action);
     }
 
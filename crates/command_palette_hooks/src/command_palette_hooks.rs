@@ -2,7 +2,7 @@
 
 #![deny(missing_docs)]
 
-use std::any::TypeId;
+use std::{any::TypeId, rc::Rc};
 
 use collections::HashSet;
 use derive_more::{Deref, DerefMut};
@@ -92,9 +92,10 @@ pub struct CommandInterceptResult {
 
 /// An interceptor for the command palette.
 #[derive(Default)]
-pub struct CommandPaletteInterceptor(
-    Option<Box<dyn Fn(&str, &AppContext) -> Option<CommandInterceptResult>>>,
-);
+pub struct CommandPaletteInterceptor {
+    handler: Option<Box<dyn Fn(&str, &AppContext) -> Option<CommandInterceptResult>>>,
+    confirm_handler: Option<Rc<dyn Fn(&str, &mut AppContext)>>,
+}
 
 #[derive(Default)]
 struct GlobalCommandPaletteInterceptor(CommandPaletteInterceptor);
@@ -118,16 +119,27 @@ impl CommandPaletteInterceptor {
 
     /// Intercepts the given query from the command palette.
     pub fn intercept(&self, query: &str, cx: &AppContext) -> Option<CommandInterceptResult> {
-        let Some(handler) = self.0.as_ref() else {
+        let Some(handler) = self.handler.as_ref() else {
             return None;
         };
 
         (handler)(query, cx)
     }
 
+    /// Notifies the global interceptor that the command it produced for the given
+    /// query was run, before its action is dispatched.
+    pub fn confirmed(query: &str, cx: &mut AppContext) {
+        let handler =
+            Self::try_global(cx).and_then(|interceptor| interceptor.confirm_handler.clone());
+        if let Some(handler) = handler {
+            (handler)(query, cx);
+        }
+    }
+
     /// Clears the global interceptor.
     pub fn clear(&mut self) {
-        self.0 = None;
+        self.handler = None;
+        self.confirm_handler = None;
     }
 
     /// Sets the global interceptor.
@@ -137,6 +149,12 @@ impl CommandPaletteInterceptor {
         &mut self,
         handler: Box<dyn Fn(&str, &AppContext) -> Option<CommandInterceptResult>>,
     ) {
-        self.0 = Some(handler);
+        self.handler = Some(handler);
+    }
+
+    /// Sets the handler that is called when a command produced by the global
+    /// interceptor is run.
+    pub fn set_confirm_handler(&mut self, handler: Rc<dyn Fn(&str, &mut AppContext)>) {
+        self.confirm_handler = Some(handler);
     }
 }
@@ -179,6 +179,7 @@ gpui::actions!(
         AcceptPartialInlineCompletion,
         AddSelectionAbove,
         AddSelectionBelow,
+        AutoIndent,
         Backspace,
         Cancel,
         CancelLanguageServerWork,
@@ -191,6 +192,7 @@ gpui::actions!(
         ConvertToLowerCamelCase,
         ConvertToLowerCase,
         ConvertToOppositeCase,
+        ConvertToRot13,
         ConvertToSnakeCase,
         ConvertToTitleCase,
         ConvertToUpperCamelCase,
@@ -304,6 +306,7 @@ gpui::actions!(
         ShowCharacterPalette,
         ShowInlineCompletion,
         ShowSignatureHelp,
+        ShowUnsavedChanges,
         ShuffleLines,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
@@ -61,7 +61,7 @@ use std::{
     sync::Arc,
 };
 use sum_tree::{Bias, TreeMap};
-use tab_map::{TabMap, TabSnapshot};
+use tab_map::{TabMap, TabPoint, TabSnapshot};
 use text::LineIndent;
 use ui::WindowContext;
 use wrap_map::{WrapMap, WrapSnapshot};
@@ -613,6 +613,17 @@ impl DisplaySnapshot {
         DisplayPoint(block_point)
     }
 
+    /// Returns the point on the same buffer line as `point` whose column, with tabs expanded
+    /// and ignoring soft wraps, is `column`.
+    pub fn point_at_expanded_column(&self, point: DisplayPoint, column: u32) -> DisplayPoint {
+        let fold_point = self.display_point_to_fold_point(point, Bias::Left);
+        let tab_point = self
+            .tab_snapshot
+            .clip_point(TabPoint::new(fold_point.row(), column), Bias::Left);
+        let wrap_point = self.wrap_snapshot.tab_point_to_wrap_point(tab_point);
+        DisplayPoint(self.block_snapshot.to_block_point(wrap_point))
+    }
+
     pub fn max_point(&self) -> DisplayPoint {
         DisplayPoint(self.block_snapshot.max_point())
     }
@@ -124,6 +124,7 @@ use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAu
 use selections_collection::{resolve_multiple, MutableSelectionsCollection, SelectionsCollection};
 use serde::{Deserialize, Serialize};
 use settings::{update_settings_file, Settings, SettingsStore};
+use similar::TextDiff;
 use smallvec::SmallVec;
 use snippet::Snippet;
 use std::{
@@ -267,6 +268,19 @@ pub enum Direction {
     Next,
 }
 
+/// How [`Editor::join_lines_impl`] joins each line with the next one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum JoinLinesStyle {
+    /// The next line's indentation is replaced by a space, unless the next line is blank.
+    Editor,
+    /// Like vim's `J`: the next line's indentation, and its comment leader when both lines
+    /// are comments, is replaced by a space, unless the line ends in whitespace or the next
+    /// one starts with `)`.
+    Vim,
+    /// Like vim's `gJ`: only the newline is removed.
+    VimNoWhitespace,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Navigated {
     Yes,
@@ -595,6 +609,7 @@ pub struct Editor {
     focused_block: Option<FocusedBlock>,
     next_scroll_position: NextScrollCursorCenterTopBottom,
     addons: HashMap<TypeId, Box<dyn Addon>>,
+    jump_labels: Vec<(Anchor, SharedString)>,
     _scroll_cursor_center_top_bottom_task: Task<()>,
 }
 
@@ -1954,6 +1969,7 @@ impl Editor {
             focused_block: None,
             next_scroll_position: NextScrollCursorCenterTopBottom::default(),
             addons: HashMap::default(),
+            jump_labels: Vec::new(),
             _scroll_cursor_center_top_bottom_task: Task::ready(()),
         };
         this.tasks_update_task = Some(this.refresh_runnables(cx));
@@ -3379,7 +3395,7 @@ impl Editor {
                 s.select(new_selections)
             });
 
-            if !bracket_inserted && EditorSettings::get_global(cx).use_on_type_format {
+            if !bracket_inserted {
                 if let Some(on_type_format_task) =
                     this.trigger_on_type_formatting(text.to_string(), cx)
                 {
@@ -4127,6 +4143,13 @@ impl Editor {
             .buffer
             .read(cx)
             .text_anchor_for_position(position, cx)?;
+        if !buffer
+            .read(cx)
+            .settings_at(buffer_position, cx)
+            .use_on_type_format
+        {
+            return None;
+        }
 
         // OnTypeFormatting returns a list of edits, no need to pass them between Zed instances,
         // hence we do LSP request & edit on host side only — add formats to host's history.
@@ -5875,6 +5898,31 @@ impl Editor {
         });
     }
 
+    pub fn autoindent(&mut self, _: &AutoIndent, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+        let ranges = self
+            .selections
+            .all_adjusted(cx)
+            .into_iter()
+            .map(|selection| {
+                let mut range = selection.range();
+                // A selection that ends at the start of a line doesn't cover that line.
+                if range.end.column == 0 && range.end.row > range.start.row {
+                    range.end.row -= 1;
+                }
+                range
+            })
+            .collect::<Vec<_>>();
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.autoindent_ranges(ranges, cx));
+            let selections = this.selections.all::<usize>(cx);
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
+        });
+    }
+
     pub fn delete_line(&mut self, _: &DeleteLine, cx: &mut ViewContext<Self>) {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let selections = self.selections.all::<Point>(cx);
@@ -5958,6 +6006,11 @@ impl Editor {
     }
 
     pub fn join_lines(&mut self, _: &JoinLines, cx: &mut ViewContext<Self>) {
+        self.join_lines_impl(JoinLinesStyle::Editor, cx);
+    }
+
+    /// Joins each selected line with the next one (or the lines the selection spans).
+    pub fn join_lines_impl(&mut self, style: JoinLinesStyle, cx: &mut ViewContext<Self>) {
         if self.read_only(cx) {
             return;
         }
@@ -5994,13 +6047,19 @@ impl Editor {
                 for row in row_range.iter_rows().rev() {
                     let end_of_line = Point::new(row.0, snapshot.line_len(row));
                     let next_line_row = row.next_row();
-                    let indent = snapshot.indent_size_for_line(next_line_row);
-                    let start_of_next_line = Point::new(next_line_row.0, indent.len);
-
-                    let replace = if snapshot.line_len(next_line_row) > indent.len {
-                        " "
-                    } else {
-                        ""
+                    let (start_of_next_line, replace) = match style {
+                        JoinLinesStyle::Editor => {
+                            let indent = snapshot.indent_size_for_line(next_line_row);
+                            let start_of_next_line = Point::new(next_line_row.0, indent.len);
+                            let replace = if snapshot.line_len(next_line_row) > indent.len {
+                                " "
+                            } else {
+                                ""
+                            };
+                            (start_of_next_line, replace)
+                        }
+                        JoinLinesStyle::Vim => vim_line_join(&snapshot, row, next_line_row),
+                        JoinLinesStyle::VimNoWhitespace => (Point::new(next_line_row.0, 0), ""),
                     };
 
                     this.buffer.update(cx, |buffer, cx| {
@@ -6299,6 +6358,18 @@ impl Editor {
         })
     }
 
+    pub fn convert_to_rot13(&mut self, _: &ConvertToRot13, cx: &mut ViewContext<Self>) {
+        self.manipulate_text(cx, |text| {
+            text.chars()
+                .map(|c| match c {
+                    'A'..='M' | 'a'..='m' => ((c as u8) + 13) as char,
+                    'N'..='Z' | 'n'..='z' => ((c as u8) - 13) as char,
+                    _ => c,
+                })
+                .collect()
+        })
+    }
+
     fn manipulate_text<Fn>(&mut self, cx: &mut ViewContext<Self>, mut callback: Fn)
     where
         Fn: FnMut(&str) -> String,
@@ -7660,7 +7731,7 @@ impl Editor {
         self.nav_history.as_ref()
     }
 
-    fn push_to_nav_history(
+    pub fn push_to_nav_history(
         &mut self,
         cursor_anchor: Anchor,
         new_position: Option<Point>,
@@ -10130,6 +10201,28 @@ impl Editor {
         self.active_diagnostics.is_some()
     }
 
+    /// Shows the primary diagnostic that starts at the newest cursor, as `editor::GoToDiagnostic`
+    /// does for the diagnostic it moves to. Returns whether there was one to show.
+    pub fn activate_diagnostic_at_cursor(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let head = self.selections.newest::<usize>(cx).head();
+        let group_id = buffer
+            .diagnostics_in_range::<_, usize>(head..head, false)
+            .find(|entry| entry.diagnostic.is_primary && entry.range.start == head)
+            .map(|entry| entry.diagnostic.group_id);
+        match group_id {
+            Some(group_id) => self.activate_diagnostics(group_id, cx),
+            None => false,
+        }
+    }
+
+    #[cfg(feature = "test-support")]
+    pub fn active_diagnostic_group(&self) -> Option<usize> {
+        self.active_diagnostics
+            .as_ref()
+            .map(|active_diagnostics| active_diagnostics.group_id)
+    }
+
     fn dismiss_diagnostics(&mut self, cx: &mut ViewContext<Self>) {
         if let Some(active_diagnostic_group) = self.active_diagnostics.take() {
             self.display_map.update(cx, |display_map, cx| {
@@ -10756,6 +10849,60 @@ impl Editor {
         }
     }
 
+    /// Opens a read-only diff between the file on disk and the buffer's current contents.
+    pub fn show_unsaved_changes(&mut self, _: &ShowUnsavedChanges, cx: &mut ViewContext<Self>) {
+        let Some(buffer) = self.buffer().read(cx).as_singleton() else {
+            return;
+        };
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        let Some(file) = buffer.file().and_then(|f| f.as_local()) else {
+            return;
+        };
+        let path = file.path().to_string_lossy().to_string();
+        let load_file = file.load(cx);
+        let new_text = buffer.text();
+
+        cx.spawn(|_, mut cx| async move {
+            let old_text = load_file.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                Self::new_diff_in_workspace(
+                    workspace,
+                    (&format!("a/{path}"), &old_text),
+                    (&format!("b/{path}"), &new_text),
+                    cx,
+                );
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Opens a read-only editor containing a unified diff of two labeled texts.
+    pub fn new_diff_in_workspace(
+        workspace: &mut Workspace,
+        (old_label, old_text): (&str, &str),
+        (new_label, new_text): (&str, &str),
+        cx: &mut ViewContext<Workspace>,
+    ) -> View<Editor> {
+        let diff = TextDiff::from_lines(old_text, new_text)
+            .unified_diff()
+            .header(old_label, new_label)
+            .to_string();
+        let project = workspace.project().clone();
+        let buffer = project.update(cx, |project, cx| {
+            project.create_local_buffer(&diff, None, cx)
+        });
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(buffer, Some(project), cx);
+            editor.set_read_only(true);
+            editor
+        });
+        workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, cx);
+        editor
+    }
+
     pub fn copy_path(&mut self, _: &CopyPath, cx: &mut ViewContext<Self>) {
         if let Some(buffer) = self.buffer().read(cx).as_singleton() {
             if let Some(file) = buffer.read(cx).file().and_then(|f| f.as_local()) {
@@ -11258,6 +11405,20 @@ impl Editor {
             .map_or(false, |(_, highlights)| !highlights.is_empty())
     }
 
+    /// Returns the buffer search match starting at `position`, which is where the cursor lands
+    /// on a match when matches are collapsed.
+    pub fn search_match_at(&self, position: Anchor, cx: &AppContext) -> Option<Range<Anchor>> {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let (_, ranges) = self
+            .background_highlights
+            .get(&TypeId::of::<items::BufferSearchHighlights>())?;
+        let position = position.to_offset(&snapshot);
+        let ix = ranges
+            .binary_search_by(|probe| probe.start.to_offset(&snapshot).cmp(&position))
+            .ok()?;
+        Some(ranges[ix].clone())
+    }
+
     pub fn background_highlights_in_range(
         &self,
         search_range: Range<Anchor>,
@@ -11473,6 +11634,28 @@ impl Editor {
         }
     }
 
+    /// Draws each label over the text at its anchor, so that a jump target can be picked
+    /// by typing the label. Replaces any labels that are already shown.
+    pub fn set_jump_labels(
+        &mut self,
+        labels: Vec<(Anchor, SharedString)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.jump_labels = labels;
+        cx.notify();
+    }
+
+    pub fn clear_jump_labels(&mut self, cx: &mut ViewContext<Self>) {
+        if !self.jump_labels.is_empty() {
+            self.jump_labels.clear();
+            cx.notify();
+        }
+    }
+
+    pub fn jump_labels(&self) -> &[(Anchor, SharedString)] {
+        &self.jump_labels
+    }
+
     pub fn show_local_cursors(&self, cx: &WindowContext) -> bool {
         (self.read_only(cx) || self.blink_manager.read(cx).visible())
             && self.focus_handle.is_focused(cx)
@@ -12215,6 +12398,7 @@ pub trait CollaborationHub {
         cx: &'a AppContext,
     ) -> &'a HashMap<u64, ParticipantIndex>;
     fn user_names(&self, cx: &AppContext) -> HashMap<u64, SharedString>;
+    fn user_roles<'a>(&self, cx: &'a AppContext) -> &'a HashMap<u64, ChannelRole>;
 }
 
 impl CollaborationHub for Model<Project> {
@@ -12236,6 +12420,10 @@ impl CollaborationHub for Model<Project> {
             user_store.participant_names(user_ids, cx)
         })
     }
+
+    fn user_roles<'a>(&self, cx: &'a AppContext) -> &'a HashMap<u64, ChannelRole> {
+        self.read(cx).user_store().read(cx).participant_roles()
+    }
 }
 
 pub trait CompletionProvider {
@@ -12420,7 +12608,11 @@ impl CompletionProvider for Model<Project> {
         trigger_in_words: bool,
         cx: &mut ViewContext<Editor>,
     ) -> bool {
-        if !EditorSettings::get_global(cx).show_completions_on_input {
+        if !buffer
+            .read(cx)
+            .settings_at(position, cx)
+            .show_completions_on_input
+        {
             return false;
         }
 
@@ -12492,6 +12684,60 @@ fn ending_row(next_selection: &Selection<Point>, display_map: &DisplaySnapshot)
     }
 }
 
+/// Returns where the next row starts and what replaces the line break when joining a row
+/// with [`JoinLinesStyle::Vim`].
+fn vim_line_join(
+    snapshot: &MultiBufferSnapshot,
+    row: MultiBufferRow,
+    next_row: MultiBufferRow,
+) -> (Point, &'static str) {
+    let indent = snapshot.indent_size_for_line(next_row);
+    let mut start_of_next_line = Point::new(next_row.0, indent.len);
+    if let Some(prefix_len) = shared_line_comment_prefix_len(snapshot, row, next_row) {
+        start_of_next_line.column += prefix_len;
+        start_of_next_line.column += snapshot
+            .chars_at(start_of_next_line)
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .count() as u32;
+    }
+
+    let end_of_line = Point::new(row.0, snapshot.line_len(row));
+    let ends_with_whitespace = snapshot
+        .reversed_chars_at(end_of_line)
+        .next()
+        .map_or(true, char::is_whitespace);
+    let replace = if snapshot.line_len(next_row) > start_of_next_line.column
+        && !ends_with_whitespace
+        && !snapshot.contains_str_at(start_of_next_line, ")")
+    {
+        " "
+    } else {
+        ""
+    };
+    (start_of_next_line, replace)
+}
+
+/// Returns the length of the line comment prefix that both rows start with, if any.
+fn shared_line_comment_prefix_len(
+    snapshot: &MultiBufferSnapshot,
+    row: MultiBufferRow,
+    next_row: MultiBufferRow,
+) -> Option<u32> {
+    let start = Point::new(row.0, snapshot.indent_size_for_line(row).len);
+    let next_start = Point::new(next_row.0, snapshot.indent_size_for_line(next_row).len);
+    let scope = snapshot.language_scope_at(start)?;
+    let prefixes = scope.line_comment_prefixes();
+    let longest_prefix_at = |point: Point| {
+        prefixes
+            .iter()
+            .map(|prefix| prefix.trim_end())
+            .filter(|prefix| !prefix.is_empty() && snapshot.contains_str_at(point, prefix))
+            .max_by_key(|prefix| prefix.len())
+    };
+    let prefix = longest_prefix_at(start)?;
+    (longest_prefix_at(next_start) == Some(prefix)).then(|| prefix.len() as u32)
+}
+
 impl EditorSnapshot {
     pub fn remote_selections_in_range<'a>(
         &'a self,
@@ -12501,6 +12747,7 @@ impl EditorSnapshot {
     ) -> impl 'a + Iterator<Item = RemoteSelection> {
         let participant_names = collaboration_hub.user_names(cx);
         let participant_indices = collaboration_hub.user_participant_indices(cx);
+        let participant_roles = collaboration_hub.user_roles(cx);
         let collaborators_by_peer_id = collaboration_hub.collaborators(cx);
         let collaborators_by_replica_id = collaborators_by_peer_id
             .iter()
@@ -12511,7 +12758,15 @@ impl EditorSnapshot {
             .filter_map(move |(replica_id, line_mode, cursor_shape, selection)| {
                 let collaborator = collaborators_by_replica_id.get(&replica_id)?;
                 let participant_index = participant_indices.get(&collaborator.user_id).copied();
-                let user_name = participant_names.get(&collaborator.user_id).cloned();
+                let user_name = participant_names.get(&collaborator.user_id).map(|name| {
+                    // Only admins and members can edit, so say so next to everyone else's cursor.
+                    match participant_roles.get(&collaborator.user_id) {
+                        Some(ChannelRole::Guest | ChannelRole::Talker | ChannelRole::Banned) => {
+                            format!("{name} (read-only)").into()
+                        }
+                        Some(ChannelRole::Admin | ChannelRole::Member) | None => name.clone(),
+                    }
+                });
                 Some(RemoteSelection {
                     replica_id,
                     selection,
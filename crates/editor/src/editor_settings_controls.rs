@@ -1,8 +1,9 @@
+use std::path::Path;
 use std::sync::Arc;
 
 use gpui::{AppContext, FontFeatures, FontWeight};
 use project::project_settings::{InlineBlameSettings, ProjectSettings};
-use settings::{EditableSettingControl, Settings};
+use settings::{EditableSettingControl, Settings, SettingsLayer, SettingsLocation};
 use theme::{FontFamilyCache, ThemeSettings};
 use ui::{
     prelude::*, CheckboxWithLabel, ContextMenu, DropdownMenu, NumericStepper, SettingsContainer,
@@ -12,39 +13,103 @@ use ui::{
 use crate::EditorSettings;
 
 #[derive(IntoElement)]
-pub struct EditorSettingsControls {}
+pub struct EditorSettingsControls {
+    query: SharedString,
+    worktree_id: Option<usize>,
+}
 
 impl EditorSettingsControls {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            query: SharedString::default(),
+            worktree_id: None,
+        }
+    }
+
+    /// Only shows the groups of settings that match the given search query.
+    pub fn query(mut self, query: impl Into<SharedString>) -> Self {
+        self.query = query.into();
+        self
+    }
+
+    /// Takes the settings of the given worktree into account when showing
+    /// which layer each group of settings comes from.
+    pub fn worktree_id(mut self, worktree_id: Option<usize>) -> Self {
+        self.worktree_id = worktree_id;
+        self
     }
 }
 
 impl RenderOnce for EditorSettingsControls {
-    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let query = self.query.as_ref();
+        let location = self.worktree_id.map(|worktree_id| SettingsLocation {
+            worktree_id,
+            path: Path::new(""),
+        });
+        let show_font = "font".contains(&query.to_lowercase())
+            || BufferFontFamilyControl.matches_query(query)
+            || BufferFontWeightControl.matches_query(query)
+            || BufferFontSizeControl.matches_query(query)
+            || BufferFontLigaturesControl.matches_query(query);
+        let show_editor =
+            "editor".contains(&query.to_lowercase()) || InlineGitBlameControl.matches_query(query);
+        let show_gutter = "gutter".contains(&query.to_lowercase())
+            || LineNumbersControl.matches_query(query)
+            || RelativeLineNumbersControl.matches_query(query);
+
         SettingsContainer::new()
-            .child(
-                SettingsGroup::new("Font")
-                    .child(
-                        h_flex()
-                            .gap_2()
-                            .justify_between()
-                            .child(BufferFontFamilyControl)
-                            .child(BufferFontWeightControl),
-                    )
-                    .child(BufferFontSizeControl)
-                    .child(BufferFontLigaturesControl),
-            )
-            .child(SettingsGroup::new("Editor").child(InlineGitBlameControl))
-            .child(
-                SettingsGroup::new("Gutter").child(
-                    h_flex()
-                        .gap_2()
-                        .justify_between()
-                        .child(LineNumbersControl)
-                        .child(RelativeLineNumbersControl),
-                ),
-            )
+            .when(show_font, |this| {
+                this.child(
+                    SettingsGroup::new("Font")
+                        .layer(
+                            [
+                                BufferFontFamilyControl.layer(location, cx),
+                                BufferFontWeightControl.layer(location, cx),
+                                BufferFontSizeControl.layer(location, cx),
+                                BufferFontLigaturesControl.layer(location, cx),
+                            ]
+                            .into_iter()
+                            .max()
+                            .unwrap_or(SettingsLayer::Default)
+                            .label(),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .justify_between()
+                                .child(BufferFontFamilyControl)
+                                .child(BufferFontWeightControl),
+                        )
+                        .child(BufferFontSizeControl)
+                        .child(BufferFontLigaturesControl),
+                )
+            })
+            .when(show_editor, |this| {
+                this.child(
+                    SettingsGroup::new("Editor")
+                        .layer(InlineGitBlameControl.layer(location, cx).label())
+                        .child(InlineGitBlameControl),
+                )
+            })
+            .when(show_gutter, |this| {
+                this.child(
+                    SettingsGroup::new("Gutter")
+                        .layer(
+                            LineNumbersControl
+                                .layer(location, cx)
+                                .max(RelativeLineNumbersControl.layer(location, cx))
+                                .label(),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .justify_between()
+                                .child(LineNumbersControl)
+                                .child(RelativeLineNumbersControl),
+                        ),
+                )
+            })
     }
 }
 
@@ -59,6 +124,10 @@ impl EditableSettingControl for BufferFontFamilyControl {
         "Buffer Font Family".into()
     }
 
+    fn key_path(&self) -> &'static [&'static str] {
+        &["buffer_font_family"]
+    }
+
     fn read(cx: &AppContext) -> Self::Value {
         let settings = ThemeSettings::get_global(cx);
         settings.buffer_font.family.clone()
@@ -118,6 +187,10 @@ impl EditableSettingControl for BufferFontSizeControl {
         "Buffer Font Size".into()
     }
 
+    fn key_path(&self) -> &'static [&'static str] {
+        &["buffer_font_size"]
+    }
+
     fn read(cx: &AppContext) -> Self::Value {
         let settings = ThemeSettings::get_global(cx);
         settings.buffer_font_size
@@ -163,6 +236,10 @@ impl EditableSettingControl for BufferFontWeightControl {
         "Buffer Font Weight".into()
     }
 
+    fn key_path(&self) -> &'static [&'static str] {
+        &["buffer_font_weight"]
+    }
+
     fn read(cx: &AppContext) -> Self::Value {
         let settings = ThemeSettings::get_global(cx);
         settings.buffer_font.weight
@@ -216,6 +293,10 @@ impl EditableSettingControl for BufferFontLigaturesControl {
         "Buffer Font Ligatures".into()
     }
 
+    fn key_path(&self) -> &'static [&'static str] {
+        &["buffer_font_features"]
+    }
+
     fn read(cx: &AppContext) -> Self::Value {
         let settings = ThemeSettings::get_global(cx);
         settings
@@ -286,6 +367,10 @@ impl EditableSettingControl for InlineGitBlameControl {
         "Inline Git Blame".into()
     }
 
+    fn key_path(&self) -> &'static [&'static str] {
+        &["git", "inline_blame"]
+    }
+
     fn read(cx: &AppContext) -> Self::Value {
         let settings = ProjectSettings::get_global(cx);
         settings.git.inline_blame_enabled()
@@ -339,6 +424,10 @@ impl EditableSettingControl for LineNumbersControl {
         "Line Numbers".into()
     }
 
+    fn key_path(&self) -> &'static [&'static str] {
+        &["gutter", "line_numbers"]
+    }
+
     fn read(cx: &AppContext) -> Self::Value {
         let settings = EditorSettings::get_global(cx);
         settings.gutter.line_numbers
@@ -392,6 +481,10 @@ impl EditableSettingControl for RelativeLineNumbersControl {
         "Relative Line Numbers".into()
     }
 
+    fn key_path(&self) -> &'static [&'static str] {
+        &["relative_line_numbers"]
+    }
+
     fn read(cx: &AppContext) -> Self::Value {
         let settings = EditorSettings::get_global(cx);
         settings.relative_line_numbers
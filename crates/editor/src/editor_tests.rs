@@ -3060,6 +3060,62 @@ fn test_join_lines_with_multi_selection(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_join_lines_whitespace_and_comments(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            line_comments: vec!["// ".into(), "/// ".into()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // `editor::JoinLines` only replaces the next line's indentation.
+    cx.set_state(indoc! {"
+        // ˇone
+        //   two
+    "});
+    cx.update_editor(|e, cx| e.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {"
+        // oneˇ //   two
+    "});
+    cx.set_state("foo ˇ\n    )");
+    cx.update_editor(|e, cx| e.join_lines(&JoinLines, cx));
+    cx.assert_editor_state("foo ˇ )");
+
+    // Like vim's `J`, the comment leader of the next line is removed when both lines are
+    // comments.
+    cx.set_state(indoc! {"
+        // ˇone
+        //   two
+        /// three
+    "});
+    cx.update_editor(|e, cx| e.join_lines_impl(JoinLinesStyle::Vim, cx));
+    cx.assert_editor_state(indoc! {"
+        // oneˇ two
+        /// three
+    "});
+    cx.update_editor(|e, cx| e.join_lines_impl(JoinLinesStyle::Vim, cx));
+    cx.assert_editor_state(indoc! {"
+        // one twoˇ /// three
+    "});
+
+    // No space is inserted after trailing whitespace or before a closing parenthesis.
+    cx.set_state("ˇfoo \n    bar(\n    )");
+    cx.update_editor(|e, cx| e.join_lines_impl(JoinLinesStyle::Vim, cx));
+    cx.assert_editor_state("foo ˇbar(\n    )");
+    cx.update_editor(|e, cx| e.join_lines_impl(JoinLinesStyle::Vim, cx));
+    cx.assert_editor_state("foo bar(ˇ)");
+
+    // Without whitespace, only the newline is removed.
+    cx.set_state("ˇa\n    b");
+    cx.update_editor(|e, cx| e.join_lines_impl(JoinLinesStyle::VimNoWhitespace, cx));
+    cx.assert_editor_state("aˇ    b");
+}
+
 #[gpui::test]
 async fn test_join_lines_with_git_diff_base(
     executor: BackgroundExecutor,
@@ -3564,6 +3620,14 @@ async fn test_manipulate_text(cx: &mut TestAppContext) {
     cx.assert_editor_state(indoc! {"
         «HeLlO, wOrLD!ˇ»
     "});
+
+    cx.set_state(indoc! {"
+        «Hello, World!ˇ» «Zebra-123ˇ»
+    "});
+    cx.update_editor(|e, cx| e.convert_to_rot13(&ConvertToRot13, cx));
+    cx.assert_editor_state(indoc! {"
+        «Uryyb, Jbeyq!ˇ» «Mroen-123ˇ»
+    "});
 }
 
 #[gpui::test]
@@ -7679,8 +7743,8 @@ async fn test_completion(cx: &mut gpui::TestAppContext) {
 
     _ = cx.update(|cx| {
         cx.update_global::<SettingsStore, _>(|settings, cx| {
-            settings.update_user_settings::<EditorSettings>(cx, |settings| {
-                settings.show_completions_on_input = Some(false);
+            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.defaults.show_completions_on_input = Some(false);
             });
         })
     });
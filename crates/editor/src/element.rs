@@ -190,6 +190,7 @@ impl EditorElement {
         register_action(view, cx, Editor::tab_prev);
         register_action(view, cx, Editor::indent);
         register_action(view, cx, Editor::outdent);
+        register_action(view, cx, Editor::autoindent);
         register_action(view, cx, Editor::delete_line);
         register_action(view, cx, Editor::join_lines);
         register_action(view, cx, Editor::sort_lines_case_sensitive);
@@ -204,6 +205,7 @@ impl EditorElement {
         register_action(view, cx, Editor::convert_to_upper_camel_case);
         register_action(view, cx, Editor::convert_to_lower_camel_case);
         register_action(view, cx, Editor::convert_to_opposite_case);
+        register_action(view, cx, Editor::convert_to_rot13);
         register_action(view, cx, Editor::delete_to_previous_word_start);
         register_action(view, cx, Editor::delete_to_previous_subword_start);
         register_action(view, cx, Editor::delete_to_next_word_end);
@@ -354,6 +356,7 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_inline_completions);
         register_action(view, cx, hover_popover::hover);
         register_action(view, cx, Editor::reveal_in_finder);
+        register_action(view, cx, Editor::show_unsaved_changes);
         register_action(view, cx, Editor::copy_path);
         register_action(view, cx, Editor::copy_relative_path);
         register_action(view, cx, Editor::copy_highlight_json);
@@ -1125,6 +1128,64 @@ impl EditorElement {
         cursor_layouts
     }
 
+    /// Lays out the editor's jump labels as blocks, like a block cursor showing the label
+    /// in place of the character under it.
+    #[allow(clippy::too_many_arguments)]
+    fn layout_jump_labels(
+        &self,
+        snapshot: &EditorSnapshot,
+        visible_display_row_range: Range<DisplayRow>,
+        line_layouts: &[LineWithInvisibles],
+        scroll_pixel_position: gpui::Point<Pixels>,
+        line_height: Pixels,
+        em_width: Pixels,
+        cx: &mut WindowContext,
+    ) -> Vec<CursorLayout> {
+        let labels = self.editor.read(cx).jump_labels().to_vec();
+        let color = cx.theme().status().warning;
+        labels
+            .into_iter()
+            .filter_map(|(anchor, label)| {
+                let position = anchor.to_display_point(snapshot);
+                if !visible_display_row_range.contains(&position.row()) {
+                    return None;
+                }
+
+                let line_layout =
+                    &line_layouts[position.row().minus(visible_display_row_range.start) as usize];
+                let len = label.len();
+                let text = cx
+                    .text_system()
+                    .shape_line(
+                        label,
+                        line_layout.font_size,
+                        &[TextRun {
+                            len,
+                            font: self.style.text.font(),
+                            color: self.style.background,
+                            background_color: None,
+                            strikethrough: None,
+                            underline: None,
+                        }],
+                    )
+                    .log_err()?;
+
+                let x =
+                    line_layout.x_for_index(position.column() as usize) - scroll_pixel_position.x;
+                let y =
+                    (position.row().as_f32() - scroll_pixel_position.y / line_height) * line_height;
+                Some(CursorLayout::new(
+                    point(x, y),
+                    text.width.max(em_width),
+                    line_height,
+                    color,
+                    CursorShape::Block,
+                    Some(text),
+                ))
+            })
+            .collect()
+    }
+
     fn layout_scrollbar(
         &self,
         snapshot: &EditorSnapshot,
@@ -3422,6 +3483,7 @@ impl EditorElement {
                 self.paint_lines(&invisible_display_ranges, layout, cx);
                 self.paint_redactions(layout, cx);
                 self.paint_cursors(layout, cx);
+                self.paint_jump_labels(layout, cx);
                 self.paint_inline_blame(layout, cx);
                 cx.with_element_namespace("crease_trailers", |cx| {
                     for trailer in layout.crease_trailers.iter_mut().flatten() {
@@ -3534,6 +3596,12 @@ impl EditorElement {
         }
     }
 
+    fn paint_jump_labels(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        for label in &mut layout.jump_labels {
+            label.paint(layout.content_origin, cx);
+        }
+    }
+
     fn paint_scrollbar(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
         let Some(scrollbar_layout) = layout.scrollbar_layout.as_ref() else {
             return;
@@ -5385,6 +5453,16 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let jump_labels = self.layout_jump_labels(
+                        &snapshot,
+                        start_row..end_row,
+                        &line_layouts,
+                        scroll_pixel_position,
+                        line_height,
+                        em_width,
+                        cx,
+                    );
+
                     let scrollbar_layout = self.layout_scrollbar(
                         &snapshot,
                         bounds,
@@ -5623,6 +5701,7 @@ impl Element for EditorElement {
                         blocks,
                         cursors,
                         visible_cursors,
+                        jump_labels,
                         selections,
                         mouse_context_menu,
                         test_indicators,
@@ -5765,6 +5844,7 @@ pub struct EditorLayout {
     redacted_ranges: Vec<Range<DisplayPoint>>,
     cursors: Vec<(DisplayPoint, Hsla)>,
     visible_cursors: Vec<CursorLayout>,
+    jump_labels: Vec<CursorLayout>,
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     code_actions_indicator: Option<AnyElement>,
     test_indicators: Vec<AnyElement>,
@@ -3,7 +3,7 @@ use crate::{
     persistence::{SerializedEditor, DB},
     scroll::ScrollAnchor,
     Anchor, Autoscroll, Editor, EditorEvent, EditorSettings, ExcerptId, ExcerptRange, MultiBuffer,
-    MultiBufferSnapshot, NavigationData, SearchWithinRange, ToPoint as _,
+    MultiBufferSnapshot, NavigationData, SearchWithinRange, ShowUnsavedChanges, ToPoint as _,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::HashSet;
@@ -26,6 +26,7 @@ use project::{
 use rpc::proto::{self, update_view, PeerId};
 use settings::Settings;
 use workspace::item::{Dedup, ItemSettings, SerializableItem, TabContentParams};
+use workspace::notifications::{simple_message_notification::MessageNotification, NotificationId};
 
 use std::{
     any::TypeId,
@@ -927,7 +928,7 @@ impl SerializableItem for Editor {
 
     fn deserialize(
         project: Model<Project>,
-        _workspace: WeakView<Workspace>,
+        workspace: WeakView<Workspace>,
         workspace_id: workspace::WorkspaceId,
         item_id: ItemId,
         cx: &mut ViewContext<Pane>,
@@ -992,7 +993,7 @@ impl SerializableItem for Editor {
                         buffer.set_text(contents, cx);
                     })?;
 
-                    anyhow::Ok(buffer)
+                    anyhow::Ok((buffer, false))
                 }
             }),
             SerializedEditor {
@@ -1026,8 +1027,10 @@ impl SerializableItem for Editor {
                             // But for now, it keeps the implementation of the content serialization
                             // simple, because we don't have to persist all of the metadata that we get
                             // by loading the file (git diff base, ...).
+                            let mut recovered_changes = false;
                             if let Some(buffer_text) = contents {
                                 buffer.update(&mut cx, |buffer, cx| {
+                                    recovered_changes = buffer.text() != buffer_text;
                                     // If we did restore an mtime, we want to store it on the buffer
                                     // so that the next edit will mark the buffer as dirty/conflicted.
                                     if mtime.is_some() {
@@ -1042,7 +1045,7 @@ impl SerializableItem for Editor {
                                 })?;
                             }
 
-                            Ok(buffer)
+                            Ok((buffer, recovered_changes))
                         })
                     })
                     .unwrap_or_else(|error| Task::ready(Err(error)))
@@ -1051,16 +1054,28 @@ impl SerializableItem for Editor {
         };
 
         cx.spawn(|pane, mut cx| async move {
-            let buffer = buffer_task.await?;
+            let (buffer, recovered_changes) = buffer_task.await?;
 
-            pane.update(&mut cx, |_, cx| {
+            let editor = pane.update(&mut cx, |_, cx| {
                 cx.new_view(|cx| {
                     let mut editor = Editor::for_buffer(buffer, Some(project), cx);
 
                     editor.read_scroll_position_from_db(item_id, workspace_id, cx);
                     editor
                 })
-            })
+            })?;
+
+            // The restored contents differ from the file on disk, which happens after
+            // quitting (or crashing) with unsaved changes. Let the user review them.
+            if recovered_changes {
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        show_recovered_changes_notification(workspace, &editor, cx)
+                    })
+                    .log_err();
+            }
+
+            Ok(editor)
         })
     }
 
@@ -1495,6 +1510,41 @@ pub fn entry_git_aware_label_color(
     }
 }
 
+fn show_recovered_changes_notification(
+    workspace: &mut Workspace,
+    editor: &View<Editor>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    struct RecoveredChangesNotification;
+
+    let Some(path) = path_for_buffer(&editor.read(cx).buffer, 0, true, cx)
+        .map(|path| path.to_string_lossy().to_string())
+    else {
+        return;
+    };
+    let editor = editor.downgrade();
+    workspace.show_notification(
+        NotificationId::identified::<RecoveredChangesNotification>((
+            "recovered-changes",
+            editor.entity_id(),
+        )),
+        cx,
+        |cx| {
+            cx.new_view(move |_| {
+                MessageNotification::new(format!("Recovered unsaved changes to {path}"))
+                    .with_click_message("Show Changes")
+                    .on_click(move |cx| {
+                        editor
+                            .update(cx, |editor, cx| {
+                                editor.show_unsaved_changes(&ShowUnsavedChanges, cx)
+                            })
+                            .ok();
+                    })
+            })
+        },
+    );
+}
+
 fn path_for_buffer<'a>(
     buffer: &Model<MultiBuffer>,
     height: usize,
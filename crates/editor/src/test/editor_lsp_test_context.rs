@@ -150,6 +150,30 @@ impl EditorLspTestContext {
                 ("<" @open ">" @close)
                 ("\"" @open "\"" @close)
                 (closure_parameters "|" @open "|" @close)"#})),
+            outline: Some(Cow::from(indoc! {r#"
+                (function_item
+                    "fn" @context
+                    name: (_) @name) @item
+                (impl_item
+                    "impl" @context
+                    type: (_) @name) @item
+                (struct_item
+                    "struct" @context
+                    name: (_) @name) @item
+                (mod_item
+                    "mod" @context
+                    name: (_) @name) @item"#})),
+            textobjects: Some(Cow::from(indoc! {r#"
+                (function_item
+                    body: (_
+                        "{"
+                        (_)* @function.inside
+                        "}" )) @function.around
+                (impl_item
+                    body: (_
+                        "{"
+                        (_)* @class.inside
+                        "}" )) @class.around"#})),
             ..Default::default()
         })
         .expect("Could not parse queries");
@@ -230,7 +254,15 @@ impl EditorLspTestContext {
                 ..Default::default()
             },
             Some(tree_sitter_html::language()),
-        );
+        )
+        .with_queries(LanguageQueries {
+            matching: Some(Cow::from(indoc! {r#"
+                (element
+                    (start_tag) @match
+                    (end_tag) @match)"#})),
+            ..Default::default()
+        })
+        .expect("Could not parse queries");
         Self::new(language, Default::default(), cx).await
     }
 
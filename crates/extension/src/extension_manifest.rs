@@ -5,6 +5,7 @@ use language::LanguageServerName;
 use semantic_version::SemanticVersion;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeSet,
     ffi::OsStr,
     fmt,
     path::{Path, PathBuf},
@@ -80,6 +81,43 @@ pub struct ExtensionManifest {
     pub indexed_docs_providers: BTreeMap<Arc<str>, IndexedDocsProviderEntry>,
     #[serde(default)]
     pub snippets: Option<PathBuf>,
+    /// The host capabilities this extension may use.
+    ///
+    /// Extensions that don't declare any permissions are granted all of them,
+    /// so that extensions published before permissions existed keep working.
+    #[serde(default)]
+    pub permissions: Option<BTreeSet<ExtensionPermission>>,
+}
+
+/// A host capability that an extension has to declare in its manifest before using it.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtensionPermission {
+    /// Making HTTP requests, including looking up GitHub releases.
+    Network,
+    /// Downloading files into the extension's work directory.
+    DownloadFile,
+    /// Looking up and installing npm packages.
+    Npm,
+    /// Reading files in the user's worktree.
+    ReadFile,
+    /// Reading the worktree's shell environment.
+    ShellEnv,
+    /// Looking up binaries on the worktree's `PATH`.
+    Which,
+}
+
+impl fmt::Display for ExtensionPermission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network => write!(f, "network"),
+            Self::DownloadFile => write!(f, "download_file"),
+            Self::Npm => write!(f, "npm"),
+            Self::ReadFile => write!(f, "read_file"),
+            Self::ShellEnv => write!(f, "shell_env"),
+            Self::Which => write!(f, "which"),
+        }
+    }
 }
 
 #[derive(Clone, Default, PartialEq, Eq, Debug, Deserialize, Serialize)]
@@ -172,6 +210,13 @@ impl ExtensionManifest {
                 .with_context(|| format!("invalid extension.json for extension {extension_name}"))
         }
     }
+
+    /// Returns whether the extension may use the given host capability.
+    pub fn allows(&self, permission: ExtensionPermission) -> bool {
+        self.permissions
+            .as_ref()
+            .map_or(true, |permissions| permissions.contains(&permission))
+    }
 }
 
 fn manifest_from_old_manifest(
@@ -208,5 +253,71 @@ fn manifest_from_old_manifest(
         slash_commands: BTreeMap::default(),
         indexed_docs_providers: BTreeMap::default(),
         snippets: None,
+        permissions: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_manifest(extra: &str) -> Result<ExtensionManifest, toml::de::Error> {
+        toml::from_str(&format!(
+            r#"
+            id = "test"
+            name = "Test"
+            version = "0.1.0"
+            schema_version = 1
+            {extra}
+            "#
+        ))
+    }
+
+    #[test]
+    fn test_manifest_permissions() {
+        let manifest = parse_manifest("").unwrap();
+        assert_eq!(manifest.permissions, None);
+        assert!(manifest.allows(ExtensionPermission::Network));
+        assert!(manifest.allows(ExtensionPermission::DownloadFile));
+        assert!(manifest.allows(ExtensionPermission::Npm));
+        assert!(manifest.allows(ExtensionPermission::ReadFile));
+        assert!(manifest.allows(ExtensionPermission::ShellEnv));
+        assert!(manifest.allows(ExtensionPermission::Which));
+
+        let manifest = parse_manifest(r#"permissions = ["network", "download_file"]"#).unwrap();
+        assert_eq!(
+            manifest.permissions,
+            Some(BTreeSet::from_iter([
+                ExtensionPermission::Network,
+                ExtensionPermission::DownloadFile
+            ]))
+        );
+        assert!(manifest.allows(ExtensionPermission::Network));
+        assert!(manifest.allows(ExtensionPermission::DownloadFile));
+        assert!(!manifest.allows(ExtensionPermission::Npm));
+        assert!(!manifest.allows(ExtensionPermission::ReadFile));
+
+        let manifest =
+            parse_manifest(r#"permissions = ["read_file", "shell_env", "which"]"#).unwrap();
+        assert!(manifest.allows(ExtensionPermission::ReadFile));
+        assert!(manifest.allows(ExtensionPermission::ShellEnv));
+        assert!(manifest.allows(ExtensionPermission::Which));
+        assert!(!manifest.allows(ExtensionPermission::Network));
+
+        let manifest = parse_manifest("permissions = []").unwrap();
+        assert!(!manifest.allows(ExtensionPermission::Network));
+
+        assert!(parse_manifest(r#"permissions = ["filesystem"]"#).is_err());
+    }
+
+    #[test]
+    fn test_old_manifests_allow_everything() {
+        let manifest_json = serde_json::from_str::<OldExtensionManifest>(
+            r#"{ "name": "Test", "version": "0.1.0" }"#,
+        )
+        .unwrap();
+        let manifest = manifest_from_old_manifest(manifest_json, "test");
+        assert_eq!(manifest.permissions, None);
+        assert!(manifest.allows(ExtensionPermission::Npm));
     }
 }
@@ -62,7 +62,8 @@ use wasm_host::{
 };
 
 pub use extension_manifest::{
-    ExtensionLibraryKind, ExtensionManifest, GrammarManifestEntry, OldExtensionManifest,
+    ExtensionLibraryKind, ExtensionManifest, ExtensionPermission, GrammarManifestEntry,
+    OldExtensionManifest,
 };
 pub use extension_settings::ExtensionSettings;
 
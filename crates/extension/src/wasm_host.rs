@@ -1,6 +1,6 @@
 pub(crate) mod wit;
 
-use crate::ExtensionManifest;
+use crate::{ExtensionManifest, ExtensionPermission};
 use anyhow::{anyhow, bail, Context as _, Result};
 use fs::{normalize_path, Fs};
 use futures::future::LocalBoxFuture;
@@ -288,6 +288,16 @@ impl WasmState {
     fn work_dir(&self) -> PathBuf {
         self.host.work_dir.join(self.manifest.id.as_ref())
     }
+
+    fn require_permission(&self, permission: ExtensionPermission) -> Result<()> {
+        if !self.manifest.allows(permission) {
+            bail!(
+                "extension {} does not declare the `{permission}` permission",
+                self.manifest.id
+            );
+        }
+        Ok(())
+    }
 }
 
 impl wasi::WasiView for WasmState {
@@ -1,4 +1,5 @@
 use crate::wasm_host::{wit::ToWasmtimeResult, WasmState};
+use crate::ExtensionPermission;
 use ::http_client::AsyncBody;
 use ::settings::Settings;
 use anyhow::{anyhow, bail, Context, Result};
@@ -92,6 +93,9 @@ impl HostWorktree for WasmState {
         delegate: Resource<Arc<dyn LspAdapterDelegate>>,
         path: String,
     ) -> wasmtime::Result<Result<String, String>> {
+        if let Err(error) = self.require_permission(ExtensionPermission::ReadFile) {
+            return Ok(Err(error.to_string()));
+        }
         let delegate = self.table.get(&delegate)?;
         Ok(delegate
             .read_text_file(path.into())
@@ -103,6 +107,10 @@ impl HostWorktree for WasmState {
         &mut self,
         delegate: Resource<Arc<dyn LspAdapterDelegate>>,
     ) -> wasmtime::Result<EnvVars> {
+        if let Err(error) = self.require_permission(ExtensionPermission::ShellEnv) {
+            log::warn!("{error}");
+            return Ok(EnvVars::default());
+        }
         let delegate = self.table.get(&delegate)?;
         Ok(delegate.shell_env().await.into_iter().collect())
     }
@@ -112,6 +120,10 @@ impl HostWorktree for WasmState {
         delegate: Resource<Arc<dyn LspAdapterDelegate>>,
         binary_name: String,
     ) -> wasmtime::Result<Option<String>> {
+        if let Err(error) = self.require_permission(ExtensionPermission::Which) {
+            log::warn!("{error}");
+            return Ok(None);
+        }
         let delegate = self.table.get(&delegate)?;
         Ok(delegate
             .which(binary_name.as_ref())
@@ -135,6 +147,7 @@ impl http_client::Host for WasmState {
         request: http_client::HttpRequest,
     ) -> wasmtime::Result<Result<http_client::HttpResponse, String>> {
         maybe!(async {
+            self.require_permission(ExtensionPermission::Network)?;
             let url = &request.url;
             let request = convert_request(&request)?;
             let mut response = self.host.http_client.send(request).await?;
@@ -152,6 +165,9 @@ impl http_client::Host for WasmState {
         &mut self,
         request: http_client::HttpRequest,
     ) -> wasmtime::Result<Result<Resource<ExtensionHttpResponseStream>, String>> {
+        if let Err(error) = self.require_permission(ExtensionPermission::Network) {
+            return Ok(Err(error.to_string()));
+        }
         let request = convert_request(&request)?;
         let response = self.host.http_client.send(request);
         maybe!(async {
@@ -265,11 +281,15 @@ impl nodejs::Host for WasmState {
         &mut self,
         package_name: String,
     ) -> wasmtime::Result<Result<String, String>> {
-        self.host
-            .node_runtime
-            .npm_package_latest_version(&package_name)
-            .await
-            .to_wasmtime_result()
+        maybe!(async {
+            self.require_permission(ExtensionPermission::Npm)?;
+            self.host
+                .node_runtime
+                .npm_package_latest_version(&package_name)
+                .await
+        })
+        .await
+        .to_wasmtime_result()
     }
 
     async fn npm_package_installed_version(
@@ -288,11 +308,15 @@ impl nodejs::Host for WasmState {
         package_name: String,
         version: String,
     ) -> wasmtime::Result<Result<(), String>> {
-        self.host
-            .node_runtime
-            .npm_install_packages(&self.work_dir(), &[(&package_name, &version)])
-            .await
-            .to_wasmtime_result()
+        maybe!(async {
+            self.require_permission(ExtensionPermission::Npm)?;
+            self.host
+                .node_runtime
+                .npm_install_packages(&self.work_dir(), &[(&package_name, &version)])
+                .await
+        })
+        .await
+        .to_wasmtime_result()
     }
 }
 
@@ -325,6 +349,7 @@ impl github::Host for WasmState {
         options: github::GithubReleaseOptions,
     ) -> wasmtime::Result<Result<github::GithubRelease, String>> {
         maybe!(async {
+            self.require_permission(ExtensionPermission::Network)?;
             let release = ::http_client::github::latest_github_release(
                 &repo,
                 options.require_assets,
@@ -344,6 +369,7 @@ impl github::Host for WasmState {
         tag: String,
     ) -> wasmtime::Result<Result<github::GithubRelease, String>> {
         maybe!(async {
+            self.require_permission(ExtensionPermission::Network)?;
             let release = ::http_client::github::get_release_by_tag_name(
                 &repo,
                 &tag,
@@ -465,6 +491,7 @@ impl ExtensionImports for WasmState {
         file_type: DownloadedFileType,
     ) -> wasmtime::Result<Result<(), String>> {
         maybe!(async {
+            self.require_permission(ExtensionPermission::DownloadFile)?;
             let path = PathBuf::from(path);
             let extension_work_dir = self.host.work_dir.join(self.manifest.id.as_ref());
 
@@ -1,9 +1,15 @@
-use std::{path::Path, str};
+use std::{
+    path::{Path, PathBuf},
+    str,
+};
 
+use anyhow::{Context as _, Result};
 use collections::HashMap;
 
 use gpui::{AppContext, AssetSource, Global, SharedString};
-use serde_derive::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
 use util::{maybe, paths::PathExt};
 
 #[derive(Deserialize, Debug)]
@@ -11,15 +17,50 @@ struct TypeConfig {
     icon: SharedString,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct FileIcons {
+/// A mapping from file names, extensions and languages to icons.
+#[derive(Deserialize, Debug, Default)]
+pub struct IconTheme {
+    #[serde(default)]
+    name: SharedString,
+    #[serde(default)]
     stems: HashMap<String, String>,
+    #[serde(default)]
     suffixes: HashMap<String, String>,
+    #[serde(default)]
+    languages: HashMap<String, String>,
+    #[serde(default)]
     types: HashMap<String, TypeConfig>,
 }
 
+impl IconTheme {
+    fn type_for_path(&self, path: &Path) -> Option<&str> {
+        let suffix = path.icon_stem_or_suffix()?;
+
+        self.stems
+            .get(suffix)
+            .or_else(|| self.suffixes.get(suffix))
+            .map(String::as_str)
+    }
+
+    fn type_icon(&self, typ: &str) -> Option<SharedString> {
+        self.types
+            .get(typ)
+            .map(|type_config| type_config.icon.clone())
+    }
+}
+
+#[derive(Debug)]
+pub struct FileIcons {
+    default_theme: IconTheme,
+    themes: HashMap<SharedString, IconTheme>,
+    theme_paths: HashMap<PathBuf, SharedString>,
+}
+
 impl Global for FileIcons {}
 
+/// The name of the icon theme built into Zed.
+pub const DEFAULT_ICON_THEME: &str = "Zed";
+
 const COLLAPSED_DIRECTORY_TYPE: &str = "collapsed_folder";
 const EXPANDED_DIRECTORY_TYPE: &str = "expanded_folder";
 const COLLAPSED_CHEVRON_TYPE: &str = "collapsed_chevron";
@@ -27,6 +68,7 @@ const EXPANDED_CHEVRON_TYPE: &str = "expanded_chevron";
 pub const FILE_TYPES_ASSET: &str = "icons/file_icons/file_types.json";
 
 pub fn init(assets: impl AssetSource, cx: &mut AppContext) {
+    FileIconSettings::register(cx);
     cx.set_global(FileIcons::new(assets))
 }
 
@@ -36,41 +78,119 @@ impl FileIcons {
     }
 
     pub fn new(assets: impl AssetSource) -> Self {
+        FileIcons {
+            default_theme: Self::load_default_theme(assets),
+            themes: HashMap::default(),
+            theme_paths: HashMap::default(),
+        }
+    }
+
+    /// Reloads the default icon theme from the assets, keeping any registered icon themes.
+    pub fn reload_default_theme(&mut self, assets: impl AssetSource) {
+        self.default_theme = Self::load_default_theme(assets);
+    }
+
+    fn load_default_theme(assets: impl AssetSource) -> IconTheme {
         assets
-            .load("icons/file_icons/file_types.json")
+            .load(FILE_TYPES_ASSET)
             .ok()
             .flatten()
-            .and_then(|file| serde_json::from_str::<FileIcons>(str::from_utf8(&file).unwrap()).ok())
-            .unwrap_or_else(|| FileIcons {
-                stems: HashMap::default(),
-                suffixes: HashMap::default(),
-                types: HashMap::default(),
-            })
+            .and_then(|file| serde_json::from_str::<IconTheme>(str::from_utf8(&file).unwrap()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Registers a user-provided icon theme from the contents of the JSON file at
+    /// the given path, returning the theme's name.
+    ///
+    /// Icon paths in the theme are relative to the directory containing its file,
+    /// so that icon themes can ship their own SVGs. Icons that the theme does not
+    /// define fall back to the default icon theme.
+    pub fn register_icon_theme(&mut self, path: &Path, content: &str) -> Result<SharedString> {
+        let mut theme =
+            serde_json::from_str::<IconTheme>(content).context("failed to parse icon theme")?;
+        anyhow::ensure!(!theme.name.is_empty(), "icon theme has no name");
+        if let Some(theme_dir) = path.parent() {
+            for type_config in theme.types.values_mut() {
+                let icon_path = theme_dir.join(type_config.icon.as_ref());
+                type_config.icon = icon_path.to_string_lossy().into_owned().into();
+            }
+        }
+
+        self.unregister_icon_theme(path);
+        let name = theme.name.clone();
+        self.themes.insert(name.clone(), theme);
+        self.theme_paths.insert(path.to_path_buf(), name.clone());
+        Ok(name)
+    }
+
+    /// Removes the icon theme that was registered from the file at the given path,
+    /// returning its name.
+    pub fn unregister_icon_theme(&mut self, path: &Path) -> Option<SharedString> {
+        let name = self.theme_paths.remove(path)?;
+        if !self.theme_paths.values().any(|other| *other == name) {
+            self.themes.remove(&name);
+        }
+        Some(name)
+    }
+
+    /// Returns the names of all available icon themes.
+    pub fn icon_theme_names(&self) -> Vec<SharedString> {
+        let mut names = self.themes.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names.insert(0, DEFAULT_ICON_THEME.into());
+        names
+    }
+
+    /// Returns the user's selected icon theme, if it is not the default one.
+    fn active_theme(&self, cx: &AppContext) -> Option<&IconTheme> {
+        let name = FileIconSettings::get_global(cx).icon_theme.as_deref()?;
+        self.themes.get(name)
     }
 
     pub fn get_icon(path: &Path, cx: &AppContext) -> Option<SharedString> {
         let this = cx.try_global::<Self>()?;
+        let active_theme = this.active_theme(cx);
 
         // FIXME: Associate a type with the languages and have the file's language
         //        override these associations
         maybe!({
-            let suffix = path.icon_stem_or_suffix()?;
-
-            if let Some(type_str) = this.stems.get(suffix) {
-                return this.get_type_icon(type_str);
+            if let Some(theme) = active_theme {
+                if let Some(icon) = theme
+                    .type_for_path(path)
+                    .and_then(|typ| this.get_type_icon_in(Some(theme), typ))
+                {
+                    return Some(icon);
+                }
             }
 
-            this.suffixes
-                .get(suffix)
-                .and_then(|type_str| this.get_type_icon(type_str))
+            this.default_theme
+                .type_for_path(path)
+                .and_then(|typ| this.get_type_icon_in(active_theme, typ))
         })
-        .or_else(|| this.get_type_icon("default"))
+        .or_else(|| this.get_type_icon_in(active_theme, "default"))
+    }
+
+    /// Returns the icon for files written in the language with the given name.
+    pub fn get_language_icon(language_name: &str, cx: &AppContext) -> Option<SharedString> {
+        let this = cx.try_global::<Self>()?;
+        let active_theme = this.active_theme(cx);
+
+        let typ = active_theme
+            .and_then(|theme| theme.languages.get(language_name))
+            .or_else(|| this.default_theme.languages.get(language_name))
+            .cloned()
+            .unwrap_or_else(|| language_name.to_lowercase());
+        this.get_type_icon_in(active_theme, &typ)
     }
 
     pub fn get_type_icon(&self, typ: &str) -> Option<SharedString> {
-        self.types
-            .get(typ)
-            .map(|type_config| type_config.icon.clone())
+        self.default_theme.type_icon(typ)
+    }
+
+    fn get_type_icon_in(&self, theme: Option<&IconTheme>, typ: &str) -> Option<SharedString> {
+        theme
+            .and_then(|theme| theme.type_icon(typ))
+            .or_else(|| self.default_theme.type_icon(typ))
     }
 
     pub fn get_folder_icon(expanded: bool, cx: &AppContext) -> Option<SharedString> {
@@ -82,7 +202,7 @@ impl FileIcons {
             COLLAPSED_DIRECTORY_TYPE
         };
 
-        this.get_type_icon(key)
+        this.get_type_icon_in(this.active_theme(cx), key)
     }
 
     pub fn get_chevron_icon(expanded: bool, cx: &AppContext) -> Option<SharedString> {
@@ -94,6 +214,144 @@ impl FileIcons {
             COLLAPSED_CHEVRON_TYPE
         };
 
-        this.get_type_icon(key)
+        this.get_type_icon_in(this.active_theme(cx), key)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct FileIconSettings {
+    pub icon_theme: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct FileIconSettingsContent {
+    /// The name of the icon theme to use for files and folders in the project panel,
+    /// the outline panel, tabs and the file finder.
+    ///
+    /// Icon themes are loaded from JSON files in the `icon_themes` directory of
+    /// Zed's configuration directory, and their icon paths are relative to that
+    /// directory. Icons missing from the selected theme are taken from the default
+    /// icon theme.
+    ///
+    /// Default: null
+    pub icon_theme: Option<String>,
+}
+
+impl Settings for FileIconSettings {
+    const KEY: Option<&'static str> = None;
+
+    type FileContent = FileIconSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use gpui::{BorrowAppContext, TestAppContext};
+    use settings::SettingsStore;
+
+    use super::*;
+
+    struct TestAssets;
+
+    impl AssetSource for TestAssets {
+        fn load(&self, path: &str) -> Result<Option<Cow<'static, [u8]>>> {
+            let file_types = br#"{
+                "stems": { "Makefile": "make" },
+                "suffixes": { "rs": "rust", "md": "document" },
+                "languages": { "Plain Text": "document" },
+                "types": {
+                    "default": { "icon": "icons/file.svg" },
+                    "document": { "icon": "icons/book.svg" },
+                    "make": { "icon": "icons/hammer.svg" },
+                    "rust": { "icon": "icons/rust.svg" }
+                }
+            }"#;
+            Ok((path == FILE_TYPES_ASSET).then_some(Cow::Borrowed(file_types.as_slice())))
+        }
+
+        fn list(&self, _: &str) -> Result<Vec<SharedString>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[gpui::test]
+    fn test_icon_themes(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            init(TestAssets, cx);
+
+            let theme_path = Path::new("/icon_themes/test.json");
+            let name = cx
+                .global_mut::<FileIcons>()
+                .register_icon_theme(
+                    theme_path,
+                    r#"{
+                        "name": "Test",
+                        "suffixes": { "rs": "crab" },
+                        "types": {
+                            "crab": { "icon": "icons/crab.svg" },
+                            "document": { "icon": "icons/page.svg" }
+                        }
+                    }"#,
+                )
+                .unwrap();
+            assert_eq!(name, "Test");
+            assert_eq!(
+                cx.global::<FileIcons>().icon_theme_names(),
+                vec![SharedString::from(DEFAULT_ICON_THEME), name]
+            );
+            assert!(cx
+                .global_mut::<FileIcons>()
+                .register_icon_theme(Path::new("/icon_themes/unnamed.json"), r#"{ "types": {} }"#)
+                .is_err());
+
+            let icon = |path: &str, cx: &AppContext| FileIcons::get_icon(Path::new(path), cx);
+            assert_eq!(icon("main.rs", cx), Some("icons/rust.svg".into()));
+            assert_eq!(icon("Makefile", cx), Some("icons/hammer.svg".into()));
+            assert_eq!(icon("notes.txt", cx), Some("icons/file.svg".into()));
+
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<FileIconSettings>(cx, |settings| {
+                    settings.icon_theme = Some("Test".into())
+                });
+            });
+            // icons the theme doesn't have come from the default theme
+            assert_eq!(
+                icon("main.rs", cx),
+                Some("/icon_themes/icons/crab.svg".into())
+            );
+            assert_eq!(
+                icon("README.md", cx),
+                Some("/icon_themes/icons/page.svg".into())
+            );
+            assert_eq!(icon("Makefile", cx), Some("icons/hammer.svg".into()));
+            assert_eq!(icon("notes.txt", cx), Some("icons/file.svg".into()));
+            assert_eq!(
+                FileIcons::get_language_icon("Plain Text", cx),
+                Some("/icon_themes/icons/page.svg".into())
+            );
+            assert_eq!(
+                FileIcons::get_language_icon("Rust", cx),
+                Some("icons/rust.svg".into())
+            );
+
+            // deleting the theme's file falls back to the default theme
+            assert_eq!(
+                cx.global_mut::<FileIcons>()
+                    .unregister_icon_theme(theme_path),
+                Some("Test".into())
+            );
+            assert_eq!(
+                cx.global::<FileIcons>().icon_theme_names(),
+                vec![SharedString::from(DEFAULT_ICON_THEME)]
+            );
+            assert_eq!(icon("main.rs", cx), Some("icons/rust.svg".into()));
+        });
     }
 }
@@ -46,6 +46,9 @@ mod test_context;
 /// The duration for which futures returned from [AppContext::on_app_context] or [ModelContext::on_app_quit] can run before the application fully quits.
 pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(100);
 
+/// The default time to wait for the next keystroke of a multi-keystroke binding.
+pub const DEFAULT_PENDING_INPUT_TIMEOUT: Duration = Duration::from_secs(1);
+
 /// Temporary(?) wrapper around [`RefCell<AppContext>`] to help us debug any double borrows.
 /// Strongly consider removing after stabilization.
 #[doc(hidden)]
@@ -238,6 +241,8 @@ pub struct AppContext {
     pub(crate) windows: SlotMap<WindowId, Option<Window>>,
     pub(crate) window_handles: FxHashMap<WindowId, AnyWindowHandle>,
     pub(crate) keymap: Rc<RefCell<Keymap>>,
+    pub(crate) pending_input_timeout: Duration,
+    reduce_motion: bool,
     pub(crate) global_action_listeners:
         FxHashMap<TypeId, Vec<Rc<dyn Fn(&dyn Any, DispatchPhase, &mut Self)>>>,
     pending_effects: VecDeque<Effect>,
@@ -294,6 +299,8 @@ impl AppContext {
                 window_handles: FxHashMap::default(),
                 windows: SlotMap::with_key(),
                 keymap: Rc::new(RefCell::new(Keymap::default())),
+                pending_input_timeout: DEFAULT_PENDING_INPUT_TIMEOUT,
+                reduce_motion: false,
                 global_action_listeners: FxHashMap::default(),
                 pending_effects: VecDeque::new(),
                 pending_notifications: FxHashSet::default(),
@@ -1078,6 +1085,25 @@ impl AppContext {
         self.pending_effects.push_back(Effect::Refresh);
     }
 
+    /// Set how long to wait for the next keystroke of a multi-keystroke binding before
+    /// dispatching the keystrokes typed so far on their own.
+    pub fn set_pending_input_timeout(&mut self, timeout: Duration) {
+        self.pending_input_timeout = timeout;
+    }
+
+    /// Whether animations should be skipped, rendering their final frame right away.
+    pub fn reduce_motion(&self) -> bool {
+        self.reduce_motion
+    }
+
+    /// Set whether animations should be skipped, rendering their final frame right away.
+    pub fn set_reduce_motion(&mut self, reduce_motion: bool) {
+        if self.reduce_motion != reduce_motion {
+            self.reduce_motion = reduce_motion;
+            self.refresh();
+        }
+    }
+
     /// Register a global listener for actions invoked via the keyboard.
     pub fn on_action<A: Action>(&mut self, listener: impl Fn(&A, &mut Self) + 'static) {
         self.global_action_listeners
@@ -114,7 +114,11 @@ impl<E: IntoElement + 'static> Element for AnimationElement<E> {
                 state.start.elapsed().as_secs_f32() / self.animation.duration.as_secs_f32();
 
             let mut done = false;
-            if delta > 1.0 {
+            if cx.reduce_motion() {
+                // Looping animations are shown at rest, one-shot ones at their end.
+                done = true;
+                delta = if self.animation.oneshot { 1.0 } else { 0.0 };
+            } else if delta > 1.0 {
                 if self.animation.oneshot {
                     done = true;
                     delta = 1.0;
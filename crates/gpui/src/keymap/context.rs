@@ -207,6 +207,13 @@ impl KeyBindingContextPredicate {
     ///
     /// This syntax supports `!=`, `||` and `&&` as logical operators.
     /// You can also preface an operation or check with a `!` to negate it.
+    ///
+    /// Values that contain characters outside of identifiers can be compared by
+    /// wrapping them in single quotes:
+    ///
+    /// `Editor && language == 'C++'` -> A predicate that will match a context with the
+    ///                                  identifier `Editor` and the key `language`
+    ///                                  with the value `C++`
     pub fn parse(source: &str) -> Result<Self> {
         let source = skip_whitespace(source);
         let (predicate, rest) = Self::parse_expr(source, 0)?;
@@ -288,6 +295,17 @@ impl KeyBindingContextPredicate {
                     Err(anyhow!("expected a ')'"))
                 }
             }
+            '\'' => {
+                let end = source[1..]
+                    .find('\'')
+                    .ok_or_else(|| anyhow!("expected a closing '"))?;
+                let (literal, rest) = source[1..].split_at(end);
+                source = skip_whitespace(&rest[1..]);
+                Ok((
+                    KeyBindingContextPredicate::Identifier(literal.to_string().into()),
+                    source,
+                ))
+            }
             '!' => {
                 let source = skip_whitespace(&source[1..]);
                 let (predicate, source) = Self::parse_expr(source, PRECEDENCE_NOT)?;
@@ -531,4 +549,43 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_parse_quoted_values() {
+        assert_eq!(
+            KeyBindingContextPredicate::parse("language == 'C++'").unwrap(),
+            Equal("language".into(), "C++".into())
+        );
+        assert_eq!(
+            KeyBindingContextPredicate::parse("Editor && extension != 'd.ts'").unwrap(),
+            And(
+                Box::new(Identifier("Editor".into())),
+                Box::new(NotEqual("extension".into(), "d.ts".into())),
+            )
+        );
+        assert_eq!(
+            KeyBindingContextPredicate::parse("language == 'C++")
+                .unwrap_err()
+                .to_string(),
+            "expected a closing '"
+        );
+    }
+
+    #[test]
+    fn test_eval_compound_predicates() {
+        let mut context = KeyContext::default();
+        context.add("Editor");
+        context.set("vim_mode", "insert");
+        let contexts = [context];
+
+        let predicate =
+            KeyBindingContextPredicate::parse("Editor && vim_mode == insert && !menu_open")
+                .unwrap();
+        assert!(predicate.eval(&contexts));
+
+        let predicate =
+            KeyBindingContextPredicate::parse("Editor && (vim_mode == normal || menu_open)")
+                .unwrap();
+        assert!(!predicate.eval(&contexts));
+    }
 }
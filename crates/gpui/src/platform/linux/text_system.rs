@@ -1,7 +1,7 @@
 use crate::{
-    point, size, Bounds, DevicePixels, Font, FontFeatures, FontId, FontMetrics, FontRun, FontStyle,
-    FontWeight, GlyphId, LineLayout, Pixels, PlatformTextSystem, Point, RenderGlyphParams,
-    ShapedGlyph, SharedString, Size, SUBPIXEL_VARIANTS,
+    point, size, Bounds, DevicePixels, Font, FontFallbacks, FontFeatures, FontId, FontMetrics,
+    FontRun, FontStyle, FontWeight, GlyphId, LineLayout, Pixels, PlatformTextSystem, Point,
+    RenderGlyphParams, ShapedGlyph, SharedString, Size, SUBPIXEL_VARIANTS,
 };
 use anyhow::{anyhow, Context, Ok, Result};
 use collections::HashMap;
@@ -17,19 +17,28 @@ use pathfinder_geometry::{
     vector::{Vector2F, Vector2I},
 };
 use smallvec::SmallVec;
-use std::{borrow::Cow, sync::Arc};
+use std::{borrow::Cow, ops::Range, sync::Arc};
 
 pub(crate) struct CosmicTextSystem(RwLock<CosmicTextSystemState>);
 
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct FontKey {
+    font_family: SharedString,
+    font_fallbacks: Option<FontFallbacks>,
+}
+
 struct CosmicTextSystemState {
     swash_cache: SwashCache,
     font_system: FontSystem,
     scratch: ShapeBuffer,
     /// Contains all already loaded fonts, including all faces. Indexed by `FontId`.
     loaded_fonts_store: Vec<Arc<CosmicTextFont>>,
-    /// Caches the `FontId`s associated with a specific family to avoid iterating the font database
-    /// for every font face in a family.
-    font_ids_by_family_cache: HashMap<SharedString, SmallVec<[FontId; 4]>>,
+    /// Caches the `FontId`s associated with a specific family and its fallbacks to avoid iterating
+    /// the font database for every font face in a family.
+    font_ids_by_font_key: HashMap<FontKey, SmallVec<[FontId; 4]>>,
+    /// The fallback families configured for each font, tried in order for characters the font
+    /// has no glyph for.
+    fallbacks_by_font_id: HashMap<FontId, FontFallbacks>,
     /// The name of each font associated with the given font id
     postscript_names: HashMap<FontId, String>,
 }
@@ -46,7 +55,8 @@ impl CosmicTextSystem {
             swash_cache: SwashCache::new(),
             scratch: ShapeBuffer::default(),
             loaded_fonts_store: Vec::new(),
-            font_ids_by_family_cache: HashMap::default(),
+            font_ids_by_font_key: HashMap::default(),
+            fallbacks_by_font_id: HashMap::default(),
             postscript_names: HashMap::default(),
         }))
     }
@@ -81,14 +91,19 @@ impl PlatformTextSystem for CosmicTextSystem {
         // todo(linux): Do we need to use CosmicText's Font APIs? Can we consolidate this to use font_kit?
         let mut state = self.0.write();
 
-        let candidates = if let Some(font_ids) = state.font_ids_by_family_cache.get(&font.family) {
+        let font_key = FontKey {
+            font_family: font.family.clone(),
+            font_fallbacks: font.fallbacks.clone(),
+        };
+        let candidates = if let Some(font_ids) = state.font_ids_by_font_key.get(&font_key) {
             font_ids.as_slice()
         } else {
-            let font_ids = state.load_family(&font.family, &font.features)?;
+            let font_ids =
+                state.load_family(&font.family, &font.features, font.fallbacks.as_ref())?;
             state
-                .font_ids_by_family_cache
-                .insert(font.family.clone(), font_ids);
-            state.font_ids_by_family_cache[&font.family].as_ref()
+                .font_ids_by_font_key
+                .insert(font_key.clone(), font_ids);
+            state.font_ids_by_font_key[&font_key].as_ref()
         };
 
         // todo(linux) ideally we would make fontdb's `find_best_match` pub instead of using font-kit here
@@ -195,6 +210,7 @@ impl CosmicTextSystemState {
         &mut self,
         name: &str,
         _features: &FontFeatures,
+        fallbacks: Option<&FontFallbacks>,
     ) -> Result<SmallVec<[FontId; 4]>> {
         // TODO: Determine the proper system UI font.
         let name = if name == ".SystemUIFont" {
@@ -235,6 +251,9 @@ impl CosmicTextSystemState {
             font_ids.push(font_id);
             self.loaded_fonts_store.push(font);
             self.postscript_names.insert(font_id, postscript_name);
+            if let Some(fallbacks) = fallbacks.filter(|f| !f.fallback_list().is_empty()) {
+                self.fallbacks_by_font_id.insert(font_id, fallbacks.clone());
+            }
         }
 
         Ok(font_ids)
@@ -361,21 +380,74 @@ impl CosmicTextSystemState {
         }
     }
 
+    /// Splits the given run of text into spans, assigning each character to the first font in
+    /// the font's fallback chain that has a glyph for it. Characters that no font in the chain
+    /// can render are left to the primary font, so that cosmic-text's own fallback applies.
+    fn fallback_spans(
+        &mut self,
+        font_id: FontId,
+        text: &str,
+        offset: usize,
+    ) -> Vec<(Range<usize>, cosmic_text::fontdb::ID)> {
+        let primary = self.loaded_fonts_store[font_id.0].clone();
+        let Some(fallbacks) = self.fallbacks_by_font_id.get(&font_id).cloned() else {
+            return vec![(offset..offset + text.len(), primary.id())];
+        };
+
+        let face = self.font_system.db().face(primary.id()).unwrap();
+        let (weight, stretch, style) = (face.weight, face.stretch, face.style);
+        let mut fallback_fonts: Vec<Option<Arc<CosmicTextFont>>> = Vec::new();
+
+        let mut spans: Vec<(Range<usize>, cosmic_text::fontdb::ID)> = Vec::new();
+        for (ix, ch) in text.char_indices() {
+            let range = offset + ix..offset + ix + ch.len_utf8();
+            let mut font = primary.id();
+            if !ch.is_whitespace() && primary.as_swash().charmap().map(ch) == 0 {
+                for (fallback_ix, family) in fallbacks.fallback_list().iter().enumerate() {
+                    if fallback_ix == fallback_fonts.len() {
+                        let id = self.font_system.db().query(&cosmic_text::fontdb::Query {
+                            families: &[Family::Name(family)],
+                            weight,
+                            stretch,
+                            style,
+                        });
+                        fallback_fonts.push(id.and_then(|id| self.font_system.get_font(id)));
+                    }
+                    if let Some(fallback) = &fallback_fonts[fallback_ix] {
+                        if fallback.as_swash().charmap().map(ch) != 0 {
+                            font = fallback.id();
+                            break;
+                        }
+                    }
+                }
+            }
+
+            match spans.last_mut() {
+                Some((last_range, last_font)) if *last_font == font => last_range.end = range.end,
+                _ => spans.push((range, font)),
+            }
+        }
+        spans
+    }
+
     #[profiling::function]
     fn layout_line(&mut self, text: &str, font_size: Pixels, font_runs: &[FontRun]) -> LineLayout {
         let mut attrs_list = AttrsList::new(Attrs::new());
         let mut offs = 0;
         for run in font_runs {
-            let font = &self.loaded_fonts_store[run.font_id.0];
-            let font = self.font_system.db().face(font.id()).unwrap();
-            attrs_list.add_span(
-                offs..(offs + run.len),
-                Attrs::new()
-                    .family(Family::Name(&font.families.first().unwrap().0))
-                    .stretch(font.stretch)
-                    .style(font.style)
-                    .weight(font.weight),
-            );
+            for (range, font_id) in
+                self.fallback_spans(run.font_id, &text[offs..offs + run.len], offs)
+            {
+                let font = self.font_system.db().face(font_id).unwrap();
+                attrs_list.add_span(
+                    range,
+                    Attrs::new()
+                        .family(Family::Name(&font.families.first().unwrap().0))
+                        .stretch(font.stretch)
+                        .style(font.style)
+                        .weight(font.weight),
+                );
+            }
             offs += run.len;
         }
         let mut line = ShapeLine::new_in_buffer(
@@ -3263,8 +3263,9 @@ impl<'a> WindowContext<'a> {
         if !match_result.pending.is_empty() {
             currently_pending.keystrokes = match_result.pending;
             currently_pending.focus = self.window.focus;
+            let timeout = self.app.pending_input_timeout;
             currently_pending.timer = Some(self.spawn(|mut cx| async move {
-                cx.background_executor.timer(Duration::from_secs(1)).await;
+                cx.background_executor.timer(timeout).await;
                 cx.update(move |cx| {
                     let Some(currently_pending) = cx
                         .window
@@ -3664,6 +3665,23 @@ impl<'a> WindowContext<'a> {
         actions
     }
 
+    /// Returns the key contexts that are active for the focused element, ordered
+    /// from the root of the element tree to the focused element.
+    pub fn context_stack(&self) -> Vec<KeyContext> {
+        let dispatch_tree = &self.window.rendered_frame.dispatch_tree;
+        let node_id = self
+            .window
+            .focus
+            .and_then(|focus_id| dispatch_tree.focusable_node_id(focus_id))
+            .unwrap_or_else(|| dispatch_tree.root_node_id());
+
+        dispatch_tree
+            .dispatch_path(node_id)
+            .into_iter()
+            .filter_map(|node_id| dispatch_tree.node(node_id).context.clone())
+            .collect()
+    }
+
     /// Returns key bindings that invoke the given action on the currently focused element.
     pub fn bindings_for_action(&self, action: &dyn Action) -> Vec<KeyBinding> {
         self.window
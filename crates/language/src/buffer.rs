@@ -14,7 +14,7 @@ use crate::{
         SyntaxMapMatches, SyntaxSnapshot, ToTreeSitterPoint,
     },
     task_context::RunnableRange,
-    LanguageScope, Outline, OutlineConfig, RunnableCapture, RunnableTag,
+    LanguageScope, Outline, OutlineConfig, RunnableCapture, RunnableTag, TextObject,
 };
 use anyhow::{anyhow, Context, Result};
 use async_watch as watch;
@@ -70,6 +70,10 @@ pub use lsp::DiagnosticSeverity;
 /// a diff against the contents of its file.
 pub static BUFFER_DIFF_TASK: LazyLock<TaskLabel> = LazyLock::new(|| TaskLabel::new());
 
+/// Buffers larger than this many bytes are never parsed on the main thread,
+/// since even an incremental reparse can take longer than a frame.
+const MAX_SYNC_PARSE_LEN: usize = 1024 * 1024;
+
 /// Indicate whether a [Buffer] has permissions to edit.
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub enum Capability {
@@ -417,6 +421,7 @@ struct AutoindentRequest {
     before_edit: BufferSnapshot,
     entries: Vec<AutoindentRequestEntry>,
     is_block_mode: bool,
+    ignore_empty_lines: bool,
 }
 
 #[derive(Clone)]
@@ -1061,6 +1066,12 @@ impl Buffer {
     /// waiting on the parse to complete. As soon as it completes, we proceed
     /// synchronously, unless a 1ms timeout elapses.
     ///
+    /// For buffers larger than [`MAX_SYNC_PARSE_LEN`], we don't wait at all, so
+    /// typing in huge files never blocks on a reparse. Their text is shown with
+    /// the interpolated (or no) highlights while the root layer is reparsed in the
+    /// background, then with the root language's highlights while its injections
+    /// are reparsed.
+    ///
     /// If we time out waiting on the parse, we spawn a second task waiting
     /// until the parse does complete and return with the interpolated tree still
     /// in the foreground. When the background parse completes, call back into
@@ -1081,6 +1092,7 @@ impl Buffer {
         };
 
         let text = self.text_snapshot();
+        let text_len = text.len();
         let parsed_version = self.version();
 
         let mut syntax_map = self.syntax_map.lock();
@@ -1089,6 +1101,25 @@ impl Buffer {
         let mut syntax_snapshot = syntax_map.snapshot();
         drop(syntax_map);
 
+        self.parse_status.0.send(ParseStatus::Parsing).unwrap();
+        if text_len > MAX_SYNC_PARSE_LEN {
+            let parse_task = Self::reparse_progressively(
+                text,
+                syntax_snapshot,
+                language.clone(),
+                language_registry.clone(),
+                cx,
+            );
+            self.finish_parsing_in_background(
+                parse_task,
+                language,
+                language_registry,
+                parsed_version,
+                cx,
+            );
+            return;
+        }
+
         let parse_task = cx.background_executor().spawn({
             let language = language.clone();
             let language_registry = language_registry.clone();
@@ -1098,45 +1129,115 @@ impl Buffer {
             }
         });
 
-        self.parse_status.0.send(ParseStatus::Parsing).unwrap();
         match cx
             .background_executor()
             .block_with_timeout(self.sync_parse_timeout, parse_task)
         {
             Ok(new_syntax_snapshot) => {
                 self.did_finish_parsing(new_syntax_snapshot, cx);
-                return;
             }
             Err(parse_task) => {
-                self.parsing_in_background = true;
-                cx.spawn(move |this, mut cx| async move {
-                    let new_syntax_map = parse_task.await;
-                    this.update(&mut cx, move |this, cx| {
-                        let grammar_changed =
-                            this.language.as_ref().map_or(true, |current_language| {
-                                !Arc::ptr_eq(&language, current_language)
-                            });
-                        let language_registry_changed = new_syntax_map
-                            .contains_unknown_injections()
-                            && language_registry.map_or(false, |registry| {
-                                registry.version() != new_syntax_map.language_registry_version()
-                            });
-                        let parse_again = language_registry_changed
-                            || grammar_changed
-                            || this.version.changed_since(&parsed_version);
-                        this.did_finish_parsing(new_syntax_map, cx);
-                        this.parsing_in_background = false;
-                        if parse_again {
-                            this.reparse(cx);
-                        }
-                    })
-                    .ok();
-                })
-                .detach();
+                self.finish_parsing_in_background(
+                    parse_task,
+                    language,
+                    language_registry,
+                    parsed_version,
+                    cx,
+                );
             }
         }
     }
 
+    /// Parses a buffer too large to wait on in two steps: the root layer first,
+    /// which is shown as soon as it's ready, and then its injections.
+    fn reparse_progressively(
+        text: text::BufferSnapshot,
+        mut syntax_snapshot: SyntaxSnapshot,
+        language: Arc<Language>,
+        language_registry: Option<Arc<LanguageRegistry>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<SyntaxSnapshot> {
+        cx.spawn(|this, mut cx| async move {
+            let (syntax_snapshot, edit_ranges) = cx
+                .background_executor()
+                .spawn({
+                    let text = text.clone();
+                    let language = language.clone();
+                    async move {
+                        let edit_ranges = syntax_snapshot.reparse_root_layer(&text, language);
+                        (syntax_snapshot, edit_ranges)
+                    }
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.did_parse_root_layer(syntax_snapshot.clone(), cx)
+            })
+            .ok();
+
+            cx.background_executor()
+                .spawn(async move {
+                    let mut syntax_snapshot = syntax_snapshot;
+                    syntax_snapshot.reparse_edited_ranges(
+                        &text,
+                        language_registry,
+                        language,
+                        edit_ranges,
+                    );
+                    syntax_snapshot
+                })
+                .await
+        })
+    }
+
+    fn finish_parsing_in_background(
+        &mut self,
+        parse_task: impl Future<Output = SyntaxSnapshot> + 'static,
+        language: Arc<Language>,
+        language_registry: Option<Arc<LanguageRegistry>>,
+        parsed_version: clock::Global,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.parsing_in_background = true;
+        cx.spawn(move |this, mut cx| async move {
+            let new_syntax_map = parse_task.await;
+            this.update(&mut cx, move |this, cx| {
+                let grammar_changed = this.language.as_ref().map_or(true, |current_language| {
+                    !Arc::ptr_eq(&language, current_language)
+                });
+                let language_registry_changed = new_syntax_map.contains_unknown_injections()
+                    && language_registry.map_or(false, |registry| {
+                        registry.version() != new_syntax_map.language_registry_version()
+                    });
+                let parse_again = language_registry_changed
+                    || grammar_changed
+                    || this.version.changed_since(&parsed_version);
+                this.did_finish_parsing(new_syntax_map, cx);
+                this.parsing_in_background = false;
+                if parse_again {
+                    this.reparse(cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Shows the highlights of the root layer while its injections are still being parsed.
+    fn did_parse_root_layer(
+        &mut self,
+        syntax_snapshot: SyntaxSnapshot,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let text = self.text_snapshot();
+        let mut syntax_map = self.syntax_map.lock();
+        syntax_map.did_parse(syntax_snapshot);
+        syntax_map.interpolate(&text);
+        drop(syntax_map);
+        self.non_text_state_update_count += 1;
+        cx.emit(Event::Reparsed);
+        cx.notify();
+    }
+
     fn did_finish_parsing(&mut self, syntax_snapshot: SyntaxSnapshot, cx: &mut ModelContext<Self>) {
         self.non_text_state_update_count += 1;
         self.syntax_map.lock().did_parse(syntax_snapshot);
@@ -1287,6 +1388,10 @@ impl Buffer {
                         .into_iter()
                         .flatten();
                     for (new_row, suggestion) in new_edited_row_range.zip(suggestions) {
+                        if request.ignore_empty_lines && snapshot.line_len(new_row) == 0 {
+                            continue;
+                        }
+
                         if let Some(suggestion) = suggestion {
                             // Find the indent size based on the language for this row.
                             while let Some((row, size)) = language_indent_sizes.peek() {
@@ -1880,6 +1985,7 @@ impl Buffer {
                 before_edit,
                 entries,
                 is_block_mode: matches!(mode, AutoindentMode::Block { .. }),
+                ignore_empty_lines: false,
             }));
         }
 
@@ -1888,6 +1994,32 @@ impl Buffer {
         Some(edit_id)
     }
 
+    /// Re-indents the lines in the given ranges according to the language's
+    /// indentation rules, leaving empty lines alone.
+    pub fn autoindent_ranges<I, T>(&mut self, ranges: I, cx: &mut ModelContext<Self>)
+    where
+        I: IntoIterator<Item = Range<T>>,
+        T: ToOffset + Copy,
+    {
+        let before_edit = self.snapshot();
+        let entries = ranges
+            .into_iter()
+            .map(|range| AutoindentRequestEntry {
+                range: before_edit.anchor_before(range.start)..before_edit.anchor_after(range.end),
+                first_line_is_new: true,
+                indent_size: before_edit.language_indent_size_at(range.start, cx),
+                original_indent_column: None,
+            })
+            .collect();
+        self.autoindent_requests.push(Arc::new(AutoindentRequest {
+            before_edit,
+            entries,
+            is_block_mode: false,
+            ignore_empty_lines: true,
+        }));
+        self.request_autoindent(cx);
+    }
+
     fn did_edit(
         &mut self,
         old_version: &clock::Global,
@@ -3210,6 +3342,112 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns groups of keywords or tags that belong together, such as `if`/`else`/`end`
+    /// or an opening and closing tag, for any matches of the matching query that intersect
+    /// the given range. Matches that share a capture form a single group, so that chains
+    /// like `#if`/`#elif`/`#else`/`#endif` can be matched one link at a time. The ranges
+    /// within each group are sorted by position.
+    pub fn matching_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = Vec<Range<usize>>> {
+        let offset_range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut syntax_matches = self.syntax.matches(offset_range, self, |grammar| {
+            grammar.matching_config.as_ref().map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.matching_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut groups = Vec::<Vec<Range<usize>>>::new();
+        while let Some(mat) = syntax_matches.peek() {
+            let mut ranges = configs[mat.grammar_index]
+                .map(|config| {
+                    mat.captures
+                        .iter()
+                        .filter(|capture| capture.index == config.match_capture_ix)
+                        .map(|capture| capture.node.byte_range())
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            syntax_matches.advance();
+
+            groups.retain_mut(|group| {
+                if group.iter().any(|range| ranges.contains(range)) {
+                    ranges.append(group);
+                    false
+                } else {
+                    true
+                }
+            });
+            groups.push(ranges);
+        }
+
+        groups.into_iter().filter_map(|mut ranges| {
+            ranges.sort_unstable_by_key(|range| range.start);
+            ranges.dedup();
+            // A single keyword has nothing to match with.
+            (ranges.len() > 1).then_some(ranges)
+        })
+    }
+
+    /// Returns the text objects, such as functions and their bodies, whose matches of the
+    /// text object query intersect the given range. A capture that spans several nodes
+    /// in one match is returned as a single range covering all of them.
+    pub fn text_object_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = (Range<usize>, TextObject)> + '_ {
+        let offset_range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut syntax_matches = self.syntax.matches(offset_range, self, |grammar| {
+            grammar
+                .text_object_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.text_object_config.as_ref())
+            .collect::<Vec<_>>();
+
+        let mut captures = Vec::<(Range<usize>, TextObject)>::new();
+        iter::from_fn(move || loop {
+            if let Some(capture) = captures.pop() {
+                return Some(capture);
+            }
+
+            let mat = syntax_matches.peek()?;
+            if let Some(config) = configs[mat.grammar_index] {
+                for capture in mat.captures {
+                    let Some(text_object) = config
+                        .text_objects_by_capture_ix
+                        .iter()
+                        .find(|(ix, _)| *ix == capture.index)
+                        .map(|(_, text_object)| *text_object)
+                    else {
+                        continue;
+                    };
+                    let range = capture.node.byte_range();
+                    if let Some((existing, _)) = captures
+                        .iter_mut()
+                        .find(|(_, existing_object)| *existing_object == text_object)
+                    {
+                        existing.start = existing.start.min(range.start);
+                        existing.end = existing.end.max(range.end);
+                    } else {
+                        captures.push((range, text_object));
+                    }
+                }
+            }
+            syntax_matches.advance();
+        })
+    }
+
     pub fn injections_intersecting_range<T: ToOffset>(
         &self,
         range: Range<T>,
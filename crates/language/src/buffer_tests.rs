@@ -450,6 +450,24 @@ async fn test_normalize_whitespace(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_large_buffers_are_parsed_in_background(cx: &mut gpui::TestAppContext) {
+    let text = "fn a() {}\n".repeat(200_000);
+    let buffer =
+        cx.new_model(|cx| Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx));
+    assert!(buffer.update(cx, |buffer, _| buffer.is_parsing()));
+    cx.executor().run_until_parked();
+    assert!(!buffer.update(cx, |buffer, _| buffer.is_parsing()));
+
+    // Edits don't wait on the reparse either.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "fn b() {}\n")], None, cx);
+        assert!(buffer.is_parsing());
+    });
+    cx.executor().run_until_parked();
+    assert!(!buffer.update(cx, |buffer, _| buffer.is_parsing()));
+}
+
 #[gpui::test]
 async fn test_reparse(cx: &mut gpui::TestAppContext) {
     let text = "fn a() {}";
@@ -1066,6 +1084,57 @@ fn test_enclosing_bracket_ranges_where_brackets_are_not_outermost_children(cx: &
     );
 }
 
+#[gpui::test]
+fn test_matching_ranges_of_chained_alternatives(cx: &mut AppContext) {
+    let language = ruby_lang()
+        .with_matching_query(
+            r#"
+            (if
+              "if" @match
+              alternative: [
+                (elsif "elsif" @match)
+                (else "else" @match)
+              ]?
+              "end" @match)
+
+            (elsif
+              "elsif" @match
+              alternative: [
+                (elsif "elsif" @match)
+                (else "else" @match)
+              ])
+            "#,
+        )
+        .unwrap();
+    let (text, ranges) = marked_text_ranges(
+        indoc! {"
+            «if» a
+              b
+            «elsif» c
+              d
+            «elsif» e
+              f
+            «else»
+              g
+            «end»"},
+        false,
+    );
+    let buffer =
+        cx.new_model(|cx| Buffer::local(text.clone(), cx).with_language(Arc::new(language), cx));
+    let snapshot = buffer.read(cx).snapshot();
+
+    // Each link of the chain is a separate query match, but they all form one group,
+    // whichever line they are queried from.
+    for range in &ranges {
+        assert_eq!(
+            snapshot.matching_ranges(range.clone()).collect::<Vec<_>>(),
+            vec![ranges.clone()],
+            "wrong groups for {:?}",
+            &text[range.clone()]
+        );
+    }
+}
+
 #[gpui::test]
 fn test_range_for_syntax_ancestor(cx: &mut AppContext) {
     cx.new_model(|cx| {
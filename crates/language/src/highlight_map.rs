@@ -11,10 +11,11 @@ pub struct HighlightId(pub u32);
 const DEFAULT_SYNTAX_HIGHLIGHT_ID: HighlightId = HighlightId(u32::MAX);
 
 impl HighlightMap {
-    pub(crate) fn new(capture_names: &[&str], theme: &SyntaxTheme) -> Self {
+    pub(crate) fn new(capture_names: &[&str], theme: &SyntaxTheme, language_name: &str) -> Self {
         // For each capture name in the highlight query, find the longest
         // key in the theme's syntax styles that matches all of the
-        // dot-separated components of the capture name.
+        // dot-separated components of the capture name. Keys scoped to
+        // this language win over unscoped keys of the same length.
         HighlightMap(
             capture_names
                 .iter()
@@ -23,7 +24,13 @@ impl HighlightMap {
                         .highlights
                         .iter()
                         .enumerate()
-                        .filter_map(|(i, (key, _))| {
+                        .filter_map(|(i, (name, _))| {
+                            let (language, key) = SyntaxTheme::parse_scoped_name(name);
+                            let is_scoped = match language {
+                                Some(language) if language == language_name => true,
+                                Some(_) => return None,
+                                None => false,
+                            };
                             let mut len = 0;
                             let capture_parts = capture_name.split('.');
                             for key_part in key.split('.') {
@@ -33,9 +40,9 @@ impl HighlightMap {
                                     return None;
                                 }
                             }
-                            Some((i, len))
+                            Some((i, (len, is_scoped)))
                         })
-                        .max_by_key(|(_, len)| *len)
+                        .max_by_key(|(_, rank)| *rank)
                         .map_or(DEFAULT_SYNTAX_HIGHLIGHT_ID, |(i, _)| HighlightId(i as u32))
                 })
                 .collect(),
@@ -103,9 +110,34 @@ mod tests {
             "variable.builtin.self",
         ];
 
-        let map = HighlightMap::new(capture_names, &theme);
+        let map = HighlightMap::new(capture_names, &theme, "Rust");
         assert_eq!(map.get(0).name(&theme), Some("function"));
         assert_eq!(map.get(1).name(&theme), Some("function.async"));
         assert_eq!(map.get(2).name(&theme), Some("variable.builtin"));
     }
+
+    #[test]
+    fn test_highlight_map_language_scoped_keys() {
+        let theme = SyntaxTheme {
+            highlights: [
+                ("comment", rgba(0x100000ff)),
+                ("comment.doc", rgba(0x200000ff)),
+                ("Rust/comment", rgba(0x300000ff)),
+                ("Go/comment.doc", rgba(0x400000ff)),
+            ]
+            .iter()
+            .map(|(name, color)| (name.to_string(), (*color).into()))
+            .collect(),
+        };
+
+        let capture_names = &["comment", "comment.doc"];
+
+        let map = HighlightMap::new(capture_names, &theme, "Rust");
+        assert_eq!(map.get(0).name(&theme), Some("Rust/comment"));
+        assert_eq!(map.get(1).name(&theme), Some("comment.doc"));
+
+        let map = HighlightMap::new(capture_names, &theme, "Go");
+        assert_eq!(map.get(0).name(&theme), Some("comment"));
+        assert_eq!(map.get(1).name(&theme), Some("Go/comment.doc"));
+    }
 }
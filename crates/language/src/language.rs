@@ -862,6 +862,8 @@ pub struct Grammar {
     pub(crate) brackets_config: Option<BracketConfig>,
     pub(crate) redactions_config: Option<RedactionConfig>,
     pub(crate) runnable_config: Option<RunnableConfig>,
+    pub(crate) matching_config: Option<MatchingConfig>,
+    pub(crate) text_object_config: Option<TextObjectConfig>,
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
     pub embedding_config: Option<EmbeddingConfig>,
@@ -923,6 +925,37 @@ struct RunnableConfig {
     pub extra_captures: Vec<RunnableCapture>,
 }
 
+struct MatchingConfig {
+    query: Query,
+    match_capture_ix: u32,
+}
+
+/// A syntax node that can be selected as a text object, such as the body of a function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TextObject {
+    InsideFunction,
+    AroundFunction,
+    InsideClass,
+    AroundClass,
+}
+
+impl TextObject {
+    pub fn from_capture_name(name: &str) -> Option<TextObject> {
+        match name {
+            "function.inside" => Some(TextObject::InsideFunction),
+            "function.around" => Some(TextObject::AroundFunction),
+            "class.inside" => Some(TextObject::InsideClass),
+            "class.around" => Some(TextObject::AroundClass),
+            _ => None,
+        }
+    }
+}
+
+struct TextObjectConfig {
+    query: Query,
+    text_objects_by_capture_ix: Vec<(u32, TextObject)>,
+}
+
 struct OverrideConfig {
     query: Query,
     values: HashMap<u32, (String, LanguageConfigOverride)>,
@@ -965,6 +998,8 @@ impl Language {
                     override_config: None,
                     redactions_config: None,
                     runnable_config: None,
+                    matching_config: None,
+                    text_object_config: None,
                     error_query: Query::new(&ts_language, "(ERROR) @error").unwrap(),
                     ts_language,
                     highlight_map: Default::default(),
@@ -1025,6 +1060,16 @@ impl Language {
                 .with_runnable_query(query.as_ref())
                 .context("Error loading tests query")?;
         }
+        if let Some(query) = queries.matching {
+            self = self
+                .with_matching_query(query.as_ref())
+                .context("Error loading matching query")?;
+        }
+        if let Some(query) = queries.textobjects {
+            self = self
+                .with_text_object_query(query.as_ref())
+                .context("Error loading textobjects query")?;
+        }
         Ok(self)
     }
 
@@ -1330,6 +1375,46 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_matching_query(mut self, source: &str) -> Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut match_capture_ix = None;
+        get_capture_indices(&query, &mut [("match", &mut match_capture_ix)]);
+
+        if let Some(match_capture_ix) = match_capture_ix {
+            grammar.matching_config = Some(MatchingConfig {
+                query,
+                match_capture_ix,
+            });
+        }
+
+        Ok(self)
+    }
+
+    pub fn with_text_object_query(mut self, source: &str) -> Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+
+        let query = Query::new(&grammar.ts_language, source)?;
+        let text_objects_by_capture_ix = query
+            .capture_names()
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, name)| Some((ix as u32, TextObject::from_capture_name(name)?)))
+            .collect::<Vec<_>>();
+
+        grammar.text_object_config = Some(TextObjectConfig {
+            query,
+            text_objects_by_capture_ix,
+        });
+
+        Ok(self)
+    }
+
     fn grammar_mut(&mut self) -> Option<&mut Grammar> {
         Arc::get_mut(self.grammar.as_mut()?)
     }
@@ -1390,7 +1475,7 @@ impl Language {
         if let Some(grammar) = self.grammar.as_ref() {
             if let Some(highlights_query) = &grammar.highlights_query {
                 *grammar.highlight_map.lock() =
-                    HighlightMap::new(highlights_query.capture_names(), theme);
+                    HighlightMap::new(highlights_query.capture_names(), theme, &self.config.name);
             }
         }
     }
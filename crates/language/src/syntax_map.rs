@@ -377,10 +377,32 @@ impl SyntaxSnapshot {
         registry: Option<Arc<LanguageRegistry>>,
         root_language: Arc<Language>,
     ) {
-        let edit_ranges = text
-            .edits_since::<usize>(&self.parsed_version)
-            .map(|edit| edit.new)
-            .collect::<Vec<_>>();
+        let edit_ranges = self.edit_ranges(text);
+        self.reparse_edited_ranges(text, registry, root_language, edit_ranges);
+    }
+
+    /// Reparses only the root layer, leaving the injected layers as they were
+    /// interpolated, so that the root language can be highlighted sooner.
+    ///
+    /// Returns the ranges edited since the last parse, which should then be passed
+    /// to [`SyntaxSnapshot::reparse_edited_ranges`] to update the injections.
+    pub fn reparse_root_layer(
+        &mut self,
+        text: &BufferSnapshot,
+        root_language: Arc<Language>,
+    ) -> Vec<Range<usize>> {
+        let edit_ranges = self.edit_ranges(text);
+        self.reparse_with_ranges(text, root_language, edit_ranges.clone(), None);
+        edit_ranges
+    }
+
+    pub fn reparse_edited_ranges(
+        &mut self,
+        text: &BufferSnapshot,
+        registry: Option<Arc<LanguageRegistry>>,
+        root_language: Arc<Language>,
+        edit_ranges: Vec<Range<usize>>,
+    ) {
         self.reparse_with_ranges(text, root_language.clone(), edit_ranges, registry.as_ref());
 
         if let Some(registry) = registry {
@@ -420,6 +442,12 @@ impl SyntaxSnapshot {
         }
     }
 
+    fn edit_ranges(&self, text: &BufferSnapshot) -> Vec<Range<usize>> {
+        text.edits_since::<usize>(&self.parsed_version)
+            .map(|edit| edit.new)
+            .collect()
+    }
+
     fn reparse_with_ranges(
         &mut self,
         text: &BufferSnapshot,
@@ -179,6 +179,89 @@ fn test_syntax_map_layers_for_range(cx: &mut AppContext) {
     );
 }
 
+#[gpui::test]
+fn test_reparsing_root_layer_before_injections(cx: &mut AppContext) {
+    let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
+    let language = Arc::new(rust_lang());
+    registry.add(language.clone());
+
+    let mut buffer = Buffer::new(
+        0,
+        BufferId::new(1).unwrap(),
+        r#"
+            fn a() {
+                println!("{}", f(|_| true));
+            }
+        "#
+        .unindent(),
+    );
+
+    let mut syntax_map = SyntaxMap::new();
+    syntax_map.set_language_registry(registry.clone());
+    let mut snapshot = syntax_map.snapshot();
+
+    // The injections are only parsed once the root layer has been shown.
+    let edit_ranges = snapshot.reparse_root_layer(&buffer, language.clone());
+    syntax_map.did_parse(snapshot.clone());
+    assert_layers_for_range(
+        &syntax_map,
+        &buffer,
+        Point::new(1, 25)..Point::new(1, 29),
+        &["...(function_item ..."],
+    );
+
+    snapshot.reparse_edited_ranges(
+        &buffer,
+        Some(registry.clone()),
+        language.clone(),
+        edit_ranges,
+    );
+    syntax_map.did_parse(snapshot.clone());
+    assert_layers_for_range(
+        &syntax_map,
+        &buffer,
+        Point::new(1, 25)..Point::new(1, 29),
+        &[
+            "...(function_item ...",
+            "...(call_expression ... (arguments (closure_expression ...",
+        ],
+    );
+
+    // After an edit, the interpolated injection is kept until it is reparsed.
+    let edited_range = range_for_text(&buffer, "true");
+    buffer.edit([(edited_range, "false")]);
+    syntax_map.interpolate(&buffer);
+    let mut snapshot = syntax_map.snapshot();
+    let edit_ranges = snapshot.reparse_root_layer(&buffer, language.clone());
+    syntax_map.did_parse(snapshot.clone());
+    assert_layers_for_range(
+        &syntax_map,
+        &buffer,
+        Point::new(1, 25)..Point::new(1, 30),
+        &[
+            "...(function_item ...",
+            "...(call_expression ... (arguments (closure_expression ...",
+        ],
+    );
+
+    snapshot.reparse_edited_ranges(
+        &buffer,
+        Some(registry.clone()),
+        language.clone(),
+        edit_ranges,
+    );
+    syntax_map.did_parse(snapshot);
+    assert_layers_for_range(
+        &syntax_map,
+        &buffer,
+        Point::new(1, 25)..Point::new(1, 30),
+        &[
+            "...(function_item ...",
+            "...(call_expression ... (arguments (closure_expression ... (boolean_literal)...",
+        ],
+    );
+}
+
 #[gpui::test]
 fn test_dynamic_language_injection(cx: &mut AppContext) {
     let registry = Arc::new(LanguageRegistry::test(cx.background_executor().clone()));
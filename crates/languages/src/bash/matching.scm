@@ -0,0 +1,9 @@
+(if_statement
+  "if" @match
+  (elif_clause "elif" @match)*
+  (else_clause "else" @match)?
+  "fi" @match)
+
+(case_statement "case" @match "esac" @match)
+
+(do_group "do" @match "done" @match)
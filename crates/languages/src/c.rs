@@ -336,6 +336,7 @@ mod tests {
     use language::{language_settings::AllLanguageSettings, AutoindentMode, Buffer};
     use settings::SettingsStore;
     use std::num::NonZeroU32;
+    use util::test::marked_text_ranges;
 
     #[gpui::test]
     async fn test_c_autoindent(cx: &mut TestAppContext) {
@@ -376,4 +377,27 @@ mod tests {
             buffer
         });
     }
+
+    #[gpui::test]
+    async fn test_c_matching_preprocessor_conditionals(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let test_settings = SettingsStore::test(cx);
+            cx.set_global(test_settings);
+            language::init(cx);
+        });
+        let language = crate::language("c", tree_sitter_c::language());
+        let (text, ranges) = marked_text_ranges(
+            "«#if» A\na();\n«#elif» B\nb();\n«#elif» C\nc();\n«#else»\nd();\n«#endif»\n",
+            false,
+        );
+
+        let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(language, cx));
+        let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+        for range in &ranges {
+            assert_eq!(
+                snapshot.matching_ranges(range.clone()).collect::<Vec<_>>(),
+                vec![ranges.clone()]
+            );
+        }
+    }
 }
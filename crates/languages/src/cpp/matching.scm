@@ -0,0 +1,22 @@
+(preproc_if
+  "#if" @match
+  alternative: [
+    (preproc_elif "#elif" @match)
+    (preproc_else "#else" @match)
+  ]?
+  "#endif" @match)
+
+(preproc_ifdef
+  ["#ifdef" "#ifndef"] @match
+  alternative: [
+    (preproc_elif "#elif" @match)
+    (preproc_else "#else" @match)
+  ]?
+  "#endif" @match)
+
+(preproc_elif
+  "#elif" @match
+  alternative: [
+    (preproc_elif "#elif" @match)
+    (preproc_else "#else" @match)
+  ])
@@ -0,0 +1,5 @@
+(function_definition
+    body: (_) @function.inside) @function.around
+
+(class_definition
+    body: (_) @class.inside) @class.around
@@ -0,0 +1,49 @@
+(function_signature_item) @function.around
+
+(function_item
+    body: (_
+        "{"
+        (_)* @function.inside
+        "}" )) @function.around
+
+(closure_expression
+    body: (block
+        "{"
+        (_)* @function.inside
+        "}")) @function.around
+
+(struct_item
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}" )) @class.around
+
+(enum_item
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}" )) @class.around
+
+(union_item
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}" )) @class.around
+
+(trait_item
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}" )) @class.around
+
+(impl_item
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}" )) @class.around
+
+(mod_item
+    body: (_
+        "{"
+        (_)* @class.inside
+        "}" )) @class.around
@@ -13,7 +13,7 @@ use language::{
     AutoindentMode, Buffer, BufferChunks, BufferRow, BufferSnapshot, Capability, CharClassifier,
     CharKind, Chunk, CursorShape, DiagnosticEntry, File, IndentGuide, IndentSize, Language,
     LanguageScope, OffsetRangeExt, OffsetUtf16, Outline, OutlineItem, Point, PointUtf16, Selection,
-    TextDimension, ToOffset as _, ToOffsetUtf16 as _, ToPoint as _, ToPointUtf16 as _,
+    TextDimension, TextObject, ToOffset as _, ToOffsetUtf16 as _, ToPoint as _, ToPointUtf16 as _,
     TransactionId, Unclipped,
 };
 use smallvec::SmallVec;
@@ -743,6 +743,32 @@ impl MultiBuffer {
         tail(self, buffer_edits, autoindent_mode, edited_excerpt_ids, cx);
     }
 
+    pub fn autoindent_ranges<I, S>(&mut self, ranges: I, cx: &mut ModelContext<Self>)
+    where
+        I: IntoIterator<Item = Range<S>>,
+        S: ToOffset,
+    {
+        if self.read_only() {
+            return;
+        }
+
+        let mut ranges_by_buffer =
+            HashMap::<BufferId, (Model<Buffer>, Vec<Range<usize>>)>::default();
+        for range in ranges {
+            for (buffer, range, _) in self.range_to_buffer_ranges(range, cx) {
+                ranges_by_buffer
+                    .entry(buffer.read(cx).remote_id())
+                    .or_insert_with(|| (buffer.clone(), Vec::new()))
+                    .1
+                    .push(range);
+            }
+        }
+
+        for (buffer, ranges) in ranges_by_buffer.into_values() {
+            buffer.update(cx, |buffer, cx| buffer.autoindent_ranges(ranges, cx));
+        }
+    }
+
     // Inserts newlines at the given position to create an empty line, returning the start of the new line.
     // You can also request the insertion of empty lines above and below the line starting at the returned point.
     // Panics if the given position is invalid.
@@ -3329,6 +3355,58 @@ impl MultiBufferSnapshot {
         )
     }
 
+    /// Returns the groups of matching keywords or tags overlapping the given `range`, or returns
+    /// None if the `range` is not contained in a single excerpt
+    pub fn matching_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> Option<impl Iterator<Item = Vec<Range<usize>>> + '_> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let excerpt = self.excerpt_containing(range.clone())?;
+
+        Some(
+            excerpt
+                .buffer()
+                .matching_ranges(excerpt.map_range_to_buffer(range))
+                .filter_map(move |ranges| {
+                    let buffer_range = ranges.first()?.start..ranges.last()?.end;
+                    if excerpt.contains_buffer_range(buffer_range) {
+                        Some(
+                            ranges
+                                .into_iter()
+                                .map(|range| excerpt.map_range_from_buffer(range))
+                                .collect(),
+                        )
+                    } else {
+                        None
+                    }
+                }),
+        )
+    }
+
+    /// Returns the text objects overlapping the given `range`, or returns None if the
+    /// `range` is not contained in a single excerpt
+    pub fn text_object_ranges<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> Option<impl Iterator<Item = (Range<usize>, TextObject)> + '_> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let excerpt = self.excerpt_containing(range.clone())?;
+
+        Some(
+            excerpt
+                .buffer()
+                .text_object_ranges(excerpt.map_range_to_buffer(range))
+                .filter_map(move |(range, text_object)| {
+                    if excerpt.contains_buffer_range(range.clone()) {
+                        Some((excerpt.map_range_from_buffer(range), text_object))
+                    } else {
+                        None
+                    }
+                }),
+        )
+    }
+
     pub fn redacted_ranges<'a, T: ToOffset>(
         &'a self,
         range: Range<T>,
@@ -140,6 +140,35 @@ pub fn tasks_file() -> &'static PathBuf {
     TASKS_FILE.get_or_init(|| config_dir().join("tasks.json"))
 }
 
+/// Returns the path to Visual Studio Code's user configuration directory.
+///
+/// This is where VS Code stores its `settings.json` and `keybindings.json` files.
+pub fn vscode_user_dir() -> &'static PathBuf {
+    static VSCODE_USER_DIR: OnceLock<PathBuf> = OnceLock::new();
+    VSCODE_USER_DIR.get_or_init(|| {
+        if cfg!(target_os = "macos") {
+            return home_dir().join("Library/Application Support/Code/User");
+        }
+
+        dirs::config_dir()
+            .unwrap_or_else(|| home_dir().join(".config"))
+            .join("Code")
+            .join("User")
+    })
+}
+
+/// Returns the path to Visual Studio Code's `settings.json` file.
+pub fn vscode_settings_file() -> &'static PathBuf {
+    static VSCODE_SETTINGS_FILE: OnceLock<PathBuf> = OnceLock::new();
+    VSCODE_SETTINGS_FILE.get_or_init(|| vscode_user_dir().join("settings.json"))
+}
+
+/// Returns the path to Visual Studio Code's `keybindings.json` file.
+pub fn vscode_keybindings_file() -> &'static PathBuf {
+    static VSCODE_KEYBINDINGS_FILE: OnceLock<PathBuf> = OnceLock::new();
+    VSCODE_KEYBINDINGS_FILE.get_or_init(|| vscode_user_dir().join("keybindings.json"))
+}
+
 /// Returns the path to the extensions directory.
 ///
 /// This is where installed extensions are stored.
@@ -156,6 +185,22 @@ pub fn themes_dir() -> &'static PathBuf {
     THEMES_DIR.get_or_init(|| config_dir().join("themes"))
 }
 
+/// Returns the path to the icon themes directory.
+///
+/// This is where user-provided file icon themes are stored.
+pub fn icon_themes_dir() -> &'static PathBuf {
+    static ICON_THEMES_DIR: OnceLock<PathBuf> = OnceLock::new();
+    ICON_THEMES_DIR.get_or_init(|| config_dir().join("icon_themes"))
+}
+
+/// Returns the path to the scratch directory.
+///
+/// This is where the contents of the scratch window are stored.
+pub fn scratch_dir() -> &'static PathBuf {
+    static SCRATCH_DIR: OnceLock<PathBuf> = OnceLock::new();
+    SCRATCH_DIR.get_or_init(|| support_dir().join("scratch"))
+}
+
 /// Returns the path to the contexts directory.
 ///
 /// This is where the saved contexts from the Assistant are stored.
@@ -231,6 +276,14 @@ pub fn embeddings_dir() -> &'static PathBuf {
     })
 }
 
+/// Returns the path to the project search index directory.
+///
+/// This is where the indices used to speed up project search are stored.
+pub fn search_index_dir() -> &'static PathBuf {
+    static SEARCH_INDEX_DIR: OnceLock<PathBuf> = OnceLock::new();
+    SEARCH_INDEX_DIR.get_or_init(|| support_dir().join("search_index"))
+}
+
 /// Returns the path to the languages directory.
 ///
 /// This is where language servers are downloaded to for languages built-in to Zed.
@@ -7,6 +7,7 @@ pub mod lsp_store;
 mod prettier_support;
 pub mod project_settings;
 pub mod search;
+mod search_index;
 mod task_inventory;
 pub mod terminals;
 pub mod worktree_store;
@@ -28,6 +28,10 @@ pub struct ProjectSettings {
     /// Configuration for session-related features
     #[serde(default)]
     pub session: SessionSettings,
+
+    /// Configuration for the index used to speed up project search
+    #[serde(default)]
+    pub search_index: SearchIndexSettings,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -145,6 +149,30 @@ impl Default for SessionSettings {
     }
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SearchIndexSettings {
+    /// Whether or not to keep an on-disk index of the files in local worktrees,
+    /// so that project search can skip files that don't contain the query.
+    ///
+    /// Default: true
+    pub enabled: bool,
+    /// The maximum size of the index of a single worktree, in megabytes.
+    /// Files that don't fit in the index are scanned on every search.
+    ///
+    /// Default: 256
+    pub max_size_mb: u64,
+}
+
+impl Default for SearchIndexSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_size_mb: 256,
+        }
+    }
+}
+
 impl Settings for ProjectSettings {
     const KEY: Option<&'static str> = None;
 
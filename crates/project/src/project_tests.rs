@@ -3999,6 +3999,55 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_search_with_index(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "const ONE: usize = 1;",
+            "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+            "three.rs": "const THREE: usize = one::ONE + two::TWO;",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    cx.run_until_parked();
+
+    let query = SearchQuery::text(
+        "two",
+        false,
+        false,
+        false,
+        Default::default(),
+        Default::default(),
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        search(&project, query.clone(), cx).await.unwrap(),
+        HashMap::from_iter([
+            ("dir/two.rs".to_string(), vec![6..9]),
+            ("dir/three.rs".to_string(), vec![32..35, 37..40])
+        ])
+    );
+
+    // Files that change after being indexed are searched again.
+    fs.insert_file("/dir/one.rs", "const ONE: usize = two::TWO;".into())
+        .await;
+    cx.run_until_parked();
+    assert_eq!(
+        search(&project, query, cx).await.unwrap(),
+        HashMap::from_iter([
+            ("dir/one.rs".to_string(), vec![19..22, 24..27]),
+            ("dir/two.rs".to_string(), vec![6..9]),
+            ("dir/three.rs".to_string(), vec![32..35, 37..40])
+        ])
+    );
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -0,0 +1,439 @@
+//! An on-disk index of the trigrams contained in the files of a local worktree.
+//!
+//! Project search consults it to skip reading files that can't contain the
+//! query. The index is only ever used to rule files out: files that aren't
+//! indexed, or that changed since they were indexed, are still scanned.
+
+use std::{
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{anyhow, Context as _, Result};
+use collections::{HashMap, HashSet};
+use fs::{Fs, RenameOptions};
+use futures::{channel::mpsc, FutureExt as _, StreamExt as _};
+use gpui::{AsyncAppContext, Model, ModelContext, Task, WeakModel};
+use parking_lot::RwLock;
+use sha2::{Digest, Sha256};
+use util::ResultExt;
+use worktree::{PathChange, UpdatedEntriesSet, Worktree};
+
+use crate::{search::SearchQuery, worktree_store::WorktreeStore};
+
+/// Bumped whenever the on-disk format changes, so that old indices get rebuilt.
+const SEARCH_INDEX_VERSION: u32 = 2;
+
+/// How long the index has to go without updates before it is written to disk.
+const SAVE_DEBOUNCE: Duration = Duration::from_secs(5);
+
+pub(crate) struct SearchIndex {
+    index: Arc<RwLock<WorktreeSearchIndex>>,
+    updates_tx: mpsc::UnboundedSender<Vec<(Arc<Path>, Option<SystemTime>)>>,
+    _maintain_index: Task<()>,
+}
+
+#[derive(Default)]
+pub(crate) struct WorktreeSearchIndex {
+    files: HashMap<Arc<Path>, IndexedFile>,
+    size: usize,
+    max_size: usize,
+    is_dirty: bool,
+}
+
+struct IndexedFile {
+    mtime: SystemTime,
+    /// Sorted and deduplicated. Shared so that saving can snapshot the index cheaply.
+    trigrams: Arc<[u32]>,
+}
+
+impl SearchIndex {
+    pub(crate) fn new(
+        worktree: &Model<Worktree>,
+        fs: Arc<dyn Fs>,
+        max_size: usize,
+        cx: &mut ModelContext<WorktreeStore>,
+    ) -> Option<Self> {
+        let local_worktree = worktree.read(cx).as_local()?;
+        let scan_complete = local_worktree.scan_complete();
+        let abs_path = local_worktree.abs_path().clone();
+        let index = Arc::new(RwLock::new(WorktreeSearchIndex {
+            max_size,
+            ..Default::default()
+        }));
+        let (updates_tx, updates_rx) = mpsc::unbounded();
+        let maintain_index = cx.spawn({
+            let index = index.clone();
+            let worktree = worktree.downgrade();
+            |_, cx| async move {
+                Self::maintain_index(index, abs_path, worktree, fs, scan_complete, updates_rx, cx)
+                    .await
+                    .log_err();
+            }
+        });
+        Some(Self {
+            index,
+            updates_tx,
+            _maintain_index: maintain_index,
+        })
+    }
+
+    pub(crate) fn index(&self) -> Arc<RwLock<WorktreeSearchIndex>> {
+        self.index.clone()
+    }
+
+    pub(crate) fn update(&self, worktree: &Worktree, changes: &UpdatedEntriesSet) {
+        let updates = changes
+            .iter()
+            .filter_map(|(path, _, change)| {
+                if let PathChange::Removed = change {
+                    return Some((path.clone(), None));
+                }
+                let entry = worktree.entry_for_path(path)?;
+                if !entry.is_file() || entry.is_ignored || entry.is_external {
+                    return None;
+                }
+                Some((path.clone(), entry.mtime))
+            })
+            .collect::<Vec<_>>();
+        if !updates.is_empty() {
+            self.updates_tx.unbounded_send(updates).ok();
+        }
+    }
+
+    async fn maintain_index(
+        index: Arc<RwLock<WorktreeSearchIndex>>,
+        abs_path: Arc<Path>,
+        worktree: WeakModel<Worktree>,
+        fs: Arc<dyn Fs>,
+        scan_complete: impl std::future::Future<Output = ()>,
+        mut updates_rx: mpsc::UnboundedReceiver<Vec<(Arc<Path>, Option<SystemTime>)>>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let index_path = index_path(&abs_path);
+        if let Ok(content) = fs.load_bytes(&index_path).await {
+            // Indices written by other versions are rebuilt from scratch.
+            if let Some(files) = decode_index(&content).log_err() {
+                let mut index = index.write();
+                for (path, mtime, trigrams) in files {
+                    index.insert_trigrams(path, mtime, trigrams);
+                }
+                index.is_dirty = false;
+            }
+        }
+
+        scan_complete.await;
+        let files = worktree.update(&mut cx, |worktree, _| {
+            worktree
+                .files(false, 0)
+                .filter(|entry| !entry.is_external)
+                .map(|entry| (entry.path.clone(), entry.mtime))
+                .collect::<Vec<_>>()
+        })?;
+
+        let executor = cx.background_executor().clone();
+        executor
+            .clone()
+            .spawn(async move {
+                // Files that were deleted while Zed wasn't running are dropped from the index.
+                let file_paths = files
+                    .iter()
+                    .map(|(path, _)| path.clone())
+                    .collect::<HashSet<_>>();
+                index.write().retain(|path| file_paths.contains(path));
+                Self::index_files(&index, &abs_path, fs.as_ref(), files).await;
+                Self::save(&index, &index_path, fs.as_ref()).await.log_err();
+
+                while let Some(updates) = updates_rx.next().await {
+                    Self::index_files(&index, &abs_path, fs.as_ref(), updates).await;
+                    // Saving rewrites the whole index, so wait for edits to settle first.
+                    loop {
+                        let mut timer = executor.timer(SAVE_DEBOUNCE).fuse();
+                        futures::select_biased! {
+                            updates = updates_rx.next() => match updates {
+                                Some(updates) => {
+                                    Self::index_files(&index, &abs_path, fs.as_ref(), updates)
+                                        .await
+                                }
+                                None => break,
+                            },
+                            _ = timer => break,
+                        }
+                    }
+                    Self::save(&index, &index_path, fs.as_ref()).await.log_err();
+                }
+            })
+            .await;
+        Ok(())
+    }
+
+    async fn index_files(
+        index: &RwLock<WorktreeSearchIndex>,
+        abs_path: &Path,
+        fs: &dyn Fs,
+        files: Vec<(Arc<Path>, Option<SystemTime>)>,
+    ) {
+        for (path, mtime) in files {
+            let Some(mtime) = mtime else {
+                index.write().remove(&path);
+                continue;
+            };
+            if index.read().is_indexed(&path, mtime) {
+                continue;
+            }
+            let Some(content) = fs.load_bytes(&abs_path.join(&path)).await.log_err() else {
+                continue;
+            };
+            index
+                .write()
+                .insert_trigrams(path, mtime, trigrams(&content).into());
+        }
+    }
+
+    async fn save(index: &RwLock<WorktreeSearchIndex>, path: &Path, fs: &dyn Fs) -> Result<()> {
+        let snapshot = {
+            let mut index = index.write();
+            if !index.is_dirty {
+                return Ok(());
+            }
+            index.is_dirty = false;
+            index
+                .files
+                .iter()
+                .map(|(path, file)| (path.clone(), file.mtime, file.trigrams.clone()))
+                .collect::<Vec<_>>()
+        };
+        let content = encode_index(&snapshot);
+        fs.create_dir(paths::search_index_dir())
+            .await
+            .context("creating search index directory")?;
+        let temp_path = path.with_extension("tmp");
+        let mut reader = futures::io::Cursor::new(content);
+        fs.create_file_with(&temp_path, Pin::new(&mut reader))
+            .await
+            .context("writing search index")?;
+        fs.rename(
+            &temp_path,
+            path,
+            RenameOptions {
+                overwrite: true,
+                ignore_if_exists: false,
+            },
+        )
+        .await
+    }
+}
+
+impl WorktreeSearchIndex {
+    /// Returns `false` only if the file at `path` is indexed, hasn't changed since, and
+    /// is missing some of the query's trigrams.
+    pub(crate) fn might_contain(
+        &self,
+        path: &Path,
+        mtime: Option<SystemTime>,
+        query_trigrams: &[u32],
+    ) -> bool {
+        let Some(file) = self.files.get(path) else {
+            return true;
+        };
+        if Some(file.mtime) != mtime {
+            return true;
+        }
+        query_trigrams
+            .iter()
+            .all(|trigram| file.trigrams.binary_search(trigram).is_ok())
+    }
+
+    fn is_indexed(&self, path: &Path, mtime: SystemTime) -> bool {
+        self.files
+            .get(path)
+            .map_or(false, |file| file.mtime == mtime)
+    }
+
+    fn insert_trigrams(&mut self, path: Arc<Path>, mtime: SystemTime, trigrams: Arc<[u32]>) {
+        self.remove(&path);
+        let size = trigrams.len() * std::mem::size_of::<u32>();
+        // Once the index is full, the remaining files are scanned when searching.
+        if self.size + size > self.max_size {
+            return;
+        }
+        self.size += size;
+        self.is_dirty = true;
+        self.files.insert(path, IndexedFile { mtime, trigrams });
+    }
+
+    fn remove(&mut self, path: &Path) {
+        if let Some(file) = self.files.remove(path) {
+            self.size -= file.trigrams.len() * std::mem::size_of::<u32>();
+            self.is_dirty = true;
+        }
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&Path) -> bool) {
+        let removed = self
+            .files
+            .keys()
+            .filter(|path| !keep(path))
+            .cloned()
+            .collect::<Vec<_>>();
+        for path in removed {
+            self.remove(&path);
+        }
+    }
+}
+
+/// Returns the trigrams that every file matching `query` must contain, or `None`
+/// if the index can't be used for this query.
+pub(crate) fn query_trigrams(query: &SearchQuery) -> Option<Vec<u32>> {
+    match query {
+        SearchQuery::Text { .. } => {
+            let trigrams = trigrams(query.as_str().as_bytes());
+            (!trigrams.is_empty()).then_some(trigrams)
+        }
+        SearchQuery::Regex { .. } => None,
+    }
+}
+
+/// Trigrams are ASCII-lowercased, so that they are a superset of what both
+/// case-sensitive and case-insensitive queries need.
+fn trigrams(text: &[u8]) -> Vec<u32> {
+    let mut trigrams = text
+        .windows(3)
+        .map(|window| {
+            u32::from_be_bytes([
+                0,
+                window[0].to_ascii_lowercase(),
+                window[1].to_ascii_lowercase(),
+                window[2].to_ascii_lowercase(),
+            ])
+        })
+        .collect::<Vec<_>>();
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    trigrams
+}
+
+fn index_path(worktree_abs_path: &Path) -> PathBuf {
+    let mut hasher = Sha256::new();
+    hasher.update(worktree_abs_path.to_string_lossy().as_bytes());
+    paths::search_index_dir().join(format!("{:x}.idx", hasher.finalize()))
+}
+
+/// Encodes the index as the format version followed by, for each file, its
+/// path, its mtime and its trigrams. Numbers are varints, and each trigram is
+/// stored as the difference from the previous one, which keeps most of them
+/// to a single byte.
+fn encode_index(files: &[(Arc<Path>, SystemTime, Arc<[u32]>)]) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    write_varint(&mut buffer, SEARCH_INDEX_VERSION as u64);
+    write_varint(&mut buffer, files.len() as u64);
+    for (path, mtime, trigrams) in files {
+        let path = path.to_string_lossy();
+        write_varint(&mut buffer, path.len() as u64);
+        buffer.extend_from_slice(path.as_bytes());
+        let mtime = mtime
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        write_varint(&mut buffer, mtime.as_secs());
+        write_varint(&mut buffer, mtime.subsec_nanos() as u64);
+        write_varint(&mut buffer, trigrams.len() as u64);
+        let mut previous = 0;
+        for &trigram in trigrams.iter() {
+            write_varint(&mut buffer, (trigram - previous) as u64);
+            previous = trigram;
+        }
+    }
+    buffer
+}
+
+fn decode_index(mut bytes: &[u8]) -> Result<Vec<(Arc<Path>, SystemTime, Arc<[u32]>)>> {
+    let version = read_varint(&mut bytes)?;
+    if version != SEARCH_INDEX_VERSION as u64 {
+        return Err(anyhow!("unsupported search index version {version}"));
+    }
+    let file_count = read_varint(&mut bytes)?;
+    let mut files = Vec::new();
+    for _ in 0..file_count {
+        let path_len = read_varint(&mut bytes)? as usize;
+        if bytes.len() < path_len {
+            return Err(anyhow!("truncated search index"));
+        }
+        let (path, rest) = bytes.split_at(path_len);
+        bytes = rest;
+        let path: Arc<Path> = Path::new(std::str::from_utf8(path)?).into();
+        let secs = read_varint(&mut bytes)?;
+        let nanos = read_varint(&mut bytes)? as u32;
+        let mtime = SystemTime::UNIX_EPOCH + Duration::new(secs, nanos);
+        let trigram_count = read_varint(&mut bytes)?;
+        let mut trigrams = Vec::new();
+        let mut previous = 0u32;
+        for _ in 0..trigram_count {
+            previous = u32::try_from(read_varint(&mut bytes)?)?
+                .checked_add(previous)
+                .context("invalid trigram")?;
+            trigrams.push(previous);
+        }
+        files.push((path, mtime, trigrams.into()));
+    }
+    Ok(files)
+}
+
+fn write_varint(buffer: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buffer.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buffer.push(value as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = bytes.split_first().context("truncated search index")?;
+        *bytes = rest;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(anyhow!("invalid varint in search index"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_index() {
+        let mtime = SystemTime::UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+        let files: Vec<(Arc<Path>, SystemTime, Arc<[u32]>)> = vec![
+            (
+                Path::new("src/main.rs").into(),
+                mtime,
+                trigrams(b"fn main() { println!(\"Hello\"); }").into(),
+            ),
+            (Path::new("empty.txt").into(), mtime, Vec::new().into()),
+        ];
+        let encoded = encode_index(&files);
+        assert_eq!(decode_index(&encoded).unwrap(), files);
+
+        // Truncated or outdated indices are rejected rather than partially loaded.
+        assert!(decode_index(&encoded[..encoded.len() - 1]).is_err());
+        let mut outdated = Vec::new();
+        write_varint(&mut outdated, 1);
+        assert!(decode_index(&outdated).is_err());
+    }
+
+    #[test]
+    fn test_varint() {
+        for value in [0, 1, 0x7f, 0x80, 0x3fff, 0x4000, u32::MAX as u64, u64::MAX] {
+            let mut buffer = Vec::new();
+            write_varint(&mut buffer, value);
+            let mut bytes = buffer.as_slice();
+            assert_eq!(read_varint(&mut bytes).unwrap(), value);
+            assert!(bytes.is_empty());
+        }
+    }
+}
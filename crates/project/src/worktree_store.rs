@@ -15,11 +15,13 @@ use futures::{
 use gpui::{
     AppContext, AsyncAppContext, EntityId, EventEmitter, Model, ModelContext, Task, WeakModel,
 };
+use parking_lot::RwLock;
 use postage::oneshot;
 use rpc::{
     proto::{self, AnyProtoClient, SSH_PROJECT_ID},
     TypedEnvelope,
 };
+use settings::Settings as _;
 use smol::{
     channel::{Receiver, Sender},
     stream::StreamExt,
@@ -28,7 +30,12 @@ use text::ReplicaId;
 use util::{paths::compare_paths, ResultExt};
 use worktree::{Entry, ProjectEntryId, Worktree, WorktreeId, WorktreeSettings};
 
-use crate::{search::SearchQuery, ProjectPath};
+use crate::{
+    project_settings::ProjectSettings,
+    search::SearchQuery,
+    search_index::{self, SearchIndex, WorktreeSearchIndex},
+    ProjectPath,
+};
 
 struct MatchingEntry {
     worktree_path: Arc<Path>,
@@ -47,6 +54,7 @@ pub struct WorktreeStore {
     loading_worktrees:
         HashMap<Arc<Path>, Shared<Task<Result<Model<Worktree>, Arc<anyhow::Error>>>>>,
     fs: Arc<dyn Fs>,
+    search_indices: HashMap<WorktreeId, SearchIndex>,
 }
 
 pub enum WorktreeStoreEvent {
@@ -76,6 +84,7 @@ impl WorktreeStore {
             worktrees: Vec::new(),
             worktrees_reordered: false,
             fs,
+            search_indices: Default::default(),
         }
     }
 
@@ -315,8 +324,27 @@ impl WorktreeStore {
 
         cx.emit(WorktreeStoreEvent::WorktreeAdded(worktree.clone()));
 
+        let search_index_settings = ProjectSettings::get_global(cx).search_index;
+        if search_index_settings.enabled {
+            let max_size = search_index_settings.max_size_mb as usize * 1024 * 1024;
+            if let Some(search_index) = SearchIndex::new(worktree, self.fs.clone(), max_size, cx) {
+                self.search_indices
+                    .insert(worktree.read(cx).id(), search_index);
+                cx.subscribe(worktree, |this, worktree, event, cx| {
+                    if let worktree::Event::UpdatedEntries(changes) = event {
+                        let worktree = worktree.read(cx);
+                        if let Some(search_index) = this.search_indices.get(&worktree.id()) {
+                            search_index.update(worktree, changes);
+                        }
+                    }
+                })
+                .detach();
+            }
+        }
+
         let handle_id = worktree.entity_id();
-        cx.observe_release(worktree, move |_, worktree, cx| {
+        cx.observe_release(worktree, move |this, worktree, cx| {
+            this.search_indices.remove(&worktree.id());
             cx.emit(WorktreeStoreEvent::WorktreeRemoved(
                 handle_id,
                 worktree.id(),
@@ -326,6 +354,7 @@ impl WorktreeStore {
     }
 
     pub fn remove_worktree(&mut self, id_to_remove: WorktreeId, cx: &mut ModelContext<Self>) {
+        self.search_indices.remove(&id_to_remove);
         self.worktrees.retain(|worktree| {
             if let Some(worktree) = worktree.upgrade() {
                 if worktree.read(cx).id() == id_to_remove {
@@ -479,11 +508,17 @@ impl WorktreeStore {
         fs: Arc<dyn Fs>,
         cx: &ModelContext<Self>,
     ) -> Receiver<ProjectPath> {
+        let use_search_index = ProjectSettings::get_global(cx).search_index.enabled;
         let snapshots = self
             .visible_worktrees(cx)
             .filter_map(|tree| {
                 let tree = tree.read(cx);
-                Some((tree.snapshot(), tree.as_local()?.settings()))
+                let search_index = self
+                    .search_indices
+                    .get(&tree.id())
+                    .filter(|_| use_search_index)
+                    .map(|search_index| search_index.index());
+                Some((tree.snapshot(), tree.as_local()?.settings(), search_index))
             })
             .collect::<Vec<_>>();
 
@@ -641,14 +676,19 @@ impl WorktreeStore {
 
     async fn find_candidate_paths(
         fs: Arc<dyn Fs>,
-        snapshots: Vec<(worktree::Snapshot, WorktreeSettings)>,
+        snapshots: Vec<(
+            worktree::Snapshot,
+            WorktreeSettings,
+            Option<Arc<RwLock<WorktreeSearchIndex>>>,
+        )>,
         open_entries: HashSet<ProjectEntryId>,
         query: SearchQuery,
         filter_tx: Sender<MatchingEntry>,
         output_tx: Sender<oneshot::Receiver<ProjectPath>>,
     ) -> Result<()> {
         let include_root = snapshots.len() > 1;
-        for (snapshot, settings) in snapshots {
+        let query_trigrams = search_index::query_trigrams(&query);
+        for (snapshot, settings, search_index) in snapshots {
             let mut entries: Vec<_> = snapshot.entries(query.include_ignored(), 0).collect();
             entries.sort_by(|a, b| compare_paths((&a.path, a.is_file()), (&b.path, b.is_file())));
             for entry in entries {
@@ -685,9 +725,25 @@ impl WorktreeStore {
                     }
                 }
 
+                let is_open = open_entries.contains(&entry.id);
+                if let Some((search_index, query_trigrams)) =
+                    search_index.as_ref().zip(query_trigrams.as_ref())
+                {
+                    // Open buffers may have unsaved changes, so they are always searched.
+                    if !is_open
+                        && !search_index.read().might_contain(
+                            &entry.path,
+                            entry.mtime,
+                            query_trigrams,
+                        )
+                    {
+                        continue;
+                    }
+                }
+
                 let (mut tx, rx) = oneshot::channel();
 
-                if open_entries.contains(&entry.id) {
+                if is_open {
                     tx.send(ProjectPath {
                         worktree_id: snapshot.id(),
                         path: entry.path.clone(),
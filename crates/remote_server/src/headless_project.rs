@@ -2,8 +2,8 @@ use anyhow::{anyhow, Result};
 use fs::Fs;
 use gpui::{AppContext, AsyncAppContext, Context, Model, ModelContext};
 use project::{
-    buffer_store::BufferStore, search::SearchQuery, worktree_store::WorktreeStore, ProjectPath,
-    WorktreeId, WorktreeSettings,
+    buffer_store::BufferStore, project_settings::ProjectSettings, search::SearchQuery,
+    worktree_store::WorktreeStore, ProjectPath, WorktreeId, WorktreeSettings,
 };
 use remote::SshSession;
 use rpc::{
@@ -30,6 +30,7 @@ impl HeadlessProject {
     pub fn init(cx: &mut AppContext) {
         cx.set_global(SettingsStore::new(cx));
         WorktreeSettings::register(cx);
+        ProjectSettings::register(cx);
     }
 
     pub fn new(session: Arc<SshSession>, fs: Arc<dyn Fs>, cx: &mut ModelContext<Self>) -> Self {
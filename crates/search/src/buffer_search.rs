@@ -96,6 +96,7 @@ pub struct BufferSearchBar {
     scroll_handle: ScrollHandle,
     editor_scroll_handle: ScrollHandle,
     editor_needed_width: Pixels,
+    query_translator: Option<fn(&str, &AppContext) -> String>,
 }
 
 impl BufferSearchBar {
@@ -530,6 +531,7 @@ impl BufferSearchBar {
             scroll_handle: ScrollHandle::new(),
             editor_scroll_handle: ScrollHandle::new(),
             editor_needed_width: px(0.),
+            query_translator: None,
         }
     }
 
@@ -562,6 +564,8 @@ impl BufferSearchBar {
     }
 
     pub fn deploy(&mut self, deploy: &Deploy, cx: &mut ViewContext<Self>) -> bool {
+        // queries typed into a bar deployed this way are in the regex syntax
+        self.query_translator = None;
         if self.show(cx) {
             if let Some(active_item) = self.active_searchable_item.as_mut() {
                 active_item.toggle_filtered_search_ranges(deploy.selection_search_enabled, cx);
@@ -735,6 +739,18 @@ impl BufferSearchBar {
         }
     }
 
+    /// Sets a function that rewrites regex queries before they're searched for, so that they
+    /// can be typed in another syntax, like Vim's patterns. It's given the app context so that
+    /// the translation can depend on settings. It's cleared whenever the bar is deployed with
+    /// [`Deploy`], however that happens.
+    pub fn set_query_translator(&mut self, translator: Option<fn(&str, &AppContext) -> String>) {
+        self.query_translator = translator;
+    }
+
+    pub fn has_search_option(&self, search_option: SearchOptions) -> bool {
+        self.search_options.contains(search_option)
+    }
+
     pub fn set_search_options(
         &mut self,
         search_options: SearchOptions,
@@ -928,7 +944,12 @@ impl BufferSearchBar {
                 let _ = done_tx.send(());
                 cx.notify();
             } else {
+                let query_text = query.clone();
                 let query: Arc<_> = if self.search_options.contains(SearchOptions::REGEX) {
+                    let query = match self.query_translator {
+                        Some(translate) => translate(&query, cx),
+                        None => query,
+                    };
                     match SearchQuery::regex(
                         query,
                         self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -967,7 +988,6 @@ impl BufferSearchBar {
                 }
                 .into();
                 self.active_search = Some(query.clone());
-                let query_text = query.as_str().to_string();
 
                 let matches = active_searchable_item.find_matches(query, cx);
 
@@ -1,7 +1,7 @@
 use fs::Fs;
 use gpui::{AppContext, RenderOnce, SharedString};
 
-use crate::{update_settings_file, Settings};
+use crate::{update_settings_file, Settings, SettingsLayer, SettingsLocation, SettingsStore};
 
 /// A UI control that can be used to edit a setting.
 pub trait EditableSettingControl: RenderOnce {
@@ -14,6 +14,23 @@ pub trait EditableSettingControl: RenderOnce {
     /// Returns the name of this setting.
     fn name(&self) -> SharedString;
 
+    /// Returns the path of keys under which this setting is stored in the settings file.
+    fn key_path(&self) -> &'static [&'static str];
+
+    /// Returns the settings layer that the current value of this setting comes from,
+    /// taking the project settings at the given location into account.
+    fn layer(&self, location: Option<SettingsLocation>, cx: &AppContext) -> SettingsLayer {
+        cx.global::<SettingsStore>()
+            .layer_for_key_path(self.key_path(), location)
+    }
+
+    /// Returns whether this setting should be shown for the given search query.
+    fn matches_query(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.name().to_lowercase().contains(&query)
+            || self.key_path().iter().any(|key| key.contains(&query))
+    }
+
     /// Reads the setting value from the settings.
     fn read(cx: &AppContext) -> Self::Value;
 
@@ -3,6 +3,7 @@ mod json_schema;
 mod keymap_file;
 mod settings_file;
 mod settings_store;
+mod vscode_import;
 
 use gpui::AppContext;
 use rust_embed::RustEmbed;
@@ -13,7 +14,11 @@ pub use editable_setting_control::*;
 pub use json_schema::*;
 pub use keymap_file::KeymapFile;
 pub use settings_file::*;
-pub use settings_store::{Settings, SettingsLocation, SettingsSources, SettingsStore};
+pub use settings_store::{
+    parse_json_with_comments, Settings, SettingsLayer, SettingsLocation, SettingsSources,
+    SettingsStore,
+};
+pub use vscode_import::VsCodeImport;
 
 #[derive(RustEmbed)]
 #[folder = "../../assets"]
@@ -157,6 +157,27 @@ pub struct SettingsLocation<'a> {
     pub path: &'a Path,
 }
 
+/// The layer of settings files from which a setting's value was read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SettingsLayer {
+    /// The value comes from Zed's default settings.
+    Default,
+    /// The value comes from the user's settings file.
+    User,
+    /// The value comes from a project's local settings file.
+    Project,
+}
+
+impl SettingsLayer {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SettingsLayer::Default => "Default",
+            SettingsLayer::User => "User",
+            SettingsLayer::Project => "Project",
+        }
+    }
+}
+
 /// A set of strongly-typed setting values defined via multiple JSON files.
 pub struct SettingsStore {
     setting_values: HashMap<TypeId, Box<dyn AnySettingValue>>,
@@ -315,6 +336,50 @@ impl SettingsStore {
         &self.raw_user_settings
     }
 
+    /// Returns the settings layer that provides the value stored under the given
+    /// key path, taking local settings at the given location into account.
+    pub fn layer_for_key_path(
+        &self,
+        key_path: &[&str],
+        location: Option<SettingsLocation>,
+    ) -> SettingsLayer {
+        fn contains_key_path(value: &serde_json::Value, key_path: &[&str]) -> bool {
+            let mut value = value;
+            for key in key_path {
+                match value.get(key) {
+                    Some(child) => value = child,
+                    None => return false,
+                }
+            }
+            true
+        }
+
+        if let Some(SettingsLocation { worktree_id, path }) = location {
+            let is_set_locally = self.raw_local_settings.iter().any(
+                |((root_id, directory_path), local_settings)| {
+                    *root_id == worktree_id
+                        && path.starts_with(directory_path)
+                        && contains_key_path(local_settings, key_path)
+                },
+            );
+            if is_set_locally {
+                return SettingsLayer::Project;
+            }
+        }
+
+        let is_set_for_release_channel = self
+            .raw_user_settings
+            .get(release_channel::RELEASE_CHANNEL.dev_name())
+            .map_or(false, |release_settings| {
+                contains_key_path(release_settings, key_path)
+            });
+        if is_set_for_release_channel || contains_key_path(&self.raw_user_settings, key_path) {
+            return SettingsLayer::User;
+        }
+
+        SettingsLayer::Default
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn test(cx: &mut AppContext) -> Self {
         let mut this = Self::new(cx);
@@ -407,6 +472,83 @@ impl SettingsStore {
         new_text
     }
 
+    /// Merges the given raw JSON values into a settings file, returning the new
+    /// text for that file. Comments and formatting of the untouched parts of the
+    /// file are preserved.
+    pub fn new_text_for_json_update(&self, old_text: String, update: &serde_json::Value) -> String {
+        let old_value = parse_json_with_comments::<serde_json::Value>(&old_text)
+            .ok()
+            .filter(|value| value.is_object())
+            .unwrap_or_else(|| serde_json::json!({}));
+        let mut new_value = old_value.clone();
+        merge_non_null_json_value_into(update.clone(), &mut new_value);
+
+        let mut text = if old_text.trim().is_empty() {
+            "{}\n".to_string()
+        } else {
+            old_text
+        };
+        let mut key_path = Vec::new();
+        let mut edits = Vec::new();
+        update_value_in_json_text(
+            &mut text,
+            &mut key_path,
+            self.json_tab_size(),
+            &old_value,
+            &new_value,
+            &[],
+            &mut edits,
+        );
+        text
+    }
+
+    /// Appends values to the top-level array of a JSON file such as the keymap, keeping its
+    /// comments and formatting. Values that the array already contains are skipped, so
+    /// appending the same values again leaves the text unchanged.
+    pub fn new_text_for_json_array_append(
+        &self,
+        old_text: String,
+        values: &[serde_json::Value],
+    ) -> Result<String> {
+        let existing_values = if old_text.trim().is_empty() {
+            Vec::new()
+        } else {
+            parse_json_with_comments::<Vec<serde_json::Value>>(&old_text)?
+        };
+        let new_values = values
+            .iter()
+            .filter(|value| !existing_values.contains(value))
+            .collect::<Vec<_>>();
+        if new_values.is_empty() {
+            return Ok(old_text);
+        }
+
+        let tab_size = self.json_tab_size();
+        let Some((last_token, close_bracket)) = top_level_array_bounds(&old_text) else {
+            return Ok(to_pretty_json(&new_values, tab_size, 0) + "\n");
+        };
+        let needs_comma = !matches!(old_text.as_bytes()[last_token], b'[' | b',');
+        let insertion_point = old_text[..close_bracket].trim_end().len();
+
+        let mut new_text = String::new();
+        for (ix, value) in new_values.iter().enumerate() {
+            if ix > 0 {
+                new_text.push(',');
+            }
+            new_text.push('\n');
+            new_text.push_str(&" ".repeat(tab_size));
+            new_text.push_str(&to_pretty_json(value, tab_size, tab_size));
+        }
+        new_text.push('\n');
+
+        let mut text = old_text;
+        text.replace_range(insertion_point..close_bracket, &new_text);
+        if needs_comma {
+            text.insert(last_token + 1, ',');
+        }
+        Ok(text)
+    }
+
     /// Updates the value of a setting in a JSON file, returning a list
     /// of edits to apply to the JSON file.
     pub fn edits_for_update<T: Settings>(
@@ -1083,6 +1225,54 @@ fn to_pretty_json(value: &impl Serialize, indent_size: usize, indent_prefix_len:
     adjusted_text
 }
 
+/// Returns the offsets of the last token inside the top-level array of a JSON text (which is
+/// its opening bracket when it's empty) and of its closing bracket, skipping strings and
+/// comments.
+fn top_level_array_bounds(text: &str) -> Option<(usize, usize)> {
+    let bytes = text.as_bytes();
+    let mut depth = 0;
+    let mut last_token = None;
+    let mut ix = 0;
+    while ix < bytes.len() {
+        match bytes[ix] {
+            b'"' => {
+                ix += 1;
+                while ix < bytes.len() && bytes[ix] != b'"' {
+                    ix += if bytes[ix] == b'\\' { 2 } else { 1 };
+                }
+                last_token = Some(ix);
+            }
+            b'/' if bytes.get(ix + 1) == Some(&b'/') => {
+                while ix < bytes.len() && bytes[ix] != b'\n' {
+                    ix += 1;
+                }
+            }
+            b'/' if bytes.get(ix + 1) == Some(&b'*') => {
+                ix += 2;
+                while ix < bytes.len() && !bytes[ix..].starts_with(b"*/") {
+                    ix += 1;
+                }
+                ix += 1;
+            }
+            b'[' | b'{' => {
+                depth += 1;
+                last_token = Some(ix);
+            }
+            b']' | b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return (bytes[ix] == b']').then_some((last_token?, ix));
+                }
+                last_token = Some(ix);
+            }
+            byte if !byte.is_ascii_whitespace() => last_token = Some(ix),
+            _ => {}
+        }
+        ix += 1;
+    }
+    None
+}
+
 pub fn parse_json_with_comments<T: DeserializeOwned>(content: &str) -> Result<T> {
     Ok(serde_json_lenient::from_str(content)?)
 }
@@ -1222,6 +1412,56 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_settings_layer_for_key_path(cx: &mut AppContext) {
+        let mut store = SettingsStore::new(cx);
+        store.register_setting::<UserSettings>(cx);
+        store
+            .set_default_settings(
+                r#"{
+                    "user": {
+                        "name": "John Doe",
+                        "age": 30,
+                        "staff": false
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        store
+            .set_user_settings(r#"{ "user": { "age": 31 } }"#, cx)
+            .unwrap();
+        store
+            .set_local_settings(
+                1,
+                Path::new("/root1").into(),
+                Some(r#"{ "user": { "staff": true } }"#),
+                cx,
+            )
+            .unwrap();
+
+        let location = Some(SettingsLocation {
+            worktree_id: 1,
+            path: Path::new("/root1/something"),
+        });
+        assert_eq!(
+            store.layer_for_key_path(&["user", "name"], location),
+            SettingsLayer::Default
+        );
+        assert_eq!(
+            store.layer_for_key_path(&["user", "age"], location),
+            SettingsLayer::User
+        );
+        assert_eq!(
+            store.layer_for_key_path(&["user", "staff"], location),
+            SettingsLayer::Project
+        );
+        assert_eq!(
+            store.layer_for_key_path(&["user", "staff"], None),
+            SettingsLayer::Default
+        );
+    }
+
     #[gpui::test]
     fn test_setting_store_assign_json_before_register(cx: &mut AppContext) {
         let mut store = SettingsStore::new(cx);
@@ -1385,6 +1625,90 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_setting_store_json_update(cx: &mut AppContext) {
+        let store = SettingsStore::new(cx);
+        let old_json = r#"{
+            // Use a larger font
+            "buffer_font_size": 15
+        }"#
+        .unindent();
+        let new_json = store
+            .new_text_for_json_update(old_json, &serde_json::json!({ "buffer_font_size": 18 }));
+        pretty_assertions::assert_eq!(
+            new_json,
+            r#"{
+                // Use a larger font
+                "buffer_font_size": 18
+            }"#
+            .unindent()
+        );
+    }
+
+    #[gpui::test]
+    fn test_setting_store_json_array_append(cx: &mut AppContext) {
+        let store = SettingsStore::new(cx);
+        let block = serde_json::json!({ "bindings": { "ctrl-x": "editor::Cut" } });
+        let append = |old_json: String| {
+            store
+                .new_text_for_json_array_append(old_json, &[block.clone()])
+                .unwrap()
+        };
+
+        pretty_assertions::assert_eq!(
+            append(String::new()),
+            r#"[
+              {
+                "bindings": {
+                  "ctrl-x": "editor::Cut"
+                }
+              }
+            ]
+            "#
+            .unindent()
+        );
+
+        // Comments, trailing commas and brackets in strings and comments are kept as they are.
+        let new_json = append(
+            r#"// Key bindings
+            [
+              // Cut with ctrl-]
+              { "bindings": { "ctrl-]": "editor::Cut" } }, /* more ] */
+            ]
+            "#
+            .unindent(),
+        );
+        pretty_assertions::assert_eq!(
+            new_json,
+            r#"// Key bindings
+            [
+              // Cut with ctrl-]
+              { "bindings": { "ctrl-]": "editor::Cut" } }, /* more ] */
+              {
+                "bindings": {
+                  "ctrl-x": "editor::Cut"
+                }
+              }
+            ]
+            "#
+            .unindent()
+        );
+        // Appending the same values again doesn't duplicate them.
+        pretty_assertions::assert_eq!(append(new_json.clone()), new_json);
+
+        pretty_assertions::assert_eq!(
+            append(r#"[{ "bindings": {} }] // done"#.to_string()),
+            r#"[{ "bindings": {} },
+              {
+                "bindings": {
+                  "ctrl-x": "editor::Cut"
+                }
+              }
+            ] // done"#
+                .unindent()
+        );
+    }
+
     fn check_settings_update<T: Settings>(
         store: &mut SettingsStore,
         old_json: String,
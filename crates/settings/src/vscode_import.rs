@@ -0,0 +1,536 @@
+//! Translates Visual Studio Code's user configuration into Zed's.
+
+use crate::settings_store::parse_json_with_comments;
+use anyhow::Result;
+use collections::BTreeMap;
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+/// The result of translating VS Code's `settings.json` and `keybindings.json`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct VsCodeImport {
+    /// The translated settings, in the format of Zed's `settings.json`.
+    pub settings: Value,
+    /// The translated key bindings, in the format of Zed's `keymap.json`.
+    pub keymap: Vec<Value>,
+    /// The VS Code settings and commands that have no Zed equivalent.
+    pub unmapped: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VsCodeKeybinding {
+    key: String,
+    command: String,
+    #[serde(default)]
+    when: Option<String>,
+}
+
+/// Maps VS Code language identifiers to Zed language names.
+const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("c", "C"),
+    ("cpp", "C++"),
+    ("css", "CSS"),
+    ("elixir", "Elixir"),
+    ("go", "Go"),
+    ("html", "HTML"),
+    ("java", "Java"),
+    ("javascript", "JavaScript"),
+    ("javascriptreact", "TSX"),
+    ("json", "JSON"),
+    ("jsonc", "JSONC"),
+    ("markdown", "Markdown"),
+    ("python", "Python"),
+    ("ruby", "Ruby"),
+    ("rust", "Rust"),
+    ("shellscript", "Shell Script"),
+    ("toml", "TOML"),
+    ("typescript", "TypeScript"),
+    ("typescriptreact", "TSX"),
+    ("yaml", "YAML"),
+];
+
+/// Maps VS Code commands to Zed actions.
+const COMMANDS: &[(&str, &str)] = &[
+    ("actions.find", "buffer_search::Deploy"),
+    (
+        "editor.action.addSelectionToNextFindMatch",
+        "editor::SelectNext",
+    ),
+    ("editor.action.commentLine", "editor::ToggleComments"),
+    (
+        "editor.action.copyLinesDownAction",
+        "editor::DuplicateLineDown",
+    ),
+    ("editor.action.formatDocument", "editor::Format"),
+    ("editor.action.goToReferences", "editor::FindAllReferences"),
+    ("editor.action.marker.next", "editor::GoToDiagnostic"),
+    ("editor.action.moveLinesDownAction", "editor::MoveLineDown"),
+    ("editor.action.moveLinesUpAction", "editor::MoveLineUp"),
+    ("editor.action.quickFix", "editor::ToggleCodeActions"),
+    ("editor.action.rename", "editor::Rename"),
+    ("editor.action.revealDefinition", "editor::GoToDefinition"),
+    ("editor.action.selectAll", "editor::SelectAll"),
+    ("editor.action.showHover", "editor::Hover"),
+    ("redo", "editor::Redo"),
+    ("undo", "editor::Undo"),
+    (
+        "workbench.action.closeActiveEditor",
+        "pane::CloseActiveItem",
+    ),
+    (
+        "workbench.action.files.newUntitledFile",
+        "workspace::NewFile",
+    ),
+    ("workbench.action.files.openFolder", "workspace::Open"),
+    ("workbench.action.files.save", "workspace::Save"),
+    ("workbench.action.findInFiles", "workspace::NewSearch"),
+    ("workbench.action.gotoLine", "go_to_line::Toggle"),
+    ("workbench.action.gotoSymbol", "outline::Toggle"),
+    ("workbench.action.quickOpen", "file_finder::Toggle"),
+    ("workbench.action.showAllSymbols", "project_symbols::Toggle"),
+    ("workbench.action.showCommands", "command_palette::Toggle"),
+    (
+        "workbench.action.terminal.toggleTerminal",
+        "terminal_panel::ToggleFocus",
+    ),
+    (
+        "workbench.action.togglePanel",
+        "workspace::ToggleBottomDock",
+    ),
+    (
+        "workbench.action.toggleSidebarVisibility",
+        "workspace::ToggleLeftDock",
+    ),
+];
+
+/// Maps the VS Code context keys used in `when` clauses to Zed's key contexts.
+const WHEN_CONTEXTS: &[(&str, &str)] = &[
+    ("editorFocus", "Editor"),
+    ("editorTextFocus", "Editor"),
+    ("explorerViewletFocus", "ProjectPanel"),
+    ("filesExplorerFocus", "ProjectPanel"),
+    ("suggestWidgetVisible", "showing_completions"),
+    ("terminalFocus", "Terminal"),
+];
+
+impl VsCodeImport {
+    /// Translates the contents of VS Code's `settings.json` and `keybindings.json` files.
+    pub fn new(settings_content: &str, keybindings_content: &str) -> Result<Self> {
+        let mut this = Self {
+            settings: json!({}),
+            ..Default::default()
+        };
+
+        if !settings_content.trim().is_empty() {
+            let settings = parse_json_with_comments::<Map<String, Value>>(settings_content)?;
+            this.import_settings(&settings);
+        }
+
+        if !keybindings_content.trim().is_empty() {
+            let keybindings =
+                parse_json_with_comments::<Vec<VsCodeKeybinding>>(keybindings_content)?;
+            this.import_keybindings(keybindings);
+        }
+
+        Ok(this)
+    }
+
+    fn import_settings(&mut self, settings: &Map<String, Value>) {
+        for (key, value) in settings {
+            if let Some(language_id) = key.strip_prefix('[').and_then(|key| key.strip_suffix(']')) {
+                self.import_language_settings(key, language_id, value);
+                continue;
+            }
+
+            match translate_setting(key, value, settings) {
+                Some(entries) => {
+                    for (path, value) in entries {
+                        set_value_at_path(&mut self.settings, path, value);
+                    }
+                }
+                None => self.unmapped.push(key.clone()),
+            }
+        }
+    }
+
+    fn import_language_settings(&mut self, key: &str, language_id: &str, value: &Value) {
+        let (Some(language_name), Some(settings)) = (
+            LANGUAGE_NAMES
+                .iter()
+                .find(|(id, _)| *id == language_id)
+                .map(|(_, name)| *name),
+            value.as_object(),
+        ) else {
+            self.unmapped.push(key.to_string());
+            return;
+        };
+
+        for (setting_key, setting_value) in settings {
+            let entries = translate_setting(setting_key, setting_value, settings)
+                .filter(|entries| entries.iter().all(|(path, _)| is_language_setting(path)));
+            match entries {
+                Some(entries) => {
+                    for (path, value) in entries {
+                        let mut language_path = vec!["languages", language_name];
+                        language_path.extend_from_slice(path);
+                        set_value_at_path(&mut self.settings, &language_path, value);
+                    }
+                }
+                None => self.unmapped.push(format!("{key}.{setting_key}")),
+            }
+        }
+    }
+
+    fn import_keybindings(&mut self, keybindings: Vec<VsCodeKeybinding>) {
+        let mut blocks = BTreeMap::<Option<String>, Map<String, Value>>::default();
+        for keybinding in keybindings {
+            let action = COMMANDS
+                .iter()
+                .find(|(command, _)| *command == keybinding.command)
+                .map(|(_, action)| *action);
+            let Some(action) = action else {
+                self.unmapped.push(keybinding.command);
+                continue;
+            };
+
+            let context = match keybinding.when.as_deref() {
+                Some(when) => {
+                    let Some(context) = translate_when_clause(when) else {
+                        self.unmapped
+                            .push(format!("{} (when {when})", keybinding.command));
+                        continue;
+                    };
+                    Some(context)
+                }
+                None => None,
+            };
+            blocks
+                .entry(context)
+                .or_default()
+                .insert(translate_keystrokes(&keybinding.key), action.into());
+        }
+
+        self.keymap = blocks
+            .into_iter()
+            .map(|(context, bindings)| match context {
+                Some(context) => json!({ "context": context, "bindings": bindings }),
+                None => json!({ "bindings": bindings }),
+            })
+            .collect();
+    }
+}
+
+fn translate_setting(
+    key: &str,
+    value: &Value,
+    settings: &Map<String, Value>,
+) -> Option<Vec<(&'static [&'static str], Value)>> {
+    let entries = match key {
+        "editor.tabSize" => vec![(&["tab_size"][..], value.clone())],
+        "editor.insertSpaces" => vec![(&["hard_tabs"][..], json!(!value.as_bool()?))],
+        "editor.fontFamily" => {
+            let family = value.as_str()?.split(',').next()?.trim().trim_matches('\'');
+            vec![(&["buffer_font_family"][..], json!(family))]
+        }
+        "editor.fontSize" => vec![(&["buffer_font_size"][..], value.clone())],
+        "editor.fontLigatures" => vec![(
+            &["buffer_font_features"][..],
+            json!({ "calt": value.as_bool()? }),
+        )],
+        "editor.wordWrap" => {
+            let soft_wrap = match value.as_str()? {
+                "off" => "none",
+                "on" => "editor_width",
+                "wordWrapColumn" => "preferred_line_length",
+                "bounded" => "bounded",
+                _ => return None,
+            };
+            vec![(&["soft_wrap"][..], json!(soft_wrap))]
+        }
+        "editor.wordWrapColumn" => vec![(&["preferred_line_length"][..], value.clone())],
+        "editor.rulers" => vec![(&["wrap_guides"][..], value.clone())],
+        "editor.formatOnSave" => {
+            let format_on_save = if value.as_bool()? { "on" } else { "off" };
+            vec![(&["format_on_save"][..], json!(format_on_save))]
+        }
+        "editor.renderWhitespace" => {
+            let show_whitespaces = match value.as_str()? {
+                "none" => "none",
+                "all" => "all",
+                "boundary" => "boundary",
+                "selection" => "selection",
+                _ => return None,
+            };
+            vec![(&["show_whitespaces"][..], json!(show_whitespaces))]
+        }
+        "editor.lineNumbers" => match value.as_str()? {
+            "on" => vec![
+                (&["gutter", "line_numbers"][..], json!(true)),
+                (&["relative_line_numbers"][..], json!(false)),
+            ],
+            "off" => vec![(&["gutter", "line_numbers"][..], json!(false))],
+            "relative" => vec![
+                (&["gutter", "line_numbers"][..], json!(true)),
+                (&["relative_line_numbers"][..], json!(true)),
+            ],
+            _ => return None,
+        },
+        "editor.cursorBlinking" => {
+            vec![(&["cursor_blink"][..], json!(value.as_str()? != "solid"))]
+        }
+        "editor.autoClosingBrackets" => {
+            vec![(&["use_autoclose"][..], json!(value.as_str()? != "never"))]
+        }
+        "editor.linkedEditing" => vec![(&["linked_edits"][..], value.clone())],
+        "editor.quickSuggestions" => {
+            let enabled = value.as_bool().unwrap_or_else(|| value.is_object());
+            vec![(&["show_completions_on_input"][..], json!(enabled))]
+        }
+        "editor.hover.enabled" => vec![(&["hover_popover_enabled"][..], value.clone())],
+        "editor.inlayHints.enabled" => {
+            let enabled = match value {
+                Value::Bool(enabled) => *enabled,
+                Value::String(mode) => mode != "off",
+                _ => return None,
+            };
+            vec![(&["inlay_hints", "enabled"][..], json!(enabled))]
+        }
+        "files.trimTrailingWhitespace" => {
+            vec![(&["remove_trailing_whitespace_on_save"][..], value.clone())]
+        }
+        "files.insertFinalNewline" => {
+            vec![(&["ensure_final_newline_on_save"][..], value.clone())]
+        }
+        "files.autoSave" => {
+            let autosave = match value.as_str()? {
+                "off" => json!("off"),
+                "onFocusChange" => json!("on_focus_change"),
+                "onWindowChange" => json!("on_window_change"),
+                "afterDelay" => {
+                    let milliseconds = settings
+                        .get("files.autoSaveDelay")
+                        .and_then(Value::as_u64)
+                        .unwrap_or(1000);
+                    json!({ "after_delay": { "milliseconds": milliseconds } })
+                }
+                _ => return None,
+            };
+            vec![(&["autosave"][..], autosave)]
+        }
+        // Handled as part of `files.autoSave`.
+        "files.autoSaveDelay" => Vec::new(),
+        "window.confirmBeforeClose" => {
+            vec![(&["confirm_quit"][..], json!(value.as_str()? != "never"))]
+        }
+        "terminal.integrated.fontFamily" => {
+            vec![(&["terminal", "font_family"][..], value.clone())]
+        }
+        "terminal.integrated.fontSize" => vec![(&["terminal", "font_size"][..], value.clone())],
+        "vim.useSystemClipboard" => {
+            let clipboard = if value.as_bool()? { "always" } else { "never" };
+            vec![(&["vim", "use_system_clipboard"][..], json!(clipboard))]
+        }
+        _ => return None,
+    };
+    Some(entries)
+}
+
+/// Returns whether the given settings path can be overridden per language.
+fn is_language_setting(path: &[&str]) -> bool {
+    matches!(
+        path.first().copied(),
+        Some(
+            "tab_size"
+                | "hard_tabs"
+                | "soft_wrap"
+                | "preferred_line_length"
+                | "wrap_guides"
+                | "format_on_save"
+                | "show_whitespaces"
+                | "use_autoclose"
+                | "linked_edits"
+                | "show_completions_on_input"
+                | "inlay_hints"
+                | "remove_trailing_whitespace_on_save"
+                | "ensure_final_newline_on_save"
+        )
+    )
+}
+
+/// Translates a `when` clause into a Zed context predicate, keeping its `!`, `&&` and `||`
+/// operators. Returns `None` if it uses a context key or an operator Zed has no equivalent for.
+fn translate_when_clause(when: &str) -> Option<String> {
+    let alternatives = when
+        .split("||")
+        .map(|alternative| {
+            let terms = alternative
+                .split("&&")
+                .map(|term| {
+                    let term = term.trim();
+                    let (negation, key) = match term.strip_prefix('!') {
+                        Some(key) => ("!", key.trim_start()),
+                        None => ("", term),
+                    };
+                    let (_, context) = WHEN_CONTEXTS.iter().find(|(name, _)| *name == key)?;
+                    Some(format!("{negation}{context}"))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(terms.join(" && "))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(alternatives.join(" || "))
+}
+
+fn translate_keystrokes(keystrokes: &str) -> String {
+    keystrokes
+        .split_whitespace()
+        .map(|keystroke| {
+            keystroke
+                .split('+')
+                .map(|key| match key.to_lowercase().as_str() {
+                    "meta" | "win" => "super".to_string(),
+                    "esc" => "escape".to_string(),
+                    key => key.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn set_value_at_path(settings: &mut Value, path: &[&str], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+
+    let mut current = settings;
+    for key in parents {
+        let Some(object) = current.as_object_mut() else {
+            return;
+        };
+        current = object.entry(key.to_string()).or_insert_with(|| json!({}));
+    }
+    if let Some(object) = current.as_object_mut() {
+        object.insert(last.to_string(), value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_import_settings() {
+        let import = VsCodeImport::new(
+            r#"{
+                // Comments are allowed in VS Code's settings.
+                "editor.tabSize": 2,
+                "editor.insertSpaces": true,
+                "editor.fontFamily": "'Fira Code', monospace",
+                "editor.wordWrap": "on",
+                "editor.lineNumbers": "relative",
+                "files.autoSave": "afterDelay",
+                "files.autoSaveDelay": 500,
+                "workbench.colorTheme": "Monokai",
+                "[rust]": {
+                    "editor.tabSize": 4,
+                    "editor.fontSize": 16
+                },
+                "[brainfuck]": {
+                    "editor.tabSize": 8
+                }
+            }"#,
+            "",
+        )
+        .unwrap();
+
+        assert_eq!(
+            import.settings,
+            json!({
+                "tab_size": 2,
+                "hard_tabs": false,
+                "buffer_font_family": "Fira Code",
+                "soft_wrap": "editor_width",
+                "gutter": { "line_numbers": true },
+                "relative_line_numbers": true,
+                "autosave": { "after_delay": { "milliseconds": 500 } },
+                "languages": {
+                    "Rust": { "tab_size": 4 }
+                }
+            })
+        );
+        assert_eq!(
+            import.unmapped,
+            vec![
+                "workbench.colorTheme",
+                "[rust].editor.fontSize",
+                "[brainfuck]"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_keybindings() {
+        let import = VsCodeImport::new(
+            "",
+            r#"[
+                { "key": "ctrl+shift+p", "command": "workbench.action.showCommands" },
+                { "key": "ctrl+k ctrl+c", "command": "editor.action.commentLine", "when": "editorTextFocus" },
+                { "key": "ctrl+`", "command": "workbench.action.terminal.toggleTerminal" },
+                { "key": "ctrl+alt+x", "command": "myExtension.doSomething" },
+                { "key": "ctrl+s", "command": "workbench.action.files.save", "when": "!terminalFocus" },
+                { "key": "ctrl+f", "command": "actions.find", "when": "editorFocus || terminalFocus" },
+                { "key": "ctrl+.", "command": "editor.action.quickFix", "when": "editorTextFocus && !suggestWidgetVisible" },
+                { "key": "f2", "command": "editor.action.rename", "when": "editorHasRenameProvider && editorTextFocus" },
+                { "key": "ctrl+r", "command": "redo", "when": "resourceExtname == .md" }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            import.keymap,
+            vec![
+                json!({
+                    "bindings": {
+                        "ctrl-shift-p": "command_palette::Toggle",
+                        "ctrl-`": "terminal_panel::ToggleFocus"
+                    }
+                }),
+                json!({
+                    "context": "!Terminal",
+                    "bindings": {
+                        "ctrl-s": "workspace::Save"
+                    }
+                }),
+                json!({
+                    "context": "Editor",
+                    "bindings": {
+                        "ctrl-k ctrl-c": "editor::ToggleComments"
+                    }
+                }),
+                json!({
+                    "context": "Editor && !showing_completions",
+                    "bindings": {
+                        "ctrl-.": "editor::ToggleCodeActions"
+                    }
+                }),
+                json!({
+                    "context": "Editor || Terminal",
+                    "bindings": {
+                        "ctrl-f": "buffer_search::Deploy"
+                    }
+                }),
+            ]
+        );
+        assert_eq!(
+            import.unmapped,
+            vec![
+                "myExtension.doSomething",
+                "editor.action.rename (when editorHasRenameProvider && editorTextFocus)",
+                "redo (when resourceExtname == .md)",
+            ]
+        );
+    }
+}
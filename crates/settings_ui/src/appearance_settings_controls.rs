@@ -1,7 +1,8 @@
+use std::path::Path;
 use std::sync::Arc;
 
 use gpui::{AppContext, FontFeatures, FontWeight};
-use settings::{EditableSettingControl, Settings};
+use settings::{EditableSettingControl, Settings, SettingsLayer, SettingsLocation};
 use theme::{FontFamilyCache, SystemAppearance, ThemeMode, ThemeRegistry, ThemeSettings};
 use ui::{
     prelude::*, CheckboxWithLabel, ContextMenu, DropdownMenu, NumericStepper, SettingsContainer,
@@ -9,38 +10,94 @@ use ui::{
 };
 
 #[derive(IntoElement)]
-pub struct AppearanceSettingsControls {}
+pub struct AppearanceSettingsControls {
+    query: SharedString,
+    worktree_id: Option<usize>,
+}
 
 impl AppearanceSettingsControls {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            query: SharedString::default(),
+            worktree_id: None,
+        }
+    }
+
+    /// Only shows the groups of settings that match the given search query.
+    pub fn query(mut self, query: impl Into<SharedString>) -> Self {
+        self.query = query.into();
+        self
+    }
+
+    /// Takes the settings of the given worktree into account when showing
+    /// which layer each group of settings comes from.
+    pub fn worktree_id(mut self, worktree_id: Option<usize>) -> Self {
+        self.worktree_id = worktree_id;
+        self
     }
 }
 
 impl RenderOnce for AppearanceSettingsControls {
-    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        let query = self.query.as_ref();
+        let location = self.worktree_id.map(|worktree_id| SettingsLocation {
+            worktree_id,
+            path: Path::new(""),
+        });
+        let show_theme = "theme".contains(&query.to_lowercase())
+            || ThemeControl.matches_query(query)
+            || ThemeModeControl.matches_query(query);
+        let show_font = "font".contains(&query.to_lowercase())
+            || UiFontFamilyControl.matches_query(query)
+            || UiFontWeightControl.matches_query(query)
+            || UiFontSizeControl.matches_query(query)
+            || UiFontLigaturesControl.matches_query(query);
+
         SettingsContainer::new()
-            .child(
-                SettingsGroup::new("Theme").child(
-                    h_flex()
-                        .gap_2()
-                        .justify_between()
-                        .child(ThemeControl)
-                        .child(ThemeModeControl),
-                ),
-            )
-            .child(
-                SettingsGroup::new("Font")
-                    .child(
-                        h_flex()
-                            .gap_2()
-                            .justify_between()
-                            .child(UiFontFamilyControl)
-                            .child(UiFontWeightControl),
-                    )
-                    .child(UiFontSizeControl)
-                    .child(UiFontLigaturesControl),
-            )
+            .when(show_theme, |this| {
+                this.child(
+                    SettingsGroup::new("Theme")
+                        .layer(
+                            ThemeControl
+                                .layer(location, cx)
+                                .max(ThemeModeControl.layer(location, cx))
+                                .label(),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .justify_between()
+                                .child(ThemeControl)
+                                .child(ThemeModeControl),
+                        ),
+                )
+            })
+            .when(show_font, |this| {
+                this.child(
+                    SettingsGroup::new("Font")
+                        .layer(
+                            [
+                                UiFontFamilyControl.layer(location, cx),
+                                UiFontWeightControl.layer(location, cx),
+                                UiFontSizeControl.layer(location, cx),
+                                UiFontLigaturesControl.layer(location, cx),
+                            ]
+                            .into_iter()
+                            .max()
+                            .unwrap_or(SettingsLayer::Default)
+                            .label(),
+                        )
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .justify_between()
+                                .child(UiFontFamilyControl)
+                                .child(UiFontWeightControl),
+                        )
+                        .child(UiFontSizeControl)
+                        .child(UiFontLigaturesControl),
+                )
+            })
     }
 }
 
@@ -55,6 +112,10 @@ impl EditableSettingControl for ThemeControl {
         "Theme".into()
     }
 
+    fn key_path(&self) -> &'static [&'static str] {
+        &["theme"]
+    }
+
     fn read(cx: &AppContext) -> Self::Value {
         let settings = ThemeSettings::get_global(cx);
         let appearance = SystemAppearance::global(cx);
@@ -118,6 +179,10 @@ impl EditableSettingControl for ThemeModeControl {
         "Theme Mode".into()
     }
 
+    fn key_path(&self) -> &'static [&'static str] {
+        &["theme", "mode"]
+    }
+
     fn read(cx: &AppContext) -> Self::Value {
         let settings = ThemeSettings::get_global(cx);
         settings
@@ -179,6 +244,10 @@ impl EditableSettingControl for UiFontFamilyControl {
         "UI Font Family".into()
     }
 
+    fn key_path(&self) -> &'static [&'static str] {
+        &["ui_font_family"]
+    }
+
     fn read(cx: &AppContext) -> Self::Value {
         let settings = ThemeSettings::get_global(cx);
         settings.ui_font.family.clone()
@@ -238,6 +307,10 @@ impl EditableSettingControl for UiFontSizeControl {
         "UI Font Size".into()
     }
 
+    fn key_path(&self) -> &'static [&'static str] {
+        &["ui_font_size"]
+    }
+
     fn read(cx: &AppContext) -> Self::Value {
         let settings = ThemeSettings::get_global(cx);
         settings.ui_font_size
@@ -283,6 +356,10 @@ impl EditableSettingControl for UiFontWeightControl {
         "UI Font Weight".into()
     }
 
+    fn key_path(&self) -> &'static [&'static str] {
+        &["ui_font_weight"]
+    }
+
     fn read(cx: &AppContext) -> Self::Value {
         let settings = ThemeSettings::get_global(cx);
         settings.ui_font.weight
@@ -336,6 +413,10 @@ impl EditableSettingControl for UiFontLigaturesControl {
         "UI Font Ligatures".into()
     }
 
+    fn key_path(&self) -> &'static [&'static str] {
+        &["ui_font_features"]
+    }
+
     fn read(cx: &AppContext) -> Self::Value {
         let settings = ThemeSettings::get_global(cx);
         settings.ui_font.features.is_calt_enabled().unwrap_or(true)
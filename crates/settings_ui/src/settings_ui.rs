@@ -3,9 +3,10 @@ mod appearance_settings_controls;
 use std::any::TypeId;
 
 use command_palette_hooks::CommandPaletteFilter;
-use editor::EditorSettingsControls;
+use editor::{Editor, EditorEvent, EditorSettingsControls};
 use feature_flags::{FeatureFlag, FeatureFlagViewExt};
-use gpui::{actions, AppContext, EventEmitter, FocusHandle, FocusableView, View};
+use gpui::{actions, AppContext, EventEmitter, FocusHandle, FocusableView, View, WeakView};
+use settings::SettingsStore;
 use ui::prelude::*;
 use workspace::item::{Item, ItemEvent};
 use workspace::Workspace;
@@ -61,12 +62,33 @@ pub fn init(cx: &mut AppContext) {
 
 pub struct SettingsPage {
     focus_handle: FocusHandle,
+    search_editor: View<Editor>,
+    workspace: WeakView<Workspace>,
 }
 
 impl SettingsPage {
-    pub fn new(_workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
-        cx.new_view(|cx| Self {
-            focus_handle: cx.focus_handle(),
+    pub fn new(workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let workspace = workspace.weak_handle();
+        cx.new_view(|cx| {
+            let search_editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Search settings...", cx);
+                editor
+            });
+            cx.subscribe(&search_editor, |_, _, event: &EditorEvent, cx| {
+                if let EditorEvent::Edited { .. } = event {
+                    cx.notify();
+                }
+            })
+            .detach();
+            cx.observe_global::<SettingsStore>(|_, cx| cx.notify())
+                .detach();
+
+            Self {
+                focus_handle: cx.focus_handle(),
+                search_editor,
+                workspace,
+            }
         })
     }
 }
@@ -101,23 +123,44 @@ impl Item for SettingsPage {
 
 impl Render for SettingsPage {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let query = self.search_editor.read(cx).text(cx);
+        let worktree_id = self.workspace.upgrade().and_then(|workspace| {
+            let worktree = workspace.read(cx).visible_worktrees(cx).next()?;
+            Some(worktree.read(cx).id().to_usize())
+        });
+
         v_flex()
             .p_4()
             .size_full()
             .gap_4()
             .child(Label::new("Settings").size(LabelSize::Large))
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .rounded_lg()
+                    .child(Icon::new(IconName::MagnifyingGlass))
+                    .child(self.search_editor.clone()),
+            )
             .child(
                 v_flex().gap_1().child(Label::new("Appearance")).child(
-                    v_flex()
-                        .elevation_2(cx)
-                        .child(AppearanceSettingsControls::new()),
+                    v_flex().elevation_2(cx).child(
+                        AppearanceSettingsControls::new()
+                            .query(query.clone())
+                            .worktree_id(worktree_id),
+                    ),
                 ),
             )
             .child(
                 v_flex().gap_1().child(Label::new("Editor")).child(
-                    v_flex()
-                        .elevation_2(cx)
-                        .child(EditorSettingsControls::new()),
+                    v_flex().elevation_2(cx).child(
+                        EditorSettingsControls::new()
+                            .query(query)
+                            .worktree_id(worktree_id),
+                    ),
                 ),
             )
     }
@@ -1,11 +1,16 @@
 use crate::one_themes::one_dark;
-use crate::{Appearance, SyntaxTheme, Theme, ThemeRegistry, ThemeStyleContent};
+use crate::{
+    syntax_styles, Appearance, HighlightStyleContent, SyntaxTheme, Theme, ThemeRegistry,
+    ThemeStyleContent,
+};
 use anyhow::Result;
+use collections::HashMap;
 use derive_more::{Deref, DerefMut};
 use gpui::{
     px, AppContext, Font, FontFallbacks, FontFeatures, FontStyle, FontWeight, Global, Pixels,
     Subscription, ViewContext, WindowContext,
 };
+use indexmap::IndexMap;
 use refineable::Refineable;
 use schemars::{
     gen::SchemaGenerator,
@@ -90,8 +95,11 @@ pub struct ThemeSettings {
     pub theme_selection: Option<ThemeSelection>,
     pub active_theme: Arc<Theme>,
     pub theme_overrides: Option<ThemeStyleContent>,
+    pub language_syntax_overrides: HashMap<String, IndexMap<String, HighlightStyleContent>>,
     pub ui_density: UiDensity,
     pub unnecessary_code_fade: f32,
+    pub high_contrast: bool,
+    pub reduce_motion: bool,
 }
 
 impl ThemeSettings {
@@ -290,11 +298,27 @@ pub struct ThemeSettingsContent {
     #[serde(default)]
     pub unnecessary_code_fade: Option<f32>,
 
+    /// Whether to raise the contrast of muted text, icons, borders and line
+    /// numbers in the current theme.
+    #[serde(default)]
+    pub high_contrast: Option<bool>,
+
+    /// Whether to stop the cursor from blinking and skip UI animations.
+    #[serde(default)]
+    pub reduce_motion: Option<bool>,
+
     /// EXPERIMENTAL: Overrides for the current theme.
     ///
     /// These values will override the ones on the current theme specified in `theme`.
     #[serde(rename = "experimental.theme_overrides", default)]
     pub theme_overrides: Option<ThemeStyleContent>,
+
+    /// EXPERIMENTAL: Syntax style overrides for specific languages, keyed by language name.
+    ///
+    /// These styles are applied on top of the current theme and `experimental.theme_overrides`,
+    /// only for buffers in that language.
+    #[serde(rename = "experimental.language_syntax_overrides", default)]
+    pub language_syntax_overrides: Option<HashMap<String, IndexMap<String, HighlightStyleContent>>>,
 }
 
 fn default_font_features() -> Option<FontFeatures> {
@@ -404,7 +428,8 @@ impl ThemeSettings {
         new_theme
     }
 
-    /// Applies the theme overrides, if there are any, to the current theme.
+    /// Applies the theme overrides and language syntax overrides, if there are any, and the
+    /// high contrast adjustments, if enabled, to the current theme.
     pub fn apply_theme_overrides(&mut self) {
         if let Some(theme_overrides) = &self.theme_overrides {
             let mut base_theme = (*self.active_theme).clone();
@@ -430,6 +455,37 @@ impl ThemeSettings {
 
             self.active_theme = Arc::new(base_theme);
         }
+
+        if !self.language_syntax_overrides.is_empty() {
+            let mut base_theme = (*self.active_theme).clone();
+            let language_syntax_styles = self
+                .language_syntax_overrides
+                .iter()
+                .map(|(language, syntax)| (language.clone(), syntax_styles(syntax)))
+                .collect();
+            base_theme.styles.syntax = SyntaxTheme::merge_language_overrides(
+                base_theme.styles.syntax,
+                language_syntax_styles,
+            );
+
+            self.active_theme = Arc::new(base_theme);
+        }
+
+        if self.high_contrast {
+            let mut base_theme = (*self.active_theme).clone();
+            let colors = &mut base_theme.styles.colors;
+            // Only derive from colors that aren't adjusted here, so that applying
+            // the adjustments again leaves the theme unchanged.
+            colors.text_muted = colors.text;
+            colors.text_placeholder = colors.text;
+            colors.text_placeholder.fade_out(0.2);
+            colors.icon_muted = colors.icon;
+            colors.border = colors.border_focused;
+            colors.border_variant = colors.border_focused;
+            colors.editor_line_number = colors.editor_active_line_number;
+
+            self.active_theme = Arc::new(base_theme);
+        }
     }
 }
 
@@ -560,8 +616,11 @@ impl settings::Settings for ThemeSettings {
                 .or(themes.get(&one_dark().name))
                 .unwrap(),
             theme_overrides: None,
+            language_syntax_overrides: HashMap::default(),
             ui_density: defaults.ui_density.unwrap_or(UiDensity::Default),
             unnecessary_code_fade: defaults.unnecessary_code_fade.unwrap_or(0.0),
+            high_contrast: defaults.high_contrast.unwrap_or(false),
+            reduce_motion: defaults.reduce_motion.unwrap_or(false),
         };
 
         for value in sources.user.into_iter().chain(sources.release_channel) {
@@ -605,7 +664,14 @@ impl settings::Settings for ThemeSettings {
                 }
             }
 
+            merge(&mut this.high_contrast, value.high_contrast);
+            merge(&mut this.reduce_motion, value.reduce_motion);
+
             this.theme_overrides.clone_from(&value.theme_overrides);
+            if let Some(language_syntax_overrides) = &value.language_syntax_overrides {
+                this.language_syntax_overrides
+                    .clone_from(language_syntax_overrides);
+            }
             this.apply_theme_overrides();
 
             merge(&mut this.ui_font_size, value.ui_font_size.map(Into::into));
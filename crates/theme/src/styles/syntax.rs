@@ -62,18 +62,7 @@ impl SyntaxTheme {
                 .iter_mut()
                 .find(|(existing_name, _)| existing_name == &name)
             {
-                existing_highlight.color = highlight.color.or(existing_highlight.color);
-                existing_highlight.font_weight =
-                    highlight.font_weight.or(existing_highlight.font_weight);
-                existing_highlight.font_style =
-                    highlight.font_style.or(existing_highlight.font_style);
-                existing_highlight.background_color = highlight
-                    .background_color
-                    .or(existing_highlight.background_color);
-                existing_highlight.underline = highlight.underline.or(existing_highlight.underline);
-                existing_highlight.strikethrough =
-                    highlight.strikethrough.or(existing_highlight.strikethrough);
-                existing_highlight.fade_out = highlight.fade_out.or(existing_highlight.fade_out);
+                refine_highlight(existing_highlight, highlight);
             } else {
                 merged_highlights.push((name, highlight));
             }
@@ -83,6 +72,105 @@ impl SyntaxTheme {
             highlights: merged_highlights,
         })
     }
+
+    /// Returns a new [`Arc<SyntaxTheme>`] with the given per-language syntax styles merged in.
+    ///
+    /// Each style is layered on top of the one the theme uses for that key, and is stored
+    /// under a language-scoped name (see [`SyntaxTheme::language_scoped_name`]) so that only
+    /// the highlights of that language pick it up.
+    pub fn merge_language_overrides(
+        base: Arc<Self>,
+        language_syntax_styles: Vec<(String, Vec<(String, HighlightStyle)>)>,
+    ) -> Arc<Self> {
+        if language_syntax_styles.is_empty() {
+            return base;
+        }
+
+        let mut merged_highlights = base.highlights.clone();
+
+        for (language, mut syntax_styles) in language_syntax_styles {
+            // Apply less specific keys first, so that e.g. a `comment` override also
+            // carries over to the `comment.doc` override.
+            syntax_styles.sort_by_key(|(key, _)| key.split('.').count());
+
+            for (key, highlight) in syntax_styles {
+                // Overriding a key also overrides every more specific key of the theme,
+                // which would otherwise take precedence over it.
+                let more_specific_keys = base
+                    .highlights
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .filter(|name| {
+                        Self::parse_scoped_name(name).0.is_none()
+                            && *name != key
+                            && key_matches(&key, name)
+                    });
+                let targets = Some(key.as_str())
+                    .into_iter()
+                    .chain(more_specific_keys)
+                    .collect::<Vec<_>>();
+
+                for target in targets {
+                    let scoped_name = Self::language_scoped_name(&language, target);
+                    if let Some((_, existing_highlight)) = merged_highlights
+                        .iter_mut()
+                        .find(|(existing_name, _)| existing_name == &scoped_name)
+                    {
+                        refine_highlight(existing_highlight, highlight);
+                    } else {
+                        let mut style = base.best_match(target).unwrap_or_default();
+                        refine_highlight(&mut style, highlight);
+                        merged_highlights.push((scoped_name, style));
+                    }
+                }
+            }
+        }
+
+        Arc::new(Self {
+            highlights: merged_highlights,
+        })
+    }
+
+    /// Returns the name under which a style for the given key is stored when it only applies
+    /// to the given language.
+    pub fn language_scoped_name(language: &str, key: &str) -> String {
+        format!("{language}/{key}")
+    }
+
+    /// Splits a highlight name into the language it is scoped to, if any, and its key.
+    pub fn parse_scoped_name(name: &str) -> (Option<&str>, &str) {
+        match name.rsplit_once('/') {
+            Some((language, key)) => (Some(language), key),
+            None => (None, name),
+        }
+    }
+
+    /// Returns the style of the most specific theme key that matches the given name.
+    fn best_match(&self, name: &str) -> Option<HighlightStyle> {
+        self.highlights
+            .iter()
+            .filter(|(key, _)| Self::parse_scoped_name(key).0.is_none() && key_matches(key, name))
+            .max_by_key(|(key, _)| key.split('.').count())
+            .map(|(_, style)| *style)
+    }
+}
+
+/// Whether all the dot-separated components of `key` appear in `name`.
+fn key_matches(key: &str, name: &str) -> bool {
+    key.split('.')
+        .all(|key_part| name.split('.').any(|part| part == key_part))
+}
+
+fn refine_highlight(existing_highlight: &mut HighlightStyle, highlight: HighlightStyle) {
+    existing_highlight.color = highlight.color.or(existing_highlight.color);
+    existing_highlight.font_weight = highlight.font_weight.or(existing_highlight.font_weight);
+    existing_highlight.font_style = highlight.font_style.or(existing_highlight.font_style);
+    existing_highlight.background_color = highlight
+        .background_color
+        .or(existing_highlight.background_color);
+    existing_highlight.underline = highlight.underline.or(existing_highlight.underline);
+    existing_highlight.strikethrough = highlight.strikethrough.or(existing_highlight.strikethrough);
+    existing_highlight.fade_out = highlight.fade_out.or(existing_highlight.fade_out);
 }
 
 #[cfg(test)]
@@ -192,4 +280,42 @@ mod tests {
             ]))
         );
     }
+
+    #[test]
+    fn test_syntax_theme_merge_language_overrides() {
+        let italic = HighlightStyle {
+            font_style: Some(FontStyle::Italic),
+            ..Default::default()
+        };
+        let syntax_theme = SyntaxTheme::merge_language_overrides(
+            Arc::new(SyntaxTheme::new_test([
+                ("comment", gpui::red()),
+                ("comment.doc", gpui::green()),
+                ("string", gpui::blue()),
+            ])),
+            vec![("Rust".to_string(), vec![("comment".to_string(), italic)])],
+        );
+        assert_eq!(
+            syntax_theme,
+            Arc::new(SyntaxTheme::new_test_styles([
+                ("comment", gpui::red().into()),
+                ("comment.doc", gpui::green().into()),
+                ("string", gpui::blue().into()),
+                (
+                    "Rust/comment",
+                    HighlightStyle {
+                        color: Some(gpui::red()),
+                        ..italic
+                    }
+                ),
+                (
+                    "Rust/comment.doc",
+                    HighlightStyle {
+                        color: Some(gpui::green()),
+                        ..italic
+                    }
+                ),
+            ]))
+        );
+    }
 }
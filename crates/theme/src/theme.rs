@@ -87,12 +87,14 @@ pub fn init(themes_to_load: LoadThemes, cx: &mut AppContext) {
     FontFamilyCache::init_global(cx);
 
     let mut prev_buffer_font_size = ThemeSettings::get_global(cx).buffer_font_size;
+    cx.set_reduce_motion(ThemeSettings::get_global(cx).reduce_motion);
     cx.observe_global::<SettingsStore>(move |cx| {
         let buffer_font_size = ThemeSettings::get_global(cx).buffer_font_size;
         if buffer_font_size != prev_buffer_font_size {
             prev_buffer_font_size = buffer_font_size;
             reset_buffer_font_size(cx);
         }
+        cx.set_reduce_motion(ThemeSettings::get_global(cx).reduce_motion);
     })
     .detach();
 }
@@ -7,6 +7,7 @@ use crate::{prelude::*, ListHeader};
 #[derive(IntoElement)]
 pub struct SettingsGroup {
     header: SharedString,
+    layer: Option<SharedString>,
     children: SmallVec<[AnyElement; 2]>,
 }
 
@@ -14,9 +15,16 @@ impl SettingsGroup {
     pub fn new(header: impl Into<SharedString>) -> Self {
         Self {
             header: header.into(),
+            layer: None,
             children: SmallVec::new(),
         }
     }
+
+    /// Sets the name of the settings layer that the values in this group come from.
+    pub fn layer(mut self, layer: impl Into<SharedString>) -> Self {
+        self.layer = Some(layer.into());
+        self
+    }
 }
 
 impl ParentElement for SettingsGroup {
@@ -30,7 +38,12 @@ impl RenderOnce for SettingsGroup {
         v_flex()
             .p_1()
             .gap_2()
-            .child(ListHeader::new(self.header))
+            .child(
+                ListHeader::new(self.header).end_slot(
+                    self.layer
+                        .map(|layer| Label::new(layer).size(LabelSize::Small).color(Color::Muted)),
+                ),
+            )
             .children(self.children)
     }
 }
@@ -31,14 +31,17 @@ impl Vim {
         let Some(selections) = self.change_list.get(next).cloned() else {
             return;
         };
-        self.update_editor(cx, |_, editor, cx| {
-            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
-                let map = s.display_map();
-                s.select_display_ranges(selections.into_iter().map(|a| {
-                    let point = a.to_display_point(&map);
-                    point..point
-                }))
-            })
+        // moving through the change list doesn't add to the jump list
+        self.record_jump(false, cx, |vim, cx| {
+            vim.update_editor(cx, |_, editor, cx| {
+                editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                    let map = s.display_map();
+                    s.select_display_ranges(selections.into_iter().map(|a| {
+                        let point = a.to_display_point(&map);
+                        point..point
+                    }))
+                })
+            });
         });
     }
 
@@ -84,7 +87,10 @@ impl Vim {
 mod test {
     use indoc::indoc;
 
-    use crate::{state::Mode, test::NeovimBackedTestContext};
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+    };
 
     #[gpui::test]
     async fn test_change_list_insert(cx: &mut gpui::TestAppContext) {
@@ -207,4 +213,44 @@ mod test {
         "one two
         three fˇor"});
     }
+
+    #[gpui::test]
+    async fn test_change_list_is_not_the_jump_list(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state(
+            indoc! {"
+            0
+            1
+            2
+            3
+            4
+            ˇ5
+            6
+            7
+            8
+            9
+            10
+            11"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("x shift-g x g g g ;");
+        cx.simulate_keystrokes("ctrl-o");
+        cx.run_until_parked();
+        cx.assert_state(
+            indoc! {"
+            0
+            1
+            2
+            3
+            4
+            ˇ
+            6
+            7
+            8
+            9
+            10
+            1"},
+            Mode::Normal,
+        );
+    }
 }
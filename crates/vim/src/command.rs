@@ -1,4 +1,4 @@
-use std::{iter::Peekable, ops::Range, str::Chars, sync::OnceLock};
+use std::{iter::Peekable, ops::Range, path::PathBuf, str::Chars, sync::OnceLock};
 
 use anyhow::{anyhow, Result};
 use command_palette_hooks::CommandInterceptResult;
@@ -10,17 +10,25 @@ use gpui::{actions, impl_actions, Action, AppContext, Global, ViewContext};
 use language::Point;
 use multi_buffer::MultiBufferRow;
 use serde::Deserialize;
+use smol::{io::AsyncWriteExt, process::Stdio};
 use ui::WindowContext;
 use util::ResultExt;
-use workspace::{notifications::NotifyResultExt, SaveIntent};
+use workspace::{
+    notifications::{NotifyResultExt, NotifyTaskExt},
+    SaveIntent,
+};
 
 use crate::{
+    mark_picker::ShowMarks,
     motion::{EndOfDocument, Motion, StartOfDocument},
     normal::{
-        search::{FindCommand, ReplaceCommand, Replacement},
+        mark::DeleteMarks,
+        search::{ClearSearchHighlights, FindCommand, ReplaceCommand, Replacement},
         JoinLines,
     },
-    state::Mode,
+    object::Object,
+    register_picker::ShowRegisters,
+    state::{Mode, VimGlobals},
     visual::VisualDeleteLine,
     Vim,
 };
@@ -37,8 +45,20 @@ pub struct WithRange {
     action: Box<dyn Action>,
 }
 
-actions!(vim, [VisualCommand, CountCommand]);
-impl_actions!(vim, [GoToLine, WithRange]);
+/// `:keepjumps {cmd}`, which runs the command without changing the jump list.
+#[derive(Debug)]
+pub struct KeepJumps {
+    action: Box<dyn Action>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ShellExec {
+    command: String,
+    range: CommandRange,
+}
+
+actions!(vim, [VisualCommand, CountCommand, ShellCommand]);
+impl_actions!(vim, [GoToLine, WithRange, KeepJumps, ShellExec]);
 
 impl<'de> Deserialize<'de> for WithRange {
     fn deserialize<D>(_: D) -> Result<Self, D::Error>
@@ -65,6 +85,29 @@ impl Clone for WithRange {
     }
 }
 
+impl<'de> Deserialize<'de> for KeepJumps {
+    fn deserialize<D>(_: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Err(serde::de::Error::custom("Cannot deserialize KeepJumps"))
+    }
+}
+
+impl PartialEq for KeepJumps {
+    fn eq(&self, other: &Self) -> bool {
+        self.action.partial_eq(&*other.action)
+    }
+}
+
+impl Clone for KeepJumps {
+    fn clone(&self) -> Self {
+        Self {
+            action: self.action.boxed_clone(),
+        }
+    }
+}
+
 pub fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
     Vim::action(editor, cx, |vim, _: &VisualCommand, cx| {
         let Some(workspace) = vim.workspace(cx) else {
@@ -89,6 +132,23 @@ pub fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
         })
     });
 
+    Vim::action(editor, cx, |vim, _: &ShellCommand, cx| {
+        let Some(workspace) = vim.workspace(cx) else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            command_palette::CommandPalette::toggle(workspace, "'<,'>!", cx);
+        })
+    });
+
+    Vim::action(editor, cx, Vim::shell_exec);
+
+    Vim::action(editor, cx, |vim, action: &KeepJumps, cx| {
+        let keep_jumps = vim.keep_jumps(cx);
+        cx.dispatch_action(action.action.boxed_clone());
+        cx.defer(move |_, cx| keep_jumps(cx));
+    });
+
     Vim::action(editor, cx, |vim, action: &GoToLine, cx| {
         vim.switch_mode(Mode::Normal, false, cx);
         let result = vim.update_editor(cx, |vim, editor, cx| {
@@ -151,6 +211,195 @@ pub fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
     });
 }
 
+impl Vim {
+    pub(crate) fn shell_command_motion(
+        &mut self,
+        motion: Motion,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(workspace) = self.workspace(cx) else {
+            return;
+        };
+        let query = self.update_editor(cx, |_, editor, cx| {
+            let text_layout_details = editor.text_layout_details(cx);
+            editor.change_selections(None, cx, |s| {
+                s.move_with(|map, selection| {
+                    motion.expand_selection(map, selection, times, false, &text_layout_details);
+                });
+            });
+            shell_command_query(editor, cx)
+        });
+        if let Some(query) = query {
+            workspace.update(cx, |workspace, cx| {
+                command_palette::CommandPalette::toggle(workspace, &query, cx);
+            });
+        }
+    }
+
+    pub(crate) fn shell_command_object(
+        &mut self,
+        object: Object,
+        around: bool,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(workspace) = self.workspace(cx) else {
+            return;
+        };
+        let query = self.update_editor(cx, |_, editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.move_with(|map, selection| {
+                    object.expand_selection(map, selection, around, times);
+                });
+            });
+            shell_command_query(editor, cx)
+        });
+        if let Some(query) = query {
+            workspace.update(cx, |workspace, cx| {
+                command_palette::CommandPalette::toggle(workspace, &query, cx);
+            });
+        }
+    }
+
+    fn shell_exec(&mut self, action: &ShellExec, cx: &mut ViewContext<Self>) {
+        self.switch_mode(Mode::Normal, false, cx);
+        if action.command.is_empty() {
+            return;
+        }
+        let Some(workspace) = self.workspace(cx) else {
+            return;
+        };
+        let result = self.update_editor(cx, |vim, editor, cx| {
+            action.range.buffer_range(vim, editor, cx)
+        });
+        let range = match result {
+            None => return,
+            Some(e @ Err(_)) => {
+                workspace.update(cx, |workspace, cx| {
+                    e.notify_err(workspace, cx);
+                });
+                return;
+            }
+            Some(Ok(result)) => result,
+        };
+
+        let Some((input, replace_range, ends_at_eof)) = self.update_editor(cx, |_, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let start = Point::new(range.start.0, 0);
+            let end = Point::new(range.end.0, snapshot.line_len(range.end));
+            let mut input = snapshot.text_for_range(start..end).collect::<String>();
+            input.push('\n');
+            // Replace the trailing newline too, so that a command printing nothing removes the lines.
+            let ends_at_eof = range.end >= snapshot.max_buffer_row();
+            let end = if ends_at_eof {
+                end
+            } else {
+                Point::new(range.end.0 + 1, 0)
+            };
+            (
+                input,
+                snapshot.anchor_before(start)..snapshot.anchor_after(end),
+                ends_at_eof,
+            )
+        }) else {
+            return;
+        };
+
+        let cwd = workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf());
+        let command = action.command.clone();
+        let output = cx
+            .background_executor()
+            .spawn(async move { filter_through_command(&command, cwd, input).await });
+        cx.spawn(|vim, mut cx| async move {
+            let mut output = output.await?;
+            if ends_at_eof && output.ends_with('\n') {
+                output.pop();
+            }
+            vim.update(&mut cx, |vim, cx| {
+                vim.update_editor(cx, |_, editor, cx| {
+                    editor.transact(cx, |editor, cx| {
+                        editor.edit([(replace_range.clone(), output)], cx);
+                        let snapshot = editor.buffer().read(cx).snapshot(cx);
+                        let start = replace_range.start.to_point(&snapshot);
+                        editor.change_selections(None, cx, |s| s.select_ranges([start..start]));
+                    });
+                });
+            })
+        })
+        .detach_and_notify_err(cx);
+    }
+}
+
+/// Collapses the newest selection to the start of its first line and returns the command
+/// palette query that filters its lines, as `!{motion}` does.
+fn shell_command_query(editor: &mut Editor, cx: &mut ViewContext<Editor>) -> String {
+    let selection = editor.selections.newest::<Point>(cx);
+    let mut end_row = selection.end.row;
+    if end_row > selection.start.row && selection.end.column == 0 {
+        end_row -= 1;
+    }
+    let start = Point::new(selection.start.row, 0);
+    editor.change_selections(None, cx, |s| s.select_ranges([start..start]));
+    if end_row == selection.start.row {
+        ".!".to_string()
+    } else {
+        format!(".,.+{}!", end_row - selection.start.row)
+    }
+}
+
+/// Runs `command` in the user's shell with `input` on its stdin and returns its stdout.
+async fn filter_through_command(
+    command: &str,
+    cwd: Option<PathBuf>,
+    input: String,
+) -> Result<String> {
+    let mut process = if cfg!(windows) {
+        let mut process = smol::process::Command::new("cmd");
+        process.arg("/C");
+        process
+    } else {
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string());
+        let mut process = smol::process::Command::new(shell);
+        process.arg("-c");
+        process
+    };
+    process.arg(command);
+    if let Some(cwd) = cwd.filter(|cwd| cwd.is_dir()) {
+        process.current_dir(cwd);
+    }
+
+    let mut child = process
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("failed to acquire stdin"))?;
+    // Commands are free to ignore their input, so failing to write it is not an error.
+    let write_input = async move {
+        stdin.write_all(input.as_bytes()).await.ok();
+    };
+    let (_, output) = smol::future::zip(write_input, child.output()).await;
+    let output = output?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{command} failed with {}:\n{}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
 #[derive(Debug, Default)]
 struct VimCommand {
     prefix: &'static str,
@@ -227,13 +476,7 @@ impl VimCommand {
         match chars.peek() {
             Some('%') => {
                 chars.next();
-                return (
-                    Some(CommandRange {
-                        start: Position::Line { row: 1, offset: 0 },
-                        end: Some(Position::LastLine { offset: 0 }),
-                    }),
-                    chars.collect(),
-                );
+                return (Some(CommandRange::whole_file()), chars.collect());
             }
             Some('*') => {
                 chars.next();
@@ -384,6 +627,20 @@ pub(crate) struct CommandRange {
 }
 
 impl CommandRange {
+    pub(crate) fn current_line() -> Self {
+        CommandRange {
+            start: Position::CurrentLine { offset: 0 },
+            end: None,
+        }
+    }
+
+    pub(crate) fn whole_file() -> Self {
+        CommandRange {
+            start: Position::Line { row: 1, offset: 0 },
+            end: Some(Position::LastLine { offset: 0 }),
+        }
+    }
+
     fn head(&self) -> &Position {
         self.end.as_ref().unwrap_or(&self.start)
     }
@@ -589,6 +846,10 @@ fn generate_commands(_: &AppContext) -> Vec<VimCommand> {
         VimCommand::new(("d", "elete"), VisualDeleteLine).range(),
         VimCommand::new(("sor", "t"), SortLinesCaseSensitive).range(),
         VimCommand::new(("sort i", ""), SortLinesCaseInsensitive).range(),
+        VimCommand::new(("reg", "isters"), ShowRegisters),
+        VimCommand::new(("di", "splay"), ShowRegisters),
+        VimCommand::new(("marks", ""), ShowMarks),
+        VimCommand::new(("noh", "lsearch"), ClearSearchHighlights),
         VimCommand::str(("E", "xplore"), "project_panel::ToggleFocus"),
         VimCommand::str(("H", "explore"), "project_panel::ToggleFocus"),
         VimCommand::str(("L", "explore"), "project_panel::ToggleFocus"),
@@ -619,6 +880,17 @@ fn commands(cx: &AppContext) -> &Vec<VimCommand> {
         .0
 }
 
+/// Remembers an ex command that is about to run in the `:` register and the command history.
+pub fn record_ex_command(input: &str, cx: &mut AppContext) {
+    let command = input.trim_start_matches(':');
+    if command.trim().is_empty() {
+        return;
+    }
+    let globals = Vim::globals(cx);
+    globals.registers.insert(':', command.to_string().into());
+    VimGlobals::push_history(&mut globals.command_history, command);
+}
+
 pub fn command_interceptor(mut input: &str, cx: &AppContext) -> Option<CommandInterceptResult> {
     // NOTE: We also need to support passing arguments to commands like :w
     // (ideally with filename autocompletion).
@@ -626,6 +898,23 @@ pub fn command_interceptor(mut input: &str, cx: &AppContext) -> Option<CommandIn
         input = &input[1..];
     }
 
+    if let Some(mut args) = input.strip_prefix("keepj") {
+        for char in "umps".chars() {
+            let Some(rest) = args.strip_prefix(char) else {
+                break;
+            };
+            args = rest;
+        }
+        let command = args.strip_prefix(' ')?.trim_start();
+        let mut result = command_interceptor(command, cx)?;
+        result.action = Box::new(KeepJumps {
+            action: result.action,
+        });
+        result.string = format!(":keepjumps {}", result.string.trim_start_matches(':'));
+        result.positions = generate_positions(&result.string, input);
+        return Some(result);
+    }
+
     let (range, query) = VimCommand::parse_range(input);
     let range_prefix = input[0..(input.len() - query.len())].to_string();
     let query = query.as_str();
@@ -645,6 +934,26 @@ pub fn command_interceptor(mut input: &str, cx: &AppContext) -> Option<CommandIn
             }
             .boxed_clone(),
         )
+    } else if let Some((command, range)) = query.strip_prefix('!').zip(range.as_ref()) {
+        Some(
+            ShellExec {
+                command: command.trim().to_string(),
+                range: range.clone(),
+            }
+            .boxed_clone(),
+        )
+    } else if let Some(mut args) = query.strip_prefix("delm") {
+        for char in "arks".chars() {
+            let Some(rest) = args.strip_prefix(char) else {
+                break;
+            };
+            args = rest;
+        }
+        if args == "!" || args.starts_with(' ') {
+            DeleteMarks::parse(args.trim()).map(|action| action.boxed_clone())
+        } else {
+            None
+        }
     } else if query.starts_with('s') {
         let mut substitute = "substitute".chars().peekable();
         let mut query = query.chars().peekable();
@@ -736,10 +1045,12 @@ fn generate_positions(string: &str, query: &str) -> Vec<usize> {
 mod test {
     use std::path::Path;
 
+    use super::{command_interceptor, filter_through_command, CommandRange, Position, ShellExec};
     use crate::{
         state::Mode,
         test::{NeovimBackedTestContext, VimTestContext},
     };
+    use command_palette::CommandPalette;
     use editor::Editor;
     use gpui::TestAppContext;
     use indoc::indoc;
@@ -974,4 +1285,54 @@ mod test {
             assert_active_item(workspace, "/root/dir/file2.rs", "This is file2.rs", cx);
         });
     }
+
+    #[gpui::test]
+    async fn test_shell_command(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            a
+            bˇb
+            c
+            d"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("! j");
+        assert!(
+            cx.workspace(|workspace, cx| workspace.active_modal::<CommandPalette>(cx).is_some())
+        );
+        cx.assert_editor_state(indoc! {"
+            a
+            ˇbb
+            c
+            d"});
+
+        let action = cx
+            .update(|cx| command_interceptor(".,.+1!sort -r", cx))
+            .unwrap()
+            .action;
+        assert!(action.partial_eq(&ShellExec {
+            command: "sort -r".to_string(),
+            range: CommandRange {
+                start: Position::CurrentLine { offset: 0 },
+                end: Some(Position::CurrentLine { offset: 1 }),
+            },
+        }));
+        assert!(cx.update(|cx| command_interceptor("!sort", cx)).is_none());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_filter_through_command() {
+        let output = smol::block_on(filter_through_command("sort", None, "b\na\n".to_string()));
+        assert_eq!(output.unwrap(), "a\nb\n");
+
+        let output = smol::block_on(filter_through_command(
+            "echo oops >&2; exit 3",
+            None,
+            String::new(),
+        ));
+        assert!(output.unwrap_err().to_string().contains("oops"));
+    }
 }
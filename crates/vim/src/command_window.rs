@@ -0,0 +1,188 @@
+use editor::Editor;
+use gpui::{
+    actions, Action, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, View,
+    ViewContext, VisualContext, WeakView,
+};
+use language::Point;
+use multi_buffer::MultiBufferRow;
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::{
+    command::{command_interceptor, record_ex_command},
+    normal::search::FindCommand,
+    Vim,
+};
+
+actions!(vim, [CommandWindowExecute, CommandWindowClose]);
+
+impl Vim {
+    /// Opens the command-line window for `q:`, `q/` or `q?`, listing the ex command or search
+    /// history in a buffer that can be edited like any other.
+    pub(crate) fn open_command_window(&mut self, kind: char, cx: &mut ViewContext<Self>) {
+        self.clear_operator(cx);
+        let Some(workspace) = self.workspace(cx) else {
+            return;
+        };
+        let Some(editor) = self.editor() else {
+            return;
+        };
+        let globals = Vim::globals(cx);
+        let history = if kind == ':' {
+            globals.command_history.clone()
+        } else {
+            globals.search_history.clone()
+        };
+        let editor = editor.downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(cx, |cx| CommandWindow::new(kind, history, editor, cx));
+        });
+    }
+}
+
+/// The command-line window: the history of ex commands (`q:`) or searches (`q/`, `q?`), one
+/// per line with an empty line at the end. Enter runs the line under the cursor in the editor
+/// the window was opened from.
+pub struct CommandWindow {
+    kind: char,
+    editor: View<Editor>,
+    target: WeakView<Editor>,
+}
+
+impl CommandWindow {
+    fn new(
+        kind: char,
+        history: Vec<String>,
+        target: WeakView<Editor>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let text = history
+            .iter()
+            .map(|entry| format!("{}\n", entry))
+            .collect::<String>();
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::multi_line(cx);
+            editor.set_show_gutter(false, cx);
+            editor.set_text(text, cx);
+            editor.move_to_end(&Default::default(), cx);
+            editor
+        });
+        Self {
+            kind,
+            editor,
+            target,
+        }
+    }
+
+    fn execute(&mut self, _: &CommandWindowExecute, cx: &mut ViewContext<Self>) {
+        let line = self.editor.update(cx, |editor, cx| {
+            let row = editor.selections.newest::<Point>(cx).head().row;
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            snapshot
+                .text_for_range(
+                    Point::new(row, 0)..Point::new(row, snapshot.line_len(MultiBufferRow(row))),
+                )
+                .collect::<String>()
+        });
+        cx.emit(DismissEvent);
+
+        let Some(target) = self.target.upgrade() else {
+            return;
+        };
+        let action = if line.trim().is_empty() {
+            None
+        } else if self.kind == ':' {
+            record_ex_command(&line, cx);
+            command_interceptor(&line, cx).map(|result| result.action)
+        } else {
+            Some(
+                FindCommand {
+                    query: line,
+                    backwards: self.kind == '?',
+                }
+                .boxed_clone(),
+            )
+        };
+        // the action goes to the editor the window was opened from, like the command palette's
+        cx.focus_view(&target);
+        if let Some(action) = action {
+            cx.dispatch_action(action);
+        }
+    }
+
+    fn close(&mut self, _: &CommandWindowClose, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for CommandWindow {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let title = if self.kind == ':' {
+            "Command history"
+        } else {
+            "Search history"
+        };
+        v_flex()
+            .key_context("VimCommandWindow")
+            .on_action(cx.listener(Self::execute))
+            .on_action(cx.listener(Self::close))
+            .elevation_2(cx)
+            .w(rems(34.))
+            .child(
+                div()
+                    .h(rems(12.))
+                    .p_2()
+                    .bg(cx.theme().colors().editor_background)
+                    .child(self.editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .child(Label::new(title).color(Color::Muted)),
+            )
+    }
+}
+
+impl FocusableView for CommandWindow {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for CommandWindow {}
+impl ModalView for CommandWindow {}
+
+#[cfg(test)]
+mod test {
+    use crate::{state::Mode, test::VimTestContext};
+
+    use super::CommandWindow;
+
+    #[gpui::test]
+    async fn test_command_window(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo\nthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes(": 3 enter");
+        cx.simulate_keystrokes(": 2 enter");
+        cx.assert_state("one\nˇtwo\nthree\nfour", Mode::Normal);
+
+        cx.simulate_keystrokes("q :");
+        cx.run_until_parked();
+        cx.workspace(|workspace, cx| {
+            assert!(workspace.active_modal::<CommandWindow>(cx).is_some());
+        });
+        // the commands are listed oldest first, and the first one is changed before it runs
+        cx.simulate_keystrokes("k k r 4 enter");
+        cx.run_until_parked();
+        cx.workspace(|workspace, cx| {
+            assert!(workspace.active_modal::<CommandWindow>(cx).is_none());
+        });
+        cx.assert_state("one\ntwo\nthree\nˇfour", Mode::Normal);
+
+        cx.simulate_keystrokes("g g q / i t w o enter");
+        cx.run_until_parked();
+        cx.assert_state("one\nˇtwo\nthree\nfour", Mode::Normal);
+    }
+}
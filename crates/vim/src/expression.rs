@@ -0,0 +1,313 @@
+use std::{iter::Peekable, str::Chars, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use editor::Editor;
+use gpui::{actions, ViewContext};
+use workspace::notifications::NotifyResultExt;
+
+use crate::{
+    state::{Mode, Operator},
+    Vim,
+};
+
+actions!(vim, [ExpressionBackspace]);
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
+    // Deletes the last character typed at the `"=` prompt, and cancels any other operator that
+    // is waiting for a character.
+    Vim::action(editor, cx, |vim, _: &ExpressionBackspace, cx| {
+        if vim.active_operator() != Some(Operator::Expression) || vim.expression.pop().is_none() {
+            vim.clear_operator(cx);
+        }
+        cx.notify();
+    });
+}
+
+impl Vim {
+    /// Handles a character typed at the `"=` prompt, evaluating the expression on enter.
+    pub(crate) fn expression_input(&mut self, text: Arc<str>, cx: &mut ViewContext<Self>) {
+        if text.as_ref() != "\n" {
+            self.expression.push_str(&text);
+            cx.notify();
+            return;
+        }
+
+        let expression = std::mem::take(&mut self.expression);
+        let result = self
+            .update_editor(cx, |_, editor, cx| {
+                evaluate(&expression, &mut |register| {
+                    Vim::update_globals(cx, |globals, cx| {
+                        globals.read_register(Some(register), Some(&mut *editor), cx)
+                    })
+                    .map(|register| register.text.to_string())
+                })
+            })
+            .unwrap_or_else(|| Err(anyhow!("No editor")));
+        let result = match result {
+            Ok(result) => result,
+            Err(error) => {
+                self.clear_operator(cx);
+                if let Some(workspace) = self.workspace(cx) {
+                    workspace.update(cx, |workspace, cx| {
+                        Err::<(), _>(error).notify_err(workspace, cx);
+                    });
+                }
+                return;
+            }
+        };
+
+        self.pop_operator(cx);
+        if self.mode == Mode::Insert {
+            self.update_editor(cx, |_, editor, cx| {
+                editor.do_paste(&result, None, false, cx)
+            });
+        } else {
+            Vim::globals(cx).registers.insert('=', result.into());
+            self.selected_register = Some('=');
+            cx.notify();
+        }
+    }
+
+    pub(crate) fn start_expression(&mut self, cx: &mut ViewContext<Self>) {
+        self.expression.clear();
+        self.pop_operator(cx);
+        self.push_operator(Operator::Expression, cx);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Number(i64),
+    Float(f64),
+    String(String),
+}
+
+impl Value {
+    fn into_string(self) -> String {
+        match self {
+            Value::Number(number) => number.to_string(),
+            Value::Float(float) if float.fract() == 0.0 => format!("{:.1}", float),
+            Value::Float(float) => float.to_string(),
+            Value::String(string) => string,
+        }
+    }
+
+    /// Converts the value for arithmetic like Vim does, reading a string's leading digits.
+    fn into_number(self) -> Value {
+        match self {
+            Value::String(string) => {
+                let string = string.trim_start();
+                let (sign, digits) = match string.strip_prefix('-') {
+                    Some(rest) => (-1, rest),
+                    None => (1, string),
+                };
+                let digits = digits
+                    .chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>();
+                Value::Number(sign * digits.parse::<i64>().unwrap_or(0))
+            }
+            value => value,
+        }
+    }
+}
+
+/// Evaluates a Vim expression as typed at the `"=` prompt. Numbers, floats, strings in single
+/// or double quotes and registers (`@a`) can be combined with `+ - * / %`, concatenated with
+/// `.` or `..` and grouped with parentheses.
+fn evaluate(expression: &str, register: &mut dyn FnMut(char) -> Option<String>) -> Result<String> {
+    let mut parser = Parser {
+        chars: expression.chars().peekable(),
+        register,
+    };
+    let value = parser.additive()?;
+    parser.skip_whitespace();
+    if let Some(c) = parser.chars.next() {
+        return Err(anyhow!("Unexpected `{}` in expression", c));
+    }
+    Ok(value.into_string())
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    register: &'a mut dyn FnMut(char) -> Option<String>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    /// Parses `+`, `-` and string concatenation, which share a precedence level in Vim.
+    fn additive(&mut self) -> Result<Value> {
+        let mut value = self.multiplicative()?;
+        loop {
+            self.skip_whitespace();
+            let Some(op) = self.chars.next_if(|c| matches!(c, '+' | '-' | '.')) else {
+                return Ok(value);
+            };
+            if op == '.' {
+                self.chars.next_if_eq(&'.');
+                let right = self.multiplicative()?;
+                value = Value::String(value.into_string() + &right.into_string());
+            } else {
+                let right = self.multiplicative()?;
+                value = arithmetic(value, op, right)?;
+            }
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Value> {
+        let mut value = self.unary()?;
+        loop {
+            self.skip_whitespace();
+            let Some(op) = self.chars.next_if(|c| matches!(c, '*' | '/' | '%')) else {
+                return Ok(value);
+            };
+            let right = self.unary()?;
+            value = arithmetic(value, op, right)?;
+        }
+    }
+
+    fn unary(&mut self) -> Result<Value> {
+        self.skip_whitespace();
+        if self.chars.next_if_eq(&'-').is_some() {
+            return arithmetic(Value::Number(0), '-', self.unary()?);
+        }
+        if self.chars.next_if_eq(&'+').is_some() {
+            return Ok(self.unary()?.into_number());
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Value> {
+        match self.chars.next() {
+            Some('(') => {
+                let value = self.additive()?;
+                self.skip_whitespace();
+                if self.chars.next_if_eq(&')').is_none() {
+                    return Err(anyhow!("Missing `)` in expression"));
+                }
+                Ok(value)
+            }
+            Some('"') => {
+                let mut string = String::new();
+                loop {
+                    match self.chars.next() {
+                        Some('"') => return Ok(Value::String(string)),
+                        Some('\\') => match self.chars.next() {
+                            Some('n') => string.push('\n'),
+                            Some('t') => string.push('\t'),
+                            Some(c) => string.push(c),
+                            None => break,
+                        },
+                        Some(c) => string.push(c),
+                        None => break,
+                    }
+                }
+                Err(anyhow!("Missing `\"` in expression"))
+            }
+            Some('\'') => {
+                let mut string = String::new();
+                loop {
+                    match self.chars.next() {
+                        Some('\'') if self.chars.next_if_eq(&'\'').is_some() => string.push('\''),
+                        Some('\'') => return Ok(Value::String(string)),
+                        Some(c) => string.push(c),
+                        None => return Err(anyhow!("Missing `'` in expression")),
+                    }
+                }
+            }
+            Some('@') => {
+                let register = self.chars.next().unwrap_or('"');
+                Ok(Value::String((self.register)(register).unwrap_or_default()))
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let mut digits = c.to_string();
+                while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit()) {
+                    digits.push(c);
+                }
+                let mut lookahead = self.chars.clone();
+                if lookahead.next() == Some('.')
+                    && lookahead.peek().is_some_and(char::is_ascii_digit)
+                {
+                    self.chars.next();
+                    digits.push('.');
+                    while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit()) {
+                        digits.push(c);
+                    }
+                    return Ok(Value::Float(digits.parse()?));
+                }
+                Ok(Value::Number(digits.parse()?))
+            }
+            Some(c) => Err(anyhow!("Unexpected `{}` in expression", c)),
+            None => Err(anyhow!("Unexpected end of expression")),
+        }
+    }
+}
+
+fn arithmetic(left: Value, op: char, right: Value) -> Result<Value> {
+    match (left.into_number(), right.into_number()) {
+        (Value::Number(left), Value::Number(right)) => {
+            let result = match op {
+                '+' => left.checked_add(right),
+                '-' => left.checked_sub(right),
+                '*' => left.checked_mul(right),
+                '/' => left.checked_div(right),
+                _ => left.checked_rem(right),
+            };
+            result
+                .map(Value::Number)
+                .ok_or_else(|| anyhow!("Cannot evaluate {} {} {}", left, op, right))
+        }
+        (left, right) => {
+            let as_float = |value| match value {
+                Value::Number(number) => number as f64,
+                Value::Float(float) => float,
+                Value::String(_) => 0.0,
+            };
+            let (left, right) = (as_float(left), as_float(right));
+            Ok(Value::Float(match op {
+                '+' => left + right,
+                '-' => left - right,
+                '*' => left * right,
+                '/' => left / right,
+                _ => return Err(anyhow!("Cannot use `%` with a float")),
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{state::Mode, test::VimTestContext};
+
+    use super::evaluate;
+
+    #[test]
+    fn test_evaluate() {
+        let mut register = |register| (register == 'a').then(|| "12".to_string());
+        let mut eval = |expression| evaluate(expression, &mut register).unwrap();
+        assert_eq!(eval("1 + 2 * 3"), "7");
+        assert_eq!(eval("(1 + 2) * 3"), "9");
+        assert_eq!(eval("7 / 2"), "3");
+        assert_eq!(eval("7 % 2 - -1"), "2");
+        assert_eq!(eval("1.5 * 2"), "3.0");
+        assert_eq!(eval("\"a\\tb\" . 'it''s' .. 1"), "a\tbit's1");
+        assert_eq!(eval("@a * 2 . @b"), "24");
+        assert!(evaluate("1 / 0", &mut register).is_err());
+        assert!(evaluate("(1", &mut register).is_err());
+    }
+
+    #[gpui::test]
+    async fn test_expression_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇa", Mode::Normal);
+        cx.simulate_keystrokes("\" = 6 * 7 enter p");
+        cx.assert_state("a4ˇ2", Mode::Normal);
+
+        cx.simulate_keystrokes("a ctrl-r = 1 + 2 backspace 3 enter escape");
+        cx.assert_state("a42ˇ4", Mode::Normal);
+    }
+}
@@ -83,6 +83,24 @@ mod test {
         cx.shared_state().await.assert_eq("oi\noi\noˇi\nhello\n");
     }
 
+    #[gpui::test]
+    async fn test_insert_words_with_counts(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇhello", Mode::Normal);
+        cx.simulate_keystrokes("3 i f o o escape");
+        cx.assert_state("foofoofoˇohello", Mode::Normal);
+
+        cx.set_state("ˇhello", Mode::Normal);
+        cx.simulate_keystrokes("2 o a b space c escape");
+        cx.assert_state("hello\nab c\nab ˇc", Mode::Normal);
+
+        cx.set_state("heˇllo", Mode::Normal);
+        cx.simulate_keystrokes("i x escape w");
+        cx.simulate_keystrokes("3 g i y z escape");
+        cx.assert_state("hexyzyzyˇzllo", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_insert_with_repeat(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -0,0 +1,305 @@
+use std::sync::Arc;
+
+use editor::{
+    display_map::{DisplayRow, DisplaySnapshot, ToDisplayPoint},
+    Bias, DisplayPoint, Editor,
+};
+use gpui::{impl_actions, SharedString, ViewContext};
+use language::CharKind;
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::{
+    motion::{first_non_whitespace, Motion},
+    normal::search::search_regex,
+    state::Operator,
+    Vim,
+};
+
+/// The keys used as labels, in the order they are handed out (nearest target first).
+const LABEL_CHARS: &str = "asdghklqwertyuiopzxcvbnmfj";
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+enum JumpTarget {
+    WordStart,
+    LineStart,
+    SearchMatch,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct ShowJumpLabels {
+    target: JumpTarget,
+}
+
+impl_actions!(vim, [ShowJumpLabels]);
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
+    Vim::action(editor, cx, |vim, action: &ShowJumpLabels, cx| {
+        vim.show_jump_labels(action.target, cx)
+    });
+}
+
+impl Vim {
+    fn show_jump_labels(&mut self, target: JumpTarget, cx: &mut ViewContext<Self>) {
+        let search = Vim::globals(cx)
+            .registers
+            .get(&'/')
+            .filter(|register| !register.text.is_empty())
+            .map(|register| search_regex(&register.text, true, cx));
+        let Some(labels) = self.update_editor(cx, |_, editor, cx| {
+            let text_layout_details = editor.text_layout_details(cx);
+            let map = editor.snapshot(cx).display_snapshot;
+            let head = editor.selections.newest_display(cx).head();
+
+            let first_row = text_layout_details
+                .scroll_anchor
+                .anchor
+                .to_display_point(&map)
+                .row();
+            let last_row = match text_layout_details.visible_rows {
+                Some(visible_rows) => DisplayRow(first_row.0 + visible_rows as u32),
+                None => map.max_point().row(),
+            }
+            .min(map.max_point().row());
+
+            let mut targets = match target {
+                JumpTarget::WordStart => word_starts(&map, first_row, last_row),
+                JumpTarget::LineStart => line_starts(&map, first_row, last_row),
+                JumpTarget::SearchMatch => {
+                    let Some(search) = search else {
+                        return Vec::new();
+                    };
+                    search_matches(&map, first_row, last_row, &search)
+                }
+            };
+            targets.retain(|point| *point != head);
+            // nearest first: by rows away, below before above, then by columns away
+            targets.sort_by_key(|point| {
+                (
+                    point.row().0.abs_diff(head.row().0),
+                    point.row() < head.row(),
+                    point.column().abs_diff(head.column()),
+                )
+            });
+
+            let labels = LABEL_CHARS
+                .chars()
+                .zip(targets)
+                .map(|(label, point)| (label, map.display_point_to_anchor(point, Bias::Left)))
+                .collect::<Vec<_>>();
+            editor.set_jump_labels(
+                labels
+                    .iter()
+                    .map(|(label, anchor)| (*anchor, SharedString::from(label.to_string())))
+                    .collect(),
+                cx,
+            );
+            labels
+        }) else {
+            return;
+        };
+
+        if labels.is_empty() {
+            self.clear_operator(cx);
+            return;
+        }
+        self.jump_labels = labels;
+        self.push_operator(Operator::JumpLabel, cx);
+    }
+
+    pub(crate) fn jump_to_label(&mut self, text: Arc<str>, cx: &mut ViewContext<Self>) {
+        let target = text.chars().next().and_then(|char| {
+            self.jump_labels
+                .iter()
+                .find(|(label, _)| *label == char)
+                .map(|(_, anchor)| *anchor)
+        });
+        let Some(anchor) = target else {
+            self.clear_operator(cx);
+            return;
+        };
+        self.pop_operator(cx);
+        self.motion(
+            Motion::Jump {
+                anchor,
+                line: false,
+            },
+            cx,
+        )
+    }
+}
+
+fn word_starts(
+    map: &DisplaySnapshot,
+    first_row: DisplayRow,
+    last_row: DisplayRow,
+) -> Vec<DisplayPoint> {
+    let mut points = Vec::new();
+    for row in first_row.0..=last_row.0 {
+        let row = DisplayRow(row);
+        let start = DisplayPoint::new(row, 0);
+        let classifier = map.buffer_snapshot.char_classifier_at(start.to_point(map));
+        let mut prev_kind = CharKind::Whitespace;
+        for (column, char) in map.line(row).char_indices() {
+            let kind = classifier.kind(char);
+            if kind != CharKind::Whitespace && kind != prev_kind {
+                points.push(DisplayPoint::new(row, column as u32));
+            }
+            prev_kind = kind;
+        }
+    }
+    points
+}
+
+fn line_starts(
+    map: &DisplaySnapshot,
+    first_row: DisplayRow,
+    last_row: DisplayRow,
+) -> Vec<DisplayPoint> {
+    (first_row.0..=last_row.0)
+        .map(|row| first_non_whitespace(map, false, DisplayPoint::new(DisplayRow(row), 0)))
+        .collect()
+}
+
+fn search_matches(
+    map: &DisplaySnapshot,
+    first_row: DisplayRow,
+    last_row: DisplayRow,
+    regex: &str,
+) -> Vec<DisplayPoint> {
+    let Ok(regex) = Regex::new(regex) else {
+        return Vec::new();
+    };
+    let mut points = Vec::new();
+    for row in first_row.0..=last_row.0 {
+        let row = DisplayRow(row);
+        for found in regex.find_iter(&map.line(row)) {
+            points.push(DisplayPoint::new(row, found.start() as u32));
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use settings::SettingsStore;
+
+    use crate::{state::Mode, test::VimTestContext, Vim, VimSettings};
+
+    use super::{JumpTarget, ShowJumpLabels};
+
+    fn labels(cx: &mut VimTestContext) -> Vec<String> {
+        cx.update_editor(|editor, _| {
+            editor
+                .jump_labels()
+                .iter()
+                .map(|(_, label)| label.to_string())
+                .collect()
+        })
+    }
+
+    #[gpui::test]
+    async fn test_jump_labels(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇone two three
+            four five"},
+            Mode::Normal,
+        );
+        cx.dispatch_action(ShowJumpLabels {
+            target: JumpTarget::WordStart,
+        });
+        assert_eq!(labels(&mut cx), vec!["a", "s", "d", "g"]);
+        cx.simulate_keystrokes("s");
+        cx.assert_state(
+            indoc! {"
+            one two ˇthree
+            four five"},
+            Mode::Normal,
+        );
+        assert!(labels(&mut cx).is_empty());
+
+        cx.dispatch_action(ShowJumpLabels {
+            target: JumpTarget::LineStart,
+        });
+        // the start of the cursor's own line is nearest, so it gets `a`
+        assert_eq!(labels(&mut cx), vec!["a", "s"]);
+        cx.simulate_keystrokes("s");
+        cx.assert_state(
+            indoc! {"
+            one two three
+            ˇfour five"},
+            Mode::Normal,
+        );
+
+        // an unknown label cancels the jump
+        cx.dispatch_action(ShowJumpLabels {
+            target: JumpTarget::WordStart,
+        });
+        cx.simulate_keystrokes("1");
+        assert!(labels(&mut cx).is_empty());
+        cx.assert_state(
+            indoc! {"
+            one two three
+            ˇfour five"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_jump_labels_with_operator(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("d");
+        cx.dispatch_action(ShowJumpLabels {
+            target: JumpTarget::WordStart,
+        });
+        cx.simulate_keystrokes("s");
+        cx.assert_state("ˇthree", Mode::Normal);
+
+        cx.set_state("ˇone two one three one", Mode::Normal);
+        cx.simulate_keystrokes("*");
+        cx.set_state("ˇone two one three one", Mode::Normal);
+        cx.dispatch_action(ShowJumpLabels {
+            target: JumpTarget::SearchMatch,
+        });
+        assert_eq!(labels(&mut cx), vec!["a", "s"]);
+
+        // the register holds a vim pattern, matched like `n` would
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| s.ignorecase = Some(true));
+        });
+        cx.set_state("ˇone One tone ONE", Mode::Normal);
+        cx.update(|cx| {
+            Vim::globals(cx)
+                .registers
+                .insert('/', "\\<one\\>".to_string().into());
+        });
+        cx.dispatch_action(ShowJumpLabels {
+            target: JumpTarget::SearchMatch,
+        });
+        assert_eq!(labels(&mut cx), vec!["a", "s"]);
+        cx.simulate_keystrokes("s");
+        cx.assert_state("one One tone ˇONE", Mode::Normal);
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| s.smartcase = Some(true));
+        });
+        cx.update(|cx| {
+            Vim::globals(cx)
+                .registers
+                .insert('/', "\\<One\\>".to_string().into());
+        });
+        cx.dispatch_action(ShowJumpLabels {
+            target: JumpTarget::SearchMatch,
+        });
+        assert_eq!(labels(&mut cx), vec!["a"]);
+    }
+}
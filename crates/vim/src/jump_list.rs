@@ -0,0 +1,129 @@
+use editor::{Anchor, Direction, Editor, ToOffset};
+use gpui::{actions, AppContext, ViewContext};
+use workspace::NavigationMode;
+
+use crate::Vim;
+
+actions!(vim, [JumpListOlder, JumpListNewer]);
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
+    Vim::action(editor, cx, |vim, _: &JumpListOlder, cx| {
+        vim.move_in_jump_list(Direction::Prev, cx);
+    });
+    Vim::action(editor, cx, |vim, _: &JumpListNewer, cx| {
+        vim.move_in_jump_list(Direction::Next, cx);
+    });
+}
+
+impl Vim {
+    /// Runs `f` without the editor recording its cursor movements in the pane's navigation
+    /// history, which doubles as the jump list. If `is_jump` is set, the position the cursor
+    /// left is recorded instead.
+    pub(crate) fn record_jump<R>(
+        &mut self,
+        is_jump: bool,
+        cx: &mut ViewContext<Self>,
+        f: impl FnOnce(&mut Self, &mut ViewContext<Self>) -> R,
+    ) -> R {
+        let history = self
+            .pane(cx)
+            .map(|pane| pane.read(cx).nav_history().clone())
+            .filter(|history| matches!(history.mode(), NavigationMode::Normal));
+        let from = self.update_editor(cx, |_, editor, _| editor.selections.newest_anchor().head());
+
+        if let Some(mut history) = history.clone() {
+            history.disable();
+        }
+        let result = f(self, cx);
+        if let Some(mut history) = history {
+            history.enable();
+        }
+
+        if let Some(from) = from.filter(|_| is_jump) {
+            self.push_jump(from, cx);
+        }
+        result
+    }
+
+    /// Stops vim and the editor from recording jumps, like `:keepjumps`, until the returned
+    /// function is called, which should be once the actions dispatched meanwhile have run.
+    pub(crate) fn keep_jumps(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+    ) -> impl FnOnce(&mut AppContext) {
+        let history = self
+            .pane(cx)
+            .map(|pane| pane.read(cx).nav_history().clone())
+            .filter(|history| matches!(history.mode(), NavigationMode::Normal));
+        if let Some(mut history) = history.clone() {
+            history.disable();
+        }
+        let kept_jumps = std::mem::replace(&mut Vim::globals(cx).keep_jumps, true);
+        move |cx| {
+            Vim::globals(cx).keep_jumps = kept_jumps;
+            if let Some(mut history) = history {
+                history.enable();
+            }
+        }
+    }
+
+    /// Whether jumps are kept out of the jump list, as they are while a macro or `.` replays
+    /// and while a `:keepjumps` command runs.
+    fn keeps_jumps(cx: &mut AppContext) -> bool {
+        let globals = Vim::globals(cx);
+        globals.keep_jumps || globals.is_replaying()
+    }
+
+    /// Records `from` in the jump list, unless the cursor hasn't left it.
+    pub(crate) fn push_jump(&mut self, from: Anchor, cx: &mut ViewContext<Self>) {
+        if Self::keeps_jumps(cx) {
+            return;
+        }
+        self.update_editor(cx, |_, editor, cx| {
+            let buffer = editor.buffer().read(cx).snapshot(cx);
+            let head = editor.selections.newest_anchor().head();
+            if head.to_offset(&buffer) != from.to_offset(&buffer) {
+                editor.push_to_nav_history(from, None, cx);
+            }
+        });
+    }
+
+    fn move_in_jump_list(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
+        let count = self.take_count(cx).unwrap_or(1);
+        let (Some(workspace), Some(pane)) = (self.workspace(cx), self.pane(cx)) else {
+            return;
+        };
+        let pane = pane.downgrade();
+        cx.spawn(|vim, mut cx| async move {
+            for _ in 0..count {
+                // the pane only records the position it leaves when switching files, so record
+                // it here to be able to come back to it with the opposite direction
+                vim.update(&mut cx, |vim, cx| {
+                    let Some(pane) = pane.upgrade() else { return };
+                    let mut history = pane.read(cx).nav_history().clone();
+                    history.set_mode(if direction == Direction::Prev {
+                        NavigationMode::GoingBack
+                    } else {
+                        NavigationMode::GoingForward
+                    });
+                    vim.update_editor(cx, |_, editor, cx| {
+                        let head = editor.selections.newest_anchor().head();
+                        editor.push_to_nav_history(head, None, cx);
+                    });
+                    history.set_mode(NavigationMode::Normal);
+                })?;
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        if direction == Direction::Prev {
+                            workspace.go_back(pane.clone(), cx)
+                        } else {
+                            workspace.go_forward(pane.clone(), cx)
+                        }
+                    })?
+                    .await?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+}
@@ -0,0 +1,332 @@
+use std::sync::Arc;
+
+use editor::{Bias, Editor, ToPoint};
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, View, ViewContext,
+    VisualContext, WeakView,
+};
+use language::Point;
+use multi_buffer::MultiBufferRow;
+use picker::{Picker, PickerDelegate};
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing, Tooltip};
+use util::ResultExt;
+use workspace::{ItemHandle, ModalView};
+
+use crate::Vim;
+
+actions!(vim, [ShowMarks]);
+
+/// The marks listed by `:marks`, in the order Vim lists them.
+const MARKS: &str = "'abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789\"[]^.<>";
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
+    Vim::action(editor, cx, |vim, _: &ShowMarks, cx| {
+        let Some(workspace) = vim.workspace(cx) else {
+            return;
+        };
+        let path = vim.editor().and_then(|editor| editor.project_path(cx));
+        let global_marks = Vim::global_marks(&workspace, cx).clone();
+        let Some(mut marks) = vim.update_editor(cx, |vim, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let line_preview = |point: Point| {
+                let row = MultiBufferRow(point.row);
+                snapshot
+                    .text_for_range(
+                        Point::new(point.row, 0)..Point::new(point.row, snapshot.line_len(row)),
+                    )
+                    .collect::<String>()
+                    .trim()
+                    .to_string()
+            };
+            let local_marks = vim
+                .marks
+                .iter()
+                .map(|(name, anchors)| (name.clone(), anchors.first().copied()))
+                .chain(
+                    vim.change_list
+                        .last()
+                        .map(|anchors| (".".to_string(), anchors.first().copied())),
+                )
+                .filter_map(|(name, anchor)| {
+                    let point = anchor?.to_point(&snapshot);
+                    Some(Mark {
+                        name,
+                        point,
+                        preview: line_preview(point),
+                    })
+                });
+            let global_marks = global_marks.iter().map(|(name, mark)| {
+                let point = mark.point(cx);
+                // like `:marks`, a mark in another file shows the file instead of the line
+                let preview = if path.as_ref() == Some(&mark.path) {
+                    line_preview(snapshot.clip_point(point, Bias::Left))
+                } else {
+                    mark.path.path.to_string_lossy().to_string()
+                };
+                Mark {
+                    name: name.clone(),
+                    point,
+                    preview,
+                }
+            });
+            local_marks.chain(global_marks).collect::<Vec<_>>()
+        }) else {
+            return;
+        };
+        marks.sort_by_key(|mark| MARKS.find(mark.name.as_str()).unwrap_or(usize::MAX));
+
+        let vim = cx.view().downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(cx, |cx| MarkPicker::new(vim, marks, cx));
+        });
+    });
+}
+
+struct Mark {
+    name: String,
+    point: Point,
+    preview: String,
+}
+
+/// Lists the marks like `:marks`, and jumps to the one picked or deletes it.
+pub struct MarkPicker {
+    picker: View<Picker<MarkPickerDelegate>>,
+}
+
+impl MarkPicker {
+    fn new(vim: WeakView<Vim>, marks: Vec<Mark>, cx: &mut ViewContext<Self>) -> Self {
+        let delegate = MarkPickerDelegate {
+            mark_picker: cx.view().downgrade(),
+            vim,
+            marks,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for MarkPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for MarkPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for MarkPicker {}
+impl ModalView for MarkPicker {}
+
+pub struct MarkPickerDelegate {
+    mark_picker: WeakView<MarkPicker>,
+    vim: WeakView<Vim>,
+    marks: Vec<Mark>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl MarkPickerDelegate {
+    fn delete_mark(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(candidate_id) = self.matches.get(ix).map(|mat| mat.candidate_id) else {
+            return;
+        };
+        let mark = self.marks.remove(candidate_id);
+        self.vim
+            .update(cx, |vim, cx| vim.delete_marks([mark.name], cx))
+            .log_err();
+
+        self.matches.remove(ix);
+        for mat in &mut self.matches {
+            if mat.candidate_id > candidate_id {
+                mat.candidate_id -= 1;
+            }
+        }
+        self.selected_index = self
+            .selected_index
+            .min(self.matches.len().saturating_sub(1));
+        cx.notify();
+    }
+}
+
+impl PickerDelegate for MarkPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Jump to a mark...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if secondary {
+            self.delete_mark(self.selected_index, cx);
+            return;
+        }
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let name = self.marks[mat.candidate_id].name.clone();
+            self.vim
+                .update(cx, |vim, cx| vim.jump_to_mark(name.into(), false, cx))
+                .log_err();
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.mark_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .marks
+            .iter()
+            .enumerate()
+            .map(|(id, mark)| StringMatchCandidate::new(id, mark.name.clone()))
+            .collect::<Vec<_>>();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let mark = &self.marks[mat.candidate_id];
+        let delete_button = IconButton::new("delete", IconName::Close)
+            .icon_size(IconSize::Small)
+            .on_click(cx.listener(move |this, _, cx| {
+                cx.stop_propagation();
+                this.delegate.delete_mark(ix, cx);
+            }))
+            .tooltip(|cx| Tooltip::text("Delete Mark", cx));
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            mark.name.clone(),
+                            mat.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(format!("{}:{}", mark.point.row + 1, mark.point.column + 1))
+                                .color(Color::Muted),
+                        )
+                        .child(Label::new(mark.preview.clone()).color(Color::Muted)),
+                )
+                .end_hover_slot(delete_button),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use crate::{state::Mode, test::VimTestContext};
+
+    use super::MarkPicker;
+
+    #[gpui::test]
+    async fn test_mark_picker(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇone
+            two
+            three"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("m a j l m b shift-g");
+        cx.simulate_keystrokes(": m a r k s enter");
+        cx.run_until_parked();
+        cx.workspace(|workspace, cx| {
+            assert!(workspace.active_modal::<MarkPicker>(cx).is_some());
+        });
+
+        cx.simulate_keystrokes("b enter");
+        cx.run_until_parked();
+        cx.workspace(|workspace, cx| {
+            assert!(workspace.active_modal::<MarkPicker>(cx).is_none());
+        });
+        cx.assert_state(
+            indoc! {"
+            one
+            tˇwo
+            three"},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes(": d e l m a r k s space a - b enter");
+        cx.simulate_keystrokes("' a");
+        cx.assert_state(
+            indoc! {"
+            one
+            tˇwo
+            three"},
+            Mode::Normal,
+        );
+    }
+}
@@ -2,7 +2,7 @@ use gpui::{div, Element, Render, Subscription, View, ViewContext, WeakView};
 use itertools::Itertools;
 use workspace::{item::ItemHandle, ui::prelude::*, StatusItemView};
 
-use crate::{Vim, VimEvent};
+use crate::{recording_register, state::Operator, MacroRecording, Vim, VimEvent};
 
 /// The ModeIndicator displays the current mode in the status bar.
 pub struct ModeIndicator {
@@ -19,6 +19,8 @@ impl ModeIndicator {
             cx.notify();
         })
         .detach();
+        cx.observe_global::<MacroRecording>(|_, cx| cx.notify())
+            .detach();
 
         let handle = cx.view().clone();
         let window = cx.window_handle();
@@ -61,8 +63,7 @@ impl ModeIndicator {
     }
 
     fn current_operators_description(&self, vim: View<Vim>, cx: &mut ViewContext<Self>) -> String {
-        let recording = Vim::globals(cx)
-            .recording_register
+        let recording = recording_register(cx)
             .map(|reg| format!("recording @{reg} "))
             .into_iter();
 
@@ -70,7 +71,10 @@ impl ModeIndicator {
         recording
             .chain(vim.pre_count.map(|count| format!("{}", count)))
             .chain(vim.selected_register.map(|reg| format!("\"{reg}")))
-            .chain(vim.operator_stack.iter().map(|item| item.id().to_string()))
+            .chain(vim.operator_stack.iter().map(|item| match item {
+                Operator::Expression => format!("\"={}", vim.expression),
+                _ => item.id().to_string(),
+            }))
             .chain(vim.post_count.map(|count| format!("{}", count)))
             .collect::<Vec<_>>()
             .join("")
@@ -4,19 +4,22 @@ use editor::{
         self, find_boundary, find_preceding_boundary_display_point, FindRange, TextLayoutDetails,
     },
     scroll::Autoscroll,
-    Anchor, Bias, DisplayPoint, Editor, RowExt, ToOffset,
+    Anchor, Bias, Direction, DisplayPoint, Editor, RowExt, ToOffset,
 };
 use gpui::{actions, impl_actions, px, ViewContext};
-use language::{CharKind, Point, Selection, SelectionGoal};
-use multi_buffer::MultiBufferRow;
+use language::{
+    CharKind, Diagnostic, DiagnosticSeverity, Point, Selection, SelectionGoal, TextObject,
+};
+use multi_buffer::{MultiBufferRow, MultiBufferSnapshot};
 use serde::Deserialize;
+use settings::Settings;
 use std::ops::Range;
 
 use crate::{
     normal::mark,
     state::{Mode, Operator},
     surrounds::SurroundsType,
-    Vim,
+    Vim, VimSettings,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -70,6 +73,7 @@ pub enum Motion {
     StartOfDocument,
     EndOfDocument,
     Matching,
+    GoToPercentage,
     FindForward {
         before: bool,
         char: char,
@@ -96,6 +100,30 @@ pub enum Motion {
     WindowTop,
     WindowMiddle,
     WindowBottom,
+    NextMisspelling,
+    PreviousMisspelling,
+    NextSectionStart,
+    NextSectionEnd,
+    PreviousSectionStart,
+    PreviousSectionEnd,
+    NextMethodStart,
+    NextMethodEnd,
+    PreviousMethodStart,
+    PreviousMethodEnd,
+    UnmatchedForward {
+        char: char,
+    },
+    UnmatchedBackward {
+        char: char,
+    },
+    NextHunk,
+    PreviousHunk,
+    NextDiagnostic {
+        severity: Option<DiagnosticSeverity>,
+    },
+    PreviousDiagnostic {
+        severity: Option<DiagnosticSeverity>,
+    },
 
     // we don't have a good way to run a search synchronously, so
     // we handle search motions by running the search async and then
@@ -103,13 +131,36 @@ pub enum Motion {
     ZedSearchResult {
         prior_selections: Vec<Range<Anchor>>,
         new_selections: Vec<Range<Anchor>>,
+        /// Set by a line offset (`/foo/+1`).
+        linewise: bool,
+        /// Set by an offset from the end of the match (`/foo/e`).
+        inclusive: bool,
     },
     Jump {
         anchor: Anchor,
         line: bool,
     },
+    /// A motion whose kind was forced with `v` or `V` after an operator (`dvj`, `dVe`).
+    Forced {
+        motion: Box<Motion>,
+        linewise: bool,
+        inclusive: bool,
+    },
+}
+
+/// The kind an operator's motion can be forced to with `v`, `V` or `<C-v>` (`:h o_v`), and
+/// the kind of text a register holds (`:h getregtype()`).
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+pub enum MotionKind {
+    #[default]
+    Charwise,
+    Linewise,
+    Blockwise,
 }
 
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct ForceMotion(pub MotionKind);
+
 #[derive(Clone, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 struct NextWordStart {
@@ -201,6 +252,53 @@ pub struct StartOfLine {
     pub(crate) display_lines: bool,
 }
 
+#[derive(Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct UnmatchedForward {
+    char: char,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct UnmatchedBackward {
+    char: char,
+}
+
+/// The least severe kind of diagnostic that the diagnostic motions stop at.
+#[derive(Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+enum Severity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+impl From<Severity> for DiagnosticSeverity {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Error => DiagnosticSeverity::ERROR,
+            Severity::Warning => DiagnosticSeverity::WARNING,
+            Severity::Information => DiagnosticSeverity::INFORMATION,
+            Severity::Hint => DiagnosticSeverity::HINT,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct NextDiagnostic {
+    #[serde(default)]
+    severity: Option<Severity>,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct PreviousDiagnostic {
+    #[serde(default)]
+    severity: Option<Severity>,
+}
+
 impl_actions!(
     vim,
     [
@@ -217,6 +315,11 @@ impl_actions!(
         NextSubwordEnd,
         PreviousSubwordStart,
         PreviousSubwordEnd,
+        UnmatchedForward,
+        UnmatchedBackward,
+        NextDiagnostic,
+        PreviousDiagnostic,
+        ForceMotion,
     ]
 );
 
@@ -233,6 +336,7 @@ actions!(
         StartOfDocument,
         EndOfDocument,
         Matching,
+        GoToPercentage,
         NextLineStart,
         PreviousLineStart,
         StartOfLineDownward,
@@ -243,13 +347,32 @@ actions!(
         WindowTop,
         WindowMiddle,
         WindowBottom,
+        NextMisspelling,
+        PreviousMisspelling,
+        NextSectionStart,
+        NextSectionEnd,
+        PreviousSectionStart,
+        PreviousSectionEnd,
+        NextMethodStart,
+        NextMethodEnd,
+        PreviousMethodStart,
+        PreviousMethodEnd,
+        NextHunk,
+        PreviousHunk,
     ]
 );
 
 pub fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
-    Vim::action(editor, cx, |vim, _: &Left, cx| vim.motion(Motion::Left, cx));
+    Vim::action(editor, cx, |vim, action: &ForceMotion, _| {
+        vim.forced_motion = Some(action.0);
+    });
+    Vim::action(editor, cx, |vim, _: &Left, cx| {
+        let motion = vim.horizontal_motion('h', Motion::Backspace, Motion::Left, cx);
+        vim.motion(motion, cx)
+    });
     Vim::action(editor, cx, |vim, _: &Backspace, cx| {
-        vim.motion(Motion::Backspace, cx)
+        let motion = vim.horizontal_motion('b', Motion::Backspace, Motion::Left, cx);
+        vim.motion(motion, cx)
     });
     Vim::action(editor, cx, |vim, action: &Down, cx| {
         vim.motion(
@@ -268,10 +391,12 @@ pub fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
         )
     });
     Vim::action(editor, cx, |vim, _: &Right, cx| {
-        vim.motion(Motion::Right, cx)
+        let motion = vim.horizontal_motion('l', Motion::Space, Motion::Right, cx);
+        vim.motion(motion, cx)
     });
     Vim::action(editor, cx, |vim, _: &Space, cx| {
-        vim.motion(Motion::Space, cx)
+        let motion = vim.horizontal_motion('s', Motion::Space, Motion::Right, cx);
+        vim.motion(motion, cx)
     });
     Vim::action(editor, cx, |vim, action: &FirstNonWhitespace, cx| {
         vim.motion(
@@ -315,6 +440,9 @@ pub fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
     Vim::action(editor, cx, |vim, _: &Matching, cx| {
         vim.motion(Motion::Matching, cx)
     });
+    Vim::action(editor, cx, |vim, _: &GoToPercentage, cx| {
+        vim.motion(Motion::GoToPercentage, cx)
+    });
 
     Vim::action(
         editor,
@@ -408,6 +536,56 @@ pub fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
     Vim::action(editor, cx, |vim, &WindowBottom, cx| {
         vim.motion(Motion::WindowBottom, cx)
     });
+    Vim::action(editor, cx, |vim, &NextMisspelling, cx| {
+        vim.motion(Motion::NextMisspelling, cx)
+    });
+    Vim::action(editor, cx, |vim, &PreviousMisspelling, cx| {
+        vim.motion(Motion::PreviousMisspelling, cx)
+    });
+    Vim::action(editor, cx, |vim, &NextSectionStart, cx| {
+        vim.motion(Motion::NextSectionStart, cx)
+    });
+    Vim::action(editor, cx, |vim, &NextSectionEnd, cx| {
+        vim.motion(Motion::NextSectionEnd, cx)
+    });
+    Vim::action(editor, cx, |vim, &PreviousSectionStart, cx| {
+        vim.motion(Motion::PreviousSectionStart, cx)
+    });
+    Vim::action(editor, cx, |vim, &PreviousSectionEnd, cx| {
+        vim.motion(Motion::PreviousSectionEnd, cx)
+    });
+    Vim::action(editor, cx, |vim, &NextMethodStart, cx| {
+        vim.motion(Motion::NextMethodStart, cx)
+    });
+    Vim::action(editor, cx, |vim, &NextMethodEnd, cx| {
+        vim.motion(Motion::NextMethodEnd, cx)
+    });
+    Vim::action(editor, cx, |vim, &PreviousMethodStart, cx| {
+        vim.motion(Motion::PreviousMethodStart, cx)
+    });
+    Vim::action(editor, cx, |vim, &PreviousMethodEnd, cx| {
+        vim.motion(Motion::PreviousMethodEnd, cx)
+    });
+    Vim::action(editor, cx, |vim, &NextHunk, cx| {
+        vim.motion(Motion::NextHunk, cx)
+    });
+    Vim::action(editor, cx, |vim, &PreviousHunk, cx| {
+        vim.motion(Motion::PreviousHunk, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &NextDiagnostic, cx| {
+        let severity = action.severity.map(Into::into);
+        vim.diagnostic_motion(Motion::NextDiagnostic { severity }, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &PreviousDiagnostic, cx| {
+        let severity = action.severity.map(Into::into);
+        vim.diagnostic_motion(Motion::PreviousDiagnostic { severity }, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &UnmatchedForward, cx| {
+        vim.motion(Motion::UnmatchedForward { char: action.char }, cx)
+    });
+    Vim::action(editor, cx, |vim, action: &UnmatchedBackward, cx| {
+        vim.motion(Motion::UnmatchedBackward { char: action.char }, cx)
+    });
 }
 
 impl Vim {
@@ -428,6 +606,9 @@ impl Vim {
                 }
                 Mode::Normal | Mode::Replace | Mode::Insert => {
                     if self.active_operator().is_none() {
+                        if let Some(prior) = prior_selections.first() {
+                            self.push_jump(prior.end, cx);
+                        }
                         return;
                     }
                 }
@@ -437,6 +618,23 @@ impl Vim {
         self.motion(m, cx)
     }
 
+    /// Picks between the wrapping and non-wrapping variant of a horizontal motion based on
+    /// the `whichwrap` setting. Motions used by an operator never wrap for `h` and `l`.
+    fn horizontal_motion(
+        &self,
+        key: char,
+        wrapping: Motion,
+        non_wrapping: Motion,
+        cx: &mut ViewContext<Self>,
+    ) -> Motion {
+        let wraps = VimSettings::get_global(cx).wraps(key);
+        if wraps && (matches!(key, 'b' | 's') || self.active_operator().is_none()) {
+            wrapping
+        } else {
+            non_wrapping
+        }
+    }
+
     pub(crate) fn motion(&mut self, motion: Motion, cx: &mut ViewContext<Self>) {
         if let Some(Operator::FindForward { .. }) | Some(Operator::FindBackward { .. }) =
             self.active_operator()
@@ -446,6 +644,16 @@ impl Vim {
 
         let count = self.take_count(cx);
         let active_operator = self.active_operator();
+        let motion = match (self.forced_motion.take(), &active_operator) {
+            (Some(MotionKind::Blockwise), Some(operator)) if self.mode == Mode::Normal => {
+                let operator = operator.clone();
+                self.clear_operator(cx);
+                self.blockwise_motion(motion, operator, count, cx);
+                return;
+            }
+            (Some(kind), Some(_)) => motion.forced(kind),
+            _ => motion,
+        };
         let mut waiting_operator: Option<Operator> = None;
         match self.mode {
             Mode::Normal | Mode::Replace | Mode::Insert => {
@@ -453,6 +661,10 @@ impl Vim {
                     waiting_operator = Some(Operator::AddSurrounds {
                         target: Some(SurroundsType::Motion(motion)),
                     });
+                } else if active_operator == Some(Operator::Align { target: None }) {
+                    waiting_operator = Some(Operator::Align {
+                        target: Some(SurroundsType::Motion(motion)),
+                    });
                 } else {
                     self.normal_motion(motion.clone(), active_operator.clone(), count, cx)
                 }
@@ -467,6 +679,39 @@ impl Vim {
             self.pre_count = count
         }
     }
+
+    /// Moves to a diagnostic with `]d` or `[d`, and shows it below its line in normal mode,
+    /// like `editor::GoToDiagnostic`.
+    fn diagnostic_motion(&mut self, motion: Motion, cx: &mut ViewContext<Self>) {
+        let shows_diagnostic = self.mode == Mode::Normal && self.active_operator().is_none();
+        self.motion(motion, cx);
+        if shows_diagnostic {
+            self.update_editor(cx, |_, editor, cx| editor.activate_diagnostic_at_cursor(cx));
+        }
+    }
+
+    /// Applies `operator` to the block between the cursor and the target of `motion`, for a
+    /// motion forced blockwise with `<C-v>` (`d<C-v>2j`).
+    fn blockwise_motion(
+        &mut self,
+        motion: Motion,
+        operator: Operator,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.switch_mode(Mode::VisualBlock, false, cx);
+        self.visual_motion(motion, times, cx);
+        self.visual_operator(operator, cx);
+        if self.mode.is_visual() {
+            self.update_editor(cx, |_, editor, cx| {
+                editor.change_selections(None, cx, |s| {
+                    let cursor = s.first_anchor().start;
+                    s.select_anchor_ranges([cursor..cursor]);
+                });
+            });
+            self.switch_mode(Mode::Normal, true, cx);
+        }
+    }
 }
 
 // Motion handling is specified here:
@@ -475,8 +720,12 @@ impl Motion {
     pub fn linewise(&self) -> bool {
         use Motion::*;
         match self {
-            Down { .. }
-            | Up { .. }
+            Down {
+                display_lines: false,
+            }
+            | Up {
+                display_lines: false,
+            }
             | StartOfDocument
             | EndOfDocument
             | CurrentLine
@@ -487,9 +736,17 @@ impl Motion {
             | WindowTop
             | WindowMiddle
             | WindowBottom
+            | GoToPercentage
             | Jump { line: true, .. }
             | EndOfParagraph => true,
-            EndOfLine { .. }
+            // gj and gk are exclusive, characterwise motions.
+            Down {
+                display_lines: true,
+            }
+            | Up {
+                display_lines: true,
+            }
+            | EndOfLine { .. }
             | Matching
             | FindForward { .. }
             | Left
@@ -511,15 +768,32 @@ impl Motion {
             | FindBackward { .. }
             | RepeatFind { .. }
             | RepeatFindReversed { .. }
-            | Jump { line: false, .. }
-            | ZedSearchResult { .. } => false,
+            | NextMisspelling
+            | PreviousMisspelling
+            | NextSectionStart
+            | NextSectionEnd
+            | PreviousSectionStart
+            | PreviousSectionEnd
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | UnmatchedForward { .. }
+            | UnmatchedBackward { .. }
+            | NextHunk
+            | PreviousHunk
+            | NextDiagnostic { .. }
+            | PreviousDiagnostic { .. }
+            | Jump { line: false, .. } => false,
+            ZedSearchResult { linewise, .. } | Forced { linewise, .. } => *linewise,
         }
     }
 
     pub fn infallible(&self) -> bool {
         use Motion::*;
         match self {
-            StartOfDocument | EndOfDocument | CurrentLine => true,
+            StartOfDocument | EndOfDocument | CurrentLine | StartOfLineDownward
+            | EndOfLineDownward | GoToPercentage => true,
             Down { .. }
             | Up { .. }
             | EndOfLine { .. }
@@ -533,8 +807,6 @@ impl Motion {
             | StartOfLine { .. }
             | StartOfParagraph
             | EndOfParagraph
-            | StartOfLineDownward
-            | EndOfLineDownward
             | GoToColumn
             | NextWordStart { .. }
             | NextWordEnd { .. }
@@ -552,22 +824,107 @@ impl Motion {
             | WindowBottom
             | NextLineStart
             | PreviousLineStart
+            | NextMisspelling
+            | PreviousMisspelling
+            | NextSectionStart
+            | NextSectionEnd
+            | PreviousSectionStart
+            | PreviousSectionEnd
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | UnmatchedForward { .. }
+            | UnmatchedBackward { .. }
+            | NextHunk
+            | PreviousHunk
+            | NextDiagnostic { .. }
+            | PreviousDiagnostic { .. }
             | ZedSearchResult { .. }
             | Jump { .. } => false,
+            Forced { motion, .. } => motion.infallible(),
         }
     }
 
-    pub fn inclusive(&self) -> bool {
+    /// Whether the motion is a jump (`:h jump-motions`), which records the position it leaves
+    /// in the jump list.
+    pub fn is_jump(&self) -> bool {
         use Motion::*;
         match self {
-            Down { .. }
+            StartOfParagraph
+            | EndOfParagraph
+            | StartOfDocument
+            | EndOfDocument
+            | Matching
+            | GoToPercentage
+            | WindowTop
+            | WindowMiddle
+            | WindowBottom
+            | NextSectionStart
+            | NextSectionEnd
+            | PreviousSectionStart
+            | PreviousSectionEnd
+            | NextHunk
+            | PreviousHunk
+            | ZedSearchResult { .. }
+            | Jump { .. } => true,
+            Left
+            | Backspace
+            | Down { .. }
             | Up { .. }
+            | Right
+            | Space
+            | NextWordStart { .. }
+            | NextWordEnd { .. }
+            | PreviousWordStart { .. }
+            | PreviousWordEnd { .. }
+            | NextSubwordStart { .. }
+            | NextSubwordEnd { .. }
+            | PreviousSubwordStart { .. }
+            | PreviousSubwordEnd { .. }
+            | FirstNonWhitespace { .. }
+            | CurrentLine
+            | StartOfLine { .. }
+            | EndOfLine { .. }
+            | FindForward { .. }
+            | FindBackward { .. }
+            | RepeatFind { .. }
+            | RepeatFindReversed { .. }
+            | NextLineStart
+            | PreviousLineStart
+            | StartOfLineDownward
+            | EndOfLineDownward
+            | GoToColumn
+            | NextMisspelling
+            | PreviousMisspelling
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | UnmatchedForward { .. }
+            | UnmatchedBackward { .. }
+            | NextDiagnostic { .. }
+            | PreviousDiagnostic { .. } => false,
+            Forced { motion, .. } => motion.is_jump(),
+        }
+    }
+
+    pub fn inclusive(&self) -> bool {
+        use Motion::*;
+        match self {
+            Down {
+                display_lines: false,
+            }
+            | Up {
+                display_lines: false,
+            }
             | StartOfDocument
             | EndOfDocument
             | CurrentLine
             | EndOfLine { .. }
             | EndOfLineDownward
             | Matching
+            | GoToPercentage
             | FindForward { .. }
             | WindowTop
             | WindowMiddle
@@ -578,7 +935,13 @@ impl Motion {
             | PreviousSubwordEnd { .. }
             | NextLineStart
             | PreviousLineStart => true,
-            Left
+            Down {
+                display_lines: true,
+            }
+            | Up {
+                display_lines: true,
+            }
+            | Left
             | Backspace
             | Right
             | Space
@@ -593,11 +956,42 @@ impl Motion {
             | PreviousSubwordStart { .. }
             | FirstNonWhitespace { .. }
             | FindBackward { .. }
-            | Jump { .. }
-            | ZedSearchResult { .. } => false,
+            | NextMisspelling
+            | PreviousMisspelling
+            | NextSectionStart
+            | NextSectionEnd
+            | PreviousSectionStart
+            | PreviousSectionEnd
+            | NextMethodStart
+            | NextMethodEnd
+            | PreviousMethodStart
+            | PreviousMethodEnd
+            | UnmatchedForward { .. }
+            | UnmatchedBackward { .. }
+            | NextHunk
+            | PreviousHunk
+            | NextDiagnostic { .. }
+            | PreviousDiagnostic { .. }
+            | Jump { .. } => false,
             RepeatFind { last_find: motion } | RepeatFindReversed { last_find: motion } => {
                 motion.inclusive()
             }
+            ZedSearchResult { inclusive, .. } | Forced { inclusive, .. } => *inclusive,
+        }
+    }
+
+    /// Forces the motion charwise or linewise (`:h o_v`). Forcing charwise toggles an already
+    /// charwise motion between inclusive and exclusive, and makes a linewise one exclusive.
+    pub fn forced(self, kind: MotionKind) -> Motion {
+        let (linewise, inclusive) = match kind {
+            MotionKind::Linewise => (true, self.inclusive()),
+            MotionKind::Charwise | MotionKind::Blockwise if self.linewise() => (false, false),
+            MotionKind::Charwise | MotionKind::Blockwise => (false, !self.inclusive()),
+        };
+        Motion::Forced {
+            motion: Box::new(self),
+            linewise,
+            inclusive,
         }
     }
 
@@ -688,6 +1082,7 @@ impl Motion {
                 SelectionGoal::None,
             ),
             Matching => (matching(map, point), SelectionGoal::None),
+            GoToPercentage => (go_to_percentage(map, times), SelectionGoal::None),
             // t f
             FindForward {
                 before,
@@ -786,7 +1181,102 @@ impl Motion {
             WindowTop => window_top(map, point, &text_layout_details, times - 1),
             WindowMiddle => window_middle(map, point, &text_layout_details),
             WindowBottom => window_bottom(map, point, &text_layout_details, times - 1),
+            NextMisspelling => (
+                misspelling(map, point, Direction::Next, times),
+                SelectionGoal::None,
+            ),
+            PreviousMisspelling => (
+                misspelling(map, point, Direction::Prev, times),
+                SelectionGoal::None,
+            ),
+            NextSectionStart => (
+                section(map, point, Direction::Next, true, times),
+                SelectionGoal::None,
+            ),
+            NextSectionEnd => (
+                section(map, point, Direction::Next, false, times),
+                SelectionGoal::None,
+            ),
+            PreviousSectionStart => (
+                section(map, point, Direction::Prev, true, times),
+                SelectionGoal::None,
+            ),
+            PreviousSectionEnd => (
+                section(map, point, Direction::Prev, false, times),
+                SelectionGoal::None,
+            ),
+            NextMethodStart => (
+                method(
+                    map,
+                    point,
+                    Direction::Next,
+                    true,
+                    times,
+                    text_layout_details,
+                ),
+                SelectionGoal::None,
+            ),
+            NextMethodEnd => (
+                method(
+                    map,
+                    point,
+                    Direction::Next,
+                    false,
+                    times,
+                    text_layout_details,
+                ),
+                SelectionGoal::None,
+            ),
+            PreviousMethodStart => (
+                method(
+                    map,
+                    point,
+                    Direction::Prev,
+                    true,
+                    times,
+                    text_layout_details,
+                ),
+                SelectionGoal::None,
+            ),
+            PreviousMethodEnd => (
+                method(
+                    map,
+                    point,
+                    Direction::Prev,
+                    false,
+                    times,
+                    text_layout_details,
+                ),
+                SelectionGoal::None,
+            ),
+            NextHunk => (
+                hunk(map, point, Direction::Next, times),
+                SelectionGoal::None,
+            ),
+            PreviousHunk => (
+                hunk(map, point, Direction::Prev, times),
+                SelectionGoal::None,
+            ),
+            NextDiagnostic { severity } => (
+                diagnostic(map, point, Direction::Next, *severity, times),
+                SelectionGoal::None,
+            ),
+            PreviousDiagnostic { severity } => (
+                diagnostic(map, point, Direction::Prev, *severity, times),
+                SelectionGoal::None,
+            ),
+            UnmatchedForward { char } => (
+                unmatched_bracket(map, point, *char, Direction::Next, times),
+                SelectionGoal::None,
+            ),
+            UnmatchedBackward { char } => (
+                unmatched_bracket(map, point, *char, Direction::Prev, times),
+                SelectionGoal::None,
+            ),
             Jump { line, anchor } => mark::jump_motion(map, *anchor, *line),
+            Forced { motion, .. } => {
+                return motion.move_point(map, point, goal, maybe_times, text_layout_details)
+            }
             ZedSearchResult { new_selections, .. } => {
                 // There will be only one selection, as
                 // Search::SelectNextMatch selects a single match.
@@ -813,27 +1303,39 @@ impl Motion {
         expand_to_surrounding_newline: bool,
         text_layout_details: &TextLayoutDetails,
     ) -> Option<Range<DisplayPoint>> {
+        let mut selection = selection;
         if let Motion::ZedSearchResult {
             prior_selections,
             new_selections,
+            linewise,
+            inclusive,
         } = self
         {
             if let Some((prior_selection, new_selection)) =
                 prior_selections.first().zip(new_selections.first())
             {
-                let start = prior_selection
-                    .start
-                    .to_display_point(map)
-                    .min(new_selection.start.to_display_point(map));
-                let end = new_selection
-                    .end
-                    .to_display_point(map)
-                    .max(prior_selection.end.to_display_point(map));
-
-                if start < end {
-                    return Some(start..end);
+                // a search offset makes the motion linewise or inclusive, which is handled below
+                // like any other motion, starting from where the search started
+                if *linewise || *inclusive {
+                    selection.collapse_to(
+                        prior_selection.start.to_display_point(map),
+                        SelectionGoal::None,
+                    );
                 } else {
-                    return Some(end..start);
+                    let start = prior_selection
+                        .start
+                        .to_display_point(map)
+                        .min(new_selection.start.to_display_point(map));
+                    let end = new_selection
+                        .end
+                        .to_display_point(map)
+                        .max(prior_selection.end.to_display_point(map));
+
+                    if start < end {
+                        return Some(start..end);
+                    } else {
+                        return Some(end..start);
+                    }
                 }
             } else {
                 return None;
@@ -1540,6 +2042,14 @@ fn start_of_document(map: &DisplaySnapshot, point: DisplayPoint, line: usize) ->
     map.clip_point(new_point, Bias::Left)
 }
 
+fn go_to_percentage(map: &DisplaySnapshot, count: usize) -> DisplayPoint {
+    let line_count = map.buffer_snapshot.max_point().row as usize + 1;
+    // Vim rounds up, so that `1%` in a short file moves to the first line.
+    let line = (count.min(100) * line_count + 99) / 100;
+    let new_point = Point::new(line.saturating_sub(1) as u32, 0).to_display_point(map);
+    first_non_whitespace(map, false, new_point)
+}
+
 fn end_of_document(
     map: &DisplaySnapshot,
     point: DisplayPoint,
@@ -1570,15 +2080,36 @@ fn matching(map: &DisplaySnapshot, display_point: DisplayPoint) -> DisplayPoint
     let line_range = map.prev_line_boundary(point).0..line_end;
     let visible_line_range =
         line_range.start..Point::new(line_range.end.row, line_range.end.column.saturating_sub(1));
-    let ranges = map
+    let line_range = line_range.start.to_offset(&map.buffer_snapshot)
+        ..line_range.end.to_offset(&map.buffer_snapshot);
+    let mut closest_pair_destination = None;
+    let mut closest_distance = usize::MAX;
+
+    // Keywords and tags that belong together, such as `if`/`else`/`end`, cycle through
+    // each other in order. They are checked first, so that they take precedence over
+    // brackets inside them, like the `<` of an opening tag.
+    if let Some(groups) = map
         .buffer_snapshot
-        .bracket_ranges(visible_line_range.clone());
-    if let Some(ranges) = ranges {
-        let line_range = line_range.start.to_offset(&map.buffer_snapshot)
-            ..line_range.end.to_offset(&map.buffer_snapshot);
-        let mut closest_pair_destination = None;
-        let mut closest_distance = usize::MAX;
+        .matching_ranges(visible_line_range.clone())
+    {
+        for group in groups {
+            for (ix, range) in group.iter().enumerate() {
+                let distance = if range.contains(&offset) {
+                    0
+                } else if range.start > offset && line_range.contains(&range.start) {
+                    range.start - offset
+                } else {
+                    continue;
+                };
+                if distance < closest_distance {
+                    closest_pair_destination = Some(group[(ix + 1) % group.len()].start);
+                    closest_distance = distance;
+                }
+            }
+        }
+    }
 
+    if let Some(ranges) = map.buffer_snapshot.bracket_ranges(visible_line_range) {
         for (open_range, close_range) in ranges {
             if open_range.start >= offset && line_range.contains(&open_range.start) {
                 let distance = open_range.start - offset;
@@ -1600,13 +2131,11 @@ fn matching(map: &DisplaySnapshot, display_point: DisplayPoint) -> DisplayPoint
 
             continue;
         }
-
-        closest_pair_destination
-            .map(|destination| destination.to_display_point(map))
-            .unwrap_or(display_point)
-    } else {
-        display_point
     }
+
+    closest_pair_destination
+        .map(|destination| destination.to_display_point(map))
+        .unwrap_or(display_point)
 }
 
 fn find_forward(
@@ -1634,9 +2163,10 @@ fn find_forward(
     }
 
     if found {
-        if before && to.column() > 0 {
-            *to.column_mut() -= 1;
-            Some(map.clip_point(to, Bias::Left))
+        if before {
+            // with multiline find the target can start a line, in which
+            // case `t` stops at the end of the line before it.
+            Some(movement::left(map, to))
         } else {
             Some(to)
         }
@@ -1669,8 +2199,17 @@ fn find_backward(
     let next = map.buffer_snapshot.chars_at(to.to_point(map)).next();
     if next.is_some() && is_character_match(target, next.unwrap(), smartcase) {
         if after {
-            *to.column_mut() += 1;
-            map.clip_point(to, Bias::Right)
+            // with multiline find the target can end a line, in which
+            // case `T` stops at the start of the line after it.
+            *to.column_mut() += next.unwrap().len_utf8() as u32;
+            if mode == FindRange::MultiLine
+                && to.column() >= map.line_len(to.row())
+                && to.row() < map.max_point().row()
+            {
+                DisplayPoint::new(to.row().next_row(), 0)
+            } else {
+                map.clip_point(to, Bias::Right)
+            }
         } else {
             to
         }
@@ -1702,8 +2241,9 @@ fn previous_line_start(map: &DisplaySnapshot, point: DisplayPoint, times: usize)
 }
 
 fn go_to_column(map: &DisplaySnapshot, point: DisplayPoint, times: usize) -> DisplayPoint {
-    let correct_line = start_of_relative_buffer_row(map, point, 0);
-    right(map, correct_line, times.saturating_sub(1))
+    // `|` counts screen columns, so tabs count for their expanded width.
+    let new_point = map.point_at_expanded_column(point, times.saturating_sub(1) as u32);
+    map.clip_point(new_point, Bias::Left)
 }
 
 pub(crate) fn next_line_end(
@@ -1717,6 +2257,307 @@ pub(crate) fn next_line_end(
     end_of_line(map, false, point, 1)
 }
 
+/// Language servers that report misspelled words as diagnostics.
+const SPELL_CHECK_SOURCES: &[&str] = &["cspell", "typos", "harper", "codebook", "ltex"];
+
+fn is_misspelling(diagnostic: &Diagnostic) -> bool {
+    diagnostic.source.as_deref().map_or(false, |source| {
+        SPELL_CHECK_SOURCES
+            .iter()
+            .any(|spell_checker| source.eq_ignore_ascii_case(spell_checker))
+    })
+}
+
+fn misspelling(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    direction: Direction,
+    times: usize,
+) -> DisplayPoint {
+    let buffer = &map.buffer_snapshot;
+    let offset = point.to_offset(map, Bias::Left);
+    let mut starts = buffer
+        .diagnostics_in_range::<_, usize>(0..buffer.len(), false)
+        .filter(|entry| is_misspelling(&entry.diagnostic))
+        .map(|entry| entry.range.start)
+        .collect::<Vec<_>>();
+    starts.sort_unstable();
+    starts.dedup();
+
+    let target = match direction {
+        Direction::Next => starts
+            .into_iter()
+            .filter(|start| *start > offset)
+            .take(times)
+            .last(),
+        Direction::Prev => starts
+            .into_iter()
+            .rev()
+            .filter(|start| *start < offset)
+            .take(times)
+            .last(),
+    };
+    target.map_or(point, |offset| offset.to_display_point(map))
+}
+
+/// Like `misspelling`, but wraps around the buffer when there are no more diagnostics in
+/// the given direction.
+fn diagnostic(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    direction: Direction,
+    severity: Option<DiagnosticSeverity>,
+    times: usize,
+) -> DisplayPoint {
+    let buffer = &map.buffer_snapshot;
+    let mut starts = buffer
+        .diagnostics_in_range::<_, usize>(0..buffer.len(), false)
+        .filter(|entry| {
+            entry.diagnostic.is_primary
+                && severity.map_or(true, |severity| entry.diagnostic.severity <= severity)
+        })
+        .map(|entry| entry.range.start)
+        .collect::<Vec<_>>();
+    starts.sort_unstable();
+    starts.dedup();
+    let (Some(&first), Some(&last)) = (starts.first(), starts.last()) else {
+        return point;
+    };
+
+    let mut offset = point.to_offset(map, Bias::Left);
+    for _ in 0..times {
+        offset = match direction {
+            Direction::Next => starts
+                .iter()
+                .copied()
+                .find(|start| *start > offset)
+                .unwrap_or(first),
+            Direction::Prev => starts
+                .iter()
+                .rev()
+                .copied()
+                .find(|start| *start < offset)
+                .unwrap_or(last),
+        };
+    }
+    offset.to_display_point(map)
+}
+
+/// Returns the offsets at which sections start (or end): the top-level items of the
+/// buffer's outline, or, for buffers without one, lines starting with `{` (or `}`).
+fn section_boundaries(buffer: &MultiBufferSnapshot, starts: bool) -> Vec<usize> {
+    if let Some(outline) = buffer
+        .outline(None)
+        .filter(|outline| !outline.items.is_empty())
+    {
+        return outline
+            .items
+            .iter()
+            .filter(|item| item.depth == 0)
+            .map(|item| {
+                if starts {
+                    item.range.start.to_offset(buffer)
+                } else {
+                    let end = item.range.end.to_offset(buffer);
+                    buffer.clip_offset(end.saturating_sub(1), Bias::Left)
+                }
+            })
+            .collect();
+    }
+
+    let boundary = if starts { '{' } else { '}' };
+    (0..=buffer.max_point().row)
+        .map(|row| Point::new(row, 0))
+        .filter(|point| buffer.chars_at(*point).next() == Some(boundary))
+        .map(|point| point.to_offset(buffer))
+        .collect()
+}
+
+fn section(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    direction: Direction,
+    starts: bool,
+    times: usize,
+) -> DisplayPoint {
+    let buffer = &map.buffer_snapshot;
+    let offset = point.to_offset(map, Bias::Left);
+    let mut boundaries = section_boundaries(buffer, starts);
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    // Like in Vim, running out of sections moves to the start or end of the buffer.
+    let target = match direction {
+        Direction::Next => boundaries
+            .into_iter()
+            .filter(|boundary| *boundary > offset)
+            .take(times)
+            .last()
+            .unwrap_or(buffer.len()),
+        Direction::Prev => boundaries
+            .into_iter()
+            .rev()
+            .filter(|boundary| *boundary < offset)
+            .take(times)
+            .last()
+            .unwrap_or(0),
+    };
+    map.clip_point(target.to_display_point(map), Bias::Left)
+}
+
+/// Returns the offsets at which the functions and methods found by the language's text
+/// object query in `range` start (or end). Text objects are only found within one excerpt,
+/// so each excerpt `range` spans is queried on its own.
+fn method_boundaries(
+    buffer: &MultiBufferSnapshot,
+    range: Range<usize>,
+    starts: bool,
+) -> impl Iterator<Item = usize> + '_ {
+    let range = buffer.anchor_after(range.start)..buffer.anchor_before(range.end);
+    buffer
+        .split_ranges([range])
+        .flat_map(move |range| {
+            let range = range.start.to_offset(buffer)..range.end.to_offset(buffer);
+            buffer.text_object_ranges(range).into_iter().flatten()
+        })
+        .filter(|(_, text_object)| *text_object == TextObject::AroundFunction)
+        .map(move |(range, _)| {
+            if starts {
+                range.start
+            } else {
+                buffer.clip_offset(range.end.saturating_sub(1), Bias::Left)
+            }
+        })
+}
+
+fn method(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    direction: Direction,
+    starts: bool,
+    times: usize,
+    text_layout_details: &TextLayoutDetails,
+) -> DisplayPoint {
+    let buffer = &map.buffer_snapshot;
+    let offset = point.to_offset(map, Bias::Left);
+    let row = buffer.offset_to_point(offset).row;
+    let max_row = buffer.max_point().row;
+    let window = text_layout_details
+        .visible_rows
+        .map_or(50, |rows| rows.ceil() as u32)
+        .max(1);
+
+    // Functions are looked up a screenful at a time, moving away from the cursor, until
+    // enough of them are found, rather than across the whole buffer.
+    let mut boundaries = Vec::new();
+    let mut rows_searched = 0;
+    loop {
+        rows_searched += window;
+        let range = match direction {
+            Direction::Next => {
+                let end_row = (row + rows_searched).min(max_row);
+                offset
+                    ..Point::new(end_row, buffer.line_len(MultiBufferRow(end_row)))
+                        .to_offset(buffer)
+            }
+            Direction::Prev => {
+                Point::new(row.saturating_sub(rows_searched), 0).to_offset(buffer)..offset
+            }
+        };
+        let reached_edge = match direction {
+            Direction::Next => range.end == buffer.len(),
+            Direction::Prev => range.start == 0,
+        };
+
+        boundaries.clear();
+        boundaries.extend(method_boundaries(buffer, range, starts).filter(
+            |boundary| match direction {
+                Direction::Next => *boundary > offset,
+                Direction::Prev => *boundary < offset,
+            },
+        ));
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        if boundaries.len() >= times || reached_edge {
+            break;
+        }
+    }
+
+    let target = match direction {
+        Direction::Next => boundaries.into_iter().take(times).last(),
+        Direction::Prev => boundaries.into_iter().rev().take(times).last(),
+    };
+    target.map_or(point, |offset| offset.to_display_point(map))
+}
+
+/// Moves to the first line of the `times`-th git diff hunk that starts after (or before)
+/// the cursor's row, stopping at the last hunk in that direction.
+fn hunk(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    direction: Direction,
+    times: usize,
+) -> DisplayPoint {
+    let start_row = point.to_point(map).row;
+    let mut row = start_row;
+    for _ in 0..times {
+        let next_row = match direction {
+            Direction::Next => map
+                .buffer_snapshot
+                .git_diff_hunks_in_range(MultiBufferRow(row + 1)..MultiBufferRow::MAX)
+                .map(|hunk| hunk.associated_range.start.0)
+                .find(|start| *start > row),
+            Direction::Prev => map
+                .buffer_snapshot
+                .git_diff_hunks_in_range_rev(MultiBufferRow(0)..MultiBufferRow(row))
+                .map(|hunk| hunk.associated_range.start.0)
+                .find(|start| *start < row),
+        };
+        let Some(next_row) = next_row else {
+            break;
+        };
+        row = next_row;
+    }
+    if row == start_row {
+        return point;
+    }
+    map.clip_point(Point::new(row, 0).to_display_point(map), Bias::Left)
+}
+
+/// Finds the closest enclosing bracket pair whose closing (or, going backwards, opening)
+/// bracket is `char`, repeating `times` times to move out through nested pairs.
+fn unmatched_bracket(
+    map: &DisplaySnapshot,
+    point: DisplayPoint,
+    char: char,
+    direction: Direction,
+    times: usize,
+) -> DisplayPoint {
+    let mut offset = point.to_offset(map, Bias::Left);
+    for _ in 0..times {
+        let Some(ranges) = map.buffer_snapshot.enclosing_bracket_ranges(offset..offset) else {
+            break;
+        };
+        let target = match direction {
+            Direction::Next => ranges
+                .filter(|(open, close)| open.start <= offset && offset < close.start)
+                .map(|(_, close)| close.start)
+                .filter(|close| map.buffer_snapshot.chars_at(*close).next() == Some(char))
+                .min(),
+            Direction::Prev => ranges
+                .filter(|(open, close)| open.start < offset && offset <= close.start)
+                .map(|(open, _)| open.start)
+                .filter(|open| map.buffer_snapshot.chars_at(*open).next() == Some(char))
+                .max(),
+        };
+        let Some(target) = target else {
+            break;
+        };
+        offset = target;
+    }
+    offset.to_display_point(map)
+}
+
 fn window_top(
     map: &DisplaySnapshot,
     point: DisplayPoint,
@@ -1736,17 +2577,19 @@ fn window_top(
 
     if let Some(visible_rows) = text_layout_details.visible_rows {
         let bottom_row = first_visible_line.row().0 + visible_rows as u32;
-        let new_row = (first_visible_line.row().0 + (times as u32))
-            .min(bottom_row)
-            .min(map.max_point().row().0);
-        let new_col = point.column().min(map.line_len(first_visible_line.row()));
+        let new_row = DisplayRow(
+            (first_visible_line.row().0 + (times as u32))
+                .min(bottom_row)
+                .min(map.max_point().row().0),
+        );
+        let new_col = point.column().min(map.line_len(new_row));
 
-        let new_point = DisplayPoint::new(DisplayRow(new_row), new_col);
+        let new_point = DisplayPoint::new(new_row, new_col);
         (map.clip_point(new_point, Bias::Left), SelectionGoal::None)
     } else {
         let new_row =
             DisplayRow((first_visible_line.row().0 + (times as u32)).min(map.max_point().row().0));
-        let new_col = point.column().min(map.line_len(first_visible_line.row()));
+        let new_col = point.column().min(map.line_len(new_row));
 
         let new_point = DisplayPoint::new(new_row, new_col);
         (map.clip_point(new_point, Bias::Left), SelectionGoal::None)
@@ -1814,8 +2657,17 @@ fn window_bottom(
 #[cfg(test)]
 mod test {
 
-    use crate::test::NeovimBackedTestContext;
+    use crate::{
+        motion::{NextDiagnostic, Severity},
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+        VimSettings,
+    };
     use indoc::indoc;
+    use language::{
+        Diagnostic, DiagnosticEntry, DiagnosticSet, DiagnosticSeverity, LanguageServerId,
+    };
+    use settings::SettingsStore;
 
     #[gpui::test]
     async fn test_start_end_of_paragraph(cx: &mut gpui::TestAppContext) {
@@ -1935,6 +2787,43 @@ mod test {
         cx.shared_state().await.assert_eq("func boop(ˇ) {\n}");
     }
 
+    #[gpui::test]
+    async fn test_matching_tags(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new_html(cx).await;
+
+        cx.set_state(
+            indoc! {"
+                <div>
+                  ˇ<p>hi</p>
+                </div>"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("%");
+        cx.assert_state(
+            indoc! {"
+                <div>
+                  <p>hiˇ</p>
+                </div>"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("%");
+        cx.assert_state(
+            indoc! {"
+                <div>
+                  ˇ<p>hi</p>
+                </div>"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("k l %");
+        cx.assert_state(
+            indoc! {"
+                <div>
+                  <p>hi</p>
+                ˇ</div>"},
+            Mode::Normal,
+        );
+    }
+
     #[gpui::test]
     async fn test_comma_semicolon(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -2059,6 +2948,498 @@ mod test {
           7 8 ˇ9"});
     }
 
+    #[gpui::test]
+    async fn test_window_top_with_count_keeps_column(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state(
+            indoc! {"
+                a
+                bcdefgh
+                ijklmnˇo"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("2 shift-h");
+        cx.assert_state(
+            indoc! {"
+                a
+                bcdefgˇh
+                ijklmno"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_go_to_column_with_tabs(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("\tabcˇdef", Mode::Normal);
+        cx.simulate_keystrokes("5 |");
+        cx.assert_state("\tˇabcdef", Mode::Normal);
+        cx.simulate_keystrokes("3 |");
+        cx.assert_state("ˇ\tabcdef", Mode::Normal);
+        cx.simulate_keystrokes("2 0 |");
+        cx.assert_state("\tabcdeˇf", Mode::Normal);
+
+        cx.set_state("ˇ\tabcdef", Mode::Normal);
+        cx.simulate_keystrokes("d 7 |");
+        cx.assert_state("ˇcdef", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_section_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                ˇuse std::fmt;
+
+                fn one() {
+                    1
+                }
+
+                struct Two;
+
+                impl Two {
+                    fn two() {}
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] ]");
+        cx.assert_state(
+            indoc! {"
+                use std::fmt;
+
+                ˇfn one() {
+                    1
+                }
+
+                struct Two;
+
+                impl Two {
+                    fn two() {}
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("2 ] ]");
+        cx.assert_state(
+            indoc! {"
+                use std::fmt;
+
+                fn one() {
+                    1
+                }
+
+                struct Two;
+
+                ˇimpl Two {
+                    fn two() {}
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ ]");
+        cx.assert_state(
+            indoc! {"
+                use std::fmt;
+
+                fn one() {
+                    1
+                }
+
+                struct Twoˇ;
+
+                impl Two {
+                    fn two() {}
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] [");
+        cx.assert_state(
+            indoc! {"
+                use std::fmt;
+
+                fn one() {
+                    1
+                }
+
+                struct Two;
+
+                impl Two {
+                    fn two() {}
+                ˇ}"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("2 [ [");
+        cx.assert_state(
+            indoc! {"
+                use std::fmt;
+
+                fn one() {
+                    1
+                }
+
+                ˇstruct Two;
+
+                impl Two {
+                    fn two() {}
+                }"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_section_motions_without_outline(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                ˇa
+                {
+                  b
+                }
+                c
+                {
+                  d
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] ]");
+        cx.assert_state(
+            indoc! {"
+                a
+                ˇ{
+                  b
+                }
+                c
+                {
+                  d
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] [");
+        cx.assert_state(
+            indoc! {"
+                a
+                {
+                  b
+                ˇ}
+                c
+                {
+                  d
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("d [ [");
+        cx.assert_state(
+            indoc! {"
+                a
+                ˇ}
+                c
+                {
+                  d
+                }"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_method_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                ˇimpl Foo {
+                    fn one() {
+                        1
+                    }
+
+                    fn two() {}
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] m");
+        cx.assert_state(
+            indoc! {"
+                impl Foo {
+                    ˇfn one() {
+                        1
+                    }
+
+                    fn two() {}
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] shift-m");
+        cx.assert_state(
+            indoc! {"
+                impl Foo {
+                    fn one() {
+                        1
+                    ˇ}
+
+                    fn two() {}
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] m");
+        cx.assert_state(
+            indoc! {"
+                impl Foo {
+                    fn one() {
+                        1
+                    }
+
+                    ˇfn two() {}
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ shift-m");
+        cx.assert_state(
+            indoc! {"
+                impl Foo {
+                    fn one() {
+                        1
+                    ˇ}
+
+                    fn two() {}
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ m d ] m");
+        cx.assert_state(
+            indoc! {"
+                impl Foo {
+                    ˇfn two() {}
+                }"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_method_motions_with_count(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        let functions = (0..40)
+            .map(|ix| format!("fn f{ix}() {{\n    {ix}\n}}\n"))
+            .collect::<Vec<_>>();
+        cx.set_state(&format!("ˇ{}", functions.concat()), Mode::Normal);
+        cx.simulate_keystrokes("3 0 ] m");
+        cx.assert_state(
+            &format!("{}ˇ{}", functions[..30].concat(), functions[30..].concat()),
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("2 5 [ m");
+        cx.assert_state(
+            &format!("{}ˇ{}", functions[..5].concat(), functions[5..].concat()),
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("1 0 0 ] m");
+        cx.assert_state(
+            &format!("{}ˇ{}", functions[..39].concat(), functions[39..].concat()),
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_unmatched_bracket_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                fn a() {
+                    if (b(1, ˇ2)) {
+                        c();
+                    }
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] )");
+        cx.assert_state(
+            indoc! {"
+                fn a() {
+                    if (b(1, 2ˇ)) {
+                        c();
+                    }
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ (");
+        cx.assert_state(
+            indoc! {"
+                fn a() {
+                    if (bˇ(1, 2)) {
+                        c();
+                    }
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ (");
+        cx.assert_state(
+            indoc! {"
+                fn a() {
+                    if ˇ(b(1, 2)) {
+                        c();
+                    }
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] }");
+        cx.assert_state(
+            indoc! {"
+                fn a() {
+                    if (b(1, 2)) {
+                        c();
+                    }
+                ˇ}"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ {");
+        cx.assert_state(
+            indoc! {"
+                fn a() ˇ{
+                    if (b(1, 2)) {
+                        c();
+                    }
+                }"},
+            Mode::Normal,
+        );
+
+        cx.set_state("fn a() { c(b(1, ˇ2)) }", Mode::Normal);
+        cx.simulate_keystrokes("2 ] )");
+        cx.assert_state("fn a() { c(b(1, 2)ˇ) }", Mode::Normal);
+
+        cx.set_state("fn a() { c(b(1, ˇ2)) }", Mode::Normal);
+        cx.simulate_keystrokes("d ] )");
+        cx.assert_state("fn a() { c(b(1, ˇ)) }", Mode::Normal);
+
+        cx.set_state("fn a() { c(b(1, ˇ2)) }", Mode::Normal);
+        cx.simulate_keystrokes("d [ (");
+        cx.assert_state("fn a() { c(bˇ2)) }", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_hunk_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                ˇone
+                two
+                three
+                four
+                five
+                six"},
+            Mode::Normal,
+        );
+        cx.set_diff_base(Some(indoc! {"
+            one
+            TWO
+            three
+            four
+            FIVE
+            six"}));
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes("] c");
+        cx.assert_state(
+            indoc! {"
+                one
+                ˇtwo
+                three
+                four
+                five
+                six"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] c");
+        cx.assert_state(
+            indoc! {"
+                one
+                two
+                three
+                four
+                ˇfive
+                six"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("] c");
+        cx.assert_state(
+            indoc! {"
+                one
+                two
+                three
+                four
+                ˇfive
+                six"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ c");
+        cx.assert_state(
+            indoc! {"
+                one
+                ˇtwo
+                three
+                four
+                five
+                six"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g g 2 ] c");
+        cx.assert_state(
+            indoc! {"
+                one
+                two
+                three
+                four
+                ˇfive
+                six"},
+            Mode::Normal,
+        );
+        // hunk motions are jumps
+        cx.simulate_keystrokes("ctrl-o");
+        cx.run_until_parked();
+        cx.assert_state(
+            indoc! {"
+                ˇone
+                two
+                three
+                four
+                five
+                six"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g g d ] c");
+        cx.assert_state(
+            indoc! {"
+                ˇtwo
+                three
+                four
+                five
+                six"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_go_to_percentage(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+        let text = indoc! {"
+            ˇone
+            two
+            three
+            four
+              five
+            six
+            seven
+            eight
+            nine
+            ten"};
+
+        cx.simulate("5 0 %", text).await.assert_matches();
+        cx.simulate("1 %", text).await.assert_matches();
+        cx.simulate("1 0 0 %", text).await.assert_matches();
+        cx.simulate("d 2 0 %", text).await.assert_matches();
+    }
+
     #[gpui::test]
     async fn test_window_middle(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -2329,4 +3710,185 @@ mod test {
             }ˇ»
         "});
     }
+
+    #[gpui::test]
+    async fn test_whichwrap(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                abc
+                ˇdef"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("h");
+        cx.assert_state(
+            indoc! {"
+                abc
+                ˇdef"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("backspace");
+        cx.assert_state(
+            indoc! {"
+                abˇc
+                def"},
+            Mode::Normal,
+        );
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store
+                .update_user_settings::<VimSettings>(cx, |s| s.whichwrap = Some("h,l".to_string()));
+        });
+
+        cx.simulate_keystrokes("l");
+        cx.assert_state(
+            indoc! {"
+                abc
+                ˇdef"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("h");
+        cx.assert_state(
+            indoc! {"
+                abˇc
+                def"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("j 0 backspace");
+        cx.assert_state(
+            indoc! {"
+                abc
+                ˇdef"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_misspelling_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state(
+            indoc! {"
+                ˇThe qick brown fox jumpd over
+                the lazzy dog"},
+            Mode::Normal,
+        );
+        cx.update_buffer(|buffer, cx| {
+            let entry = |range: std::ops::Range<usize>, source: &str| DiagnosticEntry {
+                range: buffer.anchor_before(range.start)..buffer.anchor_after(range.end),
+                diagnostic: Diagnostic {
+                    source: Some(source.to_string()),
+                    message: "Unknown word".to_string(),
+                    ..Default::default()
+                },
+            };
+            let diagnostics = DiagnosticSet::from_sorted_entries(
+                vec![
+                    entry(4..8, "cSpell"),
+                    entry(9..14, "rust-analyzer"),
+                    entry(19..24, "cSpell"),
+                    entry(34..39, "cSpell"),
+                ],
+                &buffer.text_snapshot(),
+            );
+            buffer.update_diagnostics(LanguageServerId(0), diagnostics, cx);
+        });
+
+        cx.simulate_keystrokes("] s");
+        cx.assert_state(
+            indoc! {"
+                The ˇqick brown fox jumpd over
+                the lazzy dog"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("2 ] s");
+        cx.assert_state(
+            indoc! {"
+                The qick brown fox jumpd over
+                the ˇlazzy dog"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("[ s");
+        cx.assert_state(
+            indoc! {"
+                The qick brown fox ˇjumpd over
+                the lazzy dog"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("d ] s");
+        cx.assert_state("The qick brown fox ˇlazzy dog", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_diagnostic_motions(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state("ˇone two three four", Mode::Normal);
+        cx.update_buffer(|buffer, cx| {
+            let entry = |range: std::ops::Range<usize>, severity, group_id| DiagnosticEntry {
+                range: buffer.anchor_before(range.start)..buffer.anchor_after(range.end),
+                diagnostic: Diagnostic {
+                    severity,
+                    group_id,
+                    is_primary: true,
+                    ..Default::default()
+                },
+            };
+            let diagnostics = DiagnosticSet::from_sorted_entries(
+                vec![
+                    entry(4..7, DiagnosticSeverity::ERROR, 0),
+                    entry(8..13, DiagnosticSeverity::WARNING, 1),
+                    entry(14..18, DiagnosticSeverity::HINT, 2),
+                ],
+                &buffer.text_snapshot(),
+            );
+            buffer.update_diagnostics(LanguageServerId(0), diagnostics, cx);
+        });
+
+        cx.simulate_keystrokes("] d");
+        cx.assert_state("one ˇtwo three four", Mode::Normal);
+        cx.update_editor(|editor, _| assert_eq!(editor.active_diagnostic_group(), Some(0)));
+        cx.simulate_keystrokes("2 ] d");
+        cx.assert_state("one two three ˇfour", Mode::Normal);
+        cx.simulate_keystrokes("] d");
+        cx.assert_state("one ˇtwo three four", Mode::Normal);
+        cx.simulate_keystrokes("[ d");
+        cx.assert_state("one two three ˇfour", Mode::Normal);
+        cx.update_editor(|editor, _| assert_eq!(editor.active_diagnostic_group(), Some(2)));
+
+        cx.dispatch_action(NextDiagnostic {
+            severity: Some(Severity::Warning),
+        });
+        cx.assert_state("one ˇtwo three four", Mode::Normal);
+        cx.dispatch_action(NextDiagnostic {
+            severity: Some(Severity::Warning),
+        });
+        cx.assert_state("one two ˇthree four", Mode::Normal);
+
+        cx.simulate_keystrokes("d [ d");
+        cx.assert_state("one ˇthree four", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_forced_motion(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            "The ˇquick brown\nfox jumps over\nthe lazy dog",
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("d v j");
+        cx.assert_state("The ˇjumps over\nthe lazy dog", Mode::Normal);
+
+        cx.set_state("The ˇquick brown\nfox jumps over", Mode::Normal);
+        cx.simulate_keystrokes("d v e");
+        cx.assert_state("The ˇk brown\nfox jumps over", Mode::Normal);
+
+        cx.set_state("The ˇquick brown\nfox jumps over", Mode::Normal);
+        cx.simulate_keystrokes("d shift-v e");
+        cx.assert_state("ˇfox jumps over", Mode::Normal);
+
+        cx.set_state("The ˇquick brown\nfox jumps over", Mode::Normal);
+        cx.simulate_keystrokes("d ctrl-v j");
+        cx.assert_state("The ˇuick brown\nfox umps over", Mode::Normal);
+    }
 }
@@ -1,15 +1,18 @@
-mod case;
+mod align;
+pub(crate) mod case;
 mod change;
 mod delete;
+mod exchange;
 mod increment;
 mod indent;
 pub(crate) mod mark;
-mod paste;
+pub(crate) mod paste;
 pub(crate) mod repeat;
+mod rewrap;
 mod scroll;
 pub(crate) mod search;
 pub mod substitute;
-mod toggle_comments;
+pub(crate) mod toggle_comments;
 pub(crate) mod yank;
 
 use std::collections::HashMap;
@@ -28,6 +31,7 @@ use editor::scroll::Autoscroll;
 use editor::Anchor;
 use editor::Bias;
 use editor::Editor;
+use editor::JoinLinesStyle;
 use editor::{display_map::ToDisplayPoint, movement};
 use gpui::{actions, ViewContext};
 use language::{Point, SelectionGoal};
@@ -56,10 +60,15 @@ actions!(
         ChangeCase,
         ConvertToUpperCase,
         ConvertToLowerCase,
+        ConvertToRot13,
         JoinLines,
+        JoinLinesNoWhitespace,
         Indent,
         Outdent,
+        AutoIndent,
         ToggleComments,
+        Rewrap,
+        ClearExchange,
         Undo,
         Redo,
     ]
@@ -76,9 +85,12 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
     Vim::action(editor, cx, Vim::change_case);
     Vim::action(editor, cx, Vim::convert_to_upper_case);
     Vim::action(editor, cx, Vim::convert_to_lower_case);
+    Vim::action(editor, cx, Vim::convert_to_rot13);
     Vim::action(editor, cx, Vim::yank_line);
     Vim::action(editor, cx, Vim::yank_to_end_of_line);
     Vim::action(editor, cx, Vim::toggle_comments);
+    Vim::action(editor, cx, Vim::rewrap);
+    Vim::action(editor, cx, Vim::clear_exchange);
     Vim::action(editor, cx, Vim::paste);
 
     Vim::action(editor, cx, |vim, _: &DeleteLeft, cx| {
@@ -114,56 +126,50 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
         );
     });
     Vim::action(editor, cx, |vim, _: &JoinLines, cx| {
-        vim.record_current_action(cx);
-        let mut times = vim.take_count(cx).unwrap_or(1);
-        if vim.mode.is_visual() {
-            times = 1;
-        } else if times > 1 {
-            // 2J joins two lines together (same as J or 1J)
-            times -= 1;
-        }
+        vim.join_lines(JoinLinesStyle::Vim, cx);
+    });
 
-        vim.update_editor(cx, |_, editor, cx| {
-            editor.transact(cx, |editor, cx| {
-                for _ in 0..times {
-                    editor.join_lines(&Default::default(), cx)
-                }
-            })
-        });
-        if vim.mode.is_visual() {
-            vim.switch_mode(Mode::Normal, false, cx)
-        }
+    Vim::action(editor, cx, |vim, _: &JoinLinesNoWhitespace, cx| {
+        vim.join_lines(JoinLinesStyle::VimNoWhitespace, cx);
     });
 
     Vim::action(editor, cx, |vim, _: &Indent, cx| {
         vim.record_current_action(cx);
         let count = vim.take_count(cx).unwrap_or(1);
+        if vim.mode.is_visual() {
+            vim.indent_selections(IndentDirection::In, count, cx);
+            vim.switch_mode(Mode::Normal, false, cx);
+            return;
+        }
         vim.update_editor(cx, |_, editor, cx| {
             editor.transact(cx, |editor, cx| {
                 let mut original_positions = save_selection_starts(editor, cx);
-                for _ in 0..count {
-                    editor.indent(&Default::default(), cx);
-                }
+                indent::shift_lines(editor, &IndentDirection::In, count, true, cx);
                 restore_selection_cursors(editor, cx, &mut original_positions);
             });
         });
-        if vim.mode.is_visual() {
-            vim.switch_mode(Mode::Normal, false, cx)
-        }
     });
 
     Vim::action(editor, cx, |vim, _: &Outdent, cx| {
         vim.record_current_action(cx);
         let count = vim.take_count(cx).unwrap_or(1);
+        if vim.mode.is_visual() {
+            vim.indent_selections(IndentDirection::Out, count, cx);
+            vim.switch_mode(Mode::Normal, false, cx);
+            return;
+        }
         vim.update_editor(cx, |_, editor, cx| {
             editor.transact(cx, |editor, cx| {
                 let mut original_positions = save_selection_starts(editor, cx);
-                for _ in 0..count {
-                    editor.outdent(&Default::default(), cx);
-                }
+                indent::shift_lines(editor, &IndentDirection::Out, count, true, cx);
                 restore_selection_cursors(editor, cx, &mut original_positions);
             });
         });
+    });
+
+    Vim::action(editor, cx, |vim, _: &AutoIndent, cx| {
+        vim.record_current_action(cx);
+        vim.indent_selections(IndentDirection::Auto, 1, cx);
         if vim.mode.is_visual() {
             vim.switch_mode(Mode::Normal, false, cx)
         }
@@ -189,6 +195,7 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
     repeat::register(editor, cx);
     scroll::register(editor, cx);
     search::register(editor, cx);
+    mark::register(editor, cx);
     substitute::register(editor, cx);
     increment::register(editor, cx);
 }
@@ -209,6 +216,9 @@ impl Vim {
             Some(Operator::AddSurrounds { target: None }) => {}
             Some(Operator::Indent) => self.indent_motion(motion, times, IndentDirection::In, cx),
             Some(Operator::Outdent) => self.indent_motion(motion, times, IndentDirection::Out, cx),
+            Some(Operator::AutoIndent) => {
+                self.indent_motion(motion, times, IndentDirection::Auto, cx)
+            }
             Some(Operator::Lowercase) => {
                 self.change_case_motion(motion, times, CaseTarget::Lowercase, cx)
             }
@@ -218,7 +228,16 @@ impl Vim {
             Some(Operator::OppositeCase) => {
                 self.change_case_motion(motion, times, CaseTarget::OppositeCase, cx)
             }
-            Some(Operator::ToggleComments) => self.toggle_comments_motion(motion, times, cx),
+            Some(Operator::Rot13) => self.change_case_motion(motion, times, CaseTarget::Rot13, cx),
+            Some(Operator::Rewrap) => self.rewrap_motion(motion, times, cx),
+            Some(Operator::ReplaceWithRegister) => {
+                self.replace_with_register_motion(motion, times, cx)
+            }
+            Some(Operator::Exchange) => self.exchange_motion(motion, times, cx),
+            Some(Operator::ShellCommand) => self.shell_command_motion(motion, times, cx),
+            Some(Operator::Registered(operator)) => {
+                self.registered_operator_motion(&operator, motion, times, cx)
+            }
             Some(operator) => {
                 // Can't do anything for text objects, Ignoring
                 error!("Unexpected normal mode motion operator: {:?}", operator)
@@ -228,32 +247,54 @@ impl Vim {
 
     pub fn normal_object(&mut self, object: Object, cx: &mut ViewContext<Self>) {
         let mut waiting_operator: Option<Operator> = None;
+        let times = self.take_count(cx);
         match self.maybe_pop_operator() {
             Some(Operator::Object { around }) => match self.maybe_pop_operator() {
-                Some(Operator::Change) => self.change_object(object, around, cx),
-                Some(Operator::Delete) => self.delete_object(object, around, cx),
-                Some(Operator::Yank) => self.yank_object(object, around, cx),
+                Some(Operator::Change) => self.change_object(object, around, times, cx),
+                Some(Operator::Delete) => self.delete_object(object, around, times, cx),
+                Some(Operator::Yank) => self.yank_object(object, around, times, cx),
                 Some(Operator::Indent) => {
-                    self.indent_object(object, around, IndentDirection::In, cx)
+                    self.indent_object(object, around, times, IndentDirection::In, cx)
                 }
                 Some(Operator::Outdent) => {
-                    self.indent_object(object, around, IndentDirection::Out, cx)
+                    self.indent_object(object, around, times, IndentDirection::Out, cx)
+                }
+                Some(Operator::AutoIndent) => {
+                    self.indent_object(object, around, times, IndentDirection::Auto, cx)
                 }
                 Some(Operator::Lowercase) => {
-                    self.change_case_object(object, around, CaseTarget::Lowercase, cx)
+                    self.change_case_object(object, around, times, CaseTarget::Lowercase, cx)
                 }
                 Some(Operator::Uppercase) => {
-                    self.change_case_object(object, around, CaseTarget::Uppercase, cx)
+                    self.change_case_object(object, around, times, CaseTarget::Uppercase, cx)
                 }
                 Some(Operator::OppositeCase) => {
-                    self.change_case_object(object, around, CaseTarget::OppositeCase, cx)
+                    self.change_case_object(object, around, times, CaseTarget::OppositeCase, cx)
+                }
+                Some(Operator::Rot13) => {
+                    self.change_case_object(object, around, times, CaseTarget::Rot13, cx)
                 }
                 Some(Operator::AddSurrounds { target: None }) => {
                     waiting_operator = Some(Operator::AddSurrounds {
-                        target: Some(SurroundsType::Object(object)),
+                        target: Some(SurroundsType::Object(object, false)),
+                    });
+                }
+                Some(Operator::Align { target: None }) => {
+                    waiting_operator = Some(Operator::Align {
+                        target: Some(SurroundsType::Object(object, around)),
                     });
                 }
-                Some(Operator::ToggleComments) => self.toggle_comments_object(object, around, cx),
+                Some(Operator::Rewrap) => self.rewrap_object(object, around, times, cx),
+                Some(Operator::ReplaceWithRegister) => {
+                    self.replace_with_register_object(object, around, times, cx)
+                }
+                Some(Operator::Exchange) => self.exchange_object(object, around, times, cx),
+                Some(Operator::ShellCommand) => {
+                    self.shell_command_object(object, around, times, cx)
+                }
+                Some(Operator::Registered(operator)) => {
+                    self.registered_operator_object(&operator, object, around, times, cx)
+                }
                 _ => {
                     // Can't do anything for namespace operators. Ignoring
                 }
@@ -274,7 +315,34 @@ impl Vim {
         }
         self.clear_operator(cx);
         if let Some(operator) = waiting_operator {
+            // `g=` applies the count to the object once the delimiter is typed.
+            let keeps_count = matches!(operator, Operator::Align { .. });
             self.push_operator(operator, cx);
+            if keeps_count {
+                self.pre_count = times;
+            }
+        }
+    }
+
+    fn join_lines(&mut self, style: JoinLinesStyle, cx: &mut ViewContext<Self>) {
+        self.record_current_action(cx);
+        let mut times = self.take_count(cx).unwrap_or(1);
+        if self.mode.is_visual() {
+            times = 1;
+        } else if times > 1 {
+            // 2J joins two lines together (same as J or 1J)
+            times -= 1;
+        }
+
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(cx, |editor, cx| {
+                for _ in 0..times {
+                    editor.join_lines_impl(style, cx)
+                }
+            })
+        });
+        if self.mode.is_visual() {
+            self.switch_mode(Mode::Normal, false, cx)
         }
     }
 
@@ -284,15 +352,17 @@ impl Vim {
         times: Option<usize>,
         cx: &mut ViewContext<Self>,
     ) {
-        self.update_editor(cx, |_, editor, cx| {
-            let text_layout_details = editor.text_layout_details(cx);
-            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
-                s.move_cursors_with(|map, cursor, goal| {
-                    motion
-                        .move_point(map, cursor, goal, times, &text_layout_details)
-                        .unwrap_or((cursor, goal))
+        self.record_jump(motion.is_jump(), cx, |vim, cx| {
+            vim.update_editor(cx, |_, editor, cx| {
+                let text_layout_details = editor.text_layout_details(cx);
+                editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                    s.move_cursors_with(|map, cursor, goal| {
+                        motion
+                            .move_point(map, cursor, goal, times, &text_layout_details)
+                            .unwrap_or((cursor, goal))
+                    })
                 })
-            })
+            });
         });
     }
 
@@ -473,10 +543,16 @@ impl Vim {
                         range.end = new_point;
                     }
 
+                    // Like vim, `[count]r<Enter>` replaces the characters with a single line break.
+                    let new_text = if text.as_ref() == "\n" {
+                        text.to_string()
+                    } else {
+                        text.repeat(count)
+                    };
                     edits.push((
                         range.start.to_offset(&map, Bias::Left)
                             ..range.end.to_offset(&map, Bias::Left),
-                        text.repeat(count),
+                        new_text,
                     ))
                 }
 
@@ -1277,6 +1353,87 @@ mod test {
         );
     }
 
+    #[gpui::test]
+    async fn test_repeat_f_and_t_multiline(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.use_multiline_find = Some(true);
+            });
+        });
+
+        cx.set_state(
+            indoc! {"
+            ˇfn a() {
+                b();
+            }
+            b();
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("f b");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                ˇb();
+            }
+            b();
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(";");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                b();
+            }
+            ˇb();
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(",");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                ˇb();
+            }
+            b();
+            "},
+            Mode::Normal,
+        );
+
+        cx.simulate_keystrokes("0 t }");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                b()ˇ;
+            }
+            b();
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g g t b ;");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+                b();
+            ˇ}
+            b();
+            "},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("shift-g k shift-t {");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+            ˇ    b();
+            }
+            b();
+            "},
+            Mode::Normal,
+        );
+    }
+
     #[gpui::test]
     async fn test_f_and_t_smartcase(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -1441,4 +1598,17 @@ mod test {
         cx.simulate_shared_keystrokes("2 0 r - ").await;
         cx.shared_state().await.assert_eq("ˇhello world\n");
     }
+
+    #[gpui::test]
+    async fn test_r_enter(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("heˇllo world", Mode::Normal);
+        cx.simulate_keystrokes("r enter");
+        cx.assert_state("he\nˇlo world", Mode::Normal);
+
+        cx.set_state("heˇllo world", Mode::Normal);
+        cx.simulate_keystrokes("3 r enter");
+        cx.assert_state("he\nˇ world", Mode::Normal);
+    }
 }
@@ -0,0 +1,313 @@
+use std::sync::Arc;
+
+use crate::{state::Mode, surrounds::SurroundsType, Vim};
+use editor::{display_map::ToDisplayPoint, scroll::Autoscroll};
+use language::Point;
+use multi_buffer::MultiBufferRow;
+use ui::ViewContext;
+
+impl Vim {
+    /// Aligns the lines covered by `target` on every occurrence of `delimiter` (`g=ip=`).
+    pub fn align(
+        &mut self,
+        delimiter: Arc<str>,
+        target: SurroundsType,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.stop_recording(cx);
+        let count = self.take_count(cx);
+        self.update_editor(cx, |_, editor, cx| {
+            let text_layout_details = editor.text_layout_details(cx);
+            editor.transact(cx, |editor, cx| {
+                let (map, selections) = editor.selections.all_adjusted_display(cx);
+                let buffer = &map.buffer_snapshot;
+                let mut edits = Vec::new();
+                let mut cursors = Vec::new();
+                let mut previous_end_row = None;
+
+                for selection in selections {
+                    let range = match &target {
+                        SurroundsType::Motion(motion) => motion.range(
+                            &map,
+                            selection.clone(),
+                            count,
+                            false,
+                            &text_layout_details,
+                        ),
+                        SurroundsType::Object(object, around) => {
+                            object.range(&map, selection.clone(), *around, count)
+                        }
+                        SurroundsType::Selection => Some(selection.range()),
+                    };
+                    let Some(range) = range else {
+                        continue;
+                    };
+                    let start = range.start.to_point(&map);
+                    let end = range.end.to_point(&map);
+                    let start_row = start.row;
+                    let mut end_row = end.row;
+                    if end_row > start_row && end.column == 0 {
+                        end_row -= 1;
+                    }
+                    if previous_end_row
+                        .is_some_and(|previous_end_row| start_row <= previous_end_row)
+                    {
+                        continue;
+                    }
+                    previous_end_row = Some(end_row);
+
+                    let range = Point::new(start_row, 0)
+                        ..Point::new(end_row, buffer.line_len(MultiBufferRow(end_row)));
+                    let text = buffer.text_for_range(range.clone()).collect::<String>();
+                    let aligned = align_lines(&text, &delimiter);
+                    if aligned != text {
+                        edits.push((range, aligned));
+                    }
+                    let indent = buffer.indent_size_for_line(MultiBufferRow(start_row)).len;
+                    let cursor = Point::new(start_row, indent);
+                    cursors.push(cursor..cursor);
+                }
+
+                editor.edit(edits, cx);
+                if !cursors.is_empty() {
+                    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                        s.select_ranges(cursors);
+                    });
+                }
+            });
+        });
+        self.switch_mode(Mode::Normal, false, cx);
+    }
+}
+
+/// Operators that aren't split up when aligning on a delimiter they contain, so that aligning
+/// on `=` leaves `==`, `!=`, `<=`, `=>` and `+=` alone.
+const COMPOUND_OPERATORS: &[&str] = &[
+    "===", "!==", "<<=", ">>=", "**=", "==", "!=", "<=", ">=", "=>", "=~", "+=", "-=", "*=", "/=",
+    "%=", "&=", "|=", "^=", ":=", "->", "<-", "::", "&&", "||", "<<", ">>",
+];
+
+/// Pads the cells between each `delimiter` so that the delimiters line up across lines.
+/// Lines without the delimiter are left untouched, and indentation counts towards the first
+/// cell. A `,` or `;` stays attached to the cell before it, every other delimiter is
+/// surrounded by single spaces (`a = b`, `| a | b |`).
+fn align_lines(text: &str, delimiter: &str) -> String {
+    let lines = text
+        .split('\n')
+        .map(|line| {
+            let cells = split_on_delimiter(line, delimiter)
+                .into_iter()
+                .enumerate()
+                .map(|(ix, cell)| {
+                    if ix == 0 {
+                        cell.trim_end()
+                    } else {
+                        cell.trim()
+                    }
+                })
+                .collect::<Vec<_>>();
+            (line, cells)
+        })
+        .collect::<Vec<_>>();
+
+    let mut widths = Vec::<usize>::new();
+    for (_, cells) in &lines {
+        for (ix, cell) in cells.iter().enumerate().take(cells.len() - 1) {
+            let width = cell.chars().count();
+            match widths.get_mut(ix) {
+                Some(max_width) => *max_width = (*max_width).max(width),
+                None => widths.push(width),
+            }
+        }
+    }
+
+    let attached = delimiter == "," || delimiter == ";";
+    let mut aligned = Vec::with_capacity(lines.len());
+    for (line, cells) in lines {
+        if cells.len() < 2 {
+            aligned.push(line.to_string());
+            continue;
+        }
+        let mut result = String::new();
+        for (ix, cell) in cells.iter().enumerate() {
+            result.push_str(cell);
+            if ix + 1 == cells.len() {
+                break;
+            }
+            let padding = " ".repeat(widths[ix] - cell.chars().count());
+            if attached {
+                result.push_str(delimiter);
+                result.push_str(&padding);
+            } else {
+                result.push_str(&padding);
+                if ix > 0 || !cell.trim_start().is_empty() {
+                    result.push(' ');
+                }
+                result.push_str(delimiter);
+            }
+            result.push(' ');
+        }
+        aligned.push(result.trim_end().to_string());
+    }
+    aligned.join("\n")
+}
+
+/// Splits `line` on each occurrence of `delimiter` that isn't part of a longer operator.
+fn split_on_delimiter<'a>(line: &'a str, delimiter: &str) -> Vec<&'a str> {
+    if delimiter.is_empty() {
+        return vec![line];
+    }
+
+    let mut cells = Vec::new();
+    let mut cell_start = 0;
+    let mut ix = 0;
+    while ix < line.len() {
+        let rest = &line[ix..];
+        let operator = COMPOUND_OPERATORS
+            .iter()
+            .filter(|operator| rest.starts_with(**operator))
+            .max_by_key(|operator| operator.len());
+        match operator {
+            Some(operator) if *operator != delimiter && operator.len() >= delimiter.len() => {
+                ix += operator.len();
+            }
+            _ if rest.starts_with(delimiter) => {
+                cells.push(&line[cell_start..ix]);
+                ix += delimiter.len();
+                cell_start = ix;
+            }
+            _ => ix += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    cells.push(&line[cell_start..]);
+    cells
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use crate::{state::Mode, test::VimTestContext};
+
+    #[gpui::test]
+    async fn test_align(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            ˇa = 1
+            bbb = 2
+              cc=3
+
+            x = 1
+            yyyy = 2"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g = i p =");
+        cx.assert_state(
+            indoc! {"
+            ˇa    = 1
+            bbb  = 2
+              cc = 3
+
+            x = 1
+            yyyy = 2"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("} j .");
+        cx.assert_state(
+            indoc! {"
+            a    = 1
+            bbb  = 2
+              cc = 3
+
+            ˇx    = 1
+            yyyy = 2"},
+            Mode::Normal,
+        );
+
+        cx.set_state(
+            indoc! {"
+            ˇ|a|b|
+            |---|---|
+            |long cell|c|"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("shift-v j j g = |");
+        cx.assert_state(
+            indoc! {"
+            ˇ| a         | b   |
+            | ---       | --- |
+            | long cell | c   |"},
+            Mode::Normal,
+        );
+
+        cx.set_state(
+            indoc! {"
+            ˇfoo(a, bbb, c)
+            foo(dd, e, ffff)"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g = j ,");
+        cx.assert_state(
+            indoc! {"
+            ˇfoo(a,  bbb, c)
+            foo(dd, e,   ffff)"},
+            Mode::Normal,
+        );
+
+        // compound operators containing the delimiter are left alone
+        cx.set_state(
+            indoc! {"
+            ˇa != b
+            cc = d == e
+            f => g
+            hhh += 1"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g = i p =");
+        cx.assert_state(
+            indoc! {"
+            ˇa != b
+            cc = d == e
+            f => g
+            hhh += 1"},
+            Mode::Normal,
+        );
+
+        cx.set_state(
+            indoc! {"
+            ˇx = 1
+            if a == b:
+            long = 2"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g = i p =");
+        cx.assert_state(
+            indoc! {"
+            ˇx    = 1
+            if a == b:
+            long = 2"},
+            Mode::Normal,
+        );
+
+        // the count and the around form of the object are used
+        cx.set_state(
+            indoc! {"
+            ˇa = 1
+            bbb = 2
+
+            cc = 3"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g = 2 a p =");
+        cx.assert_state(
+            indoc! {"
+            ˇa   = 1
+            bbb = 2
+
+            cc  = 3"},
+            Mode::Normal,
+        );
+    }
+}
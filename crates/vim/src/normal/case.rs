@@ -6,7 +6,7 @@ use multi_buffer::MultiBufferRow;
 
 use crate::{
     motion::Motion,
-    normal::{ChangeCase, ConvertToLowerCase, ConvertToUpperCase},
+    normal::{ChangeCase, ConvertToLowerCase, ConvertToRot13, ConvertToUpperCase},
     object::Object,
     state::Mode,
     Vim,
@@ -16,6 +16,7 @@ pub enum CaseTarget {
     Lowercase,
     Uppercase,
     OppositeCase,
+    Rot13,
 }
 
 impl Vim {
@@ -33,9 +34,11 @@ impl Vim {
                 let mut selection_starts: HashMap<_, _> = Default::default();
                 editor.change_selections(None, cx, |s| {
                     s.move_with(|map, selection| {
-                        let anchor = map.display_point_to_anchor(selection.head(), Bias::Left);
-                        selection_starts.insert(selection.id, anchor);
                         motion.expand_selection(map, selection, times, false, &text_layout_details);
+                        // like vim, the cursor goes to the start of the changed text, which
+                        // is the start of the first line for `g~~`
+                        let anchor = map.display_point_to_anchor(selection.start, Bias::Left);
+                        selection_starts.insert(selection.id, anchor);
                     });
                 });
                 match mode {
@@ -44,6 +47,7 @@ impl Vim {
                     CaseTarget::OppositeCase => {
                         editor.convert_to_opposite_case(&Default::default(), cx)
                     }
+                    CaseTarget::Rot13 => editor.convert_to_rot13(&Default::default(), cx),
                 }
                 editor.change_selections(None, cx, |s| {
                     s.move_with(|map, selection| {
@@ -59,6 +63,7 @@ impl Vim {
         &mut self,
         object: Object,
         around: bool,
+        times: Option<usize>,
         mode: CaseTarget,
         cx: &mut ViewContext<Self>,
     ) {
@@ -68,7 +73,7 @@ impl Vim {
                 let mut original_positions: HashMap<_, _> = Default::default();
                 editor.change_selections(None, cx, |s| {
                     s.move_with(|map, selection| {
-                        object.expand_selection(map, selection, around);
+                        object.expand_selection(map, selection, around, times);
                         original_positions.insert(
                             selection.id,
                             map.display_point_to_anchor(selection.start, Bias::Left),
@@ -81,6 +86,7 @@ impl Vim {
                     CaseTarget::OppositeCase => {
                         editor.convert_to_opposite_case(&Default::default(), cx)
                     }
+                    CaseTarget::Rot13 => editor.convert_to_rot13(&Default::default(), cx),
                 }
                 editor.change_selections(None, cx, |s| {
                     s.move_with(|map, selection| {
@@ -92,6 +98,29 @@ impl Vim {
         });
     }
 
+    /// Changes the case of the current selections, as `gUgn` does with the search match
+    /// that `gn` selected.
+    pub fn change_case_selections(&mut self, mode: CaseTarget, cx: &mut ViewContext<Self>) {
+        self.stop_recording(cx);
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(cx, |editor, cx| {
+                match mode {
+                    CaseTarget::Lowercase => editor.convert_to_lower_case(&Default::default(), cx),
+                    CaseTarget::Uppercase => editor.convert_to_upper_case(&Default::default(), cx),
+                    CaseTarget::OppositeCase => {
+                        editor.convert_to_opposite_case(&Default::default(), cx)
+                    }
+                    CaseTarget::Rot13 => editor.convert_to_rot13(&Default::default(), cx),
+                }
+                editor.change_selections(None, cx, |s| {
+                    s.move_with(|_, selection| {
+                        selection.collapse_to(selection.start, SelectionGoal::None);
+                    });
+                });
+            });
+        });
+    }
+
     pub fn change_case(&mut self, _: &ChangeCase, cx: &mut ViewContext<Self>) {
         self.manipulate_text(cx, |c| {
             if c.is_lowercase() {
@@ -110,6 +139,14 @@ impl Vim {
         self.manipulate_text(cx, |c| c.to_lowercase().collect::<Vec<char>>())
     }
 
+    pub fn convert_to_rot13(&mut self, _: &ConvertToRot13, cx: &mut ViewContext<Self>) {
+        self.manipulate_text(cx, |c| match c {
+            'A'..='M' | 'a'..='m' => vec![((c as u8) + 13) as char],
+            'N'..='Z' | 'n'..='z' => vec![((c as u8) - 13) as char],
+            _ => vec![c],
+        })
+    }
+
     fn manipulate_text<F>(&mut self, cx: &mut ViewContext<Self>, transform: F)
     where
         F: Fn(char) -> Vec<char> + Copy,
@@ -182,7 +219,10 @@ impl Vim {
 
 #[cfg(test)]
 mod test {
-    use crate::{state::Mode, test::NeovimBackedTestContext};
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+    };
 
     #[gpui::test]
     async fn test_change_case(cx: &mut gpui::TestAppContext) {
@@ -282,4 +322,51 @@ mod test {
         cx.simulate_shared_keystrokes(".").await;
         cx.shared_state().await.assert_eq("ˇabc def");
     }
+
+    #[gpui::test]
+    async fn test_change_case_line(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state("abˇc Def\nghi").await;
+        cx.simulate_shared_keystrokes("g ~ ~").await;
+        cx.shared_state().await.assert_eq("ˇABC dEF\nghi");
+
+        cx.simulate_shared_keystrokes("g u u").await;
+        cx.shared_state().await.assert_eq("ˇabc def\nghi");
+
+        cx.simulate_shared_keystrokes("g shift-u g shift-u").await;
+        cx.shared_state().await.assert_eq("ˇABC DEF\nghi");
+
+        cx.simulate_shared_keystrokes("j .").await;
+        cx.shared_state().await.assert_eq("ABC DEF\nˇGHI");
+
+        cx.simulate_shared_keystrokes("k 2 g u u").await;
+        cx.shared_state().await.assert_eq("ˇabc def\nghi");
+    }
+
+    #[gpui::test]
+    async fn test_rot13(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇhello World", Mode::Normal);
+        cx.simulate_keystrokes("g ? w");
+        cx.assert_state("ˇuryyb World", Mode::Normal);
+
+        cx.simulate_keystrokes("g ? ?");
+        cx.assert_state("ˇhello Jbeyq", Mode::Normal);
+
+        cx.simulate_keystrokes("g ? g ?");
+        cx.assert_state("ˇuryyb World", Mode::Normal);
+
+        cx.simulate_keystrokes(".");
+        cx.assert_state("ˇhello Jbeyq", Mode::Normal);
+
+        cx.set_state("abc ˇxyz-123", Mode::Normal);
+        cx.simulate_keystrokes("g ? i w");
+        cx.assert_state("abc ˇklm-123", Mode::Normal);
+
+        cx.set_state("«abcˇ» xyz", Mode::Visual);
+        cx.simulate_keystrokes("g ?");
+        cx.assert_state("ˇnop xyz", Mode::Normal);
+    }
 }
@@ -6,7 +6,7 @@ use crate::{
 };
 use editor::{
     display_map::{DisplaySnapshot, ToDisplayPoint},
-    movement::TextLayoutDetails,
+    movement::{self, TextLayoutDetails},
     scroll::Autoscroll,
     Bias, DisplayPoint,
 };
@@ -87,7 +87,13 @@ impl Vim {
         }
     }
 
-    pub fn change_object(&mut self, object: Object, around: bool, cx: &mut ViewContext<Self>) {
+    pub fn change_object(
+        &mut self,
+        object: Object,
+        around: bool,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
         let mut objects_found = false;
         self.update_editor(cx, |vim, editor, cx| {
             // We are swapping to insert mode anyway. Just set the line end clipping behavior now
@@ -95,7 +101,18 @@ impl Vim {
             editor.transact(cx, |editor, cx| {
                 editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
                     s.move_with(|map, selection| {
-                        objects_found |= object.expand_selection(map, selection, around);
+                        objects_found |= object.expand_selection(map, selection, around, times);
+                        // Like vim, when the object covers whole lines (brackets on their own
+                        // lines, or a function or class) the change leaves an empty line to
+                        // type into.
+                        if ((!around && object.is_bracket())
+                            || matches!(object, Object::Function | Object::Class))
+                            && selection.start.column() == 0
+                            && selection.end.column() == 0
+                            && selection.end.row() > selection.start.row()
+                        {
+                            selection.end = movement::left(map, selection.end);
+                        }
                     });
                 });
                 if objects_found {
@@ -71,7 +71,13 @@ impl Vim {
         });
     }
 
-    pub fn delete_object(&mut self, object: Object, around: bool, cx: &mut ViewContext<Self>) {
+    pub fn delete_object(
+        &mut self,
+        object: Object,
+        around: bool,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
         self.stop_recording(cx);
         self.update_editor(cx, |vim, editor, cx| {
             editor.transact(cx, |editor, cx| {
@@ -81,7 +87,7 @@ impl Vim {
                 let mut should_move_to_start: HashSet<_> = Default::default();
                 editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
                     s.move_with(|map, selection| {
-                        object.expand_selection(map, selection, around);
+                        object.expand_selection(map, selection, around, times);
                         let offset_range = selection.map(|p| p.to_offset(map, Bias::Left)).range();
                         let mut move_selection_start_to_previous_line =
                             |map: &DisplaySnapshot, selection: &mut Selection<DisplayPoint>| {
@@ -119,7 +125,9 @@ impl Vim {
                         // Does post-processing for the trailing newline and EOF
                         // when not cancelled.
                         let cancelled = around && selection.start == selection.end;
-                        if object == Object::Paragraph && !cancelled {
+                        let linewise =
+                            matches!(object, Object::Paragraph | Object::IndentObj { .. });
+                        if linewise && !cancelled {
                             // EOF check should be done before including a trailing newline.
                             if ends_at_eof(map, selection) {
                                 move_selection_start_to_previous_line(map, selection);
@@ -274,6 +282,35 @@ mod test {
             .assert_matches();
     }
 
+    #[gpui::test]
+    async fn test_delete_last_non_whitespace(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+        cx.simulate("d g _", "one twˇo  \n").await.assert_matches();
+        cx.simulate("d g _", "oˇne two  \n").await.assert_matches();
+        cx.simulate("d 2 g _", "oˇne  \ntwo three \nfour\n")
+            .await
+            .assert_matches();
+    }
+
+    #[gpui::test]
+    async fn test_delete_previous_word_end(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+        cx.simulate("d g e", "456 5ˇ67 678\n")
+            .await
+            .assert_matches();
+        cx.simulate("d g shift-e", "4;5.6 5ˇ67 678\n")
+            .await
+            .assert_matches();
+        cx.simulate(
+            "d g e",
+            indoc! {"
+            Test test
+            ˇtest"},
+        )
+        .await
+        .assert_matches();
+    }
+
     #[gpui::test]
     async fn test_delete_b(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
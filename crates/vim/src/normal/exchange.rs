@@ -0,0 +1,211 @@
+use std::ops::Range;
+
+use editor::{scroll::Autoscroll, DisplayPoint, Editor};
+use gpui::ViewContext;
+use language::Bias;
+use multi_buffer::ToOffset;
+
+use workspace::{notifications::NotificationId, Toast};
+
+use crate::{motion::Motion, normal::ClearExchange, object::Object, Vim};
+
+struct HighlightOnExchange;
+
+impl Vim {
+    pub fn exchange_motion(
+        &mut self,
+        motion: Motion,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.stop_recording(cx);
+        let exchanged = self.update_editor(cx, |vim, editor, cx| {
+            let text_layout_details = editor.text_layout_details(cx);
+            let map = editor.snapshot(cx).display_snapshot;
+            let selection = editor.selections.newest_display(cx);
+            motion
+                .range(&map, selection, times, false, &text_layout_details)
+                .map_or(true, |range| vim.exchange(editor, range, cx))
+        });
+        if exchanged == Some(false) {
+            self.show_exchange_overlap(cx);
+        }
+    }
+
+    pub fn exchange_object(
+        &mut self,
+        object: Object,
+        around: bool,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.stop_recording(cx);
+        let exchanged = self.update_editor(cx, |vim, editor, cx| {
+            let map = editor.snapshot(cx).display_snapshot;
+            let selection = editor.selections.newest_display(cx);
+            object
+                .range(&map, selection, around, times)
+                .map_or(true, |range| vim.exchange(editor, range, cx))
+        });
+        if exchanged == Some(false) {
+            self.show_exchange_overlap(cx);
+        }
+    }
+
+    /// Tells the user that the regions to exchange overlapped, so nothing was swapped.
+    fn show_exchange_overlap(&mut self, cx: &mut ViewContext<Self>) {
+        struct ExchangeOverlap;
+
+        let Some(workspace) = self.workspace(cx) else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(
+                    NotificationId::unique::<ExchangeOverlap>(),
+                    "Exchange aborted: overlapping text",
+                )
+                .autohide(),
+                cx,
+            );
+        });
+    }
+
+    pub(crate) fn clear_exchange(&mut self, _: &ClearExchange, cx: &mut ViewContext<Self>) {
+        self.stop_recording(cx);
+        self.clear_operator(cx);
+        self.exchange.take();
+        self.update_editor(cx, |_, editor, cx| {
+            editor.clear_background_highlights::<HighlightOnExchange>(cx);
+        });
+    }
+
+    /// Marks `range` for exchange, or swaps it with the region marked by the previous `cx`.
+    ///
+    /// Like vim-exchange, when one region contains the other, the bigger one is replaced by
+    /// the smaller one. Regions that only partly overlap can't be swapped, so this returns
+    /// false and forgets the marked region.
+    fn exchange(
+        &mut self,
+        editor: &mut Editor,
+        range: Range<DisplayPoint>,
+        cx: &mut ViewContext<Editor>,
+    ) -> bool {
+        let map = editor.snapshot(cx).display_snapshot;
+        let snapshot = &map.buffer_snapshot;
+        let start = range.start.to_offset(&map, Bias::Left);
+        let end = range.end.to_offset(&map, Bias::Left);
+        if start == end {
+            return true;
+        }
+        let range = snapshot.anchor_after(start)..snapshot.anchor_before(end);
+
+        let Some(previous) = self.exchange.take() else {
+            editor.highlight_background::<HighlightOnExchange>(
+                &[range.clone()],
+                |colors| colors.editor_document_highlight_read_background,
+                cx,
+            );
+            self.exchange = Some(range);
+            return true;
+        };
+        editor.clear_background_highlights::<HighlightOnExchange>(cx);
+
+        let previous = previous.start.to_offset(snapshot)..previous.end.to_offset(snapshot);
+        let current = start..end;
+        let previous_text = snapshot
+            .text_for_range(previous.clone())
+            .collect::<String>();
+        let text = snapshot.text_for_range(current.clone()).collect::<String>();
+        let edits = if previous.start <= current.start && current.end <= previous.end {
+            vec![(previous, text)]
+        } else if current.start <= previous.start && previous.end <= current.end {
+            vec![(current, previous_text)]
+        } else if previous.start < current.end && current.start < previous.end {
+            return false;
+        } else {
+            let mut edits = vec![(previous, text), (current, previous_text)];
+            edits.sort_by_key(|(range, _)| range.start);
+            edits
+        };
+
+        let cursor = if edits.len() == 1 {
+            snapshot.anchor_before(edits[0].0.start)
+        } else {
+            snapshot.anchor_before(start)
+        };
+        editor.transact(cx, |editor, cx| {
+            editor.edit(edits, cx);
+            editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_anchor_ranges([cursor..cursor]);
+            });
+        });
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{state::Mode, test::VimTestContext};
+    use indoc::indoc;
+
+    #[gpui::test]
+    async fn test_exchange(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("c x i w");
+        cx.assert_state("ˇone two three", Mode::Normal);
+        cx.update_editor(|editor, cx| {
+            assert_eq!(1, editor.all_text_background_highlights(cx).len());
+        });
+        cx.simulate_keystrokes("w w c x i w");
+        cx.assert_state("three two ˇone", Mode::Normal);
+        cx.update_editor(|editor, cx| {
+            assert!(editor.all_text_background_highlights(cx).is_empty());
+        });
+
+        // cxc cancels the pending exchange
+        cx.simulate_keystrokes("b c x i w c x c w c x i w");
+        cx.assert_state("three two ˇone", Mode::Normal);
+        cx.update_editor(|editor, cx| {
+            assert_eq!(1, editor.all_text_background_highlights(cx).len());
+        });
+        cx.simulate_keystrokes("c x c");
+
+        // when one region contains the other, the bigger one is replaced
+        cx.set_state("ˇfoo(bar, baz)", Mode::Normal);
+        cx.simulate_keystrokes("c x x f b c x i w");
+        cx.assert_state("ˇbar(bar, baz)", Mode::Normal);
+        cx.set_state("foo(ˇbar, baz)", Mode::Normal);
+        cx.simulate_keystrokes("c x i w c x i (");
+        cx.assert_state("foo(ˇbar)", Mode::Normal);
+
+        // regions that partly overlap aren't exchanged
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("c x 2 e w c x 2 e");
+        cx.assert_state("one ˇtwo three", Mode::Normal);
+        cx.update_editor(|editor, cx| {
+            assert!(editor.all_text_background_highlights(cx).is_empty());
+        });
+
+        // `.` repeats `cx` with its motion, marking the next region or swapping it
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("c x i w w w .");
+        cx.assert_state("three two ˇone", Mode::Normal);
+
+        cx.set_state(
+            indoc! {"
+            ˇfirst line
+            second line"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("c x x j c x x");
+        cx.assert_state(
+            indoc! {"
+            second line
+            ˇfirst line"},
+            Mode::Normal,
+        );
+    }
+}
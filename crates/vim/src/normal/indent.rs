@@ -1,13 +1,20 @@
-use crate::{motion::Motion, object::Object, Vim};
+use crate::{
+    motion::{first_non_whitespace, Motion},
+    object::Object,
+    Vim, VimSettings,
+};
 use collections::HashMap;
-use editor::{display_map::ToDisplayPoint, Bias};
-use language::SelectionGoal;
+use editor::{display_map::ToDisplayPoint, Anchor, Editor};
+use language::{Point, SelectionGoal};
+use multi_buffer::MultiBufferRow;
+use settings::Settings;
 use ui::ViewContext;
 
 #[derive(PartialEq, Eq)]
 pub(crate) enum IndentDirection {
     In,
     Out,
+    Auto,
 }
 
 impl Vim {
@@ -22,25 +29,17 @@ impl Vim {
         self.update_editor(cx, |_, editor, cx| {
             let text_layout_details = editor.text_layout_details(cx);
             editor.transact(cx, |editor, cx| {
-                let mut selection_starts: HashMap<_, _> = Default::default();
+                let mut line_starts: HashMap<_, _> = Default::default();
                 editor.change_selections(None, cx, |s| {
                     s.move_with(|map, selection| {
-                        let anchor = map.display_point_to_anchor(selection.head(), Bias::Right);
-                        selection_starts.insert(selection.id, anchor);
                         motion.expand_selection(map, selection, times, false, &text_layout_details);
+                        let line_start = Point::new(selection.start.to_point(map).row, 0);
+                        line_starts
+                            .insert(selection.id, map.buffer_snapshot.anchor_before(line_start));
                     });
                 });
-                if dir == IndentDirection::In {
-                    editor.indent(&Default::default(), cx);
-                } else {
-                    editor.outdent(&Default::default(), cx);
-                }
-                editor.change_selections(None, cx, |s| {
-                    s.move_with(|map, selection| {
-                        let anchor = selection_starts.remove(&selection.id).unwrap();
-                        selection.collapse_to(anchor.to_display_point(map), SelectionGoal::None);
-                    });
-                });
+                indent_lines(editor, &dir, 1, cx);
+                move_to_first_non_whitespace(editor, &mut line_starts, cx);
             });
         });
     }
@@ -49,32 +48,144 @@ impl Vim {
         &mut self,
         object: Object,
         around: bool,
+        times: Option<usize>,
         dir: IndentDirection,
         cx: &mut ViewContext<Self>,
     ) {
         self.stop_recording(cx);
         self.update_editor(cx, |_, editor, cx| {
             editor.transact(cx, |editor, cx| {
-                let mut original_positions: HashMap<_, _> = Default::default();
+                let mut line_starts: HashMap<_, _> = Default::default();
                 editor.change_selections(None, cx, |s| {
                     s.move_with(|map, selection| {
-                        let anchor = map.display_point_to_anchor(selection.head(), Bias::Right);
-                        original_positions.insert(selection.id, anchor);
-                        object.expand_selection(map, selection, around);
-                    });
-                });
-                if dir == IndentDirection::In {
-                    editor.indent(&Default::default(), cx);
-                } else {
-                    editor.outdent(&Default::default(), cx);
-                }
-                editor.change_selections(None, cx, |s| {
-                    s.move_with(|map, selection| {
-                        let anchor = original_positions.remove(&selection.id).unwrap();
-                        selection.collapse_to(anchor.to_display_point(map), SelectionGoal::None);
+                        object.expand_selection(map, selection, around, times);
+                        let line_start = Point::new(selection.start.to_point(map).row, 0);
+                        line_starts
+                            .insert(selection.id, map.buffer_snapshot.anchor_before(line_start));
                     });
                 });
+                indent_lines(editor, &dir, 1, cx);
+                move_to_first_non_whitespace(editor, &mut line_starts, cx);
+            });
+        });
+    }
+
+    /// Indents the lines of the selections `times` times, as the visual mode `>`, `<` and
+    /// `=` do, leaving the cursor on the first non-blank character of the first line.
+    pub(crate) fn indent_selections(
+        &mut self,
+        dir: IndentDirection,
+        times: usize,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(cx, |editor, cx| {
+                let buffer = editor.buffer().read(cx).snapshot(cx);
+                let mut line_starts = editor
+                    .selections
+                    .all::<Point>(cx)
+                    .into_iter()
+                    .map(|selection| {
+                        let line_start = Point::new(selection.start.row, 0);
+                        (selection.id, buffer.anchor_before(line_start))
+                    })
+                    .collect();
+                indent_lines(editor, &dir, times, cx);
+                move_to_first_non_whitespace(editor, &mut line_starts, cx);
             });
         });
     }
 }
+
+fn indent_lines(
+    editor: &mut Editor,
+    dir: &IndentDirection,
+    times: usize,
+    cx: &mut ViewContext<Editor>,
+) {
+    match dir {
+        IndentDirection::Auto => editor.autoindent(&Default::default(), cx),
+        IndentDirection::In | IndentDirection::Out => shift_lines(editor, dir, times, false, cx),
+    }
+}
+
+/// Shifts the lines of the selections by `times` times the `shiftwidth` setting (or the tab
+/// size, if it is 0), like vim's `>` and `<`. Blank lines are left alone unless
+/// `include_blank_lines` is set, as it is for `ctrl-t` and `ctrl-d` in insert mode.
+pub(crate) fn shift_lines(
+    editor: &mut Editor,
+    dir: &IndentDirection,
+    times: usize,
+    include_blank_lines: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    let buffer = editor.buffer().read(cx).snapshot(cx);
+    let shiftwidth = VimSettings::get_global(cx).shiftwidth;
+    let mut edits = Vec::new();
+    let mut next_row = 0;
+    for selection in editor.selections.all_adjusted(cx) {
+        let mut end_row = selection.end.row;
+        if end_row > selection.start.row && selection.end.column == 0 {
+            end_row -= 1;
+        }
+        for row in selection.start.row.max(next_row)..=end_row {
+            let line_len = buffer.line_len(MultiBufferRow(row));
+            if line_len == 0 && !include_blank_lines {
+                continue;
+            }
+
+            let settings = buffer.settings_at(Point::new(row, 0), cx);
+            let tab_size = settings.tab_size.get();
+            let shiftwidth = if shiftwidth == 0 {
+                tab_size
+            } else {
+                shiftwidth
+            };
+
+            let mut indent_len = 0;
+            let mut indent_columns = 0;
+            for c in buffer.chars_at(Point::new(row, 0)) {
+                match c {
+                    ' ' => indent_columns += 1,
+                    '\t' => indent_columns += tab_size - indent_columns % tab_size,
+                    _ => break,
+                }
+                indent_len += 1;
+            }
+            let shift = shiftwidth * times as u32;
+            let new_columns = match dir {
+                IndentDirection::Out => indent_columns.saturating_sub(shift),
+                _ => indent_columns + shift,
+            };
+            // Lines with nothing but whitespace are not indented further.
+            if indent_len == line_len && new_columns > indent_columns && !include_blank_lines {
+                continue;
+            }
+
+            let new_indent = if settings.hard_tabs {
+                "\t".repeat((new_columns / tab_size) as usize)
+                    + &" ".repeat((new_columns % tab_size) as usize)
+            } else {
+                " ".repeat(new_columns as usize)
+            };
+            edits.push((Point::new(row, 0)..Point::new(row, indent_len), new_indent));
+        }
+        next_row = end_row + 1;
+    }
+    editor.edit(edits, cx);
+}
+
+fn move_to_first_non_whitespace(
+    editor: &mut Editor,
+    line_starts: &mut HashMap<usize, Anchor>,
+    cx: &mut ViewContext<Editor>,
+) {
+    editor.change_selections(None, cx, |s| {
+        s.move_with(|map, selection| {
+            if let Some(anchor) = line_starts.remove(&selection.id) {
+                let point = first_non_whitespace(map, false, anchor.to_display_point(map));
+                selection.collapse_to(point, SelectionGoal::None);
+            }
+        });
+    });
+}
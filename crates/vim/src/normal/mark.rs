@@ -1,22 +1,194 @@
-use std::{ops::Range, sync::Arc};
+use std::{future::Future, ops::Range, path::PathBuf, sync::Arc};
+
+use collections::HashMap;
 
 use editor::{
     display_map::{DisplaySnapshot, ToDisplayPoint},
     movement,
     scroll::Autoscroll,
-    Anchor, Bias, DisplayPoint,
+    Anchor, Bias, DisplayPoint, Editor,
 };
-use gpui::ViewContext;
-use language::SelectionGoal;
+use gpui::{impl_actions, AppContext, EntityId, View, ViewContext, WeakModel};
+use language::{Buffer, BufferSnapshot, Point, SelectionGoal, ToPoint};
+use multi_buffer::MultiBufferSnapshot;
+use project::ProjectPath;
+use serde::Deserialize;
+use util::ResultExt;
+use workspace::{ItemHandle, Workspace, WorkspaceId};
 
 use crate::{
     motion::{self, Motion},
+    persistence::DB,
     state::Mode,
-    Vim,
+    Vim, VimAddon,
 };
 
+/// An uppercase mark, which can be jumped to from any buffer in the workspace. While its buffer
+/// is open the anchor follows edits, and once the buffer is closed the file is opened again at
+/// the last known point. Marks restored from a previous session only have the point.
+#[derive(Clone)]
+pub struct GlobalMark {
+    pub(crate) path: ProjectPath,
+    abs_path: Option<PathBuf>,
+    anchor: Option<(WeakModel<Buffer>, language::Anchor)>,
+    point: Point,
+}
+
+impl GlobalMark {
+    pub(crate) fn point(&self, cx: &AppContext) -> Point {
+        match &self.anchor {
+            Some((buffer, anchor)) => match buffer.upgrade() {
+                Some(buffer) => anchor.to_point(&buffer.read(cx).snapshot()),
+                None => self.point,
+            },
+            None => self.point,
+        }
+    }
+}
+
+/// Moves the last known point of the uppercase marks in a buffer to where their anchors are,
+/// so that the marks stay where they were once the buffer is released.
+pub(crate) fn refresh_global_marks(buffer: &BufferSnapshot, cx: &mut AppContext) {
+    let buffer_id = Some(buffer.remote_id());
+    for marks in Vim::globals(cx).global_marks.values_mut() {
+        for mark in marks.values_mut() {
+            if let Some((_, anchor)) = &mark.anchor {
+                if anchor.buffer_id == buffer_id {
+                    mark.point = anchor.to_point(buffer);
+                }
+            }
+        }
+    }
+}
+
+/// Restores the uppercase marks a workspace had when it was last closed. Marks in files that
+/// aren't in the workspace's worktrees anymore are dropped.
+fn load_global_marks(workspace: &Workspace, cx: &AppContext) -> HashMap<String, GlobalMark> {
+    let Some(workspace_id) = workspace.database_id() else {
+        return HashMap::default();
+    };
+    let project = workspace.project().read(cx);
+    DB.get_global_marks(workspace_id)
+        .log_err()
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(name, abs_path, row, column)| {
+            let path = project.find_project_path(&abs_path, cx)?;
+            let mark = GlobalMark {
+                path,
+                abs_path: Some(abs_path),
+                anchor: None,
+                point: Point::new(row, column),
+            };
+            Some((name, mark))
+        })
+        .collect()
+}
+
+/// Saves the uppercase marks of a workspace so that they're restored when it's opened again.
+/// The saved marks are left alone if they weren't used in this session.
+pub(crate) fn save_global_marks(
+    workspace: EntityId,
+    workspace_id: Option<WorkspaceId>,
+    cx: &mut AppContext,
+) -> impl Future<Output = ()> {
+    let marks = Vim::globals(cx)
+        .global_marks
+        .get(&workspace)
+        .cloned()
+        .map(|marks| {
+            marks
+                .into_iter()
+                .filter_map(|(name, mark)| {
+                    let point = mark.point(cx);
+                    Some((name, mark.abs_path?, point))
+                })
+                .collect::<Vec<_>>()
+        });
+    async move {
+        let (Some(workspace_id), Some(marks)) = (workspace_id, marks) else {
+            return;
+        };
+        DB.delete_global_marks(workspace_id).await.log_err();
+        for (name, abs_path, point) in marks {
+            DB.save_global_mark(workspace_id, name, abs_path, point.row, point.column)
+                .await
+                .log_err();
+        }
+    }
+}
+
+/// Deletes marks, like `:delmarks`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DeleteMarks {
+    pub(crate) marks: Vec<String>,
+}
+
+impl_actions!(vim, [DeleteMarks]);
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
+    Vim::action(editor, cx, |vim, action: &DeleteMarks, cx| {
+        vim.delete_marks(action.marks.iter().cloned(), cx)
+    });
+}
+
+impl DeleteMarks {
+    /// Parses the arguments of `:delmarks`, which are mark names and ranges like `a-d`, or `!`
+    /// to delete all the lowercase marks.
+    pub(crate) fn parse(args: &str) -> Option<Self> {
+        if args == "!" {
+            return Some(Self {
+                marks: ('a'..='z').map(|c| c.to_string()).collect(),
+            });
+        }
+        let mut marks = Vec::new();
+        let mut chars = args.chars().filter(|c| !c.is_whitespace()).peekable();
+        while let Some(start) = chars.next() {
+            if !start.is_ascii_alphanumeric() && !"\"^.[]<>'".contains(start) {
+                return None;
+            }
+            if chars.next_if_eq(&'-').is_some() {
+                let end = chars.next()?;
+                let same_kind = (start.is_ascii_lowercase() && end.is_ascii_lowercase())
+                    || (start.is_ascii_uppercase() && end.is_ascii_uppercase())
+                    || (start.is_ascii_digit() && end.is_ascii_digit());
+                if !same_kind || end < start {
+                    return None;
+                }
+                marks.extend((start..=end).map(|c| c.to_string()));
+            } else {
+                marks.push(start.to_string());
+            }
+        }
+        (!marks.is_empty()).then_some(Self { marks })
+    }
+}
+
+/// The path of the file an editor has open, which the `"` mark is remembered for.
+pub(crate) fn buffer_path(editor: &Editor, cx: &AppContext) -> Option<PathBuf> {
+    let buffer = editor.buffer().read(cx).as_singleton()?;
+    let file = buffer.read(cx).file()?;
+    Some(file.as_local()?.abs_path(cx))
+}
+
+/// The `"` mark to remember for an editor's file when the editor is closed.
+pub(crate) fn closing_position(editor: &Editor, cx: &AppContext) -> Option<(PathBuf, Point)> {
+    editor.addon::<VimAddon>()?;
+    let point = editor.selections.newest::<Point>(cx).head();
+    Some((buffer_path(editor, cx)?, point))
+}
+
+pub(crate) fn is_global_mark(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+}
+
 impl Vim {
     pub fn create_mark(&mut self, text: Arc<str>, tail: bool, cx: &mut ViewContext<Self>) {
+        if is_global_mark(&text) {
+            self.create_global_mark(text.to_string(), cx);
+            self.clear_operator(cx);
+            return;
+        }
         let Some(anchors) = self.update_editor(cx, |_, editor, _| {
             editor
                 .selections
@@ -31,6 +203,155 @@ impl Vim {
         self.clear_operator(cx);
     }
 
+    /// The uppercase marks of a workspace, which are restored from its last session the first
+    /// time they're used.
+    pub(crate) fn global_marks<'a>(
+        workspace: &View<Workspace>,
+        cx: &'a mut AppContext,
+    ) -> &'a mut HashMap<String, GlobalMark> {
+        let workspace_entity = workspace.entity_id();
+        if !Vim::globals(cx)
+            .global_marks
+            .contains_key(&workspace_entity)
+        {
+            let marks = load_global_marks(workspace.read(cx), cx);
+            Vim::globals(cx)
+                .global_marks
+                .insert(workspace_entity, marks);
+        }
+        Vim::globals(cx)
+            .global_marks
+            .entry(workspace_entity)
+            .or_default()
+    }
+
+    fn create_global_mark(&mut self, name: String, cx: &mut ViewContext<Self>) {
+        let (Some(workspace), Some(editor)) = (self.workspace(cx), self.editor()) else {
+            return;
+        };
+        let Some(path) = editor.project_path(cx) else {
+            return;
+        };
+        let abs_path = workspace
+            .read(cx)
+            .project()
+            .read(cx)
+            .absolute_path(&path, cx);
+        let Some((buffer, anchor, point)) = editor.update(cx, |editor, cx| {
+            let head = editor.selections.newest::<Point>(cx).head();
+            let multi_buffer = editor.buffer().read(cx);
+            let (_, buffer, _) = multi_buffer.excerpt_containing(head, cx)?;
+            let anchor = multi_buffer.snapshot(cx).anchor_before(head).text_anchor;
+            let point = anchor.to_point(&buffer.read(cx).snapshot());
+            Some((buffer, anchor, point))
+        }) else {
+            return;
+        };
+        if Vim::globals(cx)
+            .global_mark_buffers
+            .insert(buffer.read(cx).remote_id())
+        {
+            // Observed on the app rather than this view, which is released with its editor first.
+            AppContext::observe_release(cx, &buffer, |buffer, cx| {
+                refresh_global_marks(&buffer.snapshot(), cx);
+                Vim::globals(cx)
+                    .global_mark_buffers
+                    .remove(&buffer.remote_id());
+            })
+            .detach();
+        }
+        Vim::global_marks(&workspace, cx).insert(
+            name,
+            GlobalMark {
+                path,
+                abs_path,
+                anchor: Some((buffer.downgrade(), anchor)),
+                point,
+            },
+        );
+    }
+
+    /// Opens the file of a global mark in another buffer, and moves the cursor to the mark.
+    fn open_global_mark(&mut self, mark: GlobalMark, line: bool, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace(cx) else {
+            return;
+        };
+        let open = workspace.update(cx, |workspace, cx| {
+            workspace.open_path(mark.path.clone(), None, true, cx)
+        });
+        cx.spawn(|_, mut cx| async move {
+            let item = open.await?;
+            let Some(editor) = cx.update(|cx| item.act_as::<Editor>(cx))? else {
+                return Ok(());
+            };
+            editor.update(&mut cx, |editor, cx| {
+                let map = editor.snapshot(cx).display_snapshot;
+                let point = map.buffer_snapshot.clip_point(mark.point(cx), Bias::Left);
+                let mut point = point.to_display_point(&map);
+                if line {
+                    point = motion::first_non_whitespace(&map, false, point);
+                }
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_display_ranges([point..point])
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn delete_marks(
+        &mut self,
+        names: impl IntoIterator<Item = String>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let workspace = self.workspace(cx);
+        for name in names {
+            if !is_global_mark(&name) {
+                self.marks.remove(&name);
+            } else if let Some(workspace) = &workspace {
+                Vim::global_marks(workspace, cx).remove(&name);
+            }
+        }
+    }
+
+    /// Sets the `[` and `]` marks to the first and last characters of each range of text that
+    /// was just changed or yanked.
+    pub(crate) fn set_change_marks(
+        &mut self,
+        ranges: impl IntoIterator<Item = Range<usize>>,
+        buffer: &MultiBufferSnapshot,
+    ) {
+        let (starts, ends) = ranges
+            .into_iter()
+            .map(|range| {
+                let last = buffer.clip_offset(range.end.saturating_sub(1), Bias::Left);
+                (
+                    buffer.anchor_before(range.start),
+                    buffer.anchor_before(last.max(range.start)),
+                )
+            })
+            .unzip();
+        self.marks.insert("[".to_string(), starts);
+        self.marks.insert("]".to_string(), ends);
+    }
+
+    /// Sets the `[` mark to the start of the text typed in insert mode, given the length of the
+    /// text just typed before the cursors.
+    pub(crate) fn mark_insert_start(&mut self, typed_len: usize, cx: &mut ViewContext<Self>) {
+        let Some(starts) = self.update_editor(cx, |_, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            editor
+                .selections
+                .all::<usize>(cx)
+                .into_iter()
+                .map(|selection| snapshot.anchor_before(selection.head().saturating_sub(typed_len)))
+                .collect::<Vec<_>>()
+        }) else {
+            return;
+        };
+        self.marks.insert("[".to_string(), starts);
+    }
+
     // When handling an action, you must create visual marks if you will switch to normal
     // mode without the default selection behavior.
     pub(crate) fn store_visual_marks(&mut self, cx: &mut ViewContext<Self>) {
@@ -68,7 +389,11 @@ impl Vim {
 
     pub fn jump(&mut self, text: Arc<str>, line: bool, cx: &mut ViewContext<Self>) {
         self.pop_operator(cx);
+        self.jump_to_mark(text, line, cx);
+    }
 
+    /// Moves the cursor to a mark, or applies the pending operator up to it.
+    pub(crate) fn jump_to_mark(&mut self, text: Arc<str>, line: bool, cx: &mut ViewContext<Self>) {
         let anchors = match &*text {
             "{" | "}" => self.update_editor(cx, |_, editor, cx| {
                 let (map, selections) = editor.selections.all_display(cx);
@@ -86,6 +411,40 @@ impl Vim {
                     .collect::<Vec<Anchor>>()
             }),
             "." => self.change_list.last().cloned(),
+            "\"" => self
+                .update_editor(cx, |_, editor, cx| {
+                    let point = Vim::globals(cx).last_position(&buffer_path(editor, cx)?)?;
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    Some(vec![
+                        snapshot.anchor_before(snapshot.clip_point(point, Bias::Left))
+                    ])
+                })
+                .flatten(),
+            name if is_global_mark(name) => {
+                let Some(mark) = self
+                    .workspace(cx)
+                    .and_then(|workspace| Vim::global_marks(&workspace, cx).get(name).cloned())
+                else {
+                    return;
+                };
+                let in_this_buffer = self
+                    .editor()
+                    .is_some_and(|editor| editor.project_path(cx).as_ref() == Some(&mark.path));
+                if !in_this_buffer {
+                    // an operator can't span files, so it's cancelled
+                    if self.active_operator().is_some() {
+                        self.clear_operator(cx);
+                    } else {
+                        self.open_global_mark(mark, line, cx);
+                    }
+                    return;
+                }
+                self.update_editor(cx, |_, editor, cx| {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let point = snapshot.clip_point(mark.point(cx), Bias::Left);
+                    vec![snapshot.anchor_before(point)]
+                })
+            }
             _ => self.marks.get(&*text).cloned(),
         };
 
@@ -104,25 +463,28 @@ impl Vim {
             }
             return;
         } else {
-            self.update_editor(cx, |_, editor, cx| {
-                let map = editor.snapshot(cx);
-                let mut ranges: Vec<Range<Anchor>> = Vec::new();
-                for mut anchor in anchors {
-                    if line {
-                        let mut point = anchor.to_display_point(&map.display_snapshot);
-                        point = motion::first_non_whitespace(&map.display_snapshot, false, point);
-                        anchor = map
-                            .display_snapshot
-                            .buffer_snapshot
-                            .anchor_before(point.to_point(&map.display_snapshot));
-                    }
-                    if ranges.last() != Some(&(anchor..anchor)) {
-                        ranges.push(anchor..anchor);
+            self.record_jump(true, cx, |vim, cx| {
+                vim.update_editor(cx, |_, editor, cx| {
+                    let map = editor.snapshot(cx);
+                    let mut ranges: Vec<Range<Anchor>> = Vec::new();
+                    for mut anchor in anchors {
+                        if line {
+                            let mut point = anchor.to_display_point(&map.display_snapshot);
+                            point =
+                                motion::first_non_whitespace(&map.display_snapshot, false, point);
+                            anchor = map
+                                .display_snapshot
+                                .buffer_snapshot
+                                .anchor_before(point.to_point(&map.display_snapshot));
+                        }
+                        if ranges.last() != Some(&(anchor..anchor)) {
+                            ranges.push(anchor..anchor);
+                        }
                     }
-                }
-                editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
-                    s.select_anchor_ranges(ranges)
-                })
+                    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                        s.select_anchor_ranges(ranges)
+                    })
+                });
             });
         }
     }
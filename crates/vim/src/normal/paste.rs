@@ -1,16 +1,22 @@
 use std::cmp;
 
-use editor::{display_map::ToDisplayPoint, movement, scroll::Autoscroll, DisplayPoint, RowExt};
+use editor::{
+    display_map::ToDisplayPoint, movement, scroll::Autoscroll, ClipboardSelection, DisplayPoint,
+    Editor, RowExt,
+};
 use gpui::{impl_actions, ViewContext};
-use language::{Bias, SelectionGoal};
+use language::{Bias, Point, SelectionGoal};
+use multi_buffer::{MultiBufferRow, ToOffset};
 use serde::Deserialize;
 
 use crate::{
+    motion::{Motion, MotionKind},
+    object::Object,
     state::{Mode, Register},
     Vim,
 };
 
-#[derive(Clone, Deserialize, PartialEq)]
+#[derive(Clone, Default, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Paste {
     #[serde(default)]
@@ -37,6 +43,7 @@ impl Vim {
                 let Some(Register {
                     text,
                     clipboard_selections,
+                    kind,
                 }) = Vim::update_globals(cx, |globals, cx| {
                     globals.read_register(selected_register, Some(editor), cx)
                 })
@@ -44,8 +51,27 @@ impl Vim {
                 else {
                     return;
                 };
+                let blockwise = kind == MotionKind::Blockwise;
                 let clipboard_selections = clipboard_selections
+                    .or_else(|| {
+                        // a block appended to loses its selections, so split it into lines again
+                        blockwise.then(|| {
+                            text.split('\n')
+                                .map(|line| ClipboardSelection {
+                                    len: line.len(),
+                                    is_entire_line: false,
+                                    first_line_indent: 0,
+                                })
+                                .collect()
+                        })
+                    })
                     .filter(|sel| sel.len() > 1 && vim.mode != Mode::VisualLine);
+                let block_width = text
+                    .split('\n')
+                    .map(|line| line.chars().count())
+                    .max()
+                    .unwrap_or(0);
+                let before = action.before || vim.mode == Mode::VisualLine;
 
                 if !action.preserve_clipboard && vim.mode.is_visual() {
                     vim.copy_selections_content(editor, vim.mode == Mode::VisualLine, cx);
@@ -56,11 +82,16 @@ impl Vim {
                 // unlike zed, if you have a multi-cursor selection from vim block mode,
                 // pasting it will paste it on subsequent lines, even if you don't yet
                 // have a cursor there.
+                // a block is padded with spaces to reach its column on short lines, and
+                // extends the buffer with new lines if it runs past the end.
                 let mut selections_to_process = Vec::new();
                 let mut i = 0;
                 while i < current_selections.len() {
-                    selections_to_process
-                        .push((current_selections[i].start..current_selections[i].end, true));
+                    selections_to_process.push((
+                        current_selections[i].start..current_selections[i].end,
+                        true,
+                        String::new(),
+                    ));
                     i += 1;
                 }
                 if let Some(clipboard_selections) = clipboard_selections.as_ref() {
@@ -69,11 +100,22 @@ impl Vim {
                         .map(|selection| cmp::min(selection.start.column(), selection.end.column()))
                         .min()
                         .unwrap();
+                    let column = if before { left } else { left + 1 };
                     let mut row = current_selections.last().unwrap().end.row().next_row();
                     while i < clipboard_selections.len() {
-                        let cursor =
-                            display_map.clip_point(DisplayPoint::new(row, left), Bias::Left);
-                        selections_to_process.push((cursor..cursor, false));
+                        let mut padding = String::new();
+                        let cursor = if blockwise && row > display_map.max_point().row() {
+                            padding.push('\n');
+                            padding.push_str(&" ".repeat(column as usize));
+                            display_map.max_point()
+                        } else {
+                            if blockwise {
+                                let line_len = display_map.line_len(row);
+                                padding = " ".repeat(column.saturating_sub(line_len) as usize);
+                            }
+                            display_map.clip_point(DisplayPoint::new(row, left), Bias::Left)
+                        };
+                        selections_to_process.push((cursor..cursor, false, padding));
                         i += 1;
                         row.0 += 1;
                     }
@@ -85,14 +127,15 @@ impl Vim {
                             .first()
                             .map(|selection| selection.first_line_indent)
                     });
-                let before = action.before || vim.mode == Mode::VisualLine;
 
                 let mut edits = Vec::new();
                 let mut new_selections = Vec::new();
+                let mut changed = Vec::new();
                 let mut original_indent_columns = Vec::new();
                 let mut start_offset = 0;
 
-                for (ix, (selection, preserve)) in selections_to_process.iter().enumerate() {
+                for (ix, (selection, preserve, padding)) in selections_to_process.iter().enumerate()
+                {
                     let (mut to_insert, original_indent_column) =
                         if let Some(clipboard_selections) = &clipboard_selections {
                             if let Some(clipboard_selection) = clipboard_selections.get(ix) {
@@ -106,8 +149,16 @@ impl Vim {
                         } else {
                             (text.to_string(), first_selection_indent_column)
                         };
-                    let line_mode = to_insert.ends_with('\n');
-                    let is_multiline = to_insert.contains('\n');
+                    let line_mode = kind == MotionKind::Linewise;
+                    if line_mode && !to_insert.ends_with('\n') {
+                        to_insert.push('\n');
+                    }
+                    let in_block = blockwise
+                        && clipboard_selections
+                            .as_ref()
+                            .is_some_and(|selections| ix < selections.len());
+                    // like multiline text, a block leaves the cursor at its start
+                    let is_multiline = to_insert.contains('\n') || in_block;
 
                     if line_mode && !before {
                         if selection.is_empty() {
@@ -138,6 +189,17 @@ impl Vim {
                         point..point
                     };
 
+                    if in_block {
+                        // keep the text after the block lined up
+                        if display_range.end.column()
+                            < display_map.line_len(display_range.end.row())
+                        {
+                            let width = to_insert.chars().count();
+                            to_insert.push_str(&" ".repeat(block_width.saturating_sub(width)));
+                        }
+                        to_insert.insert_str(0, padding);
+                    }
+
                     let point_range = display_range.start.to_point(&display_map)
                         ..display_range.end.to_point(&display_map);
                     let anchor = if is_multiline || vim.mode == Mode::VisualLine {
@@ -149,12 +211,27 @@ impl Vim {
                     if *preserve {
                         new_selections.push((anchor, line_mode, is_multiline));
                     }
+                    changed.push((
+                        display_map.buffer_snapshot.anchor_before(point_range.start),
+                        display_map.buffer_snapshot.anchor_after(point_range.end),
+                        to_insert.starts_with('\n'),
+                    ));
                     edits.push((point_range, to_insert.repeat(count)));
                     original_indent_columns.extend(original_indent_column);
                 }
 
                 editor.edit_with_block_indent(edits, original_indent_columns, cx);
 
+                // text pasted as lines starts after the newline that was inserted before it
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                vim.set_change_marks(
+                    changed.into_iter().map(|(start, end, leading_newline)| {
+                        start.to_offset(&snapshot) + leading_newline as usize
+                            ..end.to_offset(&snapshot)
+                    }),
+                    &snapshot,
+                );
+
                 // in line_mode vim will insert the new text on the next (or previous if before) line
                 // and put the cursor on the first non-blank character of the first inserted line (or at the end if the first line is blank).
                 // otherwise vim will insert the next text at (or before) the current cursor position,
@@ -192,6 +269,104 @@ impl Vim {
         });
         self.switch_mode(Mode::Normal, true, cx);
     }
+
+    pub fn replace_with_register_motion(
+        &mut self,
+        motion: Motion,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.stop_recording(cx);
+        let selected_register = self.selected_register.take();
+        let mut count = Vim::globals(cx).replace_with_register_count;
+        // `[count]grr` replaces `count` lines with the register once
+        let times = match (&motion, count) {
+            (Motion::CurrentLine, Some(lines)) => {
+                count = None;
+                Some(times.unwrap_or(1) * lines)
+            }
+            _ => times,
+        };
+        self.update_editor(cx, |_, editor, cx| {
+            let text_layout_details = editor.text_layout_details(cx);
+            editor.transact(cx, |editor, cx| {
+                editor.set_clip_at_line_ends(false, cx);
+                editor.change_selections(None, cx, |s| {
+                    s.move_with(|map, selection| {
+                        motion.expand_selection(map, selection, times, false, &text_layout_details);
+                    });
+                });
+                replace_selections_with_register(editor, selected_register, count, cx);
+                editor.set_clip_at_line_ends(true, cx);
+            });
+        });
+    }
+
+    pub fn replace_with_register_object(
+        &mut self,
+        object: Object,
+        around: bool,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.stop_recording(cx);
+        let selected_register = self.selected_register.take();
+        let count = Vim::globals(cx).replace_with_register_count;
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(cx, |editor, cx| {
+                editor.set_clip_at_line_ends(false, cx);
+                editor.change_selections(None, cx, |s| {
+                    s.move_with(|map, selection| {
+                        object.expand_selection(map, selection, around, times);
+                    });
+                });
+                replace_selections_with_register(editor, selected_register, count, cx);
+                editor.set_clip_at_line_ends(true, cx);
+            });
+        });
+    }
+}
+
+/// Replaces the text of each selection with the contents of `register`, put `count` times,
+/// leaving the cursor at the start of the replaced text. Unlike a visual put, the replaced text
+/// is not yanked, so the same register can be put again (`gr`).
+///
+/// A linewise register keeps its lines whole, like `"_c{motion}<C-R>"`, except that replacing
+/// whole lines (`grr`) leaves out its last newline, as the replaced lines keep theirs.
+fn replace_selections_with_register(
+    editor: &mut Editor,
+    register: Option<char>,
+    count: Option<usize>,
+    cx: &mut ViewContext<Editor>,
+) {
+    let Some(Register { text, kind, .. }) = Vim::update_globals(cx, |globals, cx| {
+        globals.read_register(register, Some(editor), cx)
+    }) else {
+        return;
+    };
+    let text = text.repeat(count.unwrap_or(1));
+    let lines_text = match kind {
+        MotionKind::Linewise => text.strip_suffix('\n').unwrap_or(&text).to_string(),
+        _ => text.clone(),
+    };
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let selections = editor.selections.all::<Point>(cx);
+    let mut edits = Vec::new();
+    let mut anchors = Vec::new();
+    for selection in selections {
+        if !selection.is_empty() {
+            let whole_lines = selection.start.column == 0
+                && selection.end.column == snapshot.line_len(MultiBufferRow(selection.end.row));
+            let text = if whole_lines { &lines_text } else { &text };
+            edits.push((selection.start..selection.end, text.clone()));
+        }
+        let anchor = snapshot.anchor_before(selection.start);
+        anchors.push(anchor..anchor);
+    }
+    editor.edit(edits, cx);
+    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+        s.select_anchor_ranges(anchors);
+    });
 }
 
 #[cfg(test)]
@@ -321,6 +496,24 @@ mod test {
         assert_eq!(cx.read_from_clipboard(), None);
     }
 
+    #[gpui::test]
+    async fn test_delete_without_yank_on_delete(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.use_system_clipboard = Some(UseSystemClipboard::Never);
+                s.yank_on_delete = Some(false);
+            });
+        });
+
+        cx.set_state("The ˇquick brown fox", Mode::Normal);
+        cx.simulate_keystrokes("y i w w d i w");
+        cx.assert_state("The quick ˇ fox", Mode::Normal);
+        cx.simulate_keystrokes("p");
+        cx.assert_state("The quick  quicˇkfox", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_yank_system_clipboard_on_yank(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -384,6 +577,34 @@ mod test {
         );
     }
 
+    #[gpui::test]
+    async fn test_yank_system_clipboard_unnamed(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.use_system_clipboard = Some(UseSystemClipboard::Unnamed)
+            });
+        });
+
+        cx.set_state("The ˇquick brown fox", Mode::Normal);
+        cx.simulate_keystrokes("y i w");
+        #[cfg(target_os = "linux")]
+        assert_eq!(cx.read_from_clipboard(), None);
+
+        // "+ is the clipboard, and p reads "*, which is only the clipboard outside of Linux
+        cx.simulate_keystrokes("w \" + y i w p");
+        #[cfg(target_os = "linux")]
+        cx.assert_state("The quick bquicˇkrown fox", Mode::Normal);
+        #[cfg(not(target_os = "linux"))]
+        cx.assert_state("The quick bbrowˇnrown fox", Mode::Normal);
+        assert_eq!(
+            cx.read_from_clipboard()
+                .map(|item| item.text().unwrap().to_string()),
+            Some("brown".into())
+        );
+    }
+
     #[gpui::test]
     async fn test_paste_visual(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -578,6 +799,54 @@ mod test {
         "});
     }
 
+    #[gpui::test]
+    async fn test_black_hole_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("y i w w \" _ d i w shift-p");
+        cx.assert_state("one onˇe three", Mode::Normal);
+
+        cx.simulate_keystrokes("w \" _ c i w four escape p");
+        cx.assert_state("one one fouronˇe", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_small_delete_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two\nthree four", Mode::Normal);
+        cx.simulate_keystrokes("d w j d d");
+        cx.assert_state("ˇtwo", Mode::Normal);
+
+        cx.simulate_keystrokes("\" - p");
+        cx.assert_state("toneˇ wo", Mode::Normal);
+        cx.simulate_keystrokes("\" 1 p");
+        cx.assert_state("tone wo\nˇthree four", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_register_kinds(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        // a charwise yank ending in a newline still pastes inline
+        cx.set_state("«one\nˇ»two", Mode::Visual);
+        cx.simulate_keystrokes("y j p");
+        cx.assert_state("one\ntˇone\nwo", Mode::Normal);
+
+        // the lines covered by a text object paste as lines
+        cx.set_state("ˇa\nb\n\nc", Mode::Normal);
+        cx.simulate_keystrokes("y i p shift-g p");
+        cx.assert_state("a\nb\n\nc\nˇa\nb", Mode::Normal);
+
+        // a block is padded to its column, and adds lines past the end
+        cx.set_state("ˇ12\n34\nabcd\ne", Mode::Normal);
+        cx.simulate_keystrokes("ctrl-v j l y j j l p");
+        cx.assert_state("12\n34\nabˇ12cd\ne 34", Mode::Normal);
+        cx.simulate_keystrokes("shift-g 0 p");
+        cx.assert_state("12\n34\nab12cd\neˇ12 34\n 34", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_numbered_registers(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -653,6 +922,37 @@ mod test {
         cx.shared_register('a').await.assert_eq(" over");
     }
 
+    #[gpui::test]
+    async fn test_numbered_registers_shift_to_9(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇ1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11", Mode::Normal);
+        cx.simulate_keystrokes("y y j d d d d d d d d d d d d d d d d d d");
+        cx.assert_state("1\nˇ11", Mode::Normal);
+
+        cx.simulate_keystrokes("\" 9 p");
+        cx.assert_state("1\n11\nˇ2", Mode::Normal);
+        cx.simulate_keystrokes("\" 1 p");
+        cx.assert_state("1\n11\n2\nˇ10", Mode::Normal);
+        cx.simulate_keystrokes("\" 0 p");
+        cx.assert_state("1\n11\n2\n10\nˇ1", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_append_lines_to_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two\nthree", Mode::Normal);
+        cx.simulate_keystrokes("\" a y i w j \" shift-a y y");
+        cx.simulate_keystrokes("\" a p");
+        cx.assert_state("one two\nthree\nˇone\nthree", Mode::Normal);
+
+        cx.set_state("ˇone two\nthree", Mode::Normal);
+        cx.simulate_keystrokes("\" b y y w \" shift-b y i w");
+        cx.simulate_keystrokes("j \" b p");
+        cx.assert_state("one two\nthree\nˇone two\ntwo", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_special_registers(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -701,6 +1001,23 @@ mod test {
         );
     }
 
+    #[gpui::test]
+    async fn test_read_only_registers(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇ", Mode::Normal);
+        cx.simulate_keystrokes("i h e l l o escape \" . p");
+        cx.assert_state("hellohellˇo", Mode::Normal);
+
+        cx.simulate_keystrokes(": 1 enter");
+        cx.simulate_keystrokes("\" : p");
+        cx.assert_state("hˇ1ellohello", Mode::Normal);
+
+        cx.set_state("ˇ", Mode::Normal);
+        cx.simulate_keystrokes("i a b c backspace escape \" . p");
+        cx.assert_state("abaˇb", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_multicursor_paste(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -731,4 +1048,77 @@ mod test {
             Mode::Normal,
         );
     }
+
+    #[gpui::test]
+    async fn test_replace_with_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("y i w w g r i w");
+        cx.assert_state("one ˇone three", Mode::Normal);
+        // the replaced text is not yanked, so repeating puts the same text
+        cx.simulate_keystrokes("w .");
+        cx.assert_state("one one ˇone", Mode::Normal);
+
+        cx.set_state("ˇalpha beta gamma", Mode::Normal);
+        cx.simulate_keystrokes("\" a y i w w w \" a g r e");
+        cx.assert_state("alpha beta ˇalpha", Mode::Normal);
+
+        cx.set_state(
+            indoc! {"
+            ˇfirst
+            second
+            third"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("y y j g r r");
+        cx.assert_state(
+            indoc! {"
+            first
+            ˇfirst
+            third"},
+            Mode::Normal,
+        );
+
+        // the count before `grr` is the number of lines
+        cx.set_state(
+            indoc! {"
+            ˇfirst
+            second
+            third
+            fourth"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("y y j 2 g r r");
+        cx.assert_state(
+            indoc! {"
+            first
+            ˇfirst
+            fourth"},
+            Mode::Normal,
+        );
+
+        // a linewise register stays linewise in the middle of a line
+        cx.set_state(
+            indoc! {"
+            ˇfirst
+            second third"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("y y j w g r i w");
+        cx.assert_state(
+            indoc! {"
+            first
+            second ˇfirst
+            "},
+            Mode::Normal,
+        );
+
+        // the count before `gr` puts the register that many times, and `.` does too
+        cx.set_state("ˇab cd ef", Mode::Normal);
+        cx.simulate_keystrokes("y i w w 2 g r i w");
+        cx.assert_state("ab ˇabab ef", Mode::Normal);
+        cx.simulate_keystrokes("w .");
+        cx.assert_state("ab abab ˇabab", Mode::Normal);
+    }
 }
@@ -1,18 +1,167 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use anyhow::anyhow;
 
 use crate::{
+    command::command_interceptor,
     insert::NormalBefore,
     motion::Motion,
     state::{Mode, Operator, RecordedSelection, ReplayableAction, VimGlobals},
     Vim,
 };
 use editor::Editor;
-use gpui::{actions, Action, ViewContext, WindowContext};
+use gpui::{actions, Action, AppContext, Global, Keystroke, Modifiers, ViewContext, WindowContext};
 use util::ResultExt;
 use workspace::Workspace;
 
 actions!(vim, [Repeat, EndRepeat, ToggleRecord, ReplayLastRecording]);
 
+/// How long a replay runs before letting the user's keys in, so that escape can cancel it.
+/// The window only draws, and so only scrolls to the cursor, when the replay yields.
+const REPLAY_YIELD_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Vim's names for the keys written as `<...>` in a register, with gpui's. The first name for
+/// a key is the one it's recorded with.
+const KEY_NAMES: &[(&str, &str)] = &[
+    ("Esc", "escape"),
+    ("CR", "enter"),
+    ("Enter", "enter"),
+    ("Return", "enter"),
+    ("Tab", "tab"),
+    ("BS", "backspace"),
+    ("Del", "delete"),
+    ("Space", "space"),
+    ("Up", "up"),
+    ("Down", "down"),
+    ("Left", "left"),
+    ("Right", "right"),
+    ("Home", "home"),
+    ("End", "end"),
+    ("PageUp", "pageup"),
+    ("PageDown", "pagedown"),
+    ("Insert", "insert"),
+    ("lt", "<"),
+];
+
+/// Writes `keystroke` the way vim shows it in a register: typed text as itself, and other keys
+/// in `<...>` notation, like `<Esc>` or `<C-w>`.
+pub(crate) fn key_notation(keystroke: &Keystroke) -> String {
+    let modifiers = keystroke.modifiers;
+    let name = KEY_NAMES
+        .iter()
+        .find(|(_, key)| *key == keystroke.key)
+        .map(|(name, _)| name.to_string())
+        .or_else(|| {
+            let number = keystroke.key.strip_prefix('f')?;
+            (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+                .then(|| keystroke.key.to_uppercase())
+        });
+    if !modifiers.control && !modifiers.alt && !modifiers.platform {
+        if name.is_none() || keystroke.key == "space" || keystroke.key == "<" {
+            if let Some(text) = keystroke.clone().with_simulated_ime().ime_key {
+                return if text == "<" { "<lt>".into() } else { text };
+            }
+        }
+    }
+
+    let mut notation = "<".to_string();
+    if modifiers.control {
+        notation.push_str("C-");
+    }
+    if modifiers.alt {
+        notation.push_str("M-");
+    }
+    if modifiers.platform {
+        notation.push_str("D-");
+    }
+    if modifiers.shift {
+        notation.push_str("S-");
+    }
+    notation.push_str(name.as_deref().unwrap_or(&keystroke.key));
+    notation.push('>');
+    notation
+}
+
+/// Reads the keys of a macro from register text, the reverse of [`key_notation`]. A `<` that
+/// doesn't start a key name is typed as it is.
+pub(crate) fn parse_key_notation(text: &str) -> Vec<Keystroke> {
+    let mut keystrokes = Vec::new();
+    let mut rest = text;
+    while let Some(char) = rest.chars().next() {
+        if char == '<' {
+            if let Some((keystroke, len)) = rest.find('>').and_then(|end| {
+                let keystroke = parse_key_name(&rest[1..end])?;
+                Some((keystroke, end + 1))
+            }) {
+                keystrokes.push(keystroke);
+                rest = &rest[len..];
+                continue;
+            }
+        }
+
+        let (key, shift) = match char {
+            '\n' | '\r' => ("enter".to_string(), false),
+            '\t' => ("tab".to_string(), false),
+            ' ' => ("space".to_string(), false),
+            '\u{1b}' => ("escape".to_string(), false),
+            char if char.is_uppercase() => (char.to_lowercase().to_string(), true),
+            char => (char.to_string(), false),
+        };
+        let ime_key = (key.chars().count() == 1).then(|| char.to_string());
+        keystrokes.push(Keystroke {
+            modifiers: Modifiers {
+                shift,
+                ..Default::default()
+            },
+            key,
+            ime_key,
+        });
+        rest = &rest[char.len_utf8()..];
+    }
+    keystrokes
+}
+
+fn parse_key_name(mut name: &str) -> Option<Keystroke> {
+    let mut modifiers = Modifiers::default();
+    while let Some((modifier, rest)) = name
+        .split_once('-')
+        .filter(|(modifier, rest)| modifier.len() == 1 && !rest.is_empty())
+    {
+        match modifier {
+            "C" | "c" => modifiers.control = true,
+            "M" | "m" | "A" | "a" => modifiers.alt = true,
+            "S" | "s" => modifiers.shift = true,
+            "D" | "d" => modifiers.platform = true,
+            _ => return None,
+        }
+        name = rest;
+    }
+
+    let key = KEY_NAMES
+        .iter()
+        .find(|(vim_name, _)| vim_name.eq_ignore_ascii_case(name))
+        .map(|(_, key)| key.to_string())
+        .or_else(|| {
+            let number = name.strip_prefix(|c| c == 'F' || c == 'f')?;
+            (!number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+                .then(|| name.to_lowercase())
+        })
+        .or_else(|| {
+            // `<C-w>`, but not `<w>`, which vim types as it is
+            (name.chars().count() == 1 && modifiers != Modifiers::default())
+                .then(|| name.to_lowercase())
+        })?;
+    Some(Keystroke {
+        modifiers,
+        key,
+        ime_key: None,
+    })
+}
+
 fn should_replay(action: &dyn Action) -> bool {
     // skip so that we don't leave the character palette open
     if editor::actions::ShowCharacterPalette.partial_eq(action) {
@@ -28,6 +177,7 @@ fn repeatable_insert(action: &ReplayableAction) -> Option<Box<dyn Action>> {
                 || super::InsertAfter.partial_eq(&**action)
                 || super::InsertFirstNonWhitespace.partial_eq(&**action)
                 || super::InsertEndOfLine.partial_eq(&**action)
+                || super::InsertAtPrevious.partial_eq(&**action)
             {
                 Some(super::InsertBefore.boxed_clone())
             } else if super::InsertLineAbove.partial_eq(&**action)
@@ -36,6 +186,8 @@ fn repeatable_insert(action: &ReplayableAction) -> Option<Box<dyn Action>> {
                 Some(super::InsertLineBelow.boxed_clone())
             } else if crate::replace::ToggleReplace.partial_eq(&**action) {
                 Some(crate::replace::ToggleReplace.boxed_clone())
+            } else if crate::replace::ToggleVirtualReplace.partial_eq(&**action) {
+                Some(crate::replace::ToggleVirtualReplace.boxed_clone())
             } else {
                 None
             }
@@ -44,6 +196,58 @@ fn repeatable_insert(action: &ReplayableAction) -> Option<Box<dyn Action>> {
     }
 }
 
+/// Makes `actions` the change repeated by `.`, like `repeat#set()` from vim-repeat.
+///
+/// Changes made through vim operators are recorded automatically; this is for features that
+/// edit the buffer through their own actions and still want `.` to repeat them. `count` is
+/// the count replayed with the actions unless `.` is given its own (`3.`).
+pub fn set_repeat(actions: Vec<Box<dyn Action>>, count: Option<usize>, cx: &mut AppContext) {
+    let globals = Vim::globals(cx);
+    if globals.dot_replaying {
+        return;
+    }
+    globals.dot_recording = false;
+    globals.stop_recording_after_next_action = false;
+    globals.recorded_actions = actions.into_iter().map(ReplayableAction::Action).collect();
+    globals.recorded_repeat_keys = None;
+    globals.recorded_count = count;
+    globals.recorded_register = None;
+    globals.recorded_selection = RecordedSelection::None;
+}
+
+/// Like [`set_repeat`], but `.` types `keys` instead, written in vim's key notation
+/// (`cxiw`, `ysiw<lt>b>`). This is what vim-repeat's `repeat#set()` takes, and suits changes
+/// whose actions wait for more keys, like an operator and its motion. The count is typed
+/// before the keys.
+pub fn set_repeat_keys(keys: &str, count: Option<usize>, cx: &mut AppContext) {
+    set_repeat(Vec::new(), count, cx);
+    let globals = Vim::globals(cx);
+    if !globals.dot_replaying {
+        globals.recorded_repeat_keys = Some(parse_key_notation(keys));
+    }
+}
+
+/// The register a macro is being recorded into with `q`, like the "recording @q" shown in the
+/// mode indicator. It's replaced whenever recording starts or stops, so
+/// `cx.observe_global::<MacroRecording>` follows both.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MacroRecording {
+    pub register: Option<char>,
+}
+
+impl Global for MacroRecording {}
+
+/// Returns the register a macro is being recorded into, if one is.
+pub fn recording_register(cx: &AppContext) -> Option<char> {
+    cx.try_global::<MacroRecording>()
+        .and_then(|recording| recording.register)
+}
+
+fn set_recording_register(register: Option<char>, cx: &mut AppContext) {
+    Vim::globals(cx).recording_register = register;
+    cx.set_global(MacroRecording { register });
+}
+
 pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
     Vim::action(editor, cx, |vim, _: &EndRepeat, cx| {
         Vim::globals(cx).dot_replaying = false;
@@ -54,8 +258,21 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
 
     Vim::action(editor, cx, |vim, _: &ToggleRecord, cx| {
         let globals = Vim::globals(cx);
-        if let Some(char) = globals.recording_register.take() {
-            globals.last_recorded_register = Some(char)
+        if let Some(char) = globals.recording_register {
+            globals.last_recorded_register = Some(char);
+            // like vim, the keys go in the register too, where they can be pasted and edited
+            let keys = std::mem::take(&mut globals.recorded_keys);
+            let lower = char.to_ascii_lowercase();
+            globals.registers.insert(lower, keys.into());
+            if lower != char {
+                // `qA` appends to the actions of `a`. If `a` only has text, the keys are
+                // replayed instead, which now include the appended ones.
+                let appended = globals.recordings.remove(&char).unwrap_or_default();
+                if let Some(recording) = globals.recordings.get_mut(&lower) {
+                    recording.extend(appended);
+                }
+            }
+            set_recording_register(None, cx);
         } else {
             vim.push_operator(Operator::RecordRegister, cx);
         }
@@ -73,6 +290,10 @@ pub struct ReplayerState {
     actions: Vec<ReplayableAction>,
     running: bool,
     ix: usize,
+    /// The index to give up at, past the actions queued when the replay started, so that a
+    /// macro that replays itself stops without limiting how many times a count repeats it.
+    limit: usize,
+    last_yield: Instant,
 }
 
 #[derive(Clone)]
@@ -84,6 +305,8 @@ impl Replayer {
             actions: vec![],
             running: false,
             ix: 0,
+            limit: 0,
+            last_yield: Instant::now(),
         })))
     }
 
@@ -95,6 +318,8 @@ impl Replayer {
             return;
         }
         lock.running = true;
+        lock.limit = lock.actions.len() + 10000;
+        lock.last_yield = Instant::now();
         let this = self.clone();
         cx.defer(move |cx| this.next(cx))
     }
@@ -105,16 +330,29 @@ impl Replayer {
 
     pub fn next(self, cx: &mut WindowContext) {
         let mut lock = self.0.borrow_mut();
-        let action = if lock.ix < 10000 {
+        let action = if lock.ix < lock.limit {
             lock.actions.get(lock.ix).cloned()
         } else {
-            log::error!("Aborting replay after 10000 actions");
+            log::error!("Aborting replay after {} actions", lock.limit);
             None
         };
         lock.ix += 1;
+        let should_yield = lock.last_yield.elapsed() >= REPLAY_YIELD_INTERVAL;
+        if should_yield {
+            lock.last_yield = Instant::now();
+        }
         drop(lock);
         let Some(action) = action else {
-            Vim::globals(cx).replayer.take();
+            let globals = Vim::globals(cx);
+            // unless it was cancelled, and another replay started since
+            if globals
+                .replayer
+                .as_ref()
+                .is_some_and(|replayer| Rc::ptr_eq(&replayer.0, &self.0))
+            {
+                globals.replayer.take();
+            }
+            finish_macro_transaction(cx);
             return;
         };
         match action {
@@ -143,15 +381,51 @@ impl Replayer {
                     .log_err();
             }
         }
-        cx.defer(move |cx| self.next(cx));
+        if should_yield {
+            cx.spawn(|mut cx| async move {
+                smol::future::yield_now().await;
+                cx.update(|cx| self.next(cx)).ok();
+            })
+            .detach();
+        } else {
+            cx.defer(move |cx| self.next(cx));
+        }
+    }
+}
+
+/// Groups the edits of a macro that finished replaying into one undo step, once neither its
+/// actions nor its keys are left to replay.
+fn finish_macro_transaction(cx: &mut WindowContext) {
+    let globals = Vim::globals(cx);
+    if globals.is_replaying() {
+        return;
+    }
+    if let Some((editor, Some(first_tx))) = globals.macro_transaction.take() {
+        editor
+            .update(cx, |editor, cx| {
+                editor.group_until_transaction(first_tx, cx)
+            })
+            .ok();
     }
 }
 
 impl Vim {
     pub(crate) fn record_register(&mut self, register: char, cx: &mut ViewContext<Self>) {
+        set_recording_register(Some(register), cx);
         let globals = Vim::globals(cx);
-        globals.recording_register = Some(register);
         globals.recordings.remove(&register);
+        // like yanking into `"A`, `qA` adds to the keys already in register `a`
+        let lower = register.to_ascii_lowercase();
+        globals.recorded_keys = if lower != register {
+            globals
+                .registers
+                .get(&lower)
+                .map(|register| register.text.to_string())
+                .unwrap_or_default()
+        } else {
+            String::new()
+        };
+        globals.pending_recorded_keys.clear();
         globals.ignore_current_insertion = true;
         self.clear_operator(cx)
     }
@@ -167,7 +441,40 @@ impl Vim {
             };
             register = last;
         }
+        if register == ':' {
+            // `@:` runs the last ex command again, from the `:` register
+            globals.last_replayed_register = Some(register);
+            let Some(command) = globals
+                .registers
+                .get(&':')
+                .map(|command| command.text.clone())
+            else {
+                return;
+            };
+            let Some(result) = command_interceptor(&command, cx) else {
+                return;
+            };
+            for _ in 0..count {
+                cx.dispatch_action(result.action.boxed_clone());
+            }
+            return;
+        }
         let Some(actions) = globals.recordings.get(&register) else {
+            // a macro edited as text, or kept from the last session, is replayed as typed keys
+            let Some(text) = globals
+                .registers
+                .get(&register.to_ascii_lowercase())
+                .map(|register| register.text.clone())
+            else {
+                return;
+            };
+            globals.last_replayed_register = Some(register);
+            let keystrokes = parse_key_notation(&text);
+            let mut repeated_keystrokes = vec![];
+            for _ in 0..count {
+                repeated_keystrokes.extend(keystrokes.iter().cloned());
+            }
+            self.replay_keystrokes(repeated_keystrokes, cx);
             return;
         };
 
@@ -178,6 +485,9 @@ impl Vim {
         }
 
         globals.last_replayed_register = Some(register);
+        if !globals.is_replaying() {
+            globals.macro_transaction = Some((self.editor.clone(), None));
+        }
         let mut replayer = globals
             .replayer
             .get_or_insert_with(|| Replayer::new())
@@ -185,14 +495,162 @@ impl Vim {
         replayer.replay(repeated_actions, cx);
     }
 
+    /// Types `keystrokes` into the window one after the other. Those of a macro replayed by
+    /// the macro come before the rest of it.
+    fn replay_keystrokes(&mut self, keystrokes: Vec<Keystroke>, cx: &mut ViewContext<Self>) {
+        let globals = Vim::globals(cx);
+        let running = globals.replaying_keys.is_some();
+        if !globals.is_replaying() {
+            globals.macro_transaction = Some((self.editor.clone(), None));
+        }
+        globals
+            .replaying_keys
+            .get_or_insert_with(Vec::new)
+            .extend(keystrokes.into_iter().rev());
+        if running {
+            return;
+        }
+
+        cx.spawn(|_, mut cx| async move {
+            for ix in 1..=10000 {
+                if ix % 100 == 0 {
+                    // let the user's keys in now and then, so that escape can cancel
+                    smol::future::yield_now().await;
+                }
+                let done = cx.update(|cx| {
+                    let globals = Vim::globals(cx);
+                    let Some(keystroke) = globals.replaying_keys.as_mut().and_then(Vec::pop) else {
+                        globals.replaying_keys = None;
+                        globals.dot_replaying = false;
+                        finish_macro_transaction(cx);
+                        return true;
+                    };
+                    globals.typing_replayed_key = true;
+                    let focused = cx.focused();
+                    cx.dispatch_keystroke(keystroke);
+                    Vim::globals(cx).typing_replayed_key = false;
+                    if cx.focused() != focused {
+                        // like `workspace::SendKeystrokes`, get the focus events out before
+                        // the next key
+                        cx.draw();
+                    }
+                    false
+                })?;
+                if done {
+                    return Ok(());
+                }
+            }
+            cx.update(|cx| {
+                Vim::globals(cx).replaying_keys = None;
+                finish_macro_transaction(cx);
+            })?;
+            Err(anyhow!("Aborting replay after 10000 keys"))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Stops the macro or `.` that's replaying when the user presses escape or ctrl-c. The
+    /// edits it made so far stay, to be undone at once, and vim is left in normal mode.
+    pub(crate) fn cancel_replay(&mut self, keystroke: &Keystroke, cx: &mut ViewContext<Self>) {
+        let is_cancel = keystroke.key == "escape"
+            || keystroke.modifiers.control && (keystroke.key == "c" || keystroke.key == "[");
+        let globals = Vim::globals(cx);
+        if !is_cancel
+            || globals.typing_replayed_key
+            || !globals.is_replaying()
+            || !self.is_focused_vim(cx)
+        {
+            return;
+        }
+        let globals = Vim::globals(cx);
+        globals.replaying_keys = None;
+        self.stop_replaying(cx);
+        self.clear_operator(cx);
+        if self.mode != Mode::Normal {
+            self.switch_mode(Mode::Normal, false, cx);
+        }
+    }
+
+    /// Adds a typed key to the text of the macro being recorded.
+    pub(crate) fn record_keystroke(&mut self, keystroke: &Keystroke, cx: &mut ViewContext<Self>) {
+        if !self.records_keys(cx) {
+            return;
+        }
+        let globals = Vim::globals(cx);
+        // a key recorded while it was pending is reported again if it doesn't complete a binding
+        if globals.pending_recorded_keys.first() == Some(keystroke) {
+            globals.pending_recorded_keys.remove(0);
+            return;
+        }
+        globals.recorded_keys.push_str(&key_notation(keystroke));
+    }
+
+    /// Adds the keys waiting for the rest of a binding to the macro being recorded, since only
+    /// the last key of a binding is reported as typed.
+    pub(crate) fn observe_pending_input(&mut self, cx: &mut ViewContext<Self>) {
+        if !self.records_keys(cx) {
+            return;
+        }
+        let pending = cx
+            .pending_input_keystrokes()
+            .map(|keystrokes| keystrokes.to_vec())
+            .unwrap_or_default();
+        let globals = Vim::globals(cx);
+        if pending.is_empty() {
+            globals.pending_recorded_keys.clear();
+            return;
+        }
+        for keystroke in pending.iter().skip(globals.pending_recorded_keys.len()) {
+            globals.recorded_keys.push_str(&key_notation(keystroke));
+            globals.pending_recorded_keys.push(keystroke.clone());
+        }
+    }
+
+    fn records_keys(&self, cx: &mut ViewContext<Self>) -> bool {
+        // the keys of a macro replayed while recording are recorded as the `@` that replays it
+        let globals = Vim::globals(cx);
+        globals.recording_register.is_some()
+            && !globals.typing_replayed_key
+            && self.is_focused_vim(cx)
+    }
+
+    /// Whether this is the vim of the editor last focused, which gets the keys of the macros.
+    fn is_focused_vim(&self, cx: &mut ViewContext<Self>) -> bool {
+        let entity_id = cx.view().entity_id();
+        Vim::globals(cx)
+            .focused_vim
+            .as_ref()
+            .is_some_and(|vim| vim.entity_id() == entity_id)
+    }
+
     pub(crate) fn repeat(&mut self, from_insert_mode: bool, cx: &mut ViewContext<Self>) {
         let count = self.take_count(cx);
+        let globals = Vim::globals(cx);
+        if let Some(keys) = globals.recorded_repeat_keys.clone() {
+            let count = count.or(globals.recorded_count);
+            if !globals.is_replaying() {
+                if let Some(recording_register) = globals.recording_register {
+                    globals
+                        .recordings
+                        .entry(recording_register)
+                        .or_default()
+                        .push(ReplayableAction::Action(Repeat.boxed_clone()));
+                }
+            }
+            // typed keys would otherwise record themselves as the change to repeat
+            globals.dot_replaying = true;
+            let count_keys = count
+                .map(|count| parse_key_notation(&count.to_string()))
+                .unwrap_or_default();
+            self.replay_keystrokes(count_keys.into_iter().chain(keys).collect(), cx);
+            return;
+        }
         let Some((mut actions, selection, mode)) = Vim::update_globals(cx, |globals, _| {
             let actions = globals.recorded_actions.clone();
             if actions.is_empty() {
                 return None;
             }
-            if globals.replayer.is_none() {
+            if !globals.is_replaying() {
                 if let Some(recording_register) = globals.recording_register {
                     globals
                         .recordings
@@ -202,21 +660,16 @@ impl Vim {
                 }
             }
 
+            // a visual change is repeated on a region of the same size, so it keeps the count
+            // given to its operator (`v3>`) and ignores the one given to `.`.
             let mut mode = None;
             let selection = globals.recorded_selection.clone();
             match selection {
                 RecordedSelection::SingleLine { .. } | RecordedSelection::Visual { .. } => {
-                    globals.recorded_count = None;
                     mode = Some(Mode::Visual);
                 }
-                RecordedSelection::VisualLine { .. } => {
-                    globals.recorded_count = None;
-                    mode = Some(Mode::VisualLine)
-                }
-                RecordedSelection::VisualBlock { .. } => {
-                    globals.recorded_count = None;
-                    mode = Some(Mode::VisualBlock)
-                }
+                RecordedSelection::VisualLine { .. } => mode = Some(Mode::VisualLine),
+                RecordedSelection::VisualBlock { .. } => mode = Some(Mode::VisualBlock),
                 RecordedSelection::None => {
                     if let Some(count) = count {
                         globals.recorded_count = Some(count);
@@ -228,6 +681,18 @@ impl Vim {
         }) else {
             return;
         };
+        // `.` reuses the register of the repeated change unless another one was selected, and
+        // steps through the numbered registers so that `"1p..` pastes `"1`, `"2` and `"3`.
+        let register = Vim::update_globals(cx, |globals, _| {
+            let register = self.selected_register.or_else(|| {
+                globals.recorded_register.map(|register| match register {
+                    '1'..='8' => (register as u8 + 1) as char,
+                    register => register,
+                })
+            });
+            globals.recorded_register = register;
+            register
+        });
         if let Some(mode) = mode {
             self.switch_mode(mode, false, cx)
         }
@@ -280,6 +745,7 @@ impl Vim {
             }
             RecordedSelection::None => {}
         }
+        self.selected_register = register;
 
         // insert internally uses repeat to handle counts
         // vim doesn't treat 3a1 as though you literally repeated a1
@@ -323,17 +789,23 @@ impl Vim {
 
 #[cfg(test)]
 mod test {
-    use editor::test::editor_lsp_test_context::EditorLspTestContext;
+    use std::{cell::RefCell, rc::Rc};
+
+    use editor::{actions::DeleteLine, test::editor_lsp_test_context::EditorLspTestContext};
     use futures::StreamExt;
     use indoc::indoc;
 
-    use gpui::ViewInputHandler;
+    use gpui::{Action, Keystroke, ViewInputHandler};
 
     use crate::{
+        recording_register, set_repeat, set_repeat_keys,
         state::Mode,
         test::{NeovimBackedTestContext, VimTestContext},
+        Vim,
     };
 
+    use super::{key_notation, parse_key_notation, MacroRecording};
+
     #[gpui::test]
     async fn test_dot_repeat(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -594,6 +1066,63 @@ mod test {
         });
     }
 
+    #[gpui::test]
+    async fn test_repeat_count_override(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            "ˇone two three four five six seven eight nine ten",
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("2 d w");
+        cx.assert_state("ˇthree four five six seven eight nine ten", Mode::Normal);
+        cx.simulate_keystrokes("3 .");
+        cx.assert_state("ˇsix seven eight nine ten", Mode::Normal);
+        // the new count is kept for later repeats
+        cx.simulate_keystrokes(".");
+        cx.assert_state("ˇnine ten", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_repeat_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("\" a d w .");
+        cx.assert_state("ˇthree", Mode::Normal);
+        cx.simulate_keystrokes("\" a p");
+        cx.assert_state("ttwoˇ hree", Mode::Normal);
+
+        cx.set_state("ˇone\ntwo\nthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes("d d d d d d \" 1 p . .");
+        cx.assert_state("four\nthree\ntwo\nˇone", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_set_repeat(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo\nthree\nfour", Mode::Normal);
+        cx.dispatch_action(DeleteLine);
+        cx.update(|cx| set_repeat(vec![DeleteLine.boxed_clone()], None, cx));
+        cx.assert_state("ˇtwo\nthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes(".");
+        cx.assert_state("ˇthree\nfour", Mode::Normal);
+
+        cx.set_state("ˇone two three four five", Mode::Normal);
+        cx.update(|cx| set_repeat_keys("dw", None, cx));
+        cx.simulate_keystrokes(".");
+        cx.run_until_parked();
+        cx.assert_state("ˇtwo three four five", Mode::Normal);
+        cx.simulate_keystrokes("2 .");
+        cx.run_until_parked();
+        cx.assert_state("ˇfour five", Mode::Normal);
+        // the typed keys don't replace the change to repeat
+        cx.simulate_keystrokes(".");
+        cx.run_until_parked();
+        cx.assert_state("ˇfive", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_record_interrupted(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -638,6 +1167,26 @@ mod test {
         cx.shared_state().await.assert_eq("j ˇj");
     }
 
+    #[gpui::test]
+    async fn test_macro_recording_state(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let changes = changes.clone();
+            cx.observe_global::<MacroRecording>(move |cx| {
+                changes.borrow_mut().push(recording_register(cx))
+            })
+            .detach();
+        });
+
+        cx.set_state("ˇhello world", Mode::Normal);
+        cx.simulate_keystrokes("q a");
+        assert_eq!(cx.update(|cx| recording_register(cx)), Some('a'));
+        cx.simulate_keystrokes("x q");
+        assert_eq!(cx.update(|cx| recording_register(cx)), None);
+        assert_eq!(*changes.borrow(), vec![Some('a'), None]);
+    }
+
     #[gpui::test]
     async fn test_record_replay_count(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -650,6 +1199,26 @@ mod test {
         cx.shared_state().await.assert_eq("000ˇ!");
     }
 
+    #[gpui::test]
+    async fn test_record_replay_across_lines(cx: &mut gpui::TestAppContext) {
+        let mut cx = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state("ˇone\ntwo\nthree\nfour\nfive").await;
+        cx.simulate_shared_keystrokes("q q shift-a , escape j q")
+            .await;
+        cx.shared_state()
+            .await
+            .assert_eq("one,\ntwˇo\nthree\nfour\nfive");
+        cx.simulate_shared_keystrokes("2 @ q").await;
+        cx.shared_state()
+            .await
+            .assert_eq("one,\ntwo,\nthree,\nfouˇr\nfive");
+        cx.simulate_shared_keystrokes("@ @").await;
+        cx.shared_state()
+            .await
+            .assert_eq("one,\ntwo,\nthree,\nfour,\nfivˇe");
+    }
+
     #[gpui::test]
     async fn test_record_replay_dot(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -678,6 +1247,165 @@ mod test {
         cx.shared_state().await.assert_eq("ˇllo world");
     }
 
+    #[test]
+    fn test_key_notation() {
+        let text = "cwj<Esc>dd<C-w>l<lt>A <S-Tab><F2>";
+        let keystrokes = parse_key_notation(text);
+        assert_eq!(keystrokes.len(), 13);
+        assert_eq!(
+            keystrokes.iter().map(key_notation).collect::<String>(),
+            text
+        );
+        // not a key name, so typed as it is
+        assert_eq!(parse_key_notation("<w>").len(), 3);
+    }
+
+    #[gpui::test]
+    async fn test_edit_macro_as_text(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three four", Mode::Normal);
+        cx.simulate_keystrokes("q a d w q");
+        cx.assert_state("ˇtwo three four", Mode::Normal);
+        let text = cx.update(|cx| Vim::globals(cx).registers[&'a'].text.to_string());
+        assert_eq!(text, "dw");
+
+        // paste the macro, change it to `d2w`, and yank it back
+        cx.simulate_keystrokes("o escape \" a p 0 a 2 escape 0 \" a y $ d d");
+        cx.assert_state("ˇtwo three four", Mode::Normal);
+        cx.simulate_keystrokes("@ a");
+        cx.run_until_parked();
+        cx.assert_state("ˇfour", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_append_to_macro(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three four five", Mode::Normal);
+        cx.simulate_keystrokes("q a d w q");
+        cx.simulate_keystrokes("q shift-a x q");
+        cx.assert_state("ˇwo three four five", Mode::Normal);
+        let text = cx.update(|cx| Vim::globals(cx).registers[&'a'].text.to_string());
+        assert_eq!(text, "dwx");
+
+        cx.simulate_keystrokes("@ a");
+        cx.assert_state("ˇhree four five", Mode::Normal);
+
+        // appending to a macro that was yanked as text
+        cx.simulate_keystrokes("o d w escape 0 \" b y $ d d");
+        cx.simulate_keystrokes("q shift-b x q");
+        cx.assert_state("ˇree four five", Mode::Normal);
+        cx.simulate_keystrokes("@ b");
+        cx.run_until_parked();
+        cx.assert_state("ˇour five", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_replay_macro_text_as_one_change(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇGx", Mode::Normal);
+        cx.simulate_keystrokes("\" a y $");
+        cx.set_state("ˇone\ntwo\nthree", Mode::Normal);
+        cx.simulate_keystrokes("@ a");
+        cx.run_until_parked();
+        cx.assert_state("one\ntwo\nˇhree", Mode::Normal);
+
+        // the jump made by the macro isn't kept
+        cx.simulate_keystrokes("ctrl-o");
+        cx.assert_state("one\ntwo\nˇhree", Mode::Normal);
+
+        cx.simulate_keystrokes("g g 2 @ a");
+        cx.run_until_parked();
+        cx.assert_state("one\ntwo\nˇee", Mode::Normal);
+        cx.simulate_keystrokes("u");
+        assert_eq!(cx.buffer_text(), "one\ntwo\nhree");
+    }
+
+    #[gpui::test]
+    async fn test_cancel_recursive_macro(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        // a macro that appends a dash and replays itself, until it's cancelled
+        cx.set_state("ˇa-<Esc>@a", Mode::Normal);
+        cx.simulate_keystrokes("\" a y $ d d");
+        for key in ["@", "a"] {
+            cx.update(|cx| cx.dispatch_keystroke(Keystroke::parse(key).unwrap()));
+        }
+        cx.executor().tick();
+        cx.simulate_keystrokes("escape");
+
+        let text = cx.buffer_text();
+        assert!(
+            text.len() < 100 && text.chars().all(|c| c == '-'),
+            "{text:?}"
+        );
+        assert_eq!(cx.mode(), Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_undo_replayed_macro(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo\nthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes("q a r x j q");
+        cx.assert_state("xne\nˇtwo\nthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes("2 @ a");
+        cx.assert_state("xne\nxwo\nxhree\nˇfour", Mode::Normal);
+        cx.simulate_keystrokes("u");
+        assert_eq!(cx.buffer_text(), "xne\ntwo\nthree\nfour");
+    }
+
+    #[gpui::test]
+    async fn test_replay_macro_with_large_count(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        let lines = (0..1000).map(|ix| ix.to_string()).collect::<Vec<_>>();
+        cx.set_state(&format!("ˇ{}", lines.join("\n")), Mode::Normal);
+        cx.simulate_keystrokes("q a shift-a ! escape j q");
+
+        let start = std::time::Instant::now();
+        cx.simulate_keystrokes("9 9 9 @ a");
+        assert!(
+            start.elapsed() < std::time::Duration::from_secs(30),
+            "replaying the macro 999 times took {:?}",
+            start.elapsed()
+        );
+        let expected = lines
+            .iter()
+            .map(|line| format!("{line}!"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(cx.buffer_text(), expected);
+
+        cx.simulate_keystrokes("u");
+        assert_eq!(cx.buffer_text(), format!("0!\n{}", lines[1..].join("\n")));
+    }
+
+    #[gpui::test]
+    async fn test_replay_ex_command(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo\nthree\nfour\nfive", Mode::Normal);
+        cx.simulate_keystrokes(": j enter");
+        cx.run_until_parked();
+        assert_eq!(cx.buffer_text(), "one two\nthree\nfour\nfive");
+        cx.simulate_keystrokes("@ :");
+        cx.run_until_parked();
+        assert_eq!(cx.buffer_text(), "one two three\nfour\nfive");
+        cx.simulate_keystrokes("@ @");
+        cx.run_until_parked();
+        assert_eq!(cx.buffer_text(), "one two three four\nfive");
+
+        cx.set_state("ˇone\ntwo\nthree\nfour\nfive", Mode::Normal);
+        cx.simulate_keystrokes("2 @ :");
+        cx.run_until_parked();
+        assert_eq!(cx.buffer_text(), "one two three\nfour\nfive");
+        cx.simulate_keystrokes("0 \" : shift-p");
+        assert_eq!(cx.buffer_text(), "jone two three\nfour\nfive");
+    }
+
     #[gpui::test]
     async fn test_record_replay_interleaved(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
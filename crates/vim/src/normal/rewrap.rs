@@ -0,0 +1,395 @@
+use crate::{motion::Motion, normal::Rewrap, object::Object, state::Mode, Vim};
+use collections::HashSet;
+use editor::{scroll::Autoscroll, Editor};
+use language::Point;
+use multi_buffer::MultiBufferRow;
+use ui::ViewContext;
+
+impl Vim {
+    pub(crate) fn rewrap(&mut self, _: &Rewrap, cx: &mut ViewContext<Self>) {
+        self.record_current_action(cx);
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(cx, |editor, cx| {
+                rewrap_selections(editor, &HashSet::default(), cx)
+            });
+        });
+        if self.mode.is_visual() {
+            self.switch_mode(Mode::Normal, false, cx)
+        }
+    }
+
+    pub fn rewrap_motion(
+        &mut self,
+        motion: Motion,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.stop_recording(cx);
+        self.update_editor(cx, |_, editor, cx| {
+            let text_layout_details = editor.text_layout_details(cx);
+            editor.transact(cx, |editor, cx| {
+                let mut ends_adjusted = HashSet::default();
+                editor.change_selections(None, cx, |s| {
+                    s.move_with(|map, selection| {
+                        let target = motion.move_point(
+                            map,
+                            selection.head(),
+                            selection.goal,
+                            times,
+                            &text_layout_details,
+                        );
+                        motion.expand_selection(map, selection, times, false, &text_layout_details);
+                        // an exclusive motion to the start of a line, like `}`, stops at the end
+                        // of the line before it
+                        if target.is_some_and(|(target, _)| {
+                            target.column() == 0 && selection.end < target
+                        }) {
+                            ends_adjusted.insert(selection.id);
+                        }
+                    });
+                });
+                rewrap_selections(editor, &ends_adjusted, cx);
+            });
+        });
+    }
+
+    pub fn rewrap_object(
+        &mut self,
+        object: Object,
+        around: bool,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.stop_recording(cx);
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(cx, |editor, cx| {
+                editor.change_selections(None, cx, |s| {
+                    s.move_with(|map, selection| {
+                        object.expand_selection(map, selection, around, times);
+                    });
+                });
+                rewrap_selections(editor, &HashSet::default(), cx);
+            });
+        });
+    }
+}
+
+/// Reflows the lines covered by each selection to the language's preferred line length,
+/// leaving the cursor on the first non-blank character of the last line, as `gq` does. The
+/// cursors of the selections in `ends_adjusted`, whose motion went on to the next line, go
+/// there instead, so that `.` formats the lines after.
+fn rewrap_selections(
+    editor: &mut Editor,
+    ends_adjusted: &HashSet<usize>,
+    cx: &mut ViewContext<Editor>,
+) {
+    let buffer = editor.buffer().read(cx).snapshot(cx);
+    let mut edits = Vec::new();
+    let mut cursors = Vec::new();
+    let mut previous_end_row = None;
+    for selection in editor.selections.all::<Point>(cx) {
+        let start_row = selection.start.row;
+        let mut end_row = selection.end.row;
+        // ending at the start of a line doesn't cover it, unless it's a blank line like the
+        // one that ends `ap`
+        if end_row > start_row
+            && selection.end.column == 0
+            && buffer.line_len(MultiBufferRow(end_row)) > 0
+        {
+            end_row -= 1;
+        }
+        if previous_end_row.is_some_and(|previous_end_row| start_row <= previous_end_row) {
+            continue;
+        }
+        previous_end_row = Some(end_row);
+
+        let start = Point::new(start_row, 0);
+        let end = Point::new(end_row, buffer.line_len(MultiBufferRow(end_row)));
+        let settings = buffer.settings_at(start, cx);
+        let comment_prefixes = buffer
+            .language_scope_at(start)
+            .map(|scope| scope.line_comment_prefixes().to_vec())
+            .unwrap_or_default();
+        let comment_prefixes = comment_prefixes
+            .iter()
+            .map(|prefix| prefix.trim_end())
+            .filter(|prefix| !prefix.is_empty())
+            .collect::<Vec<_>>();
+
+        let text = buffer.text_for_range(start..end).collect::<String>();
+        let lines = text.split('\n').collect::<Vec<_>>();
+        let new_text = rewrap_lines(
+            &lines,
+            settings.preferred_line_length as usize,
+            settings.tab_size.get() as usize,
+            &comment_prefixes,
+        );
+
+        let last_line = new_text.rsplit('\n').next().unwrap_or_default();
+        let mut last_row = start_row + new_text.matches('\n').count() as u32;
+        let mut column = (last_line.len() - last_line.trim_start().len()) as u32;
+        if ends_adjusted.contains(&selection.id) && end_row < buffer.max_point().row {
+            let next_row = MultiBufferRow(end_row + 1);
+            last_row += 1;
+            column = buffer.indent_size_for_line(next_row).len;
+        }
+        cursors.push((edits.len(), last_row, column));
+        edits.push((start..end, new_text));
+    }
+
+    // Rows after an edit move by the number of lines the earlier edits added or removed.
+    let mut row_delta = 0i64;
+    let mut cursor_points = Vec::new();
+    for (ix, last_row, column) in cursors {
+        let (range, new_text) = &edits[ix];
+        let row = (last_row as i64 + row_delta) as u32;
+        cursor_points.push(Point::new(row, column));
+        row_delta +=
+            new_text.matches('\n').count() as i64 - (range.end.row - range.start.row) as i64;
+    }
+
+    editor.edit(edits, cx);
+    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+        s.select_ranges(cursor_points.into_iter().map(|point| point..point));
+    });
+}
+
+struct Line<'a> {
+    /// The indentation and comment leader.
+    leader: &'a str,
+    /// A list item marker, such as `- ` or `1. `.
+    marker: &'a str,
+    body: &'a str,
+}
+
+fn parse_line<'a>(line: &'a str, comment_prefixes: &[&str]) -> Line<'a> {
+    let indent_len = line.len() - line.trim_start().len();
+    let mut leader_len = indent_len;
+    if let Some(prefix) = comment_prefixes
+        .iter()
+        .filter(|prefix| line[indent_len..].starts_with(*prefix))
+        .max_by_key(|prefix| prefix.len())
+    {
+        leader_len += prefix.len();
+        leader_len += line[leader_len..].len() - line[leader_len..].trim_start().len();
+    }
+
+    let rest = &line[leader_len..];
+    let digits = rest.chars().take_while(|c| c.is_ascii_digit()).count();
+    let marker_len = if rest.starts_with("- ") || rest.starts_with("* ") || rest.starts_with("+ ") {
+        2
+    } else if digits > 0 && (rest[digits..].starts_with(". ") || rest[digits..].starts_with(") ")) {
+        digits + 2
+    } else {
+        0
+    };
+
+    Line {
+        leader: &line[..leader_len],
+        marker: &rest[..marker_len],
+        body: &rest[marker_len..],
+    }
+}
+
+fn display_width(text: &str, tab_size: usize) -> usize {
+    text.chars().fold(0, |width, c| {
+        if c == '\t' {
+            width + tab_size
+        } else {
+            width + 1
+        }
+    })
+}
+
+/// Wraps `lines` to `width` columns. Paragraphs are separated by blank lines (including
+/// comment lines without text) and by list items, and each keeps its indentation, comment
+/// leader and list marker, with continuation lines aligned after the marker.
+fn rewrap_lines(
+    lines: &[&str],
+    width: usize,
+    tab_size: usize,
+    comment_prefixes: &[&str],
+) -> String {
+    let mut result = Vec::new();
+    let mut ix = 0;
+    while ix < lines.len() {
+        let line = parse_line(lines[ix], comment_prefixes);
+        ix += 1;
+        if line.body.trim().is_empty() && line.marker.is_empty() {
+            result.push(lines[ix - 1].trim_end().to_string());
+            continue;
+        }
+
+        let first_prefix = format!("{}{}", line.leader, line.marker);
+        let continuation_prefix = format!("{}{}", line.leader, " ".repeat(line.marker.len()));
+        let mut words = line.body.split_whitespace().collect::<Vec<_>>();
+        while ix < lines.len() {
+            let next = parse_line(lines[ix], comment_prefixes);
+            let continues = next.marker.is_empty()
+                && !next.body.trim().is_empty()
+                && (next.leader == line.leader || next.leader == continuation_prefix);
+            if !continues {
+                break;
+            }
+            words.extend(next.body.split_whitespace());
+            ix += 1;
+        }
+
+        let mut current = first_prefix;
+        let mut current_width = display_width(&current, tab_size);
+        let mut has_words = false;
+        for word in words {
+            let word_width = word.chars().count();
+            if has_words && current_width + 1 + word_width > width {
+                result.push(current);
+                current = continuation_prefix.clone();
+                current_width = display_width(&current, tab_size);
+                has_words = false;
+            }
+            if has_words {
+                current.push(' ');
+                current_width += 1;
+            }
+            current.push_str(word);
+            current_width += word_width;
+            has_words = true;
+        }
+        result.push(current.trim_end().to_string());
+    }
+    result.join("\n")
+}
+
+#[cfg(test)]
+mod test {
+    use gpui::UpdateGlobal;
+    use indoc::indoc;
+    use language::language_settings::AllLanguageSettings;
+    use settings::SettingsStore;
+
+    use crate::{state::Mode, test::VimTestContext};
+
+    fn set_line_length(cx: &mut VimTestContext, length: u32) {
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |settings, cx| {
+                settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                    settings.defaults.preferred_line_length = Some(length);
+                });
+            })
+        });
+    }
+
+    #[gpui::test]
+    async fn test_rewrap(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        set_line_length(&mut cx, 20);
+
+        cx.set_state(
+            indoc! {"
+                ˇone two three four five six
+                seven
+
+                eight"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g q a p");
+        cx.assert_state(
+            indoc! {"
+                one two three four
+                five six seven
+                ˇ
+                eight"},
+            Mode::Normal,
+        );
+
+        // `}` stops before the blank line, but the cursor goes on to it
+        cx.set_state(
+            indoc! {"
+                ˇone two three four five six
+                seven
+
+                eight"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g q }");
+        cx.assert_state(
+            indoc! {"
+                one two three four
+                five six seven
+                ˇ
+                eight"},
+            Mode::Normal,
+        );
+
+        cx.set_state(
+            indoc! {"
+                ˇone
+                two
+                three"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g q j");
+        cx.assert_state(
+            indoc! {"
+                ˇone two
+                three"},
+            Mode::Normal,
+        );
+
+        cx.set_state("one two three ˇfour five six", Mode::Normal);
+        cx.simulate_keystrokes("g q g q");
+        cx.assert_state("one two three four\nˇfive six", Mode::Normal);
+
+        cx.set_state(
+            indoc! {"
+                «- one two three four five
+                - sixˇ»"},
+            Mode::Visual,
+        );
+        cx.simulate_keystrokes("g q");
+        cx.assert_state(
+            indoc! {"
+                - one two three four
+                  five
+                ˇ- six"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_rewrap_comments(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        set_line_length(&mut cx, 20);
+
+        let language = std::sync::Arc::new(language::Language::new(
+            language::LanguageConfig {
+                line_comments: vec!["// ".into()],
+                ..Default::default()
+            },
+            Some(language::tree_sitter_rust::language()),
+        ));
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+        cx.set_state(
+            indoc! {"
+                fn a() {
+                    // ˇone two three four
+                    // five
+                    //
+                    // - six seven eight nine
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("g q 3 j");
+        cx.assert_state(
+            indoc! {"
+                fn a() {
+                    // one two three
+                    // four five
+                    //
+                    // - six seven
+                    ˇ//   eight nine
+                }"},
+            Mode::Normal,
+        );
+    }
+}
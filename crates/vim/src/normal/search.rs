@@ -1,17 +1,23 @@
-use std::{iter::Peekable, str::Chars, time::Duration};
+use std::{iter::Peekable, ops::Range, str::Chars, time::Duration};
 
-use editor::Editor;
-use gpui::{actions, impl_actions, ViewContext};
-use language::Point;
+use editor::{scroll::Autoscroll, Anchor, Editor, EditorSettings, MultiBufferSnapshot, ToPoint};
+use gpui::{actions, impl_actions, AppContext, ViewContext};
+use language::{Bias, Point};
+use multi_buffer::MultiBufferRow;
 use search::{buffer_search, BufferSearchBar, SearchOptions};
 use serde_derive::Deserialize;
-use workspace::{notifications::NotifyResultExt, searchable::Direction};
+use settings::Settings;
+use workspace::{
+    notifications::{NotificationId, NotifyResultExt},
+    searchable::Direction,
+    Toast,
+};
 
 use crate::{
     command::CommandRange,
     motion::Motion,
-    state::{Mode, SearchState},
-    Vim,
+    state::{Mode, SearchState, VimGlobals},
+    Vim, VimSettings,
 };
 
 #[derive(Clone, Deserialize, PartialEq)]
@@ -46,15 +52,39 @@ pub struct ReplaceCommand {
     pub(crate) replacement: Replacement,
 }
 
+/// Where a `/` or `?` search leaves the cursor relative to the match (`:h search-offset`).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SearchOffset {
+    #[default]
+    None,
+    /// `/foo/+n`: n lines below the match, in the first column. The motion is linewise.
+    Lines(i64),
+    /// `/foo/s+n` or `/foo/b+n`: n characters after the start of the match.
+    Start(i64),
+    /// `/foo/e+n`: n characters after the end of the match. The motion is inclusive.
+    End(i64),
+}
+
 #[derive(Debug, Default, PartialEq, Deserialize, Clone)]
 pub(crate) struct Replacement {
     search: String,
     replacement: String,
     should_replace_all: bool,
-    is_case_sensitive: bool,
+    /// Set by the `I` and `i` flags, otherwise case follows the vim settings.
+    is_case_sensitive: Option<bool>,
 }
 
-actions!(vim, [SearchSubmit, MoveToNextMatch, MoveToPrevMatch]);
+actions!(
+    vim,
+    [
+        SearchSubmit,
+        MoveToNextMatch,
+        MoveToPrevMatch,
+        RepeatSubstitute,
+        RepeatSubstituteOnAllLines,
+        ClearSearchHighlights
+    ]
+);
 impl_actions!(
     vim,
     [FindCommand, ReplaceCommand, Search, MoveToPrev, MoveToNext]
@@ -69,6 +99,15 @@ pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
     Vim::action(editor, cx, Vim::search_deploy);
     Vim::action(editor, cx, Vim::find_command);
     Vim::action(editor, cx, Vim::replace_command);
+    Vim::action(editor, cx, |vim, _: &RepeatSubstitute, cx| {
+        vim.repeat_substitute(CommandRange::current_line(), false, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &RepeatSubstituteOnAllLines, cx| {
+        vim.repeat_substitute(CommandRange::whole_file(), true, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &ClearSearchHighlights, cx| {
+        vim.hide_search_highlights(cx)
+    });
 }
 
 impl Vim {
@@ -103,6 +142,10 @@ impl Vim {
                     if !search_bar.show(cx) {
                         return;
                     }
+                    search_bar.set_query_translator(Some(match direction {
+                        Direction::Next => forward_search_to_regex,
+                        Direction::Prev => backward_search_to_regex,
+                    }));
                     let query = search_bar.query(cx);
 
                     search_bar.select_query(cx);
@@ -110,12 +153,20 @@ impl Vim {
 
                     if query.is_empty() {
                         search_bar.set_replacement(None, cx);
-                        search_bar.set_search_options(SearchOptions::REGEX, cx);
                     }
+                    // with `ignorecase` set, the translated pattern matches or ignores case;
+                    // otherwise the search bar's case sensitivity toggle applies
+                    let case_sensitive = VimSettings::get_global(cx).ignorecase.is_some()
+                        || search_bar.has_search_option(SearchOptions::CASE_SENSITIVE);
+                    let mut options = SearchOptions::REGEX;
+                    options.set(SearchOptions::CASE_SENSITIVE, case_sensitive);
+                    search_bar.set_search_options(options, cx);
                     self.search = SearchState {
                         direction,
                         count,
                         initial_query: query.clone(),
+                        offset: self.search.offset,
+                        offset_jump: self.search.offset_jump.take(),
                         prior_selections,
                         prior_operator: self.operator_stack.last().cloned(),
                         prior_mode: self.mode,
@@ -138,6 +189,7 @@ impl Vim {
             let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() else {
                 return None;
             };
+            let mut history = pane.nav_history().clone();
             search_bar.update(cx, |search_bar, cx| {
                 let mut count = self.search.count;
                 let direction = self.search.direction;
@@ -149,20 +201,31 @@ impl Vim {
                     count = count.saturating_sub(1)
                 }
                 self.search.count = 1;
+                // the jump is recorded by the search motion
+                history.disable();
                 search_bar.select_match(direction, count, cx);
+                history.enable();
                 search_bar.focus_editor(&Default::default(), cx);
 
                 let prior_selections: Vec<_> = self.search.prior_selections.drain(..).collect();
                 let prior_mode = self.search.prior_mode;
                 let prior_operator = self.search.prior_operator.take();
 
-                let query = search_bar.query(cx).into();
-                Vim::globals(cx).registers.insert('/', query);
-                Some((prior_selections, prior_mode, prior_operator))
+                let query = search_bar.query(cx);
+                let separator = match direction {
+                    Direction::Next => '/',
+                    Direction::Prev => '?',
+                };
+                let (pattern, offset) = split_search_offset(&query, separator);
+                self.search.offset = offset;
+                let globals = Vim::globals(cx);
+                VimGlobals::push_history(&mut globals.search_history, &query);
+                globals.registers.insert('/', pattern.into());
+                Some((prior_selections, prior_mode, prior_operator, direction))
             })
         });
 
-        let Some((mut prior_selections, prior_mode, prior_operator)) = result else {
+        let Some((mut prior_selections, prior_mode, prior_operator, direction)) = result else {
             return;
         };
 
@@ -178,35 +241,40 @@ impl Vim {
             prior_selections.clear();
         }
 
+        self.report_search_wrap(direction, &prior_selections, &new_selections, cx);
         if prior_mode != self.mode {
             self.switch_mode(prior_mode, true, cx);
         }
         if let Some(operator) = prior_operator {
             self.push_operator(operator, cx);
         };
-        self.search_motion(
-            Motion::ZedSearchResult {
-                prior_selections,
-                new_selections,
-            },
-            cx,
-        );
+        let motion = self.search_result(prior_selections, new_selections, cx);
+        self.search_motion(motion, cx);
+        self.apply_hlsearch(cx);
     }
 
     pub fn move_to_match_internal(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
         let Some(pane) = self.pane(cx) else { return };
         let count = self.take_count(cx).unwrap_or(1);
         let prior_selections = self.editor_selections(cx);
+        let returned_to_match = self.return_to_offset_match(cx);
+        let searched_from = self.editor_selections(cx);
 
         let success = pane.update(cx, |pane, cx| {
             let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() else {
                 return false;
             };
+            let mut history = pane.nav_history().clone();
             search_bar.update(cx, |search_bar, cx| {
                 if !search_bar.has_active_match() || !search_bar.show(cx) {
                     return false;
                 }
+                if returned_to_match {
+                    search_bar.update_match_index(cx);
+                }
+                history.disable();
                 search_bar.select_match(direction, count, cx);
+                history.enable();
                 true
             })
         });
@@ -215,13 +283,10 @@ impl Vim {
         }
 
         let new_selections = self.editor_selections(cx);
-        self.search_motion(
-            Motion::ZedSearchResult {
-                prior_selections,
-                new_selections,
-            },
-            cx,
-        );
+        self.report_search_wrap(direction, &searched_from, &new_selections, cx);
+        let motion = self.search_result(prior_selections, new_selections, cx);
+        self.search_motion(motion, cx);
+        self.apply_hlsearch(cx);
     }
 
     pub fn move_to_internal(
@@ -237,6 +302,8 @@ impl Vim {
 
         let searched = pane.update(cx, |pane, cx| {
             self.search.direction = direction;
+            self.search.offset = SearchOffset::None;
+            self.search.offset_jump = None;
             let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() else {
                 return false;
             };
@@ -249,32 +316,41 @@ impl Vim {
                     drop(search_bar.search("", None, cx));
                     return None;
                 };
-                let mut query = regex::escape(&query);
+                search_bar.set_query_translator(Some(word_search_to_regex));
+                let mut query = escape_pattern(&query);
                 if whole_word {
                     query = format!(r"\<{}\>", query);
                 }
+                Vim::globals(cx).registers.insert('/', query.clone().into());
                 Some(search_bar.search(&query, Some(options), cx))
             });
 
             let Some(search) = search else { return false };
 
             let search_bar = search_bar.downgrade();
+            let mut history = pane.nav_history().clone();
             cx.spawn(|_, mut cx| async move {
                 search.await?;
                 search_bar.update(&mut cx, |search_bar, cx| {
+                    history.disable();
                     search_bar.select_match(direction, count, cx);
+                    history.enable();
 
                     vim.update(cx, |vim, cx| {
                         let new_selections = vim.editor_selections(cx);
+                        vim.report_search_wrap(direction, &prior_selections, &new_selections, cx);
                         vim.search_motion(
                             Motion::ZedSearchResult {
                                 prior_selections,
                                 new_selections,
+                                linewise: false,
+                                inclusive: false,
                             },
                             cx,
                         )
                     });
                 })?;
+                vim.update(&mut cx, |vim, cx| vim.apply_hlsearch(cx))?;
                 anyhow::Ok(())
             })
             .detach_and_log_err(cx);
@@ -289,6 +365,156 @@ impl Vim {
         }
     }
 
+    /// Tells the user when a search went past one end of the buffer and continued from the other,
+    /// like Vim's "search hit BOTTOM, continuing at TOP".
+    fn report_search_wrap(
+        &mut self,
+        direction: Direction,
+        prior_selections: &[Range<Anchor>],
+        new_selections: &[Range<Anchor>],
+        cx: &mut ViewContext<Self>,
+    ) {
+        struct SearchWrapped;
+
+        let (Some(prior), Some(new)) = (prior_selections.first(), new_selections.first()) else {
+            return;
+        };
+        // without `search_wrap` the cursor stays put at the last match instead
+        if !EditorSettings::get_global(cx).search_wrap {
+            return;
+        }
+        let Some(wrapped) = self.update_editor(cx, |_, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let order = new.start.cmp(&prior.start, &snapshot);
+            match direction {
+                Direction::Next => order.is_le(),
+                Direction::Prev => order.is_ge(),
+            }
+        }) else {
+            return;
+        };
+        let Some(workspace) = self.workspace(cx) else {
+            return;
+        };
+        let id = NotificationId::unique::<SearchWrapped>();
+        workspace.update(cx, |workspace, cx| {
+            if !wrapped {
+                workspace.dismiss_toast(&id, cx);
+                return;
+            }
+            let message = match direction {
+                Direction::Next => "search hit BOTTOM, continuing at TOP",
+                Direction::Prev => "search hit TOP, continuing at BOTTOM",
+            };
+            workspace.show_toast(Toast::new(id, message).autohide(), cx);
+        });
+    }
+
+    /// Hides the highlighted matches of the last search without forgetting it, so that `n` and
+    /// `N` still work and show them again (`:nohlsearch`).
+    fn hide_search_highlights(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(pane) = self.pane(cx) else { return };
+        pane.update(cx, |pane, cx| {
+            if let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() {
+                search_bar.update(cx, |search_bar, cx| {
+                    if !search_bar.is_dismissed() {
+                        search_bar.dismiss(&buffer_search::Dismiss, cx)
+                    }
+                });
+            }
+        });
+    }
+
+    /// Hides the matches of a search that just moved the cursor when `hlsearch` is off. They
+    /// are still highlighted while the pattern is typed.
+    fn apply_hlsearch(&mut self, cx: &mut ViewContext<Self>) {
+        if !VimSettings::get_global(cx).hlsearch {
+            self.hide_search_highlights(cx);
+        }
+    }
+
+    /// Builds the motion for a search that selected `new_selections`, moving the cursor by the
+    /// offset of the last search.
+    fn search_result(
+        &mut self,
+        prior_selections: Vec<Range<Anchor>>,
+        mut new_selections: Vec<Range<Anchor>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Motion {
+        let offset = self.search.offset;
+        let is_visual = self.mode.is_visual();
+        let jump = match (offset, new_selections.first()) {
+            (SearchOffset::None, _) | (_, None) => None,
+            (_, Some(selection)) => {
+                let match_start = selection.start;
+                self.update_editor(cx, |_, editor, cx| {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let start = match_start.to_point(&snapshot);
+                    let target = match offset {
+                        SearchOffset::None => start,
+                        SearchOffset::Lines(lines) => {
+                            let row = (start.row as i64 + lines)
+                                .clamp(0, snapshot.max_point().row as i64);
+                            Point::new(row as u32, 0)
+                        }
+                        SearchOffset::Start(chars) => move_by_chars(&snapshot, start, chars),
+                        SearchOffset::End(chars) => {
+                            let end = editor
+                                .search_match_at(match_start, cx)
+                                .map_or(start, |range| range.end.to_point(&snapshot));
+                            // the end of the match is its last character
+                            let last = if end > start {
+                                move_by_chars(&snapshot, end, -1)
+                            } else {
+                                start
+                            };
+                            move_by_chars(&snapshot, last, chars)
+                        }
+                    };
+                    let target = snapshot.anchor_before(target);
+                    // in visual mode the search motion moves the head of the selection instead
+                    if !is_visual {
+                        editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                            s.select_anchor_ranges([target..target])
+                        });
+                    }
+                    (target, match_start)
+                })
+            }
+        };
+        self.search.offset_jump = jump.filter(|_| !is_visual);
+        if let Some((target, _)) = jump {
+            new_selections = vec![target..target];
+        }
+
+        Motion::ZedSearchResult {
+            prior_selections,
+            new_selections,
+            linewise: matches!(offset, SearchOffset::Lines(_)),
+            inclusive: matches!(offset, SearchOffset::End(_)),
+        }
+    }
+
+    /// Puts the cursor back on the match a search offset moved it away from, so that `n` and
+    /// `N` carry on from the match rather than from the cursor. Returns whether it moved.
+    fn return_to_offset_match(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        let Some((target, match_start)) = self.search.offset_jump.take() else {
+            return false;
+        };
+        self.update_editor(cx, |_, editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let head = editor.selections.newest_anchor().head();
+            if head.cmp(&target, &snapshot).is_ne() || editor.selections.count() > 1 {
+                return false;
+            }
+            editor.change_selections(None, cx, |s| {
+                s.select_anchor_ranges([match_start..match_start])
+            });
+            true
+        })
+        .unwrap_or(false)
+    }
+
     fn find_command(&mut self, action: &FindCommand, cx: &mut ViewContext<Self>) {
         let Some(pane) = self.pane(cx) else { return };
         pane.update(cx, |pane, cx| {
@@ -297,10 +523,8 @@ impl Vim {
                     if !search_bar.show(cx) {
                         return None;
                     }
-                    let mut query = action.query.clone();
-                    if query == "" {
-                        query = search_bar.query(cx);
-                    };
+                    search_bar.set_query_translator(Some(pattern_search_to_regex));
+                    let query = search_pattern(action.query.clone(), search_bar, cx);
 
                     Some(search_bar.search(
                         &query,
@@ -328,13 +552,40 @@ impl Vim {
     }
 
     fn replace_command(&mut self, action: &ReplaceCommand, cx: &mut ViewContext<Self>) {
-        let replacement = action.replacement.clone();
+        Vim::globals(cx).last_replacement = Some(action.replacement.clone());
+        self.replace(action.range.as_ref(), action.replacement.clone(), cx)
+    }
+
+    /// Runs the last `:s` again over `range` (`&` and `g&`), dropping its flags unless
+    /// `keep_flags` is set.
+    fn repeat_substitute(
+        &mut self,
+        range: CommandRange,
+        keep_flags: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(mut replacement) = Vim::globals(cx).last_replacement.clone() else {
+            return;
+        };
+        if !keep_flags {
+            replacement.should_replace_all = true;
+            replacement.is_case_sensitive = None;
+        }
+        self.replace(Some(&range), replacement, cx)
+    }
+
+    fn replace(
+        &mut self,
+        range: Option<&CommandRange>,
+        replacement: Replacement,
+        cx: &mut ViewContext<Self>,
+    ) {
         let Some(((pane, workspace), editor)) =
             self.pane(cx).zip(self.workspace(cx)).zip(self.editor())
         else {
             return;
         };
-        if let Some(range) = &action.range {
+        if let Some(range) = range {
             if let Some(result) = self.update_editor(cx, |vim, editor, cx| {
                 let range = range.buffer_range(vim, editor, cx)?;
                 let snapshot = &editor.snapshot(cx).buffer_snapshot;
@@ -359,15 +610,15 @@ impl Vim {
                     return None;
                 }
 
-                let mut options = SearchOptions::REGEX;
-                if replacement.is_case_sensitive {
-                    options.set(SearchOptions::CASE_SENSITIVE, true)
-                }
-                let search = if replacement.search == "" {
-                    search_bar.query(cx)
-                } else {
-                    replacement.search
+                search_bar.set_query_translator(Some(pattern_search_to_regex));
+                let search = search_pattern(replacement.search, search_bar, cx);
+                // the `i` and `I` flags take precedence over the vim settings, like `\c` and `\C`
+                let search = match replacement.is_case_sensitive {
+                    Some(true) => format!(r"\C{search}"),
+                    Some(false) => format!(r"\c{search}"),
+                    None => search,
                 };
+                let options = SearchOptions::REGEX | SearchOptions::CASE_SENSITIVE;
 
                 search_bar.set_replacement(Some(&replacement.replacement), cx);
                 Some(search_bar.search(&search, Some(options), cx))
@@ -407,11 +658,409 @@ impl Vim {
     }
 }
 
+/// Returns `pattern`, or the last search pattern when it is empty (`:s//x/`), and remembers the
+/// result in the `/` register.
+fn search_pattern(
+    pattern: String,
+    search_bar: &BufferSearchBar,
+    cx: &mut ViewContext<BufferSearchBar>,
+) -> String {
+    let pattern = if pattern.is_empty() {
+        Vim::globals(cx)
+            .registers
+            .get(&'/')
+            .map(|register| register.text.to_string())
+            .unwrap_or_else(|| search_bar.query(cx))
+    } else {
+        pattern
+    };
+    let globals = Vim::globals(cx);
+    VimGlobals::push_history(&mut globals.search_history, &pattern);
+    globals.registers.insert('/', pattern.clone().into());
+    pattern
+}
+
+/// Splits a `/` or `?` search at the first unescaped `separator` into the pattern, in which an
+/// escaped separator stands for itself, and the offset after it (`/foo\/bar/e`).
+fn split_search_offset(query: &str, separator: char) -> (String, SearchOffset) {
+    let mut pattern = String::new();
+    let mut chars = query.char_indices();
+    while let Some((ix, c)) = chars.next() {
+        if c == separator {
+            let offset = SearchOffset::parse(&query[ix + c.len_utf8()..]).unwrap_or_default();
+            return (pattern, offset);
+        }
+        if c == '\\' {
+            match chars.next() {
+                Some((_, c)) if c == separator => pattern.push(c),
+                Some((_, c)) => {
+                    pattern.push('\\');
+                    pattern.push(c);
+                }
+                None => pattern.push('\\'),
+            }
+        } else {
+            pattern.push(c);
+        }
+    }
+    (pattern, SearchOffset::None)
+}
+
+fn forward_search_to_regex(query: &str, cx: &AppContext) -> String {
+    search_regex(&split_search_offset(query, '/').0, true, cx)
+}
+
+fn backward_search_to_regex(query: &str, cx: &AppContext) -> String {
+    search_regex(&split_search_offset(query, '?').0, true, cx)
+}
+
+fn word_search_to_regex(query: &str, cx: &AppContext) -> String {
+    search_regex(query, false, cx)
+}
+
+fn pattern_search_to_regex(query: &str, cx: &AppContext) -> String {
+    search_regex(query, true, cx)
+}
+
+/// Converts a Vim search pattern into a regex that ignores case when the `ignorecase` setting
+/// is true, unless `smartcase` is on too, `use_smartcase` is set and the pattern has an uppercase
+/// letter. `\c` and `\C` in the pattern take precedence either way.
+pub(crate) fn search_regex(pattern: &str, use_smartcase: bool, cx: &AppContext) -> String {
+    let settings = VimSettings::get_global(cx);
+    let regex = vim_pattern_to_regex(pattern);
+    let smartcase = use_smartcase && settings.smartcase && has_uppercase(pattern);
+    if settings.ignorecase == Some(true) && !smartcase {
+        format!("(?i){regex}")
+    } else {
+        regex
+    }
+}
+
+/// Whether a Vim pattern has an uppercase letter outside of its escapes like `\S` and `\%V`,
+/// which is what `smartcase` looks for.
+fn has_uppercase(pattern: &str) -> bool {
+    let mut chars = pattern.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some('_' | '%') = chars.next() {
+                chars.next();
+            }
+        } else if c.is_uppercase() {
+            return true;
+        }
+    }
+    false
+}
+
+impl SearchOffset {
+    /// Parses the part of a search after the pattern: `[+-]n` lines, or `e`, `s` or `b`
+    /// optionally followed by `[+-]n` characters. A lone `+` or `-` counts as one.
+    fn parse(offset: &str) -> Option<SearchOffset> {
+        let (kind, amount): (fn(i64) -> SearchOffset, &str) = match offset.chars().next() {
+            None => return Some(SearchOffset::None),
+            Some('e') => (SearchOffset::End, &offset[1..]),
+            Some('s') | Some('b') => (SearchOffset::Start, &offset[1..]),
+            Some(_) => (SearchOffset::Lines, offset),
+        };
+        let amount = match amount {
+            "" => 0,
+            "+" => 1,
+            "-" => -1,
+            amount => amount.parse().ok()?,
+        };
+        Some(kind(amount))
+    }
+}
+
+/// Moves `point` by `chars` characters, going on to the next or previous line at the end of one
+/// without stopping on the newline.
+fn move_by_chars(snapshot: &MultiBufferSnapshot, mut point: Point, chars: i64) -> Point {
+    for _ in 0..chars.unsigned_abs() {
+        let line_len = snapshot.line_len(MultiBufferRow(point.row));
+        point = if chars > 0 {
+            let next = snapshot.clip_point(Point::new(point.row, point.column + 1), Bias::Right);
+            if next.column < line_len {
+                next
+            } else if point.row < snapshot.max_point().row {
+                Point::new(point.row + 1, 0)
+            } else {
+                break;
+            }
+        } else if point.column > 0 {
+            snapshot.clip_point(Point::new(point.row, point.column - 1), Bias::Left)
+        } else if point.row > 0 {
+            let row = point.row - 1;
+            let len = snapshot.line_len(MultiBufferRow(row));
+            snapshot.clip_point(Point::new(row, len.saturating_sub(1)), Bias::Left)
+        } else {
+            break;
+        };
+    }
+    point
+}
+
+/// How much of a Vim pattern is special without a backslash, as switched by `\v`, `\m`, `\M`
+/// and `\V`.
+#[derive(Clone, Copy, PartialEq)]
+enum Magic {
+    VeryMagic,
+    Magic,
+    NoMagic,
+    VeryNoMagic,
+}
+
+impl Magic {
+    /// Whether `c` is special when it isn't escaped. It's the other way around for escaped
+    /// characters, so `\(` is a group in a magic pattern and `(` is one in a very magic pattern.
+    fn is_special(self, c: char) -> bool {
+        match self {
+            Magic::VeryMagic => "()|+?={@<>%.*[~".contains(c),
+            Magic::Magic => ".*[~".contains(c),
+            Magic::NoMagic | Magic::VeryNoMagic => false,
+        }
+    }
+}
+
+/// Converts a Vim search pattern into the syntax of the `regex` crate, which the search bar
+/// searches with.
+///
+/// Like in Vim, patterns are magic unless they switch with `\v`, `\m`, `\M` or `\V`, so `\(`,
+/// `\|`, `\+`, `\=` and `\{n,m}` are special while `(`, `|` and `+` match themselves. `\c` and
+/// `\C` ignore or match case, and classes like `\s` and `\u` match what they do in Vim. `\zs`,
+/// `\ze`, `~` and look-arounds aren't supported.
+pub(crate) fn vim_pattern_to_regex(pattern: &str) -> String {
+    let mut magic = Magic::Magic;
+    let mut case_sensitive = None;
+    let mut translated = String::new();
+    let mut chars = pattern.chars().peekable();
+    // whether a `^` here starts a branch, which is where it matches the start of the line
+    let mut at_branch_start = true;
+
+    while let Some(c) = chars.next() {
+        let (c, escaped) = match c {
+            '\\' => match chars.next() {
+                Some(c) => (c, true),
+                None => {
+                    translated.push_str(r"\\");
+                    break;
+                }
+            },
+            c => (c, false),
+        };
+        let was_at_branch_start = std::mem::replace(&mut at_branch_start, false);
+
+        if escaped && c.is_ascii_alphanumeric() {
+            match c {
+                'v' => magic = Magic::VeryMagic,
+                'm' => magic = Magic::Magic,
+                'M' => magic = Magic::NoMagic,
+                'V' => magic = Magic::VeryNoMagic,
+                'c' => case_sensitive = Some(false),
+                'C' => case_sensitive = Some(true),
+                'z' => {
+                    chars.next_if(|c| *c == 's' || *c == 'e');
+                }
+                c => translated.push_str(&escaped_letter(c)),
+            }
+            if "vmMVcCz".contains(c) {
+                at_branch_start = was_at_branch_start;
+            }
+            continue;
+        }
+        if c == '^' && !escaped && was_at_branch_start {
+            translated.push('^');
+            continue;
+        }
+        if c == '$' && !escaped && ends_branch(&chars, magic) {
+            translated.push('$');
+            continue;
+        }
+        if magic.is_special(c) == escaped {
+            translated.push_str(&regex::escape(c.encode_utf8(&mut [0; 4])));
+            continue;
+        }
+
+        match c {
+            '(' | '|' => {
+                translated.push(c);
+                at_branch_start = true;
+            }
+            '%' if chars.next_if_eq(&'(').is_some() => {
+                translated.push_str("(?:");
+                at_branch_start = true;
+            }
+            ')' | '+' | '*' | '.' => translated.push(c),
+            '=' | '?' => translated.push('?'),
+            '<' => translated.push_str(r"\<"),
+            '>' => translated.push_str(r"\>"),
+            '{' => match quantifier(&mut chars) {
+                Some(quantifier) => translated.push_str(&quantifier),
+                None => translated.push_str(r"\{"),
+            },
+            '[' => match collection(&mut chars) {
+                Some(collection) => translated.push_str(&collection),
+                None => translated.push_str(r"\["),
+            },
+            c => translated.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+
+    match case_sensitive {
+        Some(true) => format!("(?-i){translated}"),
+        Some(false) => format!("(?i){translated}"),
+        None => translated,
+    }
+}
+
+/// The regex for a letter or digit escaped in a Vim pattern, like the class `\d`.
+fn escaped_letter(c: char) -> String {
+    let regex = match c {
+        's' => "[ \\t]",
+        'S' => "[^ \\t]",
+        'd' => "[0-9]",
+        'D' => "[^0-9]",
+        'w' => "[0-9A-Za-z_]",
+        'W' => "[^0-9A-Za-z_]",
+        'a' => "[A-Za-z]",
+        'A' => "[^A-Za-z]",
+        'l' => "[a-z]",
+        'L' => "[^a-z]",
+        'u' => "[A-Z]",
+        'U' => "[^A-Z]",
+        'x' => "[0-9A-Fa-f]",
+        'X' => "[^0-9A-Fa-f]",
+        'o' => "[0-7]",
+        'O' => "[^0-7]",
+        'h' => "[A-Za-z_]",
+        'H' => "[^A-Za-z_]",
+        'n' => "\\n",
+        't' => "\\t",
+        'r' => "\\r",
+        'e' => "\\x1b",
+        // backreferences are kept, and fail to parse as the regex crate doesn't support them
+        '1'..='9' => return format!("\\{c}"),
+        c => return c.to_string(),
+    };
+    regex.to_string()
+}
+
+/// Whether a `$` followed by `chars` ends a branch, which is where it matches the end of the
+/// line.
+fn ends_branch(chars: &Peekable<Chars>, magic: Magic) -> bool {
+    let mut rest = chars.clone();
+    match rest.next() {
+        None => true,
+        Some('|' | ')') => magic == Magic::VeryMagic,
+        Some('\\') => magic != Magic::VeryMagic && matches!(rest.next(), Some('|' | ')')),
+        Some(_) => false,
+    }
+}
+
+/// Converts the rest of a `\{n,m}` quantifier, or returns `None` if it isn't one.
+fn quantifier(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut rest = chars.clone();
+    let mut bounds = String::new();
+    loop {
+        match rest.next()? {
+            '\\' if rest.next_if_eq(&'}').is_some() => break,
+            '}' => break,
+            c if c.is_ascii_digit() || c == ',' || c == '-' => bounds.push(c),
+            _ => return None,
+        }
+    }
+    // `\{-n,m}` matches as few as possible
+    let (lazy, bounds) = match bounds.strip_prefix('-') {
+        Some(bounds) => (true, bounds),
+        None => (false, bounds.as_str()),
+    };
+    if bounds.contains('-') {
+        return None;
+    }
+    let mut quantifier = match bounds.split_once(',') {
+        _ if bounds.is_empty() || bounds == "," => "*".to_string(),
+        None => format!("{{{bounds}}}"),
+        Some((_, max)) if max.contains(',') => return None,
+        Some(("", max)) => format!("{{0,{max}}}"),
+        Some((min, max)) => format!("{{{min},{max}}}"),
+    };
+    if lazy {
+        quantifier.push('?');
+    }
+    *chars = rest;
+    Some(quantifier)
+}
+
+/// Converts the rest of a `[...]` collection, or returns `None` if it isn't closed, in which
+/// case the `[` matches itself.
+fn collection(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut rest = chars.clone();
+    let mut collection = String::from("[");
+    if rest.next_if_eq(&'^').is_some() {
+        collection.push('^');
+    }
+    // a `]` right at the start is part of the collection
+    if rest.next_if_eq(&']').is_some() {
+        collection.push_str(r"\]");
+    }
+    loop {
+        match rest.next()? {
+            ']' => break,
+            '[' if rest.peek() == Some(&':') => {
+                // a character class like `[:alpha:]`, which both syntaxes share
+                collection.push('[');
+                loop {
+                    let c = rest.next()?;
+                    collection.push(c);
+                    if c == ']' {
+                        break;
+                    }
+                }
+            }
+            '\\' => match rest.next()? {
+                'e' => collection.push_str(r"\x1b"),
+                c @ ('t' | 'n' | 'r' | '\\' | ']' | '^' | '-') => {
+                    collection.push('\\');
+                    collection.push(c);
+                }
+                // other backslashes match themselves
+                c => {
+                    collection.push_str(r"\\");
+                    push_collection_char(&mut collection, c);
+                }
+            },
+            c => push_collection_char(&mut collection, c),
+        }
+    }
+    collection.push(']');
+    *chars = rest;
+    Some(collection)
+}
+
+/// Pushes `c` into a collection, escaping the characters that are special there in the regex
+/// crate but not in Vim.
+fn push_collection_char(collection: &mut String, c: char) {
+    if matches!(c, '[' | '&' | '~') {
+        collection.push('\\');
+    }
+    collection.push(c);
+}
+
+/// Escapes `text` to be searched for literally in a magic pattern, like `*` does.
+fn escape_pattern(text: &str) -> String {
+    let mut pattern = String::new();
+    for c in text.chars() {
+        if matches!(c, '\\' | '.' | '*' | '$' | '^' | '~' | '[') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern
+}
+
 impl Replacement {
     // convert a vim query into something more usable by zed.
-    // we don't attempt to fully convert between the two regex syntaxes,
-    // but we do flip \( and \) to ( and ) (and vice-versa) in the pattern,
-    // and convert \0..\9 to $0..$9 in the replacement so that common idioms work.
+    // the pattern is kept in vim's syntax, which is translated when it's searched for
+    // (see `vim_pattern_to_regex`), and \0..\9 become $0..$9 in the replacement.
     pub(crate) fn parse(mut chars: Peekable<Chars>) -> Option<Replacement> {
         let Some(delimiter) = chars
             .next()
@@ -437,8 +1086,6 @@ impl Replacement {
                 escaped = false;
                 if phase == 1 && c.is_digit(10) {
                     buffer.push('$')
-                // unescape escaped parens
-                } else if phase == 0 && c == '(' || c == ')' {
                 } else if c != delimiter {
                     buffer.push('\\')
                 }
@@ -456,10 +1103,6 @@ impl Replacement {
                     break;
                 }
             } else {
-                // escape unescaped parens
-                if phase == 0 && c == '(' || c == ')' {
-                    buffer.push('\\')
-                }
                 buffer.push(c)
             }
         }
@@ -468,14 +1111,15 @@ impl Replacement {
             search,
             replacement,
             should_replace_all: true,
-            is_case_sensitive: true,
+            is_case_sensitive: None,
         };
 
         for c in flags.chars() {
             match c {
-                'g' | 'I' => {}
+                'g' => {}
                 'c' | 'n' => replacement.should_replace_all = false,
-                'i' => replacement.is_case_sensitive = false,
+                'i' => replacement.is_case_sensitive = Some(false),
+                'I' => replacement.is_case_sensitive = Some(true),
                 _ => {}
             }
         }
@@ -489,15 +1133,18 @@ mod test {
     use std::time::Duration;
 
     use crate::{
-        state::Mode,
+        state::{Mode, VimGlobals},
         test::{NeovimBackedTestContext, VimTestContext},
+        VimSettings,
     };
     use editor::EditorSettings;
     use editor::{display_map::DisplayRow, DisplayPoint};
     use indoc::indoc;
-    use search::BufferSearchBar;
+    use search::{buffer_search::Deploy, BufferSearchBar, SearchOptions};
     use settings::SettingsStore;
 
+    use super::{split_search_offset, vim_pattern_to_regex, SearchOffset};
+
     #[gpui::test]
     async fn test_move_to_next(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -535,6 +1182,42 @@ mod test {
         cx.assert_state("hi\nˇhigh\nhi\n", Mode::Normal);
     }
 
+    #[gpui::test]
+    async fn test_search_wrap_message(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state("ˇhi\nhigh\nhi\n", Mode::Normal);
+
+        cx.simulate_keystrokes("*");
+        cx.run_until_parked();
+        cx.assert_state("hi\nhigh\nˇhi\n", Mode::Normal);
+        cx.workspace(|workspace, _| assert!(workspace.notification_ids().is_empty()));
+
+        // the message is shown when the search wraps around, and cleared by the next one
+        cx.simulate_keystrokes("n");
+        cx.assert_state("ˇhi\nhigh\nhi\n", Mode::Normal);
+        cx.workspace(|workspace, _| assert_eq!(workspace.notification_ids().len(), 1));
+
+        cx.simulate_keystrokes("n");
+        cx.assert_state("hi\nhigh\nˇhi\n", Mode::Normal);
+        cx.workspace(|workspace, _| assert!(workspace.notification_ids().is_empty()));
+    }
+
+    #[gpui::test]
+    async fn test_move_to_next_sets_search_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state("ˇhi\nhigh\nhi\n", Mode::Normal);
+
+        cx.simulate_keystrokes("*");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("\" / shift-p");
+        cx.assert_state("hi\nhigh\n\\<hi\\ˇ>hi\n", Mode::Normal);
+
+        cx.simulate_keystrokes("g g g *");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("\" / shift-p");
+        cx.assert_state("hi\nhˇihigh\n\\<hi\\>hi\n", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_move_to_next_with_no_search_wrap(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -673,6 +1356,254 @@ mod test {
         cx.assert_state("aa\nbb\nˇcc\ncc\ncc\n", Mode::Normal);
     }
 
+    #[test]
+    fn test_vim_pattern_to_regex() {
+        for (pattern, regex) in [
+            ("foo", "foo"),
+            (r"\(one\|two\)\+", "(one|two)+"),
+            ("(a|b)+?", r"\(a\|b\)\+\?"),
+            (r"\v(one|two)+<\<", r"(one|two)+\<<"),
+            (r"\Va.b*\.", r"a\.b\*."),
+            (r"a\{2,3}b\{-1,}c\{}", "a{2,3}b{1,}?c*"),
+            (r"\<word\>", r"\<word\>"),
+            (r"^\s*$", r"^[ \t]*$"),
+            (r"a^b$c", r"a\^b\$c"),
+            (r"\(^a\|b$\)", "(^a|b$)"),
+            (r"\cFoo", "(?i)Foo"),
+            (r"[a-z[:digit:]]\+", "[a-z[:digit:]]+"),
+            (r"[&]", r"[\&]"),
+            (r"x\%(y\)", "x(?:y)"),
+            (r"\d\u\a", "[0-9][A-Z][A-Za-z]"),
+            (r"a[b", r"a\[b"),
+        ] {
+            assert_eq!(vim_pattern_to_regex(pattern), regex, "{pattern}");
+        }
+    }
+
+    #[gpui::test]
+    async fn test_search_vim_pattern(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇzero one two (one", Mode::Normal);
+        cx.simulate_keystrokes("/ ( o n e");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("zero one two ˇ(one", Mode::Normal);
+
+        cx.simulate_keystrokes("g g / t w \\ | (");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("zero one ˇtwo (one", Mode::Normal);
+
+        // once the bar is deployed some other way, like with its toolbar button or to
+        // replace, queries are in Zed's regex syntax again
+        let search_bar = cx.workspace(|workspace, cx| {
+            workspace
+                .active_pane()
+                .read(cx)
+                .toolbar()
+                .read(cx)
+                .item_of_type::<BufferSearchBar>()
+                .expect("Buffer search bar should be deployed")
+        });
+        cx.update_view(search_bar, |search_bar, cx| {
+            search_bar.deploy(
+                &Deploy {
+                    focus: true,
+                    replace_enabled: true,
+                    selection_search_enabled: false,
+                },
+                cx,
+            );
+            drop(search_bar.search(r"\(one", Some(SearchOptions::REGEX), cx));
+        });
+        cx.run_until_parked();
+        let highlights =
+            cx.update_editor(|editor, cx| editor.search_background_highlights(cx).len());
+        assert_eq!(highlights, 1);
+    }
+
+    #[gpui::test]
+    async fn test_hlsearch(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let highlights = |cx: &mut VimTestContext| {
+            cx.update_editor(|editor, cx| editor.search_background_highlights(cx).len())
+        };
+
+        cx.set_state("ˇa one two one", Mode::Normal);
+        cx.simulate_keystrokes("/ o n e");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("a ˇone two one", Mode::Normal);
+        assert_eq!(highlights(&mut cx), 2);
+
+        // :noh hides the matches but keeps the search for `n`, which shows them again
+        cx.simulate_keystrokes(": n o h enter");
+        cx.run_until_parked();
+        assert_eq!(highlights(&mut cx), 0);
+        cx.read(|cx| {
+            assert_eq!(
+                cx.global::<VimGlobals>().registers[&'/'].text.as_ref(),
+                "one"
+            )
+        });
+        cx.simulate_keystrokes("n");
+        cx.assert_state("a one two ˇone", Mode::Normal);
+        assert_eq!(highlights(&mut cx), 2);
+
+        // without hlsearch the matches are only shown while the pattern is typed
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| s.hlsearch = Some(false));
+        });
+        cx.simulate_keystrokes("/ t w o");
+        cx.run_until_parked();
+        assert_eq!(highlights(&mut cx), 1);
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("a one ˇtwo one", Mode::Normal);
+        assert_eq!(highlights(&mut cx), 0);
+        cx.simulate_keystrokes("n");
+        assert_eq!(highlights(&mut cx), 0);
+    }
+
+    #[gpui::test]
+    async fn test_ignorecase_smartcase(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let search = |cx: &mut VimTestContext, keystrokes: &str| {
+            cx.simulate_keystrokes(keystrokes);
+            cx.run_until_parked();
+            cx.simulate_keystrokes("enter");
+        };
+
+        // without the setting, `/` follows the search bar, which ignores case by default
+        cx.set_state("ˇa Foo foo Foo", Mode::Normal);
+        search(&mut cx, "/ f o o");
+        cx.assert_state("a ˇFoo foo Foo", Mode::Normal);
+        // `*` matches case, as it did before the setting existed
+        cx.simulate_keystrokes("*");
+        cx.run_until_parked();
+        cx.assert_state("a Foo foo ˇFoo", Mode::Normal);
+        let search_bar = cx.workspace(|workspace, cx| {
+            workspace
+                .active_pane()
+                .read(cx)
+                .toolbar()
+                .read(cx)
+                .item_of_type::<BufferSearchBar>()
+                .expect("Buffer search bar should be deployed")
+        });
+        cx.update_view(search_bar.clone(), |search_bar, cx| {
+            search_bar.set_search_options(SearchOptions::CASE_SENSITIVE, cx)
+        });
+        search(&mut cx, "g g / f o o");
+        cx.assert_state("a Foo ˇfoo Foo", Mode::Normal);
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| s.ignorecase = Some(false));
+        });
+        cx.update_view(search_bar, |search_bar, cx| {
+            search_bar.set_search_options(SearchOptions::NONE, cx)
+        });
+        search(&mut cx, "g g / f o o");
+        cx.assert_state("a Foo ˇfoo Foo", Mode::Normal);
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| s.ignorecase = Some(true));
+        });
+        search(&mut cx, "g g / f o o");
+        cx.assert_state("a ˇFoo foo Foo", Mode::Normal);
+        search(&mut cx, "g g / \\ C f o o");
+        cx.assert_state("a Foo ˇfoo Foo", Mode::Normal);
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| s.smartcase = Some(true));
+        });
+        search(&mut cx, "g g / F o o");
+        cx.assert_state("a ˇFoo foo Foo", Mode::Normal);
+        cx.simulate_keystrokes("n");
+        cx.assert_state("a Foo foo ˇFoo", Mode::Normal);
+        search(&mut cx, "g g / f o o");
+        cx.assert_state("a ˇFoo foo Foo", Mode::Normal);
+
+        // `*` ignores case without smartcase
+        cx.simulate_keystrokes("*");
+        cx.run_until_parked();
+        cx.assert_state("a Foo ˇfoo Foo", Mode::Normal);
+
+        // the `I` flag of `:s` matches case regardless
+        cx.set_state("ˇfoo Foo", Mode::Normal);
+        cx.simulate_keystrokes(": s / f o o / x / g enter");
+        cx.executor().advance_clock(Duration::from_millis(250));
+        cx.run_until_parked();
+        assert_eq!(cx.buffer_text(), "x x");
+        cx.set_state("ˇfoo Foo", Mode::Normal);
+        cx.simulate_keystrokes(": s / f o o / x / g I enter");
+        cx.executor().advance_clock(Duration::from_millis(250));
+        cx.run_until_parked();
+        assert_eq!(cx.buffer_text(), "x Foo");
+    }
+
+    #[test]
+    fn test_split_search_offset() {
+        for (query, separator, pattern, offset) in [
+            ("foo", '/', "foo", SearchOffset::None),
+            ("foo/", '/', "foo", SearchOffset::None),
+            ("foo/e", '/', "foo", SearchOffset::End(0)),
+            ("foo/e-2", '/', "foo", SearchOffset::End(-2)),
+            ("foo/b+", '/', "foo", SearchOffset::Start(1)),
+            ("foo/s3", '/', "foo", SearchOffset::Start(3)),
+            ("foo/-", '/', "foo", SearchOffset::Lines(-1)),
+            ("foo/2", '/', "foo", SearchOffset::Lines(2)),
+            (r"a\/b/e", '/', "a/b", SearchOffset::End(0)),
+            ("a/b?+1", '?', "a/b", SearchOffset::Lines(1)),
+            ("foo/x", '/', "foo", SearchOffset::None),
+        ] {
+            assert_eq!(
+                split_search_offset(query, separator),
+                (pattern.to_string(), offset),
+                "{query}"
+            );
+        }
+    }
+
+    #[gpui::test]
+    async fn test_search_offset(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two\nthree two\nfour", Mode::Normal);
+        cx.simulate_keystrokes("/ t w o / e");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("one twˇo\nthree two\nfour", Mode::Normal);
+        cx.simulate_keystrokes("n");
+        cx.assert_state("one two\nthree twˇo\nfour", Mode::Normal);
+
+        cx.simulate_keystrokes("g g / t w o / b + 1");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("one tˇwo\nthree two\nfour", Mode::Normal);
+
+        cx.simulate_keystrokes("g g / t w o / +");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("one two\nˇthree two\nfour", Mode::Normal);
+        // `n` searches on from the match rather than from the cursor
+        cx.simulate_keystrokes("n");
+        cx.assert_state("one two\nthree two\nˇfour", Mode::Normal);
+
+        // an offset from the end is inclusive, and a line offset is linewise
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("d / t w o / e");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("ˇ three", Mode::Normal);
+
+        cx.set_state("ˇone\ntwo\nthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes("d / t w o / +");
+        cx.run_until_parked();
+        cx.simulate_keystrokes("enter");
+        cx.assert_state("ˇfour", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_non_vim_search(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, false).await;
@@ -853,4 +1784,49 @@ mod test {
                  "
         });
     }
+
+    #[gpui::test]
+    async fn test_repeat_substitute(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇa a\na a\na a\na a", Mode::Normal);
+        cx.simulate_keystrokes(": 1 s / a / b enter");
+        cx.executor().advance_clock(Duration::from_millis(250));
+        cx.run_until_parked();
+        cx.assert_state("ˇb b\na a\na a\na a", Mode::Normal);
+
+        cx.simulate_keystrokes("j &");
+        cx.executor().advance_clock(Duration::from_millis(250));
+        cx.run_until_parked();
+        cx.assert_state("b b\nˇb b\na a\na a", Mode::Normal);
+
+        cx.simulate_keystrokes("g &");
+        cx.executor().advance_clock(Duration::from_millis(250));
+        cx.run_until_parked();
+        cx.assert_state("b b\nb b\nb b\nˇb b", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_search_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        let search_register = |cx: &mut VimTestContext| {
+            cx.read(|cx| cx.global::<VimGlobals>().registers[&'/'].text.to_string())
+        };
+
+        cx.set_state("ˇone two\nthree two", Mode::Normal);
+        cx.simulate_keystrokes(": % s / t w o / 2 enter");
+        cx.executor().advance_clock(Duration::from_millis(250));
+        cx.run_until_parked();
+        assert_eq!(cx.buffer_text(), "one 2\nthree 2");
+        assert_eq!(search_register(&mut cx), "two");
+
+        cx.simulate_keystrokes(": / t h enter");
+        cx.run_until_parked();
+        assert_eq!(search_register(&mut cx), "th");
+
+        cx.simulate_keystrokes(": % s / / T H enter");
+        cx.executor().advance_clock(Duration::from_millis(250));
+        cx.run_until_parked();
+        assert_eq!(cx.buffer_text(), "one 2\nTHree 2");
+    }
 }
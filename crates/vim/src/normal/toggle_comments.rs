@@ -1,64 +1,21 @@
-use crate::{motion::Motion, object::Object, Vim};
-use collections::HashMap;
-use editor::{display_map::ToDisplayPoint, Bias};
-use language::SelectionGoal;
+use editor::Editor;
 use ui::ViewContext;
 
-impl Vim {
-    pub fn toggle_comments_motion(
-        &mut self,
-        motion: Motion,
-        times: Option<usize>,
-        cx: &mut ViewContext<Self>,
-    ) {
-        self.stop_recording(cx);
-        self.update_editor(cx, |_, editor, cx| {
-            let text_layout_details = editor.text_layout_details(cx);
-            editor.transact(cx, |editor, cx| {
-                let mut selection_starts: HashMap<_, _> = Default::default();
-                editor.change_selections(None, cx, |s| {
-                    s.move_with(|map, selection| {
-                        let anchor = map.display_point_to_anchor(selection.head(), Bias::Right);
-                        selection_starts.insert(selection.id, anchor);
-                        motion.expand_selection(map, selection, times, false, &text_layout_details);
-                    });
-                });
-                editor.toggle_comments(&Default::default(), cx);
-                editor.change_selections(None, cx, |s| {
-                    s.move_with(|map, selection| {
-                        let anchor = selection_starts.remove(&selection.id).unwrap();
-                        selection.collapse_to(anchor.to_display_point(map), SelectionGoal::None);
-                    });
-                });
-            });
-        });
+use crate::registered_operators::VimOperator;
+
+/// `gc`, which toggles the comments of the lines it covers and leaves the cursor in place.
+pub(crate) struct ToggleCommentsOperator;
+
+impl VimOperator for ToggleCommentsOperator {
+    fn id(&self) -> &str {
+        "gc"
+    }
+
+    fn apply(&self, editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        editor.toggle_comments(&Default::default(), cx);
     }
 
-    pub fn toggle_comments_object(
-        &mut self,
-        object: Object,
-        around: bool,
-        cx: &mut ViewContext<Self>,
-    ) {
-        self.stop_recording(cx);
-        self.update_editor(cx, |_, editor, cx| {
-            editor.transact(cx, |editor, cx| {
-                let mut original_positions: HashMap<_, _> = Default::default();
-                editor.change_selections(None, cx, |s| {
-                    s.move_with(|map, selection| {
-                        let anchor = map.display_point_to_anchor(selection.head(), Bias::Right);
-                        original_positions.insert(selection.id, anchor);
-                        object.expand_selection(map, selection, around);
-                    });
-                });
-                editor.toggle_comments(&Default::default(), cx);
-                editor.change_selections(None, cx, |s| {
-                    s.move_with(|map, selection| {
-                        let anchor = original_positions.remove(&selection.id).unwrap();
-                        selection.collapse_to(anchor.to_display_point(map), SelectionGoal::None);
-                    });
-                });
-            });
-        });
+    fn restores_cursors(&self) -> bool {
+        true
     }
 }
@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use crate::{
-    motion::Motion,
+    motion::{Motion, MotionKind},
     object::Object,
     state::{Mode, Register},
     Vim,
@@ -10,7 +10,7 @@ use collections::HashMap;
 use editor::{ClipboardSelection, Editor};
 use gpui::ViewContext;
 use language::Point;
-use multi_buffer::MultiBufferRow;
+use multi_buffer::{MultiBufferRow, ToOffset};
 
 struct HighlightOnYank;
 
@@ -44,7 +44,13 @@ impl Vim {
         });
     }
 
-    pub fn yank_object(&mut self, object: Object, around: bool, cx: &mut ViewContext<Self>) {
+    pub fn yank_object(
+        &mut self,
+        object: Object,
+        around: bool,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
         self.update_editor(cx, |vim, editor, cx| {
             editor.transact(cx, |editor, cx| {
                 editor.set_clip_at_line_ends(false, cx);
@@ -52,7 +58,7 @@ impl Vim {
                 editor.change_selections(None, cx, |s| {
                     s.move_with(|map, selection| {
                         let original_position = (selection.head(), selection.goal);
-                        object.expand_selection(map, selection, around);
+                        object.expand_selection(map, selection, around, times);
                         original_positions.insert(selection.id, original_position);
                     });
                 });
@@ -98,19 +104,27 @@ impl Vim {
         let mut clipboard_selections = Vec::with_capacity(selections.len());
         let mut ranges_to_highlight = Vec::new();
 
-        self.marks.insert(
-            "[".to_string(),
-            selections
-                .iter()
-                .map(|s| buffer.anchor_before(s.start))
-                .collect(),
-        );
-        self.marks.insert(
-            "]".to_string(),
+        // text objects like `ip` are charwise, but pasting the whole lines they cover puts
+        // them back on lines of their own
+        let covers_lines = self.mode != Mode::Visual
+            && selections.iter().all(|selection| {
+                selection.start.column == 0
+                    && selection.end.column == 0
+                    && selection.end.row > selection.start.row
+            });
+        let kind = if linewise || covers_lines {
+            MotionKind::Linewise
+        } else if self.mode == Mode::VisualBlock {
+            MotionKind::Blockwise
+        } else {
+            MotionKind::Charwise
+        };
+
+        self.set_change_marks(
             selections
                 .iter()
-                .map(|s| buffer.anchor_after(s.end))
-                .collect(),
+                .map(|s| s.start.to_offset(&buffer)..s.end.to_offset(&buffer)),
+            &buffer,
         );
 
         {
@@ -151,7 +165,7 @@ impl Vim {
                 }
                 clipboard_selections.push(ClipboardSelection {
                     len: text.len() - initial_len,
-                    is_entire_line: linewise,
+                    is_entire_line: kind == MotionKind::Linewise,
                     first_line_indent: buffer.indent_size_for_line(MultiBufferRow(start.row)).len,
                 });
             }
@@ -163,10 +177,10 @@ impl Vim {
                 Register {
                     text: text.into(),
                     clipboard_selections: Some(clipboard_selections),
+                    kind,
                 },
                 selected_register,
                 is_yank,
-                linewise,
                 cx,
             )
         });
@@ -4,13 +4,11 @@ use crate::{motion::right, state::Mode, Vim};
 use editor::{
     display_map::{DisplaySnapshot, ToDisplayPoint},
     movement::{self, FindRange},
-    Bias, DisplayPoint, Editor,
+    Bias, DisplayPoint, Editor, ToOffset, ToPoint,
 };
 
-use itertools::Itertools;
-
 use gpui::{actions, impl_actions, ViewContext};
-use language::{BufferSnapshot, CharKind, Point, Selection};
+use language::{BufferSnapshot, CharClassifier, CharKind, Point, Selection, TextObject};
 use multi_buffer::MultiBufferRow;
 use serde::Deserialize;
 
@@ -29,6 +27,9 @@ pub enum Object {
     AngleBrackets,
     Argument,
     Tag,
+    Function,
+    Class,
+    IndentObj { include_below: bool },
 }
 
 #[derive(Clone, Deserialize, PartialEq)]
@@ -38,7 +39,14 @@ struct Word {
     ignore_punctuation: bool,
 }
 
-impl_actions!(vim, [Word]);
+#[derive(Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct IndentObj {
+    #[serde(default)]
+    include_below: bool,
+}
+
+impl_actions!(vim, [Word, IndentObj]);
 
 actions!(
     vim,
@@ -54,7 +62,9 @@ actions!(
         CurlyBrackets,
         AngleBrackets,
         Argument,
-        Tag
+        Tag,
+        Function,
+        Class
     ]
 );
 
@@ -66,6 +76,13 @@ pub fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
             vim.object(Object::Word { ignore_punctuation }, cx)
         },
     );
+    Vim::action(
+        editor,
+        cx,
+        |vim, &IndentObj { include_below }: &IndentObj, cx| {
+            vim.object(Object::IndentObj { include_below }, cx)
+        },
+    );
     Vim::action(editor, cx, |vim, _: &Tag, cx| vim.object(Object::Tag, cx));
     Vim::action(editor, cx, |vim, _: &Sentence, cx| {
         vim.object(Object::Sentence, cx)
@@ -100,6 +117,12 @@ pub fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
     Vim::action(editor, cx, |vim, _: &Argument, cx| {
         vim.object(Object::Argument, cx)
     });
+    Vim::action(editor, cx, |vim, _: &Function, cx| {
+        vim.object(Object::Function, cx)
+    });
+    Vim::action(editor, cx, |vim, _: &Class, cx| {
+        vim.object(Object::Class, cx)
+    });
 }
 
 impl Vim {
@@ -129,10 +152,23 @@ impl Object {
             | Object::AngleBrackets
             | Object::CurlyBrackets
             | Object::SquareBrackets
-            | Object::Argument => true,
+            | Object::Argument
+            | Object::Function
+            | Object::Class
+            | Object::IndentObj { .. } => true,
         }
     }
 
+    pub fn is_bracket(self) -> bool {
+        matches!(
+            self,
+            Object::Parentheses
+                | Object::SquareBrackets
+                | Object::CurlyBrackets
+                | Object::AngleBrackets
+        )
+    }
+
     pub fn always_expands_both_ways(self) -> bool {
         match self {
             Object::Word { .. } | Object::Sentence | Object::Paragraph | Object::Argument => false,
@@ -144,7 +180,10 @@ impl Object {
             | Object::SquareBrackets
             | Object::Tag
             | Object::CurlyBrackets
-            | Object::AngleBrackets => true,
+            | Object::AngleBrackets
+            | Object::Function
+            | Object::Class
+            | Object::IndentObj { .. } => true,
         }
     }
 
@@ -167,8 +206,10 @@ impl Object {
             | Object::AngleBrackets
             | Object::VerticalBars
             | Object::Tag
-            | Object::Argument => Mode::Visual,
-            Object::Paragraph => Mode::VisualLine,
+            | Object::Argument
+            | Object::Function
+            | Object::Class => Mode::Visual,
+            Object::Paragraph | Object::IndentObj { .. } => Mode::VisualLine,
         }
     }
 
@@ -177,18 +218,20 @@ impl Object {
         map: &DisplaySnapshot,
         selection: Selection<DisplayPoint>,
         around: bool,
+        times: Option<usize>,
     ) -> Option<Range<DisplayPoint>> {
         let relative_to = selection.head();
         match self {
             Object::Word { ignore_punctuation } => {
+                let times = times.unwrap_or(1);
                 if around {
-                    around_word(map, relative_to, ignore_punctuation)
+                    around_word(map, relative_to, ignore_punctuation, times)
                 } else {
-                    in_word(map, relative_to, ignore_punctuation)
+                    in_word(map, relative_to, ignore_punctuation, times)
                 }
             }
             Object::Sentence => sentence(map, relative_to, around),
-            Object::Paragraph => paragraph(map, relative_to, around),
+            Object::Paragraph => paragraph(map, relative_to, around, times.unwrap_or(1)),
             Object::Quotes => {
                 surrounding_markers(map, relative_to, around, self.is_multiline(), '\'', '\'')
             }
@@ -215,6 +258,21 @@ impl Object {
                 surrounding_markers(map, relative_to, around, self.is_multiline(), '<', '>')
             }
             Object::Argument => argument(map, relative_to, around),
+            Object::Function => text_object(
+                map,
+                selection,
+                around,
+                TextObject::AroundFunction,
+                TextObject::InsideFunction,
+            ),
+            Object::Class => text_object(
+                map,
+                selection,
+                around,
+                TextObject::AroundClass,
+                TextObject::InsideClass,
+            ),
+            Object::IndentObj { include_below } => indent(map, relative_to, around, include_below),
         }
     }
 
@@ -223,8 +281,9 @@ impl Object {
         map: &DisplaySnapshot,
         selection: &mut Selection<DisplayPoint>,
         around: bool,
+        times: Option<usize>,
     ) -> bool {
-        if let Some(range) = self.range(map, selection.clone(), around) {
+        if let Some(range) = self.range(map, selection.clone(), around, times) {
             selection.start = range.start;
             selection.end = range.end;
             true
@@ -238,10 +297,12 @@ impl Object {
 ///
 /// If `relative_to` is at the start of a word, return the word.
 /// If `relative_to` is between words, return the space between.
+/// Each additional count extends the range by the next word or run of whitespace.
 fn in_word(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
     ignore_punctuation: bool,
+    times: usize,
 ) -> Option<Range<DisplayPoint>> {
     // Use motion::right so that we consider the character under the cursor when looking for the start
     let classifier = map
@@ -255,13 +316,130 @@ fn in_word(
         |left, right| classifier.kind(left) != classifier.kind(right),
     );
 
-    let end = movement::find_boundary(map, relative_to, FindRange::SingleLine, |left, right| {
-        classifier.kind(left) != classifier.kind(right)
-    });
+    let mut end = relative_to;
+    for _ in 0..times {
+        let new_end = movement::find_boundary(map, end, FindRange::SingleLine, |left, right| {
+            classifier.kind(left) != classifier.kind(right)
+        });
+        if new_end == end {
+            break;
+        }
+        end = new_end;
+    }
 
     Some(start..end)
 }
 
+/// Returns the range of the smallest function or class (per the language's text object
+/// query) around the selection, or of its body if not `around`. Ranges that start at the
+/// indentation of a line and end with a line are extended to cover those lines entirely.
+fn text_object(
+    map: &DisplaySnapshot,
+    selection: Selection<DisplayPoint>,
+    around: bool,
+    around_object: TextObject,
+    inside_object: TextObject,
+) -> Option<Range<DisplayPoint>> {
+    let snapshot = &map.buffer_snapshot;
+    let selection =
+        selection.start.to_offset(map, Bias::Left)..selection.end.to_offset(map, Bias::Left);
+    let query_range = selection.start..selection.end.max(selection.start + 1).min(snapshot.len());
+    let text_objects = snapshot
+        .text_object_ranges(query_range)?
+        .collect::<Vec<_>>();
+
+    // When the selection already is the whole object, move on to the enclosing one.
+    let outer = text_objects
+        .iter()
+        .filter(|(range, text_object)| {
+            *text_object == around_object
+                && range.start <= selection.start
+                && selection.end <= range.end
+                && (selection.len() <= 1 || *range != selection)
+        })
+        .map(|(range, _)| range.clone())
+        .min_by_key(|range| range.len())?;
+
+    let range = if around {
+        outer
+    } else {
+        text_objects
+            .iter()
+            .filter(|(range, text_object)| {
+                *text_object == inside_object
+                    && outer.start <= range.start
+                    && range.end <= outer.end
+            })
+            .map(|(range, _)| range.clone())
+            .max_by_key(|range| range.len())?
+    };
+
+    let start = range.start.to_point(snapshot);
+    let end = range.end.to_point(snapshot);
+    let range = if start.column == snapshot.indent_size_for_line(MultiBufferRow(start.row)).len
+        && end.column == snapshot.line_len(MultiBufferRow(end.row))
+        && end.row < snapshot.max_point().row
+    {
+        Point::new(start.row, 0).to_offset(snapshot)..Point::new(end.row + 1, 0).to_offset(snapshot)
+    } else {
+        range
+    };
+
+    Some(range.start.to_display_point(map)..range.end.to_display_point(map))
+}
+
+/// Returns the lines around `relative_to` that are indented at least as much as its line,
+/// along with the line above if `around` (and the line below if also `include_below`).
+/// Blank lines within the block are included, as are the lines it starts and ends on.
+fn indent(
+    map: &DisplaySnapshot,
+    relative_to: DisplayPoint,
+    around: bool,
+    include_below: bool,
+) -> Option<Range<DisplayPoint>> {
+    let snapshot = &map.buffer_snapshot;
+    let row = relative_to.to_point(map).row;
+    let max_row = snapshot.max_point().row;
+    let is_blank = |row: u32| snapshot.is_line_blank(MultiBufferRow(row));
+
+    // On a blank line, use the indentation of the next line that isn't blank
+    let target_row = (row..=max_row)
+        .find(|row| !is_blank(*row))
+        .or_else(|| (0..row).rev().find(|row| !is_blank(*row)))?;
+    let target_indent = snapshot
+        .indent_size_for_line(MultiBufferRow(target_row))
+        .len;
+    let in_block = |row: u32| {
+        is_blank(row) || snapshot.indent_size_for_line(MultiBufferRow(row)).len >= target_indent
+    };
+
+    let mut start_row = target_row;
+    while start_row > 0 && in_block(start_row - 1) {
+        start_row -= 1;
+    }
+    let mut end_row = target_row;
+    while end_row < max_row && in_block(end_row + 1) {
+        end_row += 1;
+    }
+    while is_blank(start_row) {
+        start_row += 1;
+    }
+    while is_blank(end_row) {
+        end_row -= 1;
+    }
+
+    if around {
+        start_row = start_row.saturating_sub(1);
+        if include_below && end_row < max_row {
+            end_row += 1;
+        }
+    }
+
+    let start = Point::new(start_row, 0);
+    let end = Point::new(end_row, snapshot.line_len(MultiBufferRow(end_row)));
+    Some(start.to_display_point(map)..end.to_display_point(map))
+}
+
 fn surrounding_html_tag(
     map: &DisplaySnapshot,
     selection: Selection<DisplayPoint>,
@@ -298,8 +476,23 @@ fn surrounding_html_tag(
         last_child_node = cursor.node();
     }
 
+    let selection_range = excerpt.map_offset_to_buffer(selection.start.to_offset(map, Bias::Left))
+        ..excerpt.map_offset_to_buffer(selection.end.to_offset(map, Bias::Left));
     let mut last_child_node = Some(last_child_node);
     while let Some(cur_node) = last_child_node {
+        // A self-closing tag (`<br/>`, `<Foo />`) has no content, so it is only
+        // an `at` target; `it` looks for the enclosing element instead.
+        if around && cur_node.kind().contains("self_closing") {
+            let range = cur_node.byte_range();
+            let is_valid = selection_range.len() <= 1
+                || (range.start <= selection_range.start
+                    && selection_range.end <= range.end
+                    && range != selection_range);
+            if is_valid && excerpt.contains_buffer_range(range.clone()) {
+                let result = excerpt.map_range_from_buffer(range);
+                return Some(result.start.to_display_point(map)..result.end.to_display_point(map));
+            }
+        }
         if cur_node.child_count() >= 2 {
             let first_child = cur_node.child(0);
             let last_child = cur_node.child(cur_node.child_count() - 1);
@@ -341,6 +534,7 @@ fn surrounding_html_tag(
 ///
 /// If `relative_to` is at the start of a word, return the word and following whitespace.
 /// If `relative_to` is between words, return the whitespace back and the following word.
+/// Each additional count extends the range by one more word.
 ///
 /// if in word
 ///   delete that word
@@ -353,6 +547,7 @@ fn around_word(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
     ignore_punctuation: bool,
+    times: usize,
 ) -> Option<Range<DisplayPoint>> {
     let offset = relative_to.to_offset(map, Bias::Left);
     let classifier = map
@@ -366,9 +561,9 @@ fn around_word(
         .unwrap_or(false);
 
     if in_word {
-        around_containing_word(map, relative_to, ignore_punctuation)
+        around_containing_word(map, relative_to, ignore_punctuation, times)
     } else {
-        around_next_word(map, relative_to, ignore_punctuation)
+        around_next_word(map, relative_to, ignore_punctuation, times)
     }
 }
 
@@ -376,15 +571,26 @@ fn around_containing_word(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
     ignore_punctuation: bool,
+    times: usize,
 ) -> Option<Range<DisplayPoint>> {
-    in_word(map, relative_to, ignore_punctuation)
-        .map(|range| expand_to_include_whitespace(map, range, true))
+    let mut range = in_word(map, relative_to, ignore_punctuation, 1)?;
+    if times > 1 {
+        let classifier = map
+            .buffer_snapshot
+            .char_classifier_at(relative_to.to_point(map))
+            .ignore_punctuation(ignore_punctuation);
+        for _ in 1..times {
+            range.end = next_word_end(map, range.end, &classifier);
+        }
+    }
+    Some(expand_to_include_whitespace(map, range, true))
 }
 
 fn around_next_word(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
     ignore_punctuation: bool,
+    times: usize,
 ) -> Option<Range<DisplayPoint>> {
     let classifier = map
         .buffer_snapshot
@@ -398,21 +604,32 @@ fn around_next_word(
         |left, right| classifier.kind(left) != classifier.kind(right),
     );
 
+    let mut end = relative_to;
+    for _ in 0..times {
+        end = next_word_end(map, end, &classifier);
+    }
+
+    Some(start..end)
+}
+
+/// Returns the end of the first word at or after `from`, skipping any whitespace before it.
+/// Stops early at an empty line.
+fn next_word_end(
+    map: &DisplaySnapshot,
+    from: DisplayPoint,
+    classifier: &CharClassifier,
+) -> DisplayPoint {
     let mut word_found = false;
-    let end = movement::find_boundary(map, relative_to, FindRange::MultiLine, |left, right| {
+    movement::find_boundary(map, from, FindRange::MultiLine, |left, right| {
         let left_kind = classifier.kind(left);
         let right_kind = classifier.kind(right);
 
-        let found = (word_found && left_kind != right_kind) || right == '\n' && left == '\n';
-
-        if right_kind != CharKind::Whitespace {
+        if left_kind != CharKind::Whitespace {
             word_found = true;
         }
 
-        found
-    });
-
-    Some(start..end)
+        (word_found && left_kind != right_kind) || right == '\n' && left == '\n'
+    })
 }
 
 fn argument(
@@ -556,7 +773,90 @@ fn argument(
         Some(start..end)
     }
 
-    let result = comma_delimited_range_at(buffer, excerpt.map_offset_to_buffer(offset), around)?;
+    // Without a syntax tree (or bracket query) for the buffer, fall back to scanning for
+    // the commas and brackets around the cursor.
+    fn scanned_comma_delimited_range_at(
+        buffer: &BufferSnapshot,
+        offset: usize,
+        include_comma: bool,
+    ) -> Option<Range<usize>> {
+        let mut depth = 0;
+        let mut previous_comma = None;
+        let mut open_bracket_end = None;
+        let mut ix = offset;
+        for c in buffer.reversed_chars_at(offset) {
+            ix -= c.len_utf8();
+            match c {
+                ')' | ']' | '}' => depth += 1,
+                '(' | '[' | '{' if depth == 0 => {
+                    open_bracket_end = Some(ix + c.len_utf8());
+                    break;
+                }
+                '(' | '[' | '{' => depth -= 1,
+                ',' if depth == 0 && previous_comma.is_none() => previous_comma = Some(ix),
+                _ => {}
+            }
+        }
+
+        let mut depth = 0;
+        let mut next_comma = None;
+        let mut close_bracket_start = None;
+        let mut ix = offset;
+        for c in buffer.chars_at(offset) {
+            match c {
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' if depth == 0 => {
+                    close_bracket_start = Some(ix);
+                    break;
+                }
+                ')' | ']' | '}' => depth -= 1,
+                ',' if depth == 0 && next_comma.is_none() => next_comma = Some(ix),
+                _ => {}
+            }
+            ix += c.len_utf8();
+        }
+
+        let open_bracket_end = open_bracket_end?;
+        let close_bracket_start = close_bracket_start?;
+        let mut start = previous_comma.map_or(open_bracket_end, |comma| comma + 1);
+        let mut end = next_comma.unwrap_or(close_bracket_start);
+
+        // Trim the whitespace around the argument
+        start += buffer
+            .chars_at(start)
+            .take_while(|c| c.is_whitespace())
+            .map(char::len_utf8)
+            .sum::<usize>();
+        start = start.min(end);
+        end -= buffer
+            .reversed_chars_at(end)
+            .take_while(|c| c.is_whitespace())
+            .map(char::len_utf8)
+            .sum::<usize>();
+        end = end.max(start);
+
+        if include_comma {
+            if let Some(comma) = previous_comma {
+                start = comma;
+            } else if let Some(comma) = next_comma {
+                // Select up to the beginning of the next argument
+                end = comma
+                    + 1
+                    + buffer
+                        .chars_at(comma + 1)
+                        .take_while(|c| c.is_whitespace())
+                        .map(char::len_utf8)
+                        .sum::<usize>();
+                end = end.min(close_bracket_start);
+            }
+        }
+
+        Some(start..end)
+    }
+
+    let buffer_offset = excerpt.map_offset_to_buffer(offset);
+    let result = comma_delimited_range_at(buffer, buffer_offset, around)
+        .or_else(|| scanned_comma_delimited_range_at(buffer, buffer_offset, around))?;
 
     if excerpt.contains_buffer_range(result.clone()) {
         let result = excerpt.map_range_from_buffer(result);
@@ -575,14 +875,13 @@ fn sentence(
     let relative_offset = relative_to.to_offset(map, Bias::Left);
     let mut previous_end = relative_offset;
 
-    let mut chars = map.buffer_chars_at(previous_end).peekable();
+    let mut chars = sentence_chars(map, previous_end, false).peekable();
 
     // Search backwards for the previous sentence end or current sentence start. Include the character under relative_to
-    for (char, offset) in chars
-        .peek()
-        .cloned()
+    let current = chars.peek().cloned();
+    for (char, offset) in current
         .into_iter()
-        .chain(map.reverse_buffer_chars_at(previous_end))
+        .chain(sentence_chars(map, previous_end, true))
     {
         if is_sentence_end(map, offset) {
             break;
@@ -625,6 +924,60 @@ fn sentence(
     Some(range)
 }
 
+/// The characters of the buffer from `offset`, forwards or backwards, without the comment
+/// leaders of lines that are line comments, so that sentences spanning several lines of a
+/// comment are found as if they weren't commented out.
+fn sentence_chars(
+    map: &DisplaySnapshot,
+    offset: usize,
+    reversed: bool,
+) -> impl Iterator<Item = (char, usize)> + '_ {
+    let chars: Box<dyn Iterator<Item = (char, usize)> + '_> = if reversed {
+        Box::new(map.reverse_buffer_chars_at(offset))
+    } else {
+        Box::new(map.buffer_chars_at(offset))
+    };
+    let mut leader: Option<(u32, Option<Range<usize>>)> = None;
+    chars.filter(move |(_, offset)| {
+        let row = offset.to_point(&map.buffer_snapshot).row;
+        if leader
+            .as_ref()
+            .map_or(true, |(leader_row, _)| *leader_row != row)
+        {
+            leader = Some((row, line_comment_leader(map, row)));
+        }
+        !leader
+            .as_ref()
+            .and_then(|(_, range)| range.as_ref())
+            .is_some_and(|range| range.contains(offset))
+    })
+}
+
+/// The indentation, comment prefix and following whitespace at the start of a row, if the row
+/// is a line comment.
+fn line_comment_leader(map: &DisplaySnapshot, row: u32) -> Option<Range<usize>> {
+    let buffer = &map.buffer_snapshot;
+    let line_start = Point::new(row, 0).to_offset(buffer);
+    let indent = buffer.indent_size_for_line(MultiBufferRow(row)).len as usize;
+    let scope = buffer.language_scope_at(line_start + indent)?;
+    let line = buffer
+        .chars_at(line_start + indent)
+        .take_while(|char| *char != '\n')
+        .collect::<String>();
+    let prefix = scope
+        .line_comment_prefixes()
+        .iter()
+        .map(|prefix| prefix.trim_end())
+        .filter(|prefix| !prefix.is_empty() && line.starts_with(prefix))
+        .max_by_key(|prefix| prefix.len())?;
+    let whitespace = line[prefix.len()..]
+        .chars()
+        .take_while(|char| char.is_whitespace())
+        .map(char::len_utf8)
+        .sum::<usize>();
+    Some(line_start..line_start + indent + prefix.len() + whitespace)
+}
+
 fn is_possible_sentence_start(character: char) -> bool {
     !character.is_whitespace() && character != '.'
 }
@@ -633,7 +986,7 @@ const SENTENCE_END_PUNCTUATION: &[char] = &['.', '!', '?'];
 const SENTENCE_END_FILLERS: &[char] = &[')', ']', '"', '\''];
 const SENTENCE_END_WHITESPACE: &[char] = &[' ', '\t', '\n'];
 fn is_sentence_end(map: &DisplaySnapshot, offset: usize) -> bool {
-    let mut next_chars = map.buffer_chars_at(offset).peekable();
+    let mut next_chars = sentence_chars(map, offset, false).peekable();
     if let Some((char, _)) = next_chars.next() {
         // We are at a double newline. This position is a sentence end.
         if char == '\n' && next_chars.peek().map(|(c, _)| c == &'\n').unwrap_or(false) {
@@ -646,7 +999,7 @@ fn is_sentence_end(map: &DisplaySnapshot, offset: usize) -> bool {
         }
     }
 
-    for (char, _) in map.reverse_buffer_chars_at(offset) {
+    for (char, _) in sentence_chars(map, offset, true) {
         if SENTENCE_END_PUNCTUATION.contains(&char) {
             return true;
         }
@@ -722,30 +1075,39 @@ fn paragraph(
     map: &DisplaySnapshot,
     relative_to: DisplayPoint,
     around: bool,
+    times: usize,
 ) -> Option<Range<DisplayPoint>> {
     let mut paragraph_start = start_of_paragraph(map, relative_to);
     let mut paragraph_end = end_of_paragraph(map, relative_to);
 
-    let paragraph_end_row = paragraph_end.row();
-    let paragraph_ends_with_eof = paragraph_end_row == map.max_point().row();
     let point = relative_to.to_point(map);
     let current_line_is_empty = map.buffer_snapshot.is_line_blank(MultiBufferRow(point.row));
 
-    if around {
-        if paragraph_ends_with_eof {
-            if current_line_is_empty {
-                return None;
-            }
+    // Paragraphs and runs of blank lines both count; `ap` takes each paragraph
+    // together with the blank lines next to it.
+    let runs = if around { times * 2 } else { times };
+    let mut found = 1;
+    while found < runs && paragraph_end.row() != map.max_point().row() {
+        let next_paragraph_start = Point::new(paragraph_end.row().0 + 1, 0).to_display_point(map);
+        paragraph_end = end_of_paragraph(map, next_paragraph_start);
+        found += 1;
+    }
 
-            let paragraph_start_row = paragraph_start.row();
-            if paragraph_start_row.0 != 0 {
-                let previous_paragraph_last_line_start =
-                    Point::new(paragraph_start_row.0 - 1, 0).to_display_point(map);
-                paragraph_start = start_of_paragraph(map, previous_paragraph_last_line_start);
-            }
-        } else {
-            let next_paragraph_start = Point::new(paragraph_end_row.0 + 1, 0).to_display_point(map);
-            paragraph_end = end_of_paragraph(map, next_paragraph_start);
+    if around && found < runs {
+        // The buffer ended before the trailing blank lines, so take the leading ones instead.
+        if current_line_is_empty && found == 1 {
+            return None;
+        }
+
+        let paragraph_start_row = paragraph_start.row();
+        if paragraph_start_row.0 != 0
+            && map
+                .buffer_snapshot
+                .is_line_blank(MultiBufferRow(paragraph_start_row.0 - 1))
+        {
+            let previous_paragraph_last_line_start =
+                Point::new(paragraph_start_row.0 - 1, 0).to_display_point(map);
+            paragraph_start = start_of_paragraph(map, previous_paragraph_last_line_start);
         }
     }
 
@@ -812,23 +1174,15 @@ fn surrounding_markers(
     let mut matched_closes = 0;
     let mut opening = None;
 
-    let mut before_ch = match movement::chars_before(map, point).next() {
-        Some((ch, _)) => ch,
-        _ => '\0',
-    };
     if let Some((ch, range)) = movement::chars_after(map, point).next() {
-        if ch == open_marker && before_ch != '\\' {
+        if ch == open_marker && !is_escaped(map, range.start) {
             if open_marker == close_marker {
-                let mut total = 0;
-                for ((ch, _), (before_ch, _)) in movement::chars_before(map, point).tuple_windows()
-                {
-                    if ch == '\n' {
-                        break;
-                    }
-                    if ch == open_marker && before_ch != '\\' {
-                        total += 1;
-                    }
-                }
+                // the cursor is on a quote: it opens a string if there is
+                // an even number of quotes before it on the line.
+                let total = movement::chars_before(map, point)
+                    .take_while(|(ch, _)| *ch != '\n')
+                    .filter(|(ch, range)| *ch == open_marker && !is_escaped(map, range.start))
+                    .count();
                 if total % 2 == 0 {
                     opening = Some(range)
                 }
@@ -839,16 +1193,13 @@ fn surrounding_markers(
     }
 
     if opening.is_none() {
-        let mut chars_before = movement::chars_before(map, point).peekable();
-        while let Some((ch, range)) = chars_before.next() {
+        for (ch, range) in movement::chars_before(map, point) {
             if ch == '\n' && !search_across_lines {
                 break;
             }
 
-            if let Some((before_ch, _)) = chars_before.peek() {
-                if *before_ch == '\\' {
-                    continue;
-                }
+            if is_escaped(map, range.start) {
+                continue;
             }
 
             if ch == open_marker {
@@ -863,8 +1214,14 @@ fn surrounding_markers(
         }
     }
     if opening.is_none() {
+        // like vim, when the cursor is not within the markers look for the
+        // next pair after it.
         for (ch, range) in movement::chars_after(map, point) {
-            if before_ch != '\\' {
+            if ch == '\n' && !search_across_lines {
+                break;
+            }
+
+            if !is_escaped(map, range.start) {
                 if ch == open_marker {
                     opening = Some(range);
                     break;
@@ -872,8 +1229,6 @@ fn surrounding_markers(
                     break;
                 }
             }
-
-            before_ch = ch;
         }
     }
 
@@ -883,16 +1238,12 @@ fn surrounding_markers(
 
     let mut matched_opens = 0;
     let mut closing = None;
-    before_ch = match movement::chars_before(map, opening.end).next() {
-        Some((ch, _)) => ch,
-        _ => '\0',
-    };
     for (ch, range) in movement::chars_after(map, opening.end) {
         if ch == '\n' && !search_across_lines {
             break;
         }
 
-        if before_ch != '\\' {
+        if !is_escaped(map, range.start) {
             if ch == close_marker {
                 if matched_opens == 0 {
                     closing = Some(range);
@@ -903,8 +1254,6 @@ fn surrounding_markers(
                 matched_opens += 1;
             }
         }
-
-        before_ch = ch;
     }
 
     let Some(mut closing) = closing else {
@@ -963,6 +1312,15 @@ fn surrounding_markers(
     )
 }
 
+/// Whether the character at `offset` is escaped by an odd number of backslashes.
+fn is_escaped(map: &DisplaySnapshot, offset: usize) -> bool {
+    movement::chars_before(map, offset)
+        .take_while(|(ch, _)| *ch == '\\')
+        .count()
+        % 2
+        == 1
+}
+
 #[cfg(test)]
 mod test {
     use indoc::indoc;
@@ -1053,6 +1411,98 @@ mod test {
             .assert_matches();
     }
 
+    #[gpui::test]
+    async fn test_word_object_with_count(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three four", Mode::Normal);
+        cx.simulate_keystrokes("d 2 a w");
+        cx.assert_state("ˇthree four", Mode::Normal);
+
+        cx.set_state("ˇone two three four", Mode::Normal);
+        cx.simulate_keystrokes("2 d a w");
+        cx.assert_state("ˇthree four", Mode::Normal);
+
+        cx.set_state("ˇone two three four", Mode::Normal);
+        cx.simulate_keystrokes("d 3 i w");
+        cx.assert_state("ˇ three four", Mode::Normal);
+
+        cx.set_state("one ˇ  two three", Mode::Normal);
+        cx.simulate_keystrokes("d 2 a w");
+        cx.assert_state("onˇe", Mode::Normal);
+
+        cx.set_state("foˇo.bar baz", Mode::Normal);
+        cx.simulate_keystrokes("d 2 a shift-w");
+        cx.assert_state("ˇ", Mode::Normal);
+
+        cx.set_state("foo.ˇbar baz qux", Mode::Normal);
+        cx.simulate_keystrokes("c 2 a w");
+        cx.assert_state("foo.ˇqux", Mode::Insert);
+
+        cx.set_state("ˇone two three four", Mode::Normal);
+        cx.simulate_keystrokes("v 3 i w");
+        cx.assert_state("«one twoˇ» three four", Mode::Visual);
+        cx.simulate_keystrokes("escape 0 y 2 a w");
+        cx.assert_state("ˇone two three four", Mode::Normal);
+        cx.simulate_keystrokes("shift-p");
+        cx.assert_state("one twoˇ one two three four", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_sentence_object(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("One two. Thrˇee four. Five.", Mode::Normal);
+        cx.simulate_keystrokes("c i s");
+        cx.assert_state("One two. ˇ Five.", Mode::Insert);
+
+        cx.set_state("One two. Thrˇee four. Five.", Mode::Normal);
+        cx.simulate_keystrokes("d a s");
+        cx.assert_state("One two. ˇFive.", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_sentence_object_in_comments(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        let language = std::sync::Arc::new(language::Language::new(
+            language::LanguageConfig {
+                line_comments: vec!["// ".into(), "//! ".into(), "/// ".into()],
+                ..Default::default()
+            },
+            Some(language::tree_sitter_rust::language()),
+        ));
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+        cx.set_state("// Onˇe two. Three.\nfn a() {}", Mode::Normal);
+        cx.simulate_keystrokes("d i s");
+        cx.assert_state("// ˇ Three.\nfn a() {}", Mode::Normal);
+
+        cx.set_state("// Onˇe two. Three.\nfn a() {}", Mode::Normal);
+        cx.simulate_keystrokes("d a s");
+        cx.assert_state("// ˇThree.\nfn a() {}", Mode::Normal);
+
+        cx.set_state("// First.\n// Secoˇnd one.", Mode::Normal);
+        cx.simulate_keystrokes("c i s");
+        cx.assert_state("// First.\n// ˇ", Mode::Insert);
+
+        // sentences go on across the lines of a comment, and end at an empty one
+        cx.set_state("// One. Two\n  // thrˇee. Four.", Mode::Normal);
+        cx.simulate_keystrokes("c i s");
+        cx.assert_state("// One. ˇ Four.", Mode::Insert);
+
+        cx.set_state(
+            "// One two\n//\n// Thrˇee\n// four.\nfn a() {}",
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("c i s");
+        cx.assert_state("// One two\n//\n// ˇ\nfn a() {}", Mode::Insert);
+
+        cx.set_state("// One twˇo\n//\n// Three.", Mode::Normal);
+        cx.simulate_keystrokes("d i s");
+        cx.assert_state("//ˇ \n//\n// Three.", Mode::Normal);
+    }
+
     const PARAGRAPH_EXAMPLES: &[&'static str] = &[
         // Single line
         "ˇThe quick brown fox jumpˇs over the lazy dogˇ.ˇ",
@@ -1174,6 +1624,30 @@ mod test {
         }
     }
 
+    #[gpui::test]
+    async fn test_paragraph_object_with_count(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇaa\nbb\n\ncc\n\ndd", Mode::Normal);
+        cx.simulate_keystrokes("v 2 a p");
+        cx.assert_state("«aa\nbb\n\ncc\n\nˇ»dd", Mode::VisualLine);
+
+        cx.set_state("ˇaa\nbb\n\ncc\n\ndd", Mode::Normal);
+        cx.simulate_keystrokes("v 3 i p");
+        cx.assert_state("«aa\nbb\n\ncˇ»c\n\ndd", Mode::VisualLine);
+
+        // repeating the object grows the selection
+        cx.set_state("ˇaa\nbb\n\ncc\n\ndd", Mode::Normal);
+        cx.simulate_keystrokes("v i p");
+        cx.assert_state("«aa\nbˇ»b\n\ncc\n\ndd", Mode::VisualLine);
+        cx.simulate_keystrokes("i p");
+        cx.assert_state("«aa\nbb\n\nˇ»cc\n\ndd", Mode::VisualLine);
+        cx.simulate_keystrokes("i p");
+        cx.assert_state("«aa\nbb\n\ncˇ»c\n\ndd", Mode::VisualLine);
+        cx.simulate_keystrokes("a p");
+        cx.assert_state("«aa\nbb\n\ncc\n\ndˇ»d", Mode::VisualLine);
+    }
+
     // Test string with "`" for opening surrounders and "'" for closing surrounders
     const SURROUNDING_MARKER_STRING: &str = indoc! {"
         ˇTh'ˇe ˇ`ˇ'ˇquˇi`ˇck broˇ'wn`
@@ -1361,6 +1835,96 @@ mod test {
         });
     }
 
+    #[gpui::test]
+    async fn test_quote_objects(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        // an escaped backslash does not escape the quote after it
+        cx.set_state("x = \"ˇa\\\\\" + \"b\"", Mode::Normal);
+        cx.simulate_keystrokes("d i \"");
+        cx.assert_state("x = \"ˇ\" + \"b\"", Mode::Normal);
+
+        // the closing quote at the start of the buffer
+        cx.set_state("\"abˇ\"", Mode::Normal);
+        cx.simulate_keystrokes("d i \"");
+        cx.assert_state("\"ˇ\"", Mode::Normal);
+
+        // outside of any quotes, seek forward on the line
+        cx.set_state("ˇlet s = \"hello\";", Mode::Normal);
+        cx.simulate_keystrokes("c i \"");
+        cx.assert_state("let s = \"ˇ\";", Mode::Insert);
+
+        // but not onto the next line
+        cx.set_state("ˇfoo\nlet s = \"hello\";", Mode::Normal);
+        cx.simulate_keystrokes("d i \"");
+        cx.assert_state("ˇfoo\nlet s = \"hello\";", Mode::Normal);
+
+        // around includes trailing whitespace
+        cx.set_state("print 'ˇhi'  now", Mode::Normal);
+        cx.simulate_keystrokes("d a '");
+        cx.assert_state("print ˇnow", Mode::Normal);
+
+        cx.set_state("a `bˇc` d", Mode::Normal);
+        cx.simulate_keystrokes("c i `");
+        cx.assert_state("a `ˇ` d", Mode::Insert);
+    }
+
+    #[gpui::test]
+    async fn test_bracket_object_aliases(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("call(a, ˇb)", Mode::Normal);
+        cx.simulate_keystrokes("c i b");
+        cx.assert_state("call(ˇ)", Mode::Insert);
+
+        cx.set_state("if a { ˇb }", Mode::Normal);
+        cx.simulate_keystrokes("d a shift-b");
+        cx.assert_state("if aˇ ", Mode::Normal);
+
+        cx.set_state("Vec<Option<ˇu8>>", Mode::Normal);
+        cx.simulate_keystrokes("v i <");
+        cx.assert_state("Vec<Option<«u8ˇ»>>", Mode::Visual);
+        cx.simulate_keystrokes("escape v a >");
+        cx.assert_state("Vec<Option«<u8>ˇ»>", Mode::Visual);
+    }
+
+    #[gpui::test]
+    async fn test_change_multiline_bracket_object(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+            call(
+                ˇa,
+                b,
+            )"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("c i (");
+        cx.assert_state(
+            indoc! {"
+            call(
+            ˇ
+            )"},
+            Mode::Insert,
+        );
+
+        cx.set_state(
+            indoc! {"
+            fn a() {
+                ˇb();
+            }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("d i shift-b");
+        cx.assert_state(
+            indoc! {"
+            fn a() {
+            ˇ}"},
+            Mode::Normal,
+        );
+    }
+
     #[gpui::test]
     async fn test_vertical_bars(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -1459,6 +2023,108 @@ mod test {
         cx.assert_state("let a = [«test::call(first_arg)ˇ»]", Mode::Visual);
     }
 
+    #[gpui::test]
+    async fn test_indent_object(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        let example = indoc! {"
+            def f():
+                if x:
+                    aˇ = 1
+
+                    b = 2
+                return
+            rest"};
+
+        cx.set_state(example, Mode::Normal);
+        cx.simulate_keystrokes("d i i");
+        cx.assert_state(
+            indoc! {"
+                def f():
+                    if x:
+                ˇ    return
+                rest"},
+            Mode::Normal,
+        );
+
+        cx.set_state(example, Mode::Normal);
+        cx.simulate_keystrokes("d a i");
+        cx.assert_state(
+            indoc! {"
+                def f():
+                ˇ    return
+                rest"},
+            Mode::Normal,
+        );
+
+        cx.set_state(example, Mode::Normal);
+        cx.simulate_keystrokes("d a shift-i");
+        cx.assert_state(
+            indoc! {"
+                def f():
+                ˇrest"},
+            Mode::Normal,
+        );
+
+        cx.set_state(example, Mode::Normal);
+        cx.simulate_keystrokes("v i i");
+        cx.assert_state(
+            indoc! {"
+                def f():
+                    if x:
+                «        a = 1
+
+                 ˇ»       b = 2
+                    return
+                rest"},
+            Mode::VisualLine,
+        );
+
+        // a blank line takes the indentation of the line after it
+        cx.set_state(
+            indoc! {"
+                def f():
+                ˇ
+                    a = 1
+                rest"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("d i i");
+        cx.assert_state(
+            indoc! {"
+                def f():
+
+                ˇrest"},
+            Mode::Normal,
+        );
+    }
+
+    #[gpui::test]
+    async fn test_argument_object_without_syntax(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update_buffer(|buffer, cx| buffer.set_language(None, cx));
+
+        cx.set_state("call(first, secˇond(a, b), third)", Mode::Normal);
+        cx.simulate_keystrokes("v i a");
+        cx.assert_state("call(first, «second(a, b)ˇ», third)", Mode::Visual);
+
+        cx.set_state("call(fiˇrst, second(a, b), third)", Mode::Normal);
+        cx.simulate_keystrokes("d a a");
+        cx.assert_state("call(ˇsecond(a, b), third)", Mode::Normal);
+
+        cx.set_state("call(first, second(a, b), thiˇrd)", Mode::Normal);
+        cx.simulate_keystrokes("d a a");
+        cx.assert_state("call(first, second(a, b)ˇ)", Mode::Normal);
+
+        cx.set_state("call(first, second(a, ˇb), third)", Mode::Normal);
+        cx.simulate_keystrokes("c i a");
+        cx.assert_state("call(first, second(a, ˇ), third)", Mode::Insert);
+
+        cx.set_state("no ˇbrackets, here", Mode::Normal);
+        cx.simulate_keystrokes("d i a");
+        cx.assert_state("no ˇbrackets, here", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_delete_surrounding_character_objects(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -1562,4 +2228,110 @@ mod test {
             Mode::Visual,
         );
     }
+
+    #[gpui::test]
+    async fn test_self_closing_tags(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new_html(cx).await;
+
+        cx.set_state("<p>a<bˇr/>b</p>", Mode::Normal);
+        cx.simulate_keystrokes("d a t");
+        cx.assert_state("<p>aˇb</p>", Mode::Normal);
+
+        cx.set_state("<p>a<bˇr/>b</p>", Mode::Normal);
+        cx.simulate_keystrokes("c i t");
+        cx.assert_state("<p>ˇ</p>", Mode::Insert);
+
+        cx.set_state("<p>a<bˇr/>b</p>", Mode::Normal);
+        cx.simulate_keystrokes("v a t");
+        cx.assert_state("<p>a«<br/>ˇ»b</p>", Mode::Visual);
+        cx.simulate_keystrokes("a t");
+        cx.assert_state("«<p>a<br/>b</p>ˇ»", Mode::Visual);
+    }
+
+    #[gpui::test]
+    async fn test_function_and_class_objects(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                impl S {
+                    fn a() {
+                        let ˇx = 1;
+                        x
+                    }
+                }
+                struct T;"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("v a f");
+        cx.assert_state(
+            indoc! {"
+                impl S {
+                «    fn a() {
+                        let x = 1;
+                        x
+                    }
+                ˇ»}
+                struct T;"},
+            Mode::Visual,
+        );
+        cx.simulate_keystrokes("a c");
+        cx.assert_state(
+            indoc! {"
+                «impl S {
+                    fn a() {
+                        let x = 1;
+                        x
+                    }
+                }
+                ˇ»struct T;"},
+            Mode::Visual,
+        );
+
+        cx.set_state(
+            indoc! {"
+                impl S {
+                    fn a() {
+                        let ˇx = 1;
+                        x
+                    }
+                }
+                struct T;"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("c i f");
+        cx.assert_state(
+            indoc! {"
+                impl S {
+                    fn a() {
+                ˇ
+                    }
+                }
+                struct T;"},
+            Mode::Insert,
+        );
+
+        cx.set_state(
+            indoc! {"
+                impl S {
+                    fn a() { ˇx }
+                }
+                struct T;"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes("d i f");
+        cx.assert_state(
+            indoc! {"
+                impl S {
+                    fn a() { ˇ }
+                }
+                struct T;"},
+            Mode::Normal,
+        );
+
+        // outside of any class there is nothing to delete
+        cx.set_state("fn a() { ˇx }", Mode::Normal);
+        cx.simulate_keystrokes("d a c");
+        cx.assert_state("fn a() { ˇx }", Mode::Normal);
+    }
 }
@@ -0,0 +1,218 @@
+use std::path::PathBuf;
+
+use db::sqlez_macros::sql;
+use db::{define_connection, query};
+
+use workspace::{WorkspaceDb, WorkspaceId};
+
+define_connection!(
+    // Like viminfo, keeps the state of vim mode that outlives a session.
+    //
+    // Current schema shape using pseudo-rust syntax:
+    // vim_registers(
+    //   name: String,
+    //   text: String,
+    //   kind: String, // "c", "l" or "b", as shown by `:registers`
+    // )
+    // vim_last_find(
+    //   id: usize, // always 0, there's only one
+    //   find_char: String,
+    //   backward: bool,
+    //   till: bool,
+    //   multiline: bool,
+    //   smartcase: bool,
+    // )
+    // vim_global_marks(
+    //   workspace_id: usize,
+    //   name: String,
+    //   path: PathBuf,
+    //   mark_row: u32,
+    //   mark_column: u32,
+    // )
+    // vim_last_positions(
+    //   path: PathBuf,
+    //   mark_row: u32,
+    //   mark_column: u32,
+    // )
+    pub static ref DB: VimDb<WorkspaceDb> =
+        &[sql! (
+            CREATE TABLE vim_registers(
+                name TEXT PRIMARY KEY,
+                text TEXT NOT NULL,
+                kind TEXT NOT NULL
+            ) STRICT;
+
+            CREATE TABLE vim_last_find(
+                id INTEGER PRIMARY KEY,
+                find_char TEXT NOT NULL,
+                backward INTEGER NOT NULL,
+                till INTEGER NOT NULL,
+                multiline INTEGER NOT NULL,
+                smartcase INTEGER NOT NULL
+            ) STRICT;
+
+            CREATE TABLE vim_global_marks(
+                workspace_id INTEGER NOT NULL,
+                name TEXT NOT NULL,
+                path BLOB NOT NULL,
+                mark_row INTEGER NOT NULL,
+                mark_column INTEGER NOT NULL,
+                PRIMARY KEY(workspace_id, name),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        ),
+        sql! (
+            CREATE TABLE vim_last_positions(
+                path BLOB PRIMARY KEY,
+                mark_row INTEGER NOT NULL,
+                mark_column INTEGER NOT NULL
+            ) STRICT;
+        )];
+);
+
+impl VimDb {
+    query! {
+        pub fn get_registers() -> Result<Vec<(String, String, String)>> {
+            SELECT name, text, kind FROM vim_registers
+        }
+    }
+
+    query! {
+        pub async fn delete_registers() -> Result<()> {
+            DELETE FROM vim_registers
+        }
+    }
+
+    query! {
+        pub async fn save_register(name: String, text: String, kind: String) -> Result<()> {
+            INSERT OR REPLACE INTO vim_registers(name, text, kind) VALUES (?, ?, ?)
+        }
+    }
+
+    query! {
+        pub fn get_last_find() -> Result<Option<(String, bool, bool, bool, bool)>> {
+            SELECT find_char, backward, till, multiline, smartcase FROM vim_last_find
+        }
+    }
+
+    query! {
+        pub async fn save_last_find(
+            find_char: String,
+            backward: bool,
+            till: bool,
+            multiline: bool,
+            smartcase: bool
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO vim_last_find(id, find_char, backward, till, multiline, smartcase)
+            VALUES (0, ?, ?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        pub async fn delete_last_find() -> Result<()> {
+            DELETE FROM vim_last_find
+        }
+    }
+
+    query! {
+        pub fn get_global_marks(workspace_id: WorkspaceId) -> Result<Vec<(String, PathBuf, u32, u32)>> {
+            SELECT name, path, mark_row, mark_column FROM vim_global_marks
+            WHERE workspace_id = ?
+        }
+    }
+
+    query! {
+        pub async fn delete_global_marks(workspace_id: WorkspaceId) -> Result<()> {
+            DELETE FROM vim_global_marks WHERE workspace_id = ?
+        }
+    }
+
+    query! {
+        pub async fn save_global_mark(
+            workspace_id: WorkspaceId,
+            name: String,
+            path: PathBuf,
+            mark_row: u32,
+            mark_column: u32
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO vim_global_marks(workspace_id, name, path, mark_row, mark_column)
+            VALUES (?, ?, ?, ?, ?)
+        }
+    }
+
+    query! {
+        pub fn get_last_positions() -> Result<Vec<(PathBuf, u32, u32)>> {
+            SELECT path, mark_row, mark_column FROM vim_last_positions
+            ORDER BY rowid
+        }
+    }
+
+    query! {
+        pub async fn delete_last_positions() -> Result<()> {
+            DELETE FROM vim_last_positions
+        }
+    }
+
+    query! {
+        pub async fn save_last_position(path: PathBuf, mark_row: u32, mark_column: u32) -> Result<()> {
+            INSERT OR REPLACE INTO vim_last_positions(path, mark_row, mark_column)
+            VALUES (?, ?, ?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::{Mode, VimGlobals},
+        test::VimTestContext,
+        Vim,
+    };
+
+    #[gpui::test]
+    async fn test_persisted_state(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two", Mode::Normal);
+        cx.simulate_keystrokes("\" a y w \" b y e f w");
+        let save = cx.update(|cx| Vim::globals(cx).save_persisted_state());
+        save.await;
+
+        // a register that was cleared since isn't restored by the next session
+        let save = cx.update(|cx| {
+            Vim::globals(cx).registers.remove(&'b');
+            Vim::globals(cx).save_persisted_state()
+        });
+        save.await;
+
+        cx.update(|cx| {
+            let mut globals = VimGlobals::default();
+            globals.load_persisted_state();
+            assert_eq!(globals.registers[&'a'].text.as_ref(), "one ");
+            assert!(!globals.registers.contains_key(&'b'));
+            assert!(globals.last_find.is_some());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_save_and_get_global_marks() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+
+        DB.save_global_mark(workspace_id, "A".into(), "/root/a.txt".into(), 1, 2)
+            .await
+            .unwrap();
+        DB.save_global_mark(workspace_id, "A".into(), "/root/b.txt".into(), 3, 4)
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_global_marks(workspace_id).unwrap(),
+            vec![("A".to_string(), PathBuf::from("/root/b.txt"), 3, 4)]
+        );
+
+        DB.delete_global_marks(workspace_id).await.unwrap();
+        assert_eq!(DB.get_global_marks(workspace_id).unwrap(), Vec::new());
+    }
+}
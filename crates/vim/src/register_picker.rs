@@ -0,0 +1,246 @@
+use std::sync::Arc;
+
+use editor::Editor;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, View, ViewContext,
+    VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::ModalView;
+
+use crate::{motion::MotionKind, normal::paste::Paste, state::Register, Vim};
+
+actions!(vim, [ShowRegisters]);
+
+/// The registers listed by `:registers`, in the order Vim lists them.
+const REGISTERS: &str = "\"0123456789abcdefghijklmnopqrstuvwxyz-.:%#/=+*";
+
+pub(crate) fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
+    Vim::action(editor, cx, |vim, _: &ShowRegisters, cx| {
+        let Some(workspace) = vim.workspace(cx) else {
+            return;
+        };
+        let registers = vim
+            .update_editor(cx, |_, editor, cx| {
+                REGISTERS
+                    .chars()
+                    .filter_map(|name| {
+                        let register = Vim::update_globals(cx, |globals, cx| {
+                            globals.read_register(Some(name), Some(&mut *editor), cx)
+                        })?;
+                        (!register.text.is_empty()).then_some((name, register))
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let vim = cx.view().downgrade();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(cx, |cx| RegisterPicker::new(vim, registers, cx));
+        });
+    });
+}
+
+/// Lists the contents of the registers like `:registers`, and pastes the one picked.
+pub struct RegisterPicker {
+    picker: View<Picker<RegisterPickerDelegate>>,
+}
+
+impl RegisterPicker {
+    fn new(
+        vim: WeakView<Vim>,
+        registers: Vec<(char, Register)>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = RegisterPickerDelegate {
+            register_picker: cx.view().downgrade(),
+            vim,
+            candidates: registers
+                .iter()
+                .enumerate()
+                .map(|(id, (name, _))| StringMatchCandidate::new(id, name.to_string()))
+                .collect(),
+            registers,
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for RegisterPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for RegisterPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for RegisterPicker {}
+impl ModalView for RegisterPicker {}
+
+pub struct RegisterPickerDelegate {
+    register_picker: WeakView<RegisterPicker>,
+    vim: WeakView<Vim>,
+    registers: Vec<(char, Register)>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for RegisterPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Paste a register...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let (name, _) = self.registers[mat.candidate_id];
+            self.vim
+                .update(cx, |vim, cx| {
+                    vim.selected_register = Some(name);
+                    vim.paste(&Paste::default(), cx);
+                })
+                .log_err();
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.register_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let (name, register) = &self.registers[mat.candidate_id];
+        // like `:registers`, the kind is shown as `c`, `l` or `b`, with newlines as `^J`
+        let kind = match register.kind {
+            MotionKind::Charwise => "c",
+            MotionKind::Linewise => "l",
+            MotionKind::Blockwise => "b",
+        };
+        let preview = register
+            .text
+            .strip_suffix('\n')
+            .unwrap_or(&register.text)
+            .replace('\n', "^J")
+            .chars()
+            .take(80)
+            .collect::<String>();
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(Label::new(kind).color(Color::Muted))
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(HighlightedLabel::new(
+                            format!("\"{}", name),
+                            mat.positions.iter().map(|position| position + 1).collect(),
+                        ))
+                        .child(Label::new(preview).color(Color::Muted)),
+                ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{state::Mode, test::VimTestContext};
+
+    use super::RegisterPicker;
+
+    #[gpui::test]
+    async fn test_register_picker(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo", Mode::Normal);
+        cx.simulate_keystrokes("\" a y i w j");
+        cx.simulate_keystrokes(": r e g enter");
+        cx.run_until_parked();
+        cx.workspace(|workspace, cx| {
+            assert!(workspace.active_modal::<RegisterPicker>(cx).is_some());
+        });
+
+        cx.simulate_keystrokes("a enter");
+        cx.run_until_parked();
+        cx.workspace(|workspace, cx| {
+            assert!(workspace.active_modal::<RegisterPicker>(cx).is_none());
+        });
+        cx.assert_state("one\ntonˇewo", Mode::Normal);
+    }
+}
@@ -0,0 +1,287 @@
+use std::rc::Rc;
+
+use collections::HashMap;
+use editor::{display_map::DisplaySnapshot, scroll::Autoscroll, Bias, DisplayPoint, Editor};
+use gpui::{AppContext, Global, ViewContext};
+use language::Selection;
+use serde::Deserialize;
+
+use crate::{
+    motion::Motion, normal::toggle_comments::ToggleCommentsOperator, object::Object, state::Mode,
+    Vim,
+};
+
+/// An operator that vim mode looks up by name, so that new ones can be added without
+/// changing [`crate::state::Operator`]. Built-in operators like `gc` are registered with
+/// [`register_vim_operator`] like those of other crates, and bound with
+/// `["vim::PushOperator", { "Registered": { "name": "..." } }]`.
+///
+/// The operator composes with any motion or text object, applies to the selection in visual
+/// mode and is repeated by `.`.
+pub trait VimOperator: 'static {
+    /// The name the operator is registered and bound under. While the operator is pending,
+    /// it is the `vim_operator` key context, so that the doubled form can be bound to
+    /// `vim::CurrentLine`, and it is shown in the mode indicator.
+    fn id(&self) -> &str;
+
+    /// Operates on the editor's selections, each of which covers the text given by the motion
+    /// or text object, or was selected in visual mode. It runs inside a transaction.
+    fn apply(&self, editor: &mut Editor, cx: &mut ViewContext<Editor>);
+
+    /// Whether the operator takes the next typed character instead of a motion, like `r`.
+    /// The character is then given to [`VimOperator::input`].
+    fn is_waiting(&self) -> bool {
+        false
+    }
+
+    /// Handles the character typed while the operator is waiting for one, with the
+    /// selections as they were when it was pushed. It runs inside a transaction.
+    fn input(&self, _text: &str, _editor: &mut Editor, _cx: &mut ViewContext<Editor>) {}
+
+    /// Whether the cursors go back to where they were once the operator is applied, rather
+    /// than to the start of the text it operated on.
+    fn restores_cursors(&self) -> bool {
+        false
+    }
+}
+
+/// The operator held by [`crate::state::Operator::Registered`]. It is bound by name, and
+/// the [`VimOperator`] registered under that name is looked up whenever it is used.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RegisteredOperator {
+    name: String,
+    /// Whether the operator waits for a character, set when it is pushed.
+    #[serde(skip)]
+    waiting: bool,
+}
+
+impl RegisteredOperator {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            waiting: false,
+        }
+    }
+
+    pub(crate) fn id(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn is_waiting(&self) -> bool {
+        self.waiting
+    }
+
+    /// Returns the operator to push, or `None` if nothing is registered under its name.
+    pub(crate) fn resolve(&self, cx: &AppContext) -> Option<Self> {
+        let operator = self.operator(cx)?;
+        Some(Self {
+            name: self.name.clone(),
+            waiting: operator.is_waiting(),
+        })
+    }
+
+    fn operator(&self, cx: &AppContext) -> Option<Rc<dyn VimOperator>> {
+        cx.try_global::<OperatorRegistry>()?
+            .0
+            .get(&self.name)
+            .cloned()
+    }
+}
+
+impl PartialEq for RegisteredOperator {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for RegisteredOperator {}
+
+#[derive(Default)]
+struct OperatorRegistry(HashMap<String, Rc<dyn VimOperator>>);
+
+impl Global for OperatorRegistry {}
+
+/// The work done by an operator added with [`register_operator`].
+pub type OperatorHandler = Rc<dyn Fn(&mut Editor, &mut ViewContext<Editor>)>;
+
+struct HandlerOperator {
+    name: String,
+    handler: OperatorHandler,
+}
+
+impl VimOperator for HandlerOperator {
+    fn id(&self) -> &str {
+        &self.name
+    }
+
+    fn apply(&self, editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        (self.handler)(editor, cx)
+    }
+}
+
+pub(crate) fn init(cx: &mut AppContext) {
+    register_vim_operator(ToggleCommentsOperator, cx);
+}
+
+/// Adds an operator to vim mode, replacing any that was registered with the same id.
+pub fn register_vim_operator(operator: impl VimOperator, cx: &mut AppContext) {
+    cx.default_global::<OperatorRegistry>()
+        .0
+        .insert(operator.id().to_string(), Rc::new(operator));
+}
+
+/// Registers an operator under `name` that runs `handler`, after which the cursors move to
+/// the start of the text it operated on. See [`VimOperator`].
+pub fn register_operator(
+    name: impl Into<String>,
+    handler: impl Fn(&mut Editor, &mut ViewContext<Editor>) + 'static,
+    cx: &mut AppContext,
+) {
+    register_vim_operator(
+        HandlerOperator {
+            name: name.into(),
+            handler: Rc::new(handler),
+        },
+        cx,
+    );
+}
+
+impl Vim {
+    pub(crate) fn registered_operator_motion(
+        &mut self,
+        operator: &RegisteredOperator,
+        motion: Motion,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.stop_recording(cx);
+        self.update_editor(cx, |_, editor, cx| {
+            let text_layout_details = editor.text_layout_details(cx);
+            apply_operator(operator, editor, cx, |map, selection| {
+                motion.expand_selection(map, selection, times, false, &text_layout_details);
+            });
+        });
+    }
+
+    pub(crate) fn registered_operator_object(
+        &mut self,
+        operator: &RegisteredOperator,
+        object: Object,
+        around: bool,
+        times: Option<usize>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.stop_recording(cx);
+        self.update_editor(cx, |_, editor, cx| {
+            apply_operator(operator, editor, cx, |map, selection| {
+                object.expand_selection(map, selection, around, times);
+            });
+        });
+    }
+
+    pub(crate) fn registered_operator_selections(
+        &mut self,
+        operator: &RegisteredOperator,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.record_current_action(cx);
+        self.update_editor(cx, |_, editor, cx| {
+            let selections = editor.selections.all_adjusted(cx);
+            editor.change_selections(None, cx, |s| s.select(selections));
+            apply_operator(operator, editor, cx, |_, _| {});
+        });
+        self.switch_mode(Mode::Normal, true, cx);
+    }
+
+    pub(crate) fn registered_operator_input(
+        &mut self,
+        operator: &RegisteredOperator,
+        text: &str,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.clear_operator(cx);
+        let Some(operator) = operator.operator(cx) else {
+            return;
+        };
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(cx, |editor, cx| operator.input(text, editor, cx));
+        });
+    }
+}
+
+fn apply_operator(
+    operator: &RegisteredOperator,
+    editor: &mut Editor,
+    cx: &mut ViewContext<Editor>,
+    expand: impl Fn(&DisplaySnapshot, &mut Selection<DisplayPoint>),
+) {
+    let Some(operator) = operator.operator(cx) else {
+        return;
+    };
+    let restores_cursors = operator.restores_cursors();
+    editor.transact(cx, |editor, cx| {
+        let mut cursors = Vec::new();
+        editor.change_selections(None, cx, |s| {
+            s.move_with(|map, selection| {
+                let head = map.display_point_to_anchor(selection.head(), Bias::Right);
+                expand(map, selection);
+                cursors.push(if restores_cursors {
+                    head
+                } else {
+                    map.display_point_to_anchor(selection.start, Bias::Left)
+                });
+            });
+        });
+        operator.apply(editor, cx);
+        editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.select_anchor_ranges(cursors.into_iter().map(|cursor| cursor..cursor));
+        });
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use gpui::KeyBinding;
+
+    use crate::{
+        motion::CurrentLine,
+        register_operator,
+        state::{Mode, Operator},
+        test::VimTestContext,
+        PushOperator, RegisteredOperator,
+    };
+
+    #[gpui::test]
+    async fn test_registered_operator(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.update(|cx| {
+            register_operator(
+                "gz",
+                |editor, cx| editor.convert_to_upper_case(&Default::default(), cx),
+                cx,
+            );
+            cx.bind_keys([
+                KeyBinding::new(
+                    "g z",
+                    PushOperator(Operator::Registered(RegisteredOperator::new("gz"))),
+                    Some("vim_mode == normal || vim_mode == visual"),
+                ),
+                KeyBinding::new("g z", CurrentLine, Some("vim_operator == gz")),
+            ]);
+        });
+
+        cx.set_state("one ˇtwo three\nfour five", Mode::Normal);
+        cx.simulate_keystrokes("g z i w");
+        cx.assert_state("one ˇTWO three\nfour five", Mode::Normal);
+
+        cx.simulate_keystrokes("w .");
+        cx.assert_state("one TWO ˇTHREE\nfour five", Mode::Normal);
+
+        cx.simulate_keystrokes("j g z g z");
+        cx.assert_state("one TWO THREE\nˇFOUR FIVE", Mode::Normal);
+
+        cx.set_state("«oneˇ» two", Mode::Visual);
+        cx.simulate_keystrokes("g z");
+        cx.assert_state("ˇONE two", Mode::Normal);
+    }
+}
@@ -3,17 +3,30 @@ use crate::{
     state::Mode,
     Vim,
 };
-use editor::{display_map::ToDisplayPoint, Bias, Editor, ToPoint};
+use editor::{
+    display_map::{DisplaySnapshot, ToDisplayPoint},
+    Bias, Editor, ToPoint,
+};
 use gpui::{actions, ViewContext};
 use language::{AutoindentMode, Point};
 use std::ops::Range;
 use std::sync::Arc;
 
-actions!(vim, [ToggleReplace, UndoReplace]);
+actions!(vim, [ToggleReplace, ToggleVirtualReplace, UndoReplace]);
 
 pub fn register(editor: &mut Editor, cx: &mut ViewContext<Vim>) {
+    // Like `3i`, `3R` leaves its count pending, so that `NormalBefore` replaces the typed
+    // text another `count - 1` times when replace mode is left.
     Vim::action(editor, cx, |vim, _: &ToggleReplace, cx| {
         vim.replacements = vec![];
+        vim.virtual_replace = false;
+        vim.start_recording(cx);
+        vim.switch_mode(Mode::Replace, false, cx);
+    });
+
+    Vim::action(editor, cx, |vim, _: &ToggleVirtualReplace, cx| {
+        vim.replacements = vec![];
+        vim.virtual_replace = true;
         vim.start_recording(cx);
         vim.switch_mode(Mode::Replace, false, cx);
     });
@@ -43,7 +56,7 @@ impl Vim {
                         let mut range = selection.range();
                         // "\n" need to be handled separately, because when a "\n" is typing,
                         // we don't do a replace, we need insert a "\n"
-                        if !is_new_line {
+                        if !is_new_line && !(vim.virtual_replace && is_wide_tab(&map, range.end)) {
                             range.end.column += 1;
                             range.end = map.buffer_snapshot.clip_point(range.end, Bias::Right);
                         }
@@ -126,6 +139,17 @@ impl Vim {
     }
 }
 
+/// Whether `point` is on a tab that spans more than one screen cell. In virtual replace mode,
+/// typing over such a tab inserts before it, narrowing it until only its last cell is left.
+fn is_wide_tab(map: &DisplaySnapshot, point: Point) -> bool {
+    if map.buffer_snapshot.chars_at(point).next() != Some('\t') {
+        return false;
+    }
+    let start = point.to_display_point(map);
+    let end = (point + Point::new(0, 1)).to_display_point(map);
+    end.column() > start.column() + 1
+}
+
 #[cfg(test)]
 mod test {
     use indoc::indoc;
@@ -144,6 +168,19 @@ mod test {
         assert_eq!(cx.mode(), Mode::Normal);
     }
 
+    #[gpui::test]
+    async fn test_insert_at_previous_after_replace_mode(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+        cx.set_state("ˇhello world", Mode::Normal);
+        cx.simulate_keystrokes("shift-r a b escape w");
+        cx.assert_state("abllo ˇworld", Mode::Normal);
+
+        cx.simulate_keystrokes("g i");
+        cx.assert_state("abˇllo world", Mode::Insert);
+        cx.simulate_keystrokes("x escape");
+        cx.assert_state("abˇxllo world", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_replace_mode(cx: &mut gpui::TestAppContext) {
         let mut cx: NeovimBackedTestContext = NeovimBackedTestContext::new(cx).await;
@@ -251,6 +288,17 @@ mod test {
         cx.shared_state().await.assert_eq("---lo --ˇ-ld\n");
     }
 
+    #[gpui::test]
+    async fn test_replace_mode_with_counts_repeat(cx: &mut gpui::TestAppContext) {
+        let mut cx: NeovimBackedTestContext = NeovimBackedTestContext::new(cx).await;
+
+        cx.set_shared_state("ˇhello world\n").await;
+        cx.simulate_shared_keystrokes("2 shift-r a b escape").await;
+        cx.shared_state().await.assert_eq("abaˇbo world\n");
+        cx.simulate_shared_keystrokes("w 3 .").await;
+        cx.shared_state().await.assert_eq("ababo ababaˇb\n");
+    }
+
     #[gpui::test]
     async fn test_replace_mode_undo(cx: &mut gpui::TestAppContext) {
         let mut cx: NeovimBackedTestContext = NeovimBackedTestContext::new(cx).await;
@@ -310,4 +358,26 @@ mod test {
         cx.simulate_keystrokes("0 shift-r b b b escape u");
         cx.assert_state("ˇaaaa", Mode::Normal);
     }
+
+    #[gpui::test]
+    async fn test_virtual_replace(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇa\tb", Mode::Normal);
+        cx.simulate_keystrokes("g shift-r 1 2 3");
+        cx.assert_state("123ˇ\tb", Mode::Replace);
+        cx.simulate_keystrokes("4");
+        cx.assert_state("1234ˇb", Mode::Replace);
+        cx.simulate_keystrokes("backspace");
+        cx.assert_state("123ˇ\tb", Mode::Replace);
+        cx.simulate_keystrokes("backspace backspace");
+        cx.assert_state("1ˇ\tb", Mode::Replace);
+        cx.simulate_keystrokes("escape");
+        cx.assert_state("ˇ1\tb", Mode::Normal);
+
+        // Regular replace mode overwrites the tab straight away.
+        cx.set_state("ˇa\tb", Mode::Normal);
+        cx.simulate_keystrokes("shift-r 1 2");
+        cx.assert_state("12ˇb", Mode::Replace);
+    }
 }
@@ -1,22 +1,38 @@
 use std::borrow::BorrowMut;
-use std::{fmt::Display, ops::Range, sync::Arc};
+use std::{
+    fmt::Display,
+    future::Future,
+    ops::Range,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
 
-use crate::command::command_interceptor;
+use crate::command::{command_interceptor, record_ex_command};
+use crate::normal::mark::{closing_position, save_global_marks, GlobalMark};
 use crate::normal::repeat::Replayer;
+use crate::normal::search::{Replacement, SearchOffset};
+use crate::persistence::DB;
+use crate::registered_operators::RegisteredOperator;
 use crate::surrounds::SurroundsType;
-use crate::{motion::Motion, object::Object};
+use crate::{
+    motion::{Motion, MotionKind},
+    object::Object,
+};
 use crate::{UseSystemClipboard, Vim, VimSettings};
-use collections::HashMap;
+use collections::{HashMap, HashSet};
 use command_palette_hooks::{CommandPaletteFilter, CommandPaletteInterceptor};
-use editor::{Anchor, ClipboardSelection, Editor};
+use editor::{movement::FindRange, Anchor, ClipboardSelection, Editor};
 use gpui::{
-    Action, AppContext, BorrowAppContext, ClipboardEntry, ClipboardItem, Global, View, WeakView,
+    Action, AppContext, BorrowAppContext, ClipboardEntry, ClipboardItem, EntityId, Global,
+    Keystroke, View, WeakView,
 };
-use language::Point;
+use language::{BufferId, Point, TransactionId};
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
 use ui::{SharedString, ViewContext};
-use workspace::searchable::Direction;
+use util::ResultExt;
+use workspace::{searchable::Direction, Workspace};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Mode {
@@ -70,16 +86,25 @@ pub enum Operator {
     DeleteSurrounds,
     Mark,
     Jump { line: bool },
+    JumpLabel,
     Indent,
     Outdent,
+    AutoIndent,
     Lowercase,
     Uppercase,
     OppositeCase,
+    Rot13,
     Digraph { first_char: Option<char> },
     Register,
+    Expression,
     RecordRegister,
     ReplayRegister,
-    ToggleComments,
+    Rewrap,
+    ReplaceWithRegister,
+    Exchange,
+    Align { target: Option<SurroundsType> },
+    ShellCommand,
+    Registered(RegisteredOperator),
 }
 
 #[derive(Default, Clone, Debug)]
@@ -106,6 +131,8 @@ pub enum RecordedSelection {
 pub struct Register {
     pub(crate) text: SharedString,
     pub(crate) clipboard_selections: Option<Vec<ClipboardSelection>>,
+    /// Whether the text is pasted inline, as whole lines, or as a block.
+    pub(crate) kind: MotionKind,
 }
 
 impl From<Register> for ClipboardItem {
@@ -122,10 +149,22 @@ impl From<ClipboardItem> for Register {
     fn from(item: ClipboardItem) -> Self {
         // For now, we don't store metadata for multiple entries.
         match item.entries().first() {
-            Some(ClipboardEntry::String(value)) if item.entries().len() == 1 => Register {
-                text: value.text().to_owned().into(),
-                clipboard_selections: value.metadata_json::<Vec<ClipboardSelection>>(),
-            },
+            Some(ClipboardEntry::String(value)) if item.entries().len() == 1 => {
+                let clipboard_selections = value.metadata_json::<Vec<ClipboardSelection>>();
+                let linewise = match &clipboard_selections {
+                    Some(selections) => selections.iter().all(|s| s.is_entire_line),
+                    None => value.text().ends_with('\n'),
+                };
+                Register {
+                    text: value.text().to_owned().into(),
+                    clipboard_selections,
+                    kind: if linewise {
+                        MotionKind::Linewise
+                    } else {
+                        MotionKind::Charwise
+                    },
+                }
+            }
             // For now, registers can't store images. This could change in the future.
             _ => Register::default(),
         }
@@ -134,16 +173,35 @@ impl From<ClipboardItem> for Register {
 
 impl From<String> for Register {
     fn from(text: String) -> Self {
+        // like `setreg()`, text ending in a newline is linewise
+        let kind = if text.ends_with('\n') {
+            MotionKind::Linewise
+        } else {
+            MotionKind::Charwise
+        };
         Register {
             text: text.into(),
             clipboard_selections: None,
+            kind,
         }
     }
 }
 
+/// The registers that are kept across sessions: the named registers, the last search and the
+/// last ex command.
+const PERSISTED_REGISTERS: &str = "abcdefghijklmnopqrstuvwxyz/:";
+
+/// How many files the `"` mark is remembered for, like the `'` item of viminfo.
+const MAX_LAST_POSITIONS: usize = 100;
+
+/// How many ex commands and searches are remembered for `q:` and `q/`, like the `history`
+/// option.
+const MAX_HISTORY: usize = 50;
+
 #[derive(Default, Clone)]
 pub struct VimGlobals {
     pub last_find: Option<Motion>,
+    pub last_replacement: Option<Replacement>,
 
     pub dot_recording: bool,
     pub dot_replaying: bool,
@@ -151,26 +209,101 @@ pub struct VimGlobals {
     pub stop_recording_after_next_action: bool,
     pub ignore_current_insertion: bool,
     pub recorded_count: Option<usize>,
+    pub recorded_register: Option<char>,
     pub recorded_actions: Vec<ReplayableAction>,
     pub recorded_selection: RecordedSelection,
+    /// Keys that `.` types instead of replaying `recorded_actions`, set by [`crate::set_repeat_keys`].
+    pub recorded_repeat_keys: Option<Vec<Keystroke>>,
+    /// The count typed before the last `gr`, kept here so that `.` puts the register as often.
+    pub replace_with_register_count: Option<usize>,
 
     pub recording_register: Option<char>,
     pub last_recorded_register: Option<char>,
     pub last_replayed_register: Option<char>,
     pub replayer: Option<Replayer>,
+    /// The keys typed since `q` started recording, in vim's key notation.
+    pub recorded_keys: String,
+    /// Keys added to `recorded_keys` while they were waiting for the rest of a binding.
+    pub pending_recorded_keys: Vec<Keystroke>,
+    /// The keys of a macro replayed from register text that are left to type, last first.
+    pub replaying_keys: Option<Vec<Keystroke>>,
+    /// Set while one of `replaying_keys` is typed, to tell it from the keys the user types.
+    pub typing_replayed_key: bool,
+    /// While a macro replays, the editor it started in and the first transaction it made
+    /// there, which the rest of its edits are grouped into so that `u` undoes them at once.
+    pub macro_transaction: Option<(WeakView<Editor>, Option<TransactionId>)>,
+    /// Set while a `:keepjumps` command runs, so that it leaves the jump list alone.
+    pub keep_jumps: bool,
 
     pub last_yank: Option<SharedString>,
     pub registers: HashMap<char, Register>,
     pub recordings: HashMap<char, Vec<ReplayableAction>>,
+    pub global_marks: HashMap<EntityId, HashMap<String, GlobalMark>>,
+    /// The buffers with global marks that are observed, to keep the marks' positions once
+    /// the buffers are released.
+    pub global_mark_buffers: HashSet<BufferId>,
+    /// Where the cursor was when each file was last closed, oldest first.
+    pub last_positions: Vec<(PathBuf, Point)>,
+    /// The ex commands that were run, oldest first, as listed by `q:`.
+    pub command_history: Vec<String>,
+    /// The patterns that were searched for, oldest first, as listed by `q/`.
+    pub search_history: Vec<String>,
 
     pub focused_vim: Option<WeakView<Vim>>,
 }
 impl Global for VimGlobals {}
 
+/// Set once the state saved by the last session was restored, so that it's saved again on quit.
+struct PersistState;
+
+impl Global for PersistState {}
+
 impl VimGlobals {
+    /// Loads the state saved when Zed last quit, and saves it again on quit from now on.
+    pub(crate) fn restore_persisted_state(cx: &mut AppContext) {
+        Vim::globals(cx).load_persisted_state();
+        cx.set_global(PersistState);
+    }
+
     pub(crate) fn register(cx: &mut AppContext) {
         cx.set_global(VimGlobals::default());
 
+        cx.on_app_quit(|cx| {
+            let workspaces = cx
+                .windows()
+                .into_iter()
+                .filter_map(|window| window.downcast::<Workspace>()?.root(cx).ok())
+                .collect::<Vec<_>>();
+            let editors = workspaces
+                .iter()
+                .flat_map(|workspace| workspace.read(cx).items_of_type::<Editor>(cx))
+                .collect::<Vec<_>>();
+            for editor in editors {
+                if let Some((path, point)) = closing_position(editor.read(cx), cx) {
+                    Vim::globals(cx).set_last_position(path, point);
+                }
+            }
+            let save_marks = workspaces
+                .iter()
+                .map(|workspace| {
+                    let workspace_id = workspace.read(cx).database_id();
+                    save_global_marks(workspace.entity_id(), workspace_id, cx)
+                })
+                .collect::<Vec<_>>();
+            // with vim mode off the globals were reset, and saving them would forget everything
+            let save_state = (cx.has_global::<PersistState>() && Vim::enabled(cx))
+                .then(|| Vim::globals(cx).save_persisted_state());
+            async move {
+                if let Some(save_state) = save_state {
+                    save_state.await;
+                }
+                for save in save_marks {
+                    save.await;
+                }
+            }
+        })
+        .detach();
+
         cx.observe_keystrokes(|event, cx| {
             let Some(action) = event.action.as_ref().map(|action| action.boxed_clone()) else {
                 return;
@@ -186,6 +319,7 @@ impl VimGlobals {
                 });
                 CommandPaletteInterceptor::update_global(cx, |interceptor, _| {
                     interceptor.set(Box::new(command_interceptor));
+                    interceptor.set_confirm_handler(Rc::new(record_ex_command));
                 });
             } else {
                 *Vim::globals(cx) = VimGlobals::default();
@@ -200,19 +334,184 @@ impl VimGlobals {
         .detach();
     }
 
+    /// Restores the registers, the last `f`/`t` and the `"` marks as they were when Zed last
+    /// quit, like viminfo.
+    pub(crate) fn load_persisted_state(&mut self) {
+        self.last_positions = DB
+            .get_last_positions()
+            .log_err()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(path, row, column)| (path, Point::new(row, column)))
+            .collect();
+
+        for (name, text, kind) in DB.get_registers().log_err().unwrap_or_default() {
+            let Some(name) = name.chars().next() else {
+                continue;
+            };
+            let kind = match kind.as_str() {
+                "l" => MotionKind::Linewise,
+                "b" => MotionKind::Blockwise,
+                _ => MotionKind::Charwise,
+            };
+            let register = Register {
+                text: text.into(),
+                clipboard_selections: None,
+                kind,
+            };
+            self.registers.insert(name, register);
+        }
+
+        if let Some((char, backward, till, multiline, smartcase)) =
+            DB.get_last_find().log_err().flatten()
+        {
+            let Some(char) = char.chars().next() else {
+                return;
+            };
+            let mode = if multiline {
+                FindRange::MultiLine
+            } else {
+                FindRange::SingleLine
+            };
+            self.last_find = Some(if backward {
+                Motion::FindBackward {
+                    after: till,
+                    char,
+                    mode,
+                    smartcase,
+                }
+            } else {
+                Motion::FindForward {
+                    before: till,
+                    char,
+                    mode,
+                    smartcase,
+                }
+            });
+        }
+    }
+
+    /// Remembers where the cursor was when a file was closed, for the `"` mark.
+    pub(crate) fn set_last_position(&mut self, path: PathBuf, point: Point) {
+        self.last_positions.retain(|(other, _)| *other != path);
+        self.last_positions.push((path, point));
+        if self.last_positions.len() > MAX_LAST_POSITIONS {
+            self.last_positions.remove(0);
+        }
+    }
+
+    /// Adds `entry` to the end of a history, moving it there if it's already in it.
+    pub(crate) fn push_history(history: &mut Vec<String>, entry: &str) {
+        if entry.is_empty() {
+            return;
+        }
+        history.retain(|other| other != entry);
+        history.push(entry.to_string());
+        if history.len() > MAX_HISTORY {
+            history.remove(0);
+        }
+    }
+
+    pub(crate) fn last_position(&self, path: &Path) -> Option<Point> {
+        self.last_positions
+            .iter()
+            .find(|(other, _)| other == path)
+            .map(|(_, point)| *point)
+    }
+
+    /// Saves the registers, the last `f`/`t` and the `"` marks for the next session. Registers
+    /// that are empty now and a cleared `f`/`t` are removed from what was saved before.
+    pub(crate) fn save_persisted_state(&self) -> impl Future<Output = ()> {
+        let registers = PERSISTED_REGISTERS
+            .chars()
+            .filter_map(|name| {
+                let register = self.registers.get(&name)?;
+                let kind = match register.kind {
+                    MotionKind::Charwise => "c",
+                    MotionKind::Linewise => "l",
+                    MotionKind::Blockwise => "b",
+                };
+                Some((
+                    name.to_string(),
+                    register.text.to_string(),
+                    kind.to_string(),
+                ))
+            })
+            .collect::<Vec<_>>();
+        let last_find = match self.last_find.clone() {
+            Some(Motion::FindForward {
+                before,
+                char,
+                mode,
+                smartcase,
+            }) => Some((char, false, before, mode, smartcase)),
+            Some(Motion::FindBackward {
+                after,
+                char,
+                mode,
+                smartcase,
+            }) => Some((char, true, after, mode, smartcase)),
+            _ => None,
+        };
+        let last_positions = self.last_positions.clone();
+
+        async move {
+            DB.delete_registers().await.log_err();
+            for (name, text, kind) in registers {
+                DB.save_register(name, text, kind).await.log_err();
+            }
+            if let Some((char, backward, till, mode, smartcase)) = last_find {
+                let multiline = mode == FindRange::MultiLine;
+                DB.save_last_find(char.to_string(), backward, till, multiline, smartcase)
+                    .await
+                    .log_err();
+            } else {
+                DB.delete_last_find().await.log_err();
+            }
+            // nothing was loaded or closed, as when vim mode is off
+            if last_positions.is_empty() {
+                return;
+            }
+            DB.delete_last_positions().await.log_err();
+            for (path, point) in last_positions {
+                DB.save_last_position(path, point.row, point.column)
+                    .await
+                    .log_err();
+            }
+        }
+    }
+
     pub(crate) fn write_registers(
         &mut self,
         content: Register,
         register: Option<char>,
         is_yank: bool,
-        linewise: bool,
         cx: &mut ViewContext<Editor>,
     ) {
+        let linewise = content.kind == MotionKind::Linewise;
+        if register == Some('_') {
+            return;
+        }
         if let Some(register) = register {
             let lower = register.to_lowercase().next().unwrap_or(register);
+            // the text is the macro now, which is replayed as typed keys
+            self.recordings.remove(&lower);
             if lower != register {
                 let current = self.registers.entry(lower).or_default();
-                current.text = (current.text.to_string() + &content.text).into();
+                // appending lines, or appending to lines, keeps the register linewise
+                let mut text = current.text.to_string();
+                let was_linewise = current.kind == MotionKind::Linewise;
+                if linewise && !text.is_empty() && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                text.push_str(&content.text);
+                if was_linewise && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                if linewise || current.text.is_empty() {
+                    current.kind = content.kind;
+                }
+                current.text = text.into();
                 // not clear how to support appending to registers with multiple cursors
                 current.clipboard_selections.take();
                 let yanked = current.clone();
@@ -220,16 +519,11 @@ impl VimGlobals {
             } else {
                 self.registers.insert('"', content.clone());
                 match lower {
-                    '_' | ':' | '.' | '%' | '#' | '=' | '/' => {}
+                    ':' | '.' | '%' | '#' | '=' | '/' => {}
                     '+' => {
                         cx.write_to_clipboard(content.into());
                     }
-                    '*' => {
-                        #[cfg(target_os = "linux")]
-                        cx.write_to_primary(content.into());
-                        #[cfg(not(target_os = "linux"))]
-                        cx.write_to_clipboard(content.into());
-                    }
+                    '*' => Self::write_selection(content, cx),
                     '"' => {
                         self.registers.insert('0', content.clone());
                         self.registers.insert('"', content);
@@ -240,12 +534,18 @@ impl VimGlobals {
                 }
             }
         } else {
+            if !is_yank && !VimSettings::get_global(cx).yank_on_delete {
+                return;
+            }
+
             let setting = VimSettings::get_global(cx).use_system_clipboard;
             if setting == UseSystemClipboard::Always
                 || setting == UseSystemClipboard::OnYank && is_yank
             {
                 self.last_yank.replace(content.text.clone());
                 cx.write_to_clipboard(content.clone().into());
+            } else if setting == UseSystemClipboard::Unnamed {
+                Self::write_selection(content.clone(), cx);
             } else {
                 self.last_yank = cx
                     .read_from_clipboard()
@@ -262,7 +562,7 @@ impl VimGlobals {
                 }
                 if linewise || contains_newline {
                     let mut content = content;
-                    for i in '1'..'8' {
+                    for i in '1'..='9' {
                         if let Some(moved) = self.registers.insert(i, content) {
                             content = moved;
                         } else {
@@ -283,27 +583,19 @@ impl VimGlobals {
         let Some(register) = register.filter(|reg| *reg != '"') else {
             let setting = VimSettings::get_global(cx).use_system_clipboard;
             return match setting {
-                UseSystemClipboard::Always => cx.read_from_clipboard().map(|item| item.into()),
+                UseSystemClipboard::Always => self.read_clipboard(cx),
+                UseSystemClipboard::Unnamed => self.read_selection(cx),
                 UseSystemClipboard::OnYank if self.system_clipboard_is_newer(cx) => {
-                    cx.read_from_clipboard().map(|item| item.into())
+                    self.read_clipboard(cx)
                 }
                 _ => self.registers.get(&'"').cloned(),
             };
         };
         let lower = register.to_lowercase().next().unwrap_or(register);
         match lower {
-            '_' | ':' | '.' | '#' | '=' => None,
-            '+' => cx.read_from_clipboard().map(|item| item.into()),
-            '*' => {
-                #[cfg(target_os = "linux")]
-                {
-                    cx.read_from_primary().map(|item| item.into())
-                }
-                #[cfg(not(target_os = "linux"))]
-                {
-                    cx.read_from_clipboard().map(|item| item.into())
-                }
-            }
+            '_' => None,
+            '+' => self.read_clipboard(cx),
+            '*' => self.read_selection(cx),
             '%' => editor.and_then(|editor| {
                 let selection = editor.selections.newest::<Point>(cx);
                 if let Some((_, buffer, _)) = editor
@@ -319,10 +611,52 @@ impl VimGlobals {
                     None
                 }
             }),
+            '#' => editor.and_then(|editor| {
+                let workspace = editor.workspace()?;
+                let item = workspace
+                    .read(cx)
+                    .active_pane()
+                    .read(cx)
+                    .alternate_file_item()?;
+                let project_path = item.project_path(cx)?;
+                Some(project_path.path.to_string_lossy().to_string().into())
+            }),
             _ => self.registers.get(&lower).cloned(),
         }
     }
 
+    /// Reads the `+` register, the system clipboard.
+    fn read_clipboard(&self, cx: &ViewContext<Editor>) -> Option<Register> {
+        Some(self.register_from_clipboard(cx.read_from_clipboard()?))
+    }
+
+    /// Reads the `*` register, which is the primary selection on Linux and the
+    /// system clipboard elsewhere.
+    fn read_selection(&self, cx: &ViewContext<Editor>) -> Option<Register> {
+        #[cfg(target_os = "linux")]
+        let item = cx.read_from_primary()?;
+        #[cfg(not(target_os = "linux"))]
+        let item = cx.read_from_clipboard()?;
+        Some(self.register_from_clipboard(item))
+    }
+
+    fn write_selection(content: Register, cx: &mut ViewContext<Editor>) {
+        #[cfg(target_os = "linux")]
+        cx.write_to_primary(content.into());
+        #[cfg(not(target_os = "linux"))]
+        cx.write_to_clipboard(content.into());
+    }
+
+    fn register_from_clipboard(&self, item: ClipboardItem) -> Register {
+        let register = Register::from(item);
+        // the clipboard doesn't know the kind of the text, so it's taken from the unnamed
+        // register when that holds the same text
+        match self.registers.get(&'"') {
+            Some(unnamed) if unnamed.text == register.text => unnamed.clone(),
+            _ => register,
+        }
+    }
+
     fn system_clipboard_is_newer(&self, cx: &ViewContext<Editor>) -> bool {
         cx.read_from_clipboard().is_some_and(|item| {
             if let Some(last_state) = &self.last_yank {
@@ -333,6 +667,11 @@ impl VimGlobals {
         })
     }
 
+    /// Whether a macro or `.` is replaying, either as recorded actions or as typed keys.
+    pub fn is_replaying(&self) -> bool {
+        self.replayer.is_some() || self.replaying_keys.is_some()
+    }
+
     pub fn observe_action(&mut self, action: Box<dyn Action>) {
         if self.dot_recording {
             self.recorded_actions
@@ -343,7 +682,7 @@ impl VimGlobals {
                 self.stop_recording_after_next_action = false;
             }
         }
-        if self.replayer.is_none() {
+        if !self.is_replaying() {
             if let Some(recording_register) = self.recording_register {
                 self.recordings
                     .entry(recording_register)
@@ -425,6 +764,10 @@ pub struct SearchState {
     pub direction: Direction,
     pub count: usize,
     pub initial_query: String,
+    /// The offset of the last `/` or `?` search (`/foo/e`), which `n` and `N` reuse.
+    pub offset: SearchOffset,
+    /// Where the offset last moved the cursor to, and the start of the match it moved from.
+    pub offset_jump: Option<(Anchor, Anchor)>,
 
     pub prior_selections: Vec<Range<Anchor>>,
     pub prior_operator: Option<Operator>,
@@ -432,7 +775,7 @@ pub struct SearchState {
 }
 
 impl Operator {
-    pub fn id(&self) -> &'static str {
+    pub fn id(&self) -> &str {
         match self {
             Operator::Object { around: false } => "i",
             Operator::Object { around: true } => "a",
@@ -451,26 +794,39 @@ impl Operator {
             Operator::Mark => "m",
             Operator::Jump { line: true } => "'",
             Operator::Jump { line: false } => "`",
+            Operator::JumpLabel => "jump",
             Operator::Indent => ">",
             Operator::Outdent => "<",
+            Operator::AutoIndent => "=",
             Operator::Uppercase => "gU",
             Operator::Lowercase => "gu",
             Operator::OppositeCase => "g~",
+            Operator::Rot13 => "g?",
             Operator::Register => "\"",
+            Operator::Expression => "\"=",
             Operator::RecordRegister => "q",
             Operator::ReplayRegister => "@",
-            Operator::ToggleComments => "gc",
+            Operator::Rewrap => "gq",
+            Operator::ReplaceWithRegister => "gr",
+            Operator::Exchange => "cx",
+            Operator::Align { .. } => "g=",
+            Operator::ShellCommand => "!",
+            Operator::Registered(operator) => operator.id(),
         }
     }
 
     pub fn is_waiting(&self, mode: Mode) -> bool {
         match self {
-            Operator::AddSurrounds { target } => target.is_some() || mode.is_visual(),
+            Operator::AddSurrounds { target } | Operator::Align { target } => {
+                target.is_some() || mode.is_visual()
+            }
             Operator::FindForward { .. }
             | Operator::Mark
             | Operator::Jump { .. }
+            | Operator::JumpLabel
             | Operator::FindBackward { .. }
             | Operator::Register
+            | Operator::Expression
             | Operator::RecordRegister
             | Operator::ReplayRegister
             | Operator::Replace
@@ -482,12 +838,18 @@ impl Operator {
             | Operator::Yank
             | Operator::Indent
             | Operator::Outdent
+            | Operator::AutoIndent
             | Operator::Lowercase
             | Operator::Uppercase
             | Operator::Object { .. }
             | Operator::ChangeSurrounds { target: None }
             | Operator::OppositeCase
-            | Operator::ToggleComments => false,
+            | Operator::Rot13
+            | Operator::Rewrap
+            | Operator::ReplaceWithRegister
+            | Operator::Exchange
+            | Operator::ShellCommand => false,
+            Operator::Registered(operator) => operator.is_waiting(),
         }
     }
 }
@@ -13,7 +13,8 @@ use ui::ViewContext;
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum SurroundsType {
     Motion(Motion),
-    Object(Object),
+    /// A text object, and whether it is the `a` (around) form of it.
+    Object(Object, bool),
     Selection,
 }
 
@@ -42,25 +43,15 @@ impl Vim {
             editor.transact(cx, |editor, cx| {
                 editor.set_clip_at_line_ends(false, cx);
 
-                let pair = match find_surround_pair(&all_support_surround_pair(), &text) {
-                    Some(pair) => pair.clone(),
-                    None => BracketPair {
-                        start: text.to_string(),
-                        end: text.to_string(),
-                        close: true,
-                        surround: true,
-                        newline: false,
-                    },
-                };
-                let surround = pair.end != *text;
+                let (pair, surround) = surround_pair(&text);
                 let (display_map, display_selections) = editor.selections.all_adjusted_display(cx);
                 let mut edits = Vec::new();
                 let mut anchors = Vec::new();
 
                 for selection in &display_selections {
                     let range = match &target {
-                        SurroundsType::Object(object) => {
-                            object.range(&display_map, selection.clone(), false)
+                        SurroundsType::Object(object, _) => {
+                            object.range(&display_map, selection.clone(), false, None)
                         }
                         SurroundsType::Motion(motion) => {
                             let range = motion
@@ -137,6 +128,10 @@ impl Vim {
     }
 
     pub fn delete_surrounds(&mut self, text: Arc<str>, cx: &mut ViewContext<Self>) {
+        if text.as_ref() == "t" {
+            self.replace_tag_surrounds(None, cx);
+            return;
+        }
         self.stop_recording(cx);
 
         // only legitimate surrounds can be removed
@@ -160,7 +155,9 @@ impl Vim {
 
                 for selection in &display_selections {
                     let start = selection.start.to_offset(&display_map, Bias::Left);
-                    if let Some(range) = pair_object.range(&display_map, selection.clone(), true) {
+                    if let Some(range) =
+                        pair_object.range(&display_map, selection.clone(), true, None)
+                    {
                         // If the current parenthesis object is single-line,
                         // then we need to filter whether it is the current line or not
                         if !pair_object.is_multiline() {
@@ -230,30 +227,26 @@ impl Vim {
     }
 
     pub fn change_surrounds(&mut self, text: Arc<str>, target: Object, cx: &mut ViewContext<Self>) {
+        if target == Object::Tag {
+            self.replace_tag_surrounds(Some(text), cx);
+            return;
+        }
         if let Some(will_replace_pair) = object_to_bracket_pair(target) {
             self.stop_recording(cx);
             self.update_editor(cx, |_, editor, cx| {
                 editor.transact(cx, |editor, cx| {
                     editor.set_clip_at_line_ends(false, cx);
 
-                    let pair = match find_surround_pair(&all_support_surround_pair(), &text) {
-                        Some(pair) => pair.clone(),
-                        None => BracketPair {
-                            start: text.to_string(),
-                            end: text.to_string(),
-                            close: true,
-                            surround: true,
-                            newline: false,
-                        },
-                    };
-                    let surround = pair.end != *text;
+                    let (pair, surround) = surround_pair(&text);
                     let (display_map, selections) = editor.selections.all_adjusted_display(cx);
                     let mut edits = Vec::new();
                     let mut anchors = Vec::new();
 
                     for selection in &selections {
                         let start = selection.start.to_offset(&display_map, Bias::Left);
-                        if let Some(range) = target.range(&display_map, selection.clone(), true) {
+                        if let Some(range) =
+                            target.range(&display_map, selection.clone(), true, None)
+                        {
                             if !target.is_multiline() {
                                 let is_same_row = selection.start.row() == range.start.row()
                                     && selection.end.row() == range.end.row();
@@ -336,6 +329,78 @@ impl Vim {
         }
     }
 
+    /// Collects the tag typed after `<` or `t` for a tag surround, returning the
+    /// text to surround with once it is complete.
+    pub fn surround_input(&mut self, text: Arc<str>) -> Option<Arc<str>> {
+        match self.surround_tag.as_mut() {
+            Some(tag) if text.as_ref() == ">" => {
+                let tag = format!("<{}>", tag.trim());
+                self.surround_tag = None;
+                Some(tag.into())
+            }
+            Some(tag) => {
+                tag.push_str(&text);
+                None
+            }
+            None if text.as_ref() == "<" || text.as_ref() == "t" => {
+                self.surround_tag = Some(String::new());
+                None
+            }
+            None => Some(text),
+        }
+    }
+
+    /// Replaces the opening and closing tags of the enclosing tag with the surround for
+    /// `text`, or removes them when `text` is `None` (`dst`).
+    fn replace_tag_surrounds(&mut self, text: Option<Arc<str>>, cx: &mut ViewContext<Self>) {
+        self.stop_recording(cx);
+        let (open, close) = match text {
+            Some(text) => {
+                let (pair, surround) = surround_pair(&text);
+                let maybe_space = if surround { " " } else { "" };
+                (
+                    format!("{}{}", pair.start, maybe_space),
+                    format!("{}{}", maybe_space, pair.end),
+                )
+            }
+            None => (String::new(), String::new()),
+        };
+        self.update_editor(cx, |_, editor, cx| {
+            editor.transact(cx, |editor, cx| {
+                let (display_map, selections) = editor.selections.all_display(cx);
+                let mut edits = Vec::new();
+                let mut anchors = Vec::new();
+
+                for selection in &selections {
+                    let around = Object::Tag.range(&display_map, selection.clone(), true, None);
+                    let inside = Object::Tag.range(&display_map, selection.clone(), false, None);
+                    let offset = match (around, inside) {
+                        (Some(around), Some(inside)) => {
+                            let around = around.start.to_offset(&display_map, Bias::Left)
+                                ..around.end.to_offset(&display_map, Bias::Left);
+                            let inside = inside.start.to_offset(&display_map, Bias::Left)
+                                ..inside.end.to_offset(&display_map, Bias::Left);
+                            edits.push((around.start..inside.start, open.clone()));
+                            edits.push((inside.end..around.end, close.clone()));
+                            around.start
+                        }
+                        _ => selection.head().to_offset(&display_map, Bias::Left),
+                    };
+                    let anchor = display_map.buffer_snapshot.anchor_before(offset);
+                    anchors.push(anchor..anchor);
+                }
+
+                edits.sort_by_key(|(range, _)| range.start);
+                editor.buffer().update(cx, |buffer, cx| {
+                    buffer.edit(edits, None, cx);
+                });
+                editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                    s.select_anchor_ranges(anchors);
+                });
+            });
+        });
+    }
+
     /// Checks if any of the current cursors are surrounded by a valid pair of brackets.
     ///
     /// This method supports multiple cursors and checks each cursor for a valid pair of brackets.
@@ -348,6 +413,16 @@ impl Vim {
         object: Object,
         cx: &mut ViewContext<Self>,
     ) -> bool {
+        if object == Object::Tag {
+            return self
+                .update_editor(cx, |_, editor, cx| {
+                    let (display_map, selections) = editor.selections.all_display(cx);
+                    selections.into_iter().any(|selection| {
+                        object.range(&display_map, selection, true, None).is_some()
+                    })
+                })
+                .unwrap_or(false);
+        }
         let mut valid = false;
         if let Some(pair) = object_to_bracket_pair(object) {
             self.update_editor(cx, |_, editor, cx| {
@@ -358,7 +433,9 @@ impl Vim {
 
                     for selection in &selections {
                         let start = selection.start.to_offset(&display_map, Bias::Left);
-                        if let Some(range) = object.range(&display_map, selection.clone(), true) {
+                        if let Some(range) =
+                            object.range(&display_map, selection.clone(), true, None)
+                        {
                             // If the current parenthesis object is single-line,
                             // then we need to filter whether it is the current line or not
                             if object.is_multiline()
@@ -396,6 +473,39 @@ impl Vim {
     }
 }
 
+/// Returns the pair to surround with for `text`, and whether it is padded with spaces
+/// (typing the opening bracket pads, as in vim-surround). A tag such as `<div id="a">`
+/// is closed with the matching `</div>`.
+fn surround_pair(text: &str) -> (BracketPair, bool) {
+    if let Some(tag) = text
+        .strip_prefix('<')
+        .and_then(|tag| tag.strip_suffix('>'))
+        .filter(|tag| !tag.is_empty())
+    {
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        let pair = BracketPair {
+            start: text.to_string(),
+            end: format!("</{}>", name),
+            close: true,
+            surround: true,
+            newline: false,
+        };
+        return (pair, false);
+    }
+    let pair = match find_surround_pair(&all_support_surround_pair(), text) {
+        Some(pair) => pair.clone(),
+        None => BracketPair {
+            start: text.to_string(),
+            end: text.to_string(),
+            close: true,
+            surround: true,
+            newline: false,
+        },
+    };
+    let surround = pair.end != text;
+    (pair, surround)
+}
+
 fn find_surround_pair<'a>(pairs: &'a [BracketPair], ch: &str) -> Option<&'a BracketPair> {
     pairs.iter().find(|pair| pair.start == ch || pair.end == ch)
 }
@@ -1141,4 +1251,54 @@ mod test {
             Mode::Normal,
         );
     }
+
+    #[gpui::test]
+    async fn test_tag_surrounds(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new_html(cx).await;
+
+        cx.set_state("<p>The quˇick brown</p>", Mode::Normal);
+        cx.simulate_keystrokes("y s i w < b >");
+        cx.assert_state("<p>The ˇ<b>quick</b> brown</p>", Mode::Normal);
+
+        cx.simulate_keystrokes("f q c s t t i >");
+        cx.assert_state("<p>The ˇ<i>quick</i> brown</p>", Mode::Normal);
+
+        cx.simulate_keystrokes("f q c s t )");
+        cx.assert_state("<p>The ˇ(quick) brown</p>", Mode::Normal);
+
+        cx.simulate_keystrokes("c s ) < e m >");
+        cx.assert_state("<p>The ˇ<em>quick</em> brown</p>", Mode::Normal);
+
+        cx.simulate_keystrokes("f q d s t");
+        cx.assert_state("<p>The ˇquick brown</p>", Mode::Normal);
+
+        cx.simulate_keystrokes("v i w shift-s t b >");
+        cx.assert_state("<p>The ˇ<b>quick</b> brown</p>", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_surrounds_repeat(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes("y s i w ]");
+        cx.assert_state("ˇ[one] two three", Mode::Normal);
+        cx.simulate_keystrokes("f t .");
+        cx.assert_state("[one] ˇ[two] three", Mode::Normal);
+
+        cx.simulate_keystrokes("c s [ }");
+        cx.assert_state("[one] ˇ{two} three", Mode::Normal);
+        cx.simulate_keystrokes("0 .");
+        cx.assert_state("ˇ{one} {two} three", Mode::Normal);
+
+        cx.simulate_keystrokes("d s {");
+        cx.assert_state("ˇone {two} three", Mode::Normal);
+        cx.simulate_keystrokes("f { .");
+        cx.assert_state("one ˇtwo three", Mode::Normal);
+
+        cx.simulate_keystrokes("f t y s i w < b >");
+        cx.assert_state("one two ˇ<b>three</b>", Mode::Normal);
+        cx.simulate_keystrokes("0 .");
+        cx.assert_state("ˇ<b>one</b> two <b>three</b>", Mode::Normal);
+    }
 }
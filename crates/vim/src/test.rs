@@ -2,22 +2,23 @@ mod neovim_backed_test_context;
 mod neovim_connection;
 mod vim_test_context;
 
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 
 use collections::HashMap;
 use command_palette::CommandPalette;
-use editor::{actions::DeleteLine, display_map::DisplayRow, DisplayPoint};
+use editor::{actions::DeleteLine, display_map::DisplayRow, DisplayPoint, Editor};
 use futures::StreamExt;
 use gpui::{KeyBinding, Modifiers, MouseButton, TestAppContext};
+use language::Point;
 pub use neovim_backed_test_context::*;
 use settings::SettingsStore;
 pub use vim_test_context::*;
 
 use indoc::indoc;
 use search::BufferSearchBar;
-use workspace::WorkspaceSettings;
+use workspace::{CloseActiveItem, SaveIntent, WorkspaceSettings};
 
-use crate::{insert::NormalBefore, motion, state::Mode};
+use crate::{insert::NormalBefore, motion, state::Mode, VimSettings};
 
 #[gpui::test]
 async fn test_initially_disabled(cx: &mut gpui::TestAppContext) {
@@ -176,26 +177,26 @@ async fn test_indent_outdent(cx: &mut gpui::TestAppContext) {
     // works in normal mode
     cx.set_state(indoc! {"aa\nbˇb\ncc"}, Mode::Normal);
     cx.simulate_keystrokes("> >");
-    cx.assert_editor_state("aa\n    bˇb\ncc");
+    cx.assert_editor_state("aa\n    ˇbb\ncc");
     cx.simulate_keystrokes("< <");
-    cx.assert_editor_state("aa\nbˇb\ncc");
+    cx.assert_editor_state("aa\nˇbb\ncc");
 
     // works in visual mode
     cx.simulate_keystrokes("shift-v down >");
-    cx.assert_editor_state("aa\n    bˇb\n    cc");
+    cx.assert_editor_state("aa\n    ˇbb\n    cc");
 
     // works as operator
     cx.set_state("aa\nbˇb\ncc\n", Mode::Normal);
     cx.simulate_keystrokes("> j");
-    cx.assert_editor_state("aa\n    bˇb\n    cc\n");
+    cx.assert_editor_state("aa\n    ˇbb\n    cc\n");
     cx.simulate_keystrokes("< k");
-    cx.assert_editor_state("aa\nbˇb\n    cc\n");
+    cx.assert_editor_state("ˇaa\nbb\n    cc\n");
     cx.simulate_keystrokes("> i p");
-    cx.assert_editor_state("    aa\n    bˇb\n        cc\n");
+    cx.assert_editor_state("    ˇaa\n    bb\n        cc\n");
     cx.simulate_keystrokes("< i p");
-    cx.assert_editor_state("aa\nbˇb\n    cc\n");
+    cx.assert_editor_state("ˇaa\nbb\n    cc\n");
     cx.simulate_keystrokes("< i p");
-    cx.assert_editor_state("aa\nbˇb\ncc\n");
+    cx.assert_editor_state("ˇaa\nbb\ncc\n");
 
     cx.set_state("ˇaa\nbb\ncc\n", Mode::Normal);
     cx.simulate_keystrokes("> 2 j");
@@ -203,18 +204,91 @@ async fn test_indent_outdent(cx: &mut gpui::TestAppContext) {
 
     cx.set_state("aa\nbb\nˇcc\n", Mode::Normal);
     cx.simulate_keystrokes("> 2 k");
-    cx.assert_editor_state("    aa\n    bb\n    ˇcc\n");
+    cx.assert_editor_state("    ˇaa\n    bb\n    cc\n");
+
+    // works with counts
+    cx.set_state("ˇaa\nbb\ncc\n", Mode::Normal);
+    cx.simulate_keystrokes("2 > >");
+    cx.assert_editor_state("    ˇaa\n    bb\ncc\n");
 
     // works with repeat
     cx.set_state("a\nb\nccˇc\n", Mode::Normal);
     cx.simulate_keystrokes("> 2 k");
-    cx.assert_editor_state("    a\n    b\n    ccˇc\n");
+    cx.assert_editor_state("    ˇa\n    b\n    ccc\n");
+    // `2 k` can't move up from the first line, so the repeat does nothing, like in vim.
     cx.simulate_keystrokes(".");
-    cx.assert_editor_state("        a\n        b\n        ccˇc\n");
+    cx.assert_editor_state("    ˇa\n    b\n    ccc\n");
     cx.simulate_keystrokes("v k <");
-    cx.assert_editor_state("        a\n    bˇ\n    ccc\n");
+    cx.assert_editor_state("ˇa\n    b\n    ccc\n");
+    cx.simulate_keystrokes(".");
+    cx.assert_editor_state("ˇa\n    b\n    ccc\n");
+
+    cx.set_state("ˇa\nb\nccc\n", Mode::Normal);
+    cx.simulate_keystrokes("> 2 j");
+    cx.assert_editor_state("    ˇa\n    b\n    ccc\n");
+    cx.simulate_keystrokes(".");
+    cx.assert_editor_state("        ˇa\n        b\n        ccc\n");
+    cx.simulate_keystrokes("v j <");
+    cx.assert_editor_state("    ˇa\n    b\n        ccc\n");
     cx.simulate_keystrokes(".");
-    cx.assert_editor_state("        a\nbˇ\nccc\n");
+    cx.assert_editor_state("ˇa\nb\n        ccc\n");
+}
+
+#[gpui::test]
+async fn test_indent_shiftwidth(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+    cx.update_global(|store: &mut SettingsStore, cx| {
+        store.update_user_settings::<VimSettings>(cx, |s| s.shiftwidth = Some(2));
+    });
+
+    cx.set_state("aˇa\nbb", Mode::Normal);
+    cx.simulate_keystrokes("> >");
+    cx.assert_editor_state("  ˇaa\nbb");
+    cx.simulate_keystrokes("> >");
+    cx.assert_editor_state("    ˇaa\nbb");
+    cx.simulate_keystrokes("< <");
+    cx.assert_editor_state("  ˇaa\nbb");
+
+    // empty lines are left alone
+    cx.set_state("ˇaa\n\nbb", Mode::Normal);
+    cx.simulate_keystrokes("> 2 j");
+    cx.assert_editor_state("  ˇaa\n\n  bb");
+
+    cx.set_state("ˇaa\nbb", Mode::Normal);
+    cx.simulate_keystrokes("shift-v j 3 >");
+    cx.assert_state("      ˇaa\n      bb", Mode::Normal);
+
+    // ctrl-t and ctrl-d in insert mode, including on empty lines
+    cx.set_state("aˇa\n", Mode::Insert);
+    cx.simulate_keystrokes("ctrl-t");
+    cx.assert_state("  aˇa\n", Mode::Insert);
+    cx.simulate_keystrokes("ctrl-d");
+    cx.assert_state("aˇa\n", Mode::Insert);
+    cx.set_state("aa\nˇ", Mode::Insert);
+    cx.simulate_keystrokes("ctrl-t");
+    cx.assert_state("aa\n  ˇ", Mode::Insert);
+}
+
+#[gpui::test]
+async fn test_autoindent(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state("fn a() {\nˇb();\n        c();\n}", Mode::Normal);
+    cx.simulate_keystrokes("= j");
+    cx.assert_editor_state("fn a() {\n    ˇb();\n    c();\n}");
+
+    cx.set_state("fn a() {\n    b();\n        ˇc();\n}", Mode::Normal);
+    cx.simulate_keystrokes("= =");
+    cx.assert_editor_state("fn a() {\n    b();\n    ˇc();\n}");
+
+    // empty lines are left alone
+    cx.set_state("fn a() {\nif x {\n\nb();\n}\nˇ}", Mode::Normal);
+    cx.simulate_keystrokes("g g = shift-g");
+    cx.assert_editor_state("ˇfn a() {\n    if x {\n\n        b();\n    }\n}");
+
+    cx.set_state("fn a() {\nˇb();\nc();\n}", Mode::Normal);
+    cx.simulate_keystrokes("shift-v j =");
+    cx.assert_state("fn a() {\n    ˇb();\n    c();\n}", Mode::Normal);
 }
 
 #[gpui::test]
@@ -369,6 +443,23 @@ async fn test_join_lines(cx: &mut gpui::TestAppContext) {
       "});
 }
 
+#[gpui::test]
+async fn test_join_lines_no_whitespace(cx: &mut gpui::TestAppContext) {
+    let mut cx = NeovimBackedTestContext::new(cx).await;
+
+    cx.set_shared_state("ˇone\n  two\nthree\nfour").await;
+    cx.simulate_shared_keystrokes("g shift-j").await;
+    cx.shared_state().await.assert_eq("oneˇ  two\nthree\nfour");
+
+    cx.set_shared_state("ˇone\n  two\nthree\nfour").await;
+    cx.simulate_shared_keystrokes("3 g shift-j").await;
+    cx.shared_state().await.assert_eq("one  twoˇthree\nfour");
+
+    cx.set_shared_state("one\nˇtwo\nthree\nfour").await;
+    cx.simulate_shared_keystrokes("v j g shift-j").await;
+    cx.shared_state().await.assert_eq("one\ntwoˇthree\nfour");
+}
+
 #[cfg(target_os = "macos")]
 #[gpui::test]
 async fn test_wrapped_lines(cx: &mut gpui::TestAppContext) {
@@ -638,6 +729,19 @@ async fn test_clear_counts(cx: &mut gpui::TestAppContext) {
         the lazy dog"});
 }
 
+#[gpui::test]
+async fn test_multiplied_counts(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state("ˇa b c d e f g h", Mode::Normal);
+    cx.simulate_keystrokes("2 d 3 w");
+    cx.assert_state("ˇg h", Mode::Normal);
+
+    cx.set_state("ˇa\nb\nc\nd\ne\nf\ng", Mode::Normal);
+    cx.simulate_keystrokes("3 > 2 >");
+    cx.assert_state("    ˇa\n    b\n    c\n    d\n    e\n    f\ng", Mode::Normal);
+}
+
 #[gpui::test]
 async fn test_zero(cx: &mut gpui::TestAppContext) {
     let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -676,6 +780,35 @@ async fn test_selection_goal(cx: &mut gpui::TestAppContext) {
         Lorem Ipsum"});
 }
 
+#[cfg(target_os = "macos")]
+#[gpui::test]
+async fn test_delete_display_lines(cx: &mut gpui::TestAppContext) {
+    let mut cx = NeovimBackedTestContext::new(cx).await;
+
+    cx.set_shared_wrap(12).await;
+    cx.set_shared_state(indoc! { "
+        tˇwelve char twelve char
+        twelve char
+    "})
+        .await;
+    cx.simulate_shared_keystrokes("d g j").await;
+    cx.shared_state().await.assert_eq(indoc! {"
+        tˇwelve char
+        twelve char
+    "});
+
+    cx.set_shared_state(indoc! { "
+        twelve char tˇwelve char
+        twelve char
+    "})
+        .await;
+    cx.simulate_shared_keystrokes("d g k").await;
+    cx.shared_state().await.assert_eq(indoc! {"
+        tˇwelve char
+        twelve char
+    "});
+}
+
 #[cfg(target_os = "macos")]
 #[gpui::test]
 async fn test_wrapped_motions(cx: &mut gpui::TestAppContext) {
@@ -1044,6 +1177,158 @@ async fn test_lowercase_marks(cx: &mut TestAppContext) {
         .assert_eq("line one\nˇtwo\nline three");
 }
 
+#[gpui::test]
+async fn test_marks_track_edits(cx: &mut TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state("one\ntwo\nˇthree\nfour", Mode::Normal);
+    cx.simulate_keystrokes("m a g g shift-o z e r o escape ' a");
+    cx.assert_state("zero\none\ntwo\nˇthree\nfour", Mode::Normal);
+
+    cx.simulate_keystrokes("k k d ' a");
+    cx.assert_state("zero\nˇfour", Mode::Normal);
+}
+
+#[gpui::test]
+async fn test_change_marks(cx: &mut TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state("ˇone two", Mode::Normal);
+    cx.simulate_keystrokes("y e w ` ]");
+    cx.assert_state("onˇe two", Mode::Normal);
+    cx.simulate_keystrokes("$ ` [");
+    cx.assert_state("ˇone two", Mode::Normal);
+
+    cx.simulate_keystrokes("y i w $ p 0 ` [");
+    cx.assert_state("one twoˇone", Mode::Normal);
+    cx.simulate_keystrokes("0 ` ]");
+    cx.assert_state("one twoonˇe", Mode::Normal);
+
+    cx.set_state("ˇone\ntwo", Mode::Normal);
+    cx.simulate_keystrokes("y y j p g g ' [");
+    cx.assert_state("one\ntwo\nˇone", Mode::Normal);
+
+    cx.set_state("ˇab", Mode::Normal);
+    cx.simulate_keystrokes("a x y escape ` [");
+    cx.assert_state("aˇxyb", Mode::Normal);
+    cx.simulate_keystrokes("0 ` ]");
+    cx.assert_state("axˇyb", Mode::Normal);
+}
+
+#[gpui::test]
+async fn test_global_marks(cx: &mut TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state("one\nˇtwo three", Mode::Normal);
+    cx.simulate_keystrokes("w m shift-a");
+
+    let fs = cx.workspace(|workspace, cx| workspace.project().read(cx).fs().clone());
+    fs.as_fake()
+        .insert_file("/root/dir/other.rs", b"other".to_vec())
+        .await;
+    cx.workspace(|workspace, cx| {
+        workspace
+            .open_abs_path(PathBuf::from("/root/dir/other.rs"), false, cx)
+            .detach();
+    });
+    cx.run_until_parked();
+    let editor = cx.editor.clone();
+    cx.workspace(|workspace, cx| {
+        assert_ne!(workspace.active_item_as::<Editor>(cx), Some(editor.clone()));
+    });
+
+    cx.simulate_keystrokes("` shift-a");
+    cx.run_until_parked();
+    cx.workspace(|workspace, cx| {
+        assert_eq!(workspace.active_item_as::<Editor>(cx), Some(editor));
+    });
+    cx.assert_state("one\ntwo ˇthree", Mode::Normal);
+}
+
+#[gpui::test]
+async fn test_global_marks_in_closed_files(cx: &mut TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    let fs = cx.workspace(|workspace, cx| workspace.project().read(cx).fs().clone());
+    fs.as_fake()
+        .insert_file("/root/dir/other.rs", b"one\ntwo three".to_vec())
+        .await;
+    cx.workspace(|workspace, cx| {
+        workspace
+            .open_abs_path(PathBuf::from("/root/dir/other.rs"), false, cx)
+            .detach();
+    });
+    cx.run_until_parked();
+
+    cx.simulate_keystrokes("j w m shift-a g g shift-o z e r o escape");
+    cx.workspace(|workspace, cx| {
+        workspace.save_active_item(SaveIntent::Save, cx).detach();
+    });
+    cx.run_until_parked();
+    cx.workspace(|workspace, cx| {
+        workspace.active_pane().update(cx, |pane, cx| {
+            pane.close_active_item(&CloseActiveItem { save_intent: None }, cx)
+                .unwrap()
+                .detach();
+        });
+    });
+    cx.run_until_parked();
+
+    // the mark is where the edits moved it, not where it was set
+    cx.simulate_keystrokes("` shift-a");
+    cx.run_until_parked();
+    let other = cx.workspace(|workspace, cx| workspace.active_item_as::<Editor>(cx).unwrap());
+    cx.update_view(other, |editor, cx| {
+        assert_eq!(editor.text(cx), "zero\none\ntwo three");
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).range(),
+            Point::new(2, 4)..Point::new(2, 4)
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_restore_cursor_position(cx: &mut TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    let fs = cx.workspace(|workspace, cx| workspace.project().read(cx).fs().clone());
+    fs.as_fake()
+        .insert_file("/root/dir/other.rs", b"one\ntwo\nthree".to_vec())
+        .await;
+    let open_other = |cx: &mut VimTestContext| {
+        cx.workspace(|workspace, cx| {
+            workspace
+                .open_abs_path(PathBuf::from("/root/dir/other.rs"), false, cx)
+                .detach();
+        });
+        cx.run_until_parked();
+        cx.workspace(|workspace, cx| workspace.active_item_as::<Editor>(cx).unwrap())
+    };
+
+    let other = open_other(&mut cx);
+    cx.update_view(other, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges([Point::new(2, 1)..Point::new(2, 1)])
+        });
+    });
+    cx.workspace(|workspace, cx| {
+        workspace.active_pane().update(cx, |pane, cx| {
+            pane.close_active_item(&CloseActiveItem { save_intent: None }, cx)
+                .unwrap()
+                .detach();
+        });
+    });
+    cx.run_until_parked();
+
+    let other = open_other(&mut cx);
+    cx.update_view(other, |editor, cx| {
+        assert_eq!(
+            editor.selections.newest::<Point>(cx).range(),
+            Point::new(2, 1)..Point::new(2, 1)
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_lt_gt_marks(cx: &mut TestAppContext) {
     let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -1261,6 +1546,70 @@ async fn test_toggle_comments(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_toggle_comments_operator(cx: &mut gpui::TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    let language = std::sync::Arc::new(language::Language::new(
+        language::LanguageConfig {
+            line_comments: vec!["// ".into()],
+            ..Default::default()
+        },
+        Some(language::tree_sitter_rust::language()),
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    cx.set_state(
+        indoc! {"
+        ˇone
+        two
+
+        three
+        four
+        "},
+        Mode::Normal,
+    );
+
+    // works with text objects
+    cx.simulate_keystrokes("g c a p");
+    cx.assert_state(
+        indoc! {"
+        // ˇone
+        // two
+
+        three
+        four
+        "},
+        Mode::Normal,
+    );
+
+    // repeats with dot
+    cx.simulate_keystrokes("3 j .");
+    cx.assert_state(
+        indoc! {"
+        // one
+        // two
+
+        // thrˇee
+        // four
+        "},
+        Mode::Normal,
+    );
+
+    // gcc takes a count
+    cx.simulate_keystrokes("g g 2 g c c");
+    cx.assert_state(
+        indoc! {"
+        ˇone
+        two
+
+        // three
+        // four
+        "},
+        Mode::Normal,
+    );
+}
+
 #[gpui::test]
 async fn test_find_multibyte(cx: &mut gpui::TestAppContext) {
     let mut cx = NeovimBackedTestContext::new(cx).await;
@@ -1293,6 +1642,22 @@ async fn test_plus_minus(cx: &mut gpui::TestAppContext) {
     cx.shared_state().await.assert_matches();
 }
 
+#[gpui::test]
+async fn test_plus_minus_underscore_operators(cx: &mut gpui::TestAppContext) {
+    let mut cx = NeovimBackedTestContext::new(cx).await;
+
+    cx.simulate("d _", "one\n  ˇtwo\nthree\n")
+        .await
+        .assert_matches();
+    cx.simulate("d 2 _", "ˇone\ntwo\nthree\n")
+        .await
+        .assert_matches();
+    cx.simulate("d +", "one\nˇtwo\nthree\nfour\n")
+        .await
+        .assert_matches();
+    cx.simulate("y _ p", "ˇone\ntwo\n").await.assert_matches();
+}
+
 #[gpui::test]
 async fn test_command_alias(cx: &mut gpui::TestAppContext) {
     let mut cx = VimTestContext::new(cx, true).await;
@@ -1413,6 +1778,8 @@ async fn test_visual_indent_count(cx: &mut gpui::TestAppContext) {
     cx.assert_state("            ˇhi", Mode::Normal);
     cx.simulate_keystrokes("shift-v 2 <");
     cx.assert_state("    ˇhi", Mode::Normal);
+    cx.simulate_keystrokes("shift-v 2 > .");
+    cx.assert_state("                    ˇhi", Mode::Normal);
 }
 
 #[gpui::test]
@@ -1426,3 +1793,87 @@ async fn test_record_replay_recursion(cx: &mut gpui::TestAppContext) {
     cx.simulate_shared_keystrokes(".").await;
     cx.shared_state().await.assert_eq("ˇhello world"); // takes a _long_ time
 }
+
+#[gpui::test]
+async fn test_jump_list(cx: &mut TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state(
+        indoc! {"
+            ˇone
+            two
+            three
+            four
+            five"},
+        Mode::Normal,
+    );
+    // only jumps are recorded, so `j` is skipped on the way back
+    cx.simulate_keystrokes("j shift-g");
+    cx.simulate_keystrokes("ctrl-o");
+    cx.run_until_parked();
+    cx.assert_state(
+        indoc! {"
+            one
+            ˇtwo
+            three
+            four
+            five"},
+        Mode::Normal,
+    );
+    cx.simulate_keystrokes("ctrl-i");
+    cx.run_until_parked();
+    cx.assert_state(
+        indoc! {"
+            one
+            two
+            three
+            four
+            ˇfive"},
+        Mode::Normal,
+    );
+
+    cx.simulate_keystrokes("g g } } 2 ctrl-o");
+    cx.run_until_parked();
+    cx.assert_state(
+        indoc! {"
+            one
+            two
+            three
+            four
+            ˇfive"},
+        Mode::Normal,
+    );
+}
+
+#[gpui::test]
+async fn test_keepjumps(cx: &mut TestAppContext) {
+    let mut cx = VimTestContext::new(cx, true).await;
+
+    cx.set_state(
+        indoc! {"
+            ˇone
+            two
+            three"},
+        Mode::Normal,
+    );
+    cx.simulate_keystrokes("j shift-g : k e e p j u m p s space 1 enter");
+    cx.run_until_parked();
+    cx.assert_state(
+        indoc! {"
+            ˇone
+            two
+            three"},
+        Mode::Normal,
+    );
+
+    // `:1` alone would have recorded the last line, so this would go back there instead
+    cx.simulate_keystrokes("ctrl-o");
+    cx.run_until_parked();
+    cx.assert_state(
+        indoc! {"
+            one
+            ˇtwo
+            three"},
+        Mode::Normal,
+    );
+}
@@ -5,12 +5,20 @@ mod test;
 
 mod change_list;
 mod command;
+mod command_window;
 mod digraph;
+mod expression;
 mod insert;
+mod jump_labels;
+mod jump_list;
+mod mark_picker;
 mod mode_indicator;
 mod motion;
 mod normal;
 mod object;
+mod persistence;
+mod register_picker;
+mod registered_operators;
 mod replace;
 mod state;
 mod surrounds;
@@ -20,23 +28,31 @@ use anyhow::Result;
 use collections::HashMap;
 use editor::{
     movement::{self, FindRange},
+    scroll::Autoscroll,
     Anchor, Bias, Editor, EditorEvent, EditorMode, ToPoint,
 };
 use gpui::{
     actions, impl_actions, Action, AppContext, Entity, EventEmitter, KeyContext, KeystrokeEvent,
-    Render, View, ViewContext, WeakView,
+    Render, View, ViewContext, WeakView, DEFAULT_PENDING_INPUT_TIMEOUT,
 };
 use insert::NormalBefore;
 use language::{CursorShape, Point, Selection, SelectionGoal, TransactionId};
 pub use mode_indicator::ModeIndicator;
-use motion::Motion;
-use normal::search::SearchSubmit;
+use motion::{Motion, MotionKind};
+pub use normal::repeat::{recording_register, set_repeat, set_repeat_keys, MacroRecording};
+use normal::{
+    mark::{buffer_path, closing_position, refresh_global_marks, save_global_marks},
+    search::SearchSubmit,
+};
+pub use registered_operators::{
+    register_operator, register_vim_operator, OperatorHandler, RegisteredOperator, VimOperator,
+};
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde_derive::Serialize;
 use settings::{update_settings_file, Settings, SettingsSources, SettingsStore};
 use state::{Mode, Operator, RecordedSelection, SearchState, VimGlobals};
-use std::{ops::Range, sync::Arc};
+use std::{ops::Range, sync::Arc, time::Duration};
 use surrounds::SurroundsType;
 use ui::{IntoElement, VisualContext};
 use workspace::{self, Pane, Workspace};
@@ -90,11 +106,24 @@ pub fn init(cx: &mut AppContext) {
     VimModeSetting::register(cx);
     VimSettings::register(cx);
     VimGlobals::register(cx);
+    registered_operators::init(cx);
+
+    update_pending_input_timeout(cx);
+    cx.observe_global::<SettingsStore>(update_pending_input_timeout)
+        .detach();
 
     cx.observe_new_views(|editor: &mut Editor, cx| Vim::register(editor, cx))
         .detach();
 
-    cx.observe_new_views(|workspace: &mut Workspace, _| {
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        let workspace_entity = cx.view().entity_id();
+        cx.on_release(move |workspace, _, cx| {
+            let save = save_global_marks(workspace_entity, workspace.database_id(), cx);
+            Vim::globals(cx).global_marks.remove(&workspace_entity);
+            cx.background_executor().spawn(save).detach();
+        })
+        .detach();
+
         workspace.register_action(|workspace, _: &ToggleVimMode, cx| {
             let fs = workspace.app_state().fs.clone();
             let currently_enabled = Vim::enabled(cx);
@@ -125,6 +154,22 @@ pub fn init(cx: &mut AppContext) {
     .detach();
 }
 
+/// Restores the registers, the last `f`/`t` and the `"` marks saved when Zed last quit, and
+/// saves them again on quit. It's separate from [`init`] so that tests, which share one
+/// database, don't see each other's registers.
+pub fn restore_persisted_state(cx: &mut AppContext) {
+    VimGlobals::restore_persisted_state(cx);
+}
+
+fn update_pending_input_timeout(cx: &mut AppContext) {
+    let timeout = if Vim::enabled(cx) {
+        Duration::from_millis(VimSettings::get_global(cx).keystroke_timeout_ms)
+    } else {
+        DEFAULT_PENDING_INPUT_TIMEOUT
+    };
+    cx.set_pending_input_timeout(timeout);
+}
+
 #[derive(Clone)]
 pub(crate) struct VimAddon {
     pub(crate) view: View<Vim>,
@@ -152,11 +197,24 @@ pub(crate) struct Vim {
 
     operator_stack: Vec<Operator>,
     pub(crate) replacements: Vec<(Range<editor::Anchor>, String)>,
+    /// Whether replace mode overwrites screen cells rather than characters (`gR`).
+    pub(crate) virtual_replace: bool,
+    /// The tag being typed for a tag surround (`ysiw<div>`), once `<` or `t` was pressed.
+    pub(crate) surround_tag: Option<String>,
+    /// The expression being typed at the `"=` prompt.
+    pub(crate) expression: String,
+    /// The text typed since entering insert mode, stored in the `.` register on leaving it.
+    inserted_text: String,
+    /// The region marked by a first `cx`, waiting for a second one to swap with.
+    pub(crate) exchange: Option<Range<Anchor>>,
+    /// The kind the pending operator's motion was forced to with `v`, `V` or `<C-v>`.
+    pub(crate) forced_motion: Option<MotionKind>,
 
     pub(crate) marks: HashMap<String, Vec<Anchor>>,
     pub(crate) stored_visual_mode: Option<(Mode, Vec<bool>)>,
     pub(crate) change_list: Vec<Vec<Anchor>>,
     pub(crate) change_list_position: Option<usize>,
+    pub(crate) jump_labels: Vec<(char, Anchor)>,
 
     pub(crate) current_tx: Option<TransactionId>,
     pub(crate) current_anchor: Option<Selection<Anchor>>,
@@ -197,6 +255,8 @@ impl Vim {
 
             let listener = cx.listener(Vim::observe_keystrokes);
             cx.observe_keystrokes(listener).detach();
+            cx.observe_pending_input(Vim::observe_pending_input)
+                .detach();
 
             Vim {
                 mode: Mode::Normal,
@@ -205,11 +265,18 @@ impl Vim {
                 post_count: None,
                 operator_stack: Vec::new(),
                 replacements: Vec::new(),
+                virtual_replace: false,
+                surround_tag: None,
+                expression: String::new(),
+                inserted_text: String::new(),
+                exchange: None,
+                forced_motion: None,
 
                 marks: HashMap::default(),
                 stored_visual_mode: None,
                 change_list: Vec::new(),
                 change_list_position: None,
+                jump_labels: Vec::new(),
                 current_tx: None,
                 current_anchor: None,
                 undo_modes: HashMap::default(),
@@ -254,11 +321,48 @@ impl Vim {
             }
         })
         .detach();
+
+        cx.on_release(|editor, _, cx| {
+            if let Some((path, point)) = closing_position(editor, cx) {
+                Vim::globals(cx).set_last_position(path, point);
+            }
+            for buffer in editor.buffer().read(cx).all_buffers() {
+                let snapshot = buffer.read(cx).snapshot();
+                refresh_global_marks(&snapshot, cx);
+            }
+        })
+        .detach();
+
         if was_enabled {
-            Self::activate(editor, cx)
+            Self::activate(editor, cx);
+            if VimSettings::get_global(cx).restore_cursor_position {
+                Self::restore_cursor_position(editor, cx);
+            }
         }
     }
 
+    /// Moves the cursor of a newly opened editor to the `"` mark, unless something already
+    /// moved it.
+    fn restore_cursor_position(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        let selection = editor.selections.newest::<Point>(cx);
+        if !selection.is_empty() || selection.head() != Point::zero() {
+            return;
+        }
+        let Some(point) =
+            buffer_path(editor, cx).and_then(|path| Vim::globals(cx).last_position(&path))
+        else {
+            return;
+        };
+        let point = editor
+            .buffer()
+            .read(cx)
+            .snapshot(cx)
+            .clip_point(point, Bias::Left);
+        editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+            s.select_ranges([point..point])
+        });
+    }
+
     fn activate(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
         let vim = Vim::new(cx);
 
@@ -270,7 +374,18 @@ impl Vim {
             });
 
             Vim::action(editor, cx, |vim, action: &PushOperator, cx| {
-                vim.push_operator(action.0.clone(), cx)
+                let mut operator = action.0.clone();
+                if let Operator::Registered(registered) = &mut operator {
+                    let Some(resolved) = registered.resolve(cx) else {
+                        return;
+                    };
+                    if vim.mode.is_visual() && !resolved.is_waiting() {
+                        vim.registered_operator_selections(&resolved, cx);
+                        return;
+                    }
+                    *registered = resolved;
+                }
+                vim.push_operator(operator, cx)
             });
 
             Vim::action(editor, cx, |vim, _: &ClearOperators, cx| {
@@ -294,6 +409,11 @@ impl Vim {
             object::register(editor, cx);
             visual::register(editor, cx);
             change_list::register(editor, cx);
+            jump_labels::register(editor, cx);
+            jump_list::register(editor, cx);
+            expression::register(editor, cx);
+            register_picker::register(editor, cx);
+            mark_picker::register(editor, cx);
 
             cx.defer(|vim, cx| {
                 vim.focused(false, cx);
@@ -347,7 +467,15 @@ impl Vim {
     /// Called whenever an keystroke is typed so vim can observe all actions
     /// and keystrokes accordingly.
     fn observe_keystrokes(&mut self, keystroke_event: &KeystrokeEvent, cx: &mut ViewContext<Self>) {
+        self.record_keystroke(&keystroke_event.keystroke, cx);
+        self.cancel_replay(&keystroke_event.keystroke, cx);
         if let Some(action) = keystroke_event.action.as_ref() {
+            // backspacing over inserted text takes it back out of the `.` register
+            if self.mode == Mode::Insert && editor::actions::Backspace.partial_eq(&**action)
+                || self.mode == Mode::Replace && replace::UndoReplace.partial_eq(&**action)
+            {
+                self.inserted_text.pop();
+            }
             // Keystroke is handled by the vim system, so continue forward
             if action.name().starts_with("vim::") {
                 return;
@@ -378,7 +506,15 @@ impl Vim {
             EditorEvent::InputHandled {
                 text,
                 utf16_range_to_replace: range_to_replace,
-            } => Vim::globals(cx).observe_insertion(text, range_to_replace.clone()),
+            } => {
+                if matches!(self.mode, Mode::Insert | Mode::Replace) {
+                    if self.inserted_text.is_empty() {
+                        self.mark_insert_start(text.len(), cx);
+                    }
+                    self.inserted_text.push_str(text);
+                }
+                Vim::globals(cx).observe_insertion(text, range_to_replace.clone())
+            }
             EditorEvent::TransactionBegun { transaction_id } => {
                 self.transaction_begun(*transaction_id, cx)
             }
@@ -399,13 +535,26 @@ impl Vim {
                 | Operator::Replace
                 | Operator::Indent
                 | Operator::Outdent
+                | Operator::AutoIndent
                 | Operator::Lowercase
                 | Operator::Uppercase
                 | Operator::OppositeCase
-                | Operator::ToggleComments
+                | Operator::Rot13
+                | Operator::Rewrap
+                | Operator::ReplaceWithRegister
+                | Operator::Align { target: None }
+                | Operator::Registered(_)
+                | Operator::Exchange
         ) {
             self.start_recording(cx)
         };
+        // `[count]gr{motion}` puts the register `count` times, so the count isn't the motion's
+        if operator == Operator::ReplaceWithRegister {
+            let globals = Vim::globals(cx);
+            if !globals.dot_replaying {
+                globals.replace_with_register_count = self.pre_count.take();
+            }
+        }
         // Since these operations can only be entered with pre-operators,
         // we need to clear the previous operators when pushing,
         // so that the current stack is the most correct
@@ -414,8 +563,10 @@ impl Vim {
             Operator::AddSurrounds { .. }
                 | Operator::ChangeSurrounds { .. }
                 | Operator::DeleteSurrounds
+                | Operator::Exchange
         ) {
             self.operator_stack.clear();
+            self.surround_tag.take();
             if let Operator::AddSurrounds { target: None } = operator {
                 self.start_recording(cx);
             }
@@ -428,6 +579,13 @@ impl Vim {
         let last_mode = self.mode;
         let prior_mode = self.last_mode;
         let prior_tx = self.current_tx;
+        if matches!(last_mode, Mode::Insert | Mode::Replace) && mode != last_mode {
+            let inserted_text = std::mem::take(&mut self.inserted_text);
+            if !inserted_text.is_empty() {
+                Vim::globals(cx).registers.insert('.', inserted_text.into());
+                self.create_mark("]".into(), false, cx);
+            }
+        }
         self.last_mode = last_mode;
         self.mode = mode;
         self.operator_stack.clear();
@@ -529,7 +687,12 @@ impl Vim {
         let count = if self.post_count == None && self.pre_count == None {
             return None;
         } else {
-            Some(self.post_count.take().unwrap_or(1) * self.pre_count.take().unwrap_or(1))
+            Some(
+                self.post_count
+                    .take()
+                    .unwrap_or(1)
+                    .saturating_mul(self.pre_count.take().unwrap_or(1)),
+            )
         };
 
         if global_state.dot_recording {
@@ -600,7 +763,7 @@ impl Vim {
             context.add("VimCount");
         }
 
-        if let Some(active_operator) = active_operator {
+        if let Some(active_operator) = &active_operator {
             if active_operator.is_waiting(self.mode) {
                 mode = "waiting".to_string();
             } else {
@@ -708,7 +871,9 @@ impl Vim {
             if !globals.dot_replaying {
                 globals.dot_recording = true;
                 globals.recorded_actions = Default::default();
+                globals.recorded_repeat_keys = None;
                 globals.recorded_count = None;
+                globals.recorded_register = self.selected_register;
 
                 let selections = self.editor().map(|editor| {
                     let editor = editor.read(cx);
@@ -835,6 +1000,7 @@ impl Vim {
     fn clear_operator(&mut self, cx: &mut ViewContext<Self>) {
         self.take_count(cx);
         self.selected_register.take();
+        self.forced_motion.take();
         self.operator_stack.clear();
         self.sync_vim_settings(cx);
     }
@@ -843,7 +1009,12 @@ impl Vim {
         self.operator_stack.last().cloned()
     }
 
-    fn transaction_begun(&mut self, transaction_id: TransactionId, _: &mut ViewContext<Self>) {
+    fn transaction_begun(&mut self, transaction_id: TransactionId, cx: &mut ViewContext<Self>) {
+        if let Some((editor, first_tx @ None)) = &mut Vim::globals(cx).macro_transaction {
+            if *editor == self.editor {
+                *first_tx = Some(transaction_id);
+            }
+        }
         let mode = if (self.mode == Mode::Insert
             || self.mode == Mode::Replace
             || self.mode == Mode::Normal)
@@ -992,23 +1163,42 @@ impl Vim {
             Some(Operator::AddSurrounds { target }) => match self.mode {
                 Mode::Normal => {
                     if let Some(target) = target {
-                        self.add_surrounds(text, target, cx);
-                        self.clear_operator(cx);
+                        if let Some(text) = self.surround_input(text) {
+                            self.add_surrounds(text, target, cx);
+                            self.clear_operator(cx);
+                        }
                     }
                 }
                 Mode::Visual | Mode::VisualLine | Mode::VisualBlock => {
-                    self.add_surrounds(text, SurroundsType::Selection, cx);
-                    self.clear_operator(cx);
+                    if let Some(text) = self.surround_input(text) {
+                        self.add_surrounds(text, SurroundsType::Selection, cx);
+                        self.clear_operator(cx);
+                    }
                 }
                 _ => self.clear_operator(cx),
             },
             Some(Operator::ChangeSurrounds { target }) => match self.mode {
                 Mode::Normal => {
                     if let Some(target) = target {
-                        self.change_surrounds(text, target, cx);
+                        if let Some(text) = self.surround_input(text) {
+                            self.change_surrounds(text, target, cx);
+                            self.clear_operator(cx);
+                        }
+                    }
+                }
+                _ => self.clear_operator(cx),
+            },
+            Some(Operator::Align { target }) => match self.mode {
+                Mode::Normal => {
+                    if let Some(target) = target {
+                        self.align(text, target, cx);
                         self.clear_operator(cx);
                     }
                 }
+                Mode::Visual | Mode::VisualLine | Mode::VisualBlock => {
+                    self.align(text, SurroundsType::Selection, cx);
+                    self.clear_operator(cx);
+                }
                 _ => self.clear_operator(cx),
             },
             Some(Operator::DeleteSurrounds) => match self.mode {
@@ -1018,13 +1208,19 @@ impl Vim {
                 }
                 _ => self.clear_operator(cx),
             },
-            Some(Operator::Mark) => self.create_mark(text, false, cx),
-            Some(Operator::RecordRegister) => {
-                self.record_register(text.chars().next().unwrap(), cx)
+            Some(Operator::Registered(operator)) => {
+                self.registered_operator_input(&operator, &text, cx)
             }
+            Some(Operator::Mark) => self.create_mark(text, false, cx),
+            Some(Operator::RecordRegister) => match text.chars().next().unwrap() {
+                kind @ (':' | '/' | '?') => self.open_command_window(kind, cx),
+                register => self.record_register(register, cx),
+            },
             Some(Operator::ReplayRegister) => {
                 self.replay_register(text.chars().next().unwrap(), cx)
             }
+            Some(Operator::Register) if text.as_ref() == "=" => self.start_expression(cx),
+            Some(Operator::Expression) => self.expression_input(text, cx),
             Some(Operator::Register) => match self.mode {
                 Mode::Insert => {
                     self.update_editor(cx, |_, editor, cx| {
@@ -1046,6 +1242,7 @@ impl Vim {
                 }
             },
             Some(Operator::Jump { line }) => self.jump(text, line, cx),
+            Some(Operator::JumpLabel) => self.jump_to_label(text, cx),
             _ => match self.mode {
                 Mode::Replace => self.multi_replace(text, cx),
                 _ => {}
@@ -1054,7 +1251,13 @@ impl Vim {
     }
 
     fn sync_vim_settings(&mut self, cx: &mut ViewContext<Self>) {
+        if self.active_operator() != Some(Operator::JumpLabel) {
+            self.jump_labels.clear();
+        }
         self.update_editor(cx, |vim, editor, cx| {
+            if vim.jump_labels.is_empty() {
+                editor.clear_jump_labels(cx);
+            }
             editor.set_cursor_shape(vim.cursor_shape(), cx);
             editor.set_clip_at_line_ends(vim.clip_at_line_ends(), cx);
             editor.set_collapse_matches(true);
@@ -1085,10 +1288,13 @@ impl Settings for VimModeSetting {
 pub enum UseSystemClipboard {
     /// Don't use system clipboard.
     Never,
-    /// Use system clipboard.
+    /// Use system clipboard, like vim's `clipboard=unnamedplus`.
     Always,
     /// Use system clipboard for yank operations.
     OnYank,
+    /// Use the `*` register, like vim's `clipboard=unnamed`. This is the primary
+    /// selection on Linux and the system clipboard elsewhere.
+    Unnamed,
 }
 
 #[derive(Deserialize)]
@@ -1098,6 +1304,23 @@ struct VimSettings {
     pub use_multiline_find: bool,
     pub use_smartcase_find: bool,
     pub custom_digraphs: HashMap<String, Arc<str>>,
+    pub keystroke_timeout_ms: u64,
+    pub whichwrap: String,
+    pub yank_on_delete: bool,
+    pub shiftwidth: u32,
+    pub restore_cursor_position: bool,
+    pub hlsearch: bool,
+    pub ignorecase: Option<bool>,
+    pub smartcase: bool,
+}
+
+impl VimSettings {
+    /// Whether the given key (`b`, `s`, `h` or `l`) moves across line boundaries.
+    pub fn wraps(&self, key: char) -> bool {
+        self.whichwrap
+            .split(',')
+            .any(|flag| flag.trim().chars().eq([key]))
+    }
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -1107,6 +1330,50 @@ struct VimSettingsContent {
     pub use_multiline_find: Option<bool>,
     pub use_smartcase_find: Option<bool>,
     pub custom_digraphs: Option<HashMap<String, Arc<str>>>,
+    /// How long to wait for the next key of a multi-key binding, in milliseconds.
+    /// Like vim's `timeoutlen`.
+    ///
+    /// Default: 1000
+    pub keystroke_timeout_ms: Option<u64>,
+    /// A comma-separated list of the keys that move to the previous or next line when
+    /// used at the start or end of a line. Like vim's `whichwrap`, supporting `b`
+    /// (backspace), `s` (space), `h` and `l`.
+    ///
+    /// Default: "b,s"
+    pub whichwrap: Option<String>,
+    /// Whether text removed by deletes and changes is stored in the unnamed and
+    /// numbered registers when no register is named. When false, such text is
+    /// discarded as if the `_` register was used, so the last yank is kept.
+    ///
+    /// Default: true
+    pub yank_on_delete: Option<bool>,
+    /// The number of columns that `>` and `<` shift lines by. Like vim's `shiftwidth`,
+    /// 0 means the tab size is used.
+    ///
+    /// Default: 0
+    pub shiftwidth: Option<u32>,
+    /// Whether reopening a file moves the cursor back to where it was when the file was
+    /// last closed, which is the `"` mark. Like the autocommand in vim's `defaults.vim`.
+    ///
+    /// Default: true
+    pub restore_cursor_position: Option<bool>,
+    /// Whether the matches of the last search stay highlighted after it moves the cursor.
+    /// Like vim's `hlsearch`, they are hidden by `:nohlsearch` until the next search or `n`.
+    ///
+    /// Default: true
+    pub hlsearch: Option<bool>,
+    /// Whether searches with `/`, `?`, `*`, `#`, `:s` and `gn` ignore case, like vim's
+    /// `ignorecase`. `\c` or `\C` in a pattern ignores or matches case regardless.
+    /// When unset, `/` and `?` follow the search bar's case sensitivity toggle, and the
+    /// other searches match case.
+    ///
+    /// Default: null
+    pub ignorecase: Option<bool>,
+    /// Whether a search pattern with an uppercase letter matches case when `ignorecase` is on,
+    /// like vim's `smartcase`. It doesn't apply to `*` and `#`.
+    ///
+    /// Default: false
+    pub smartcase: Option<bool>,
 }
 
 impl Settings for VimSettings {
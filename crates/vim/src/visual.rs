@@ -5,7 +5,7 @@ use editor::{
     display_map::{DisplaySnapshot, ToDisplayPoint},
     movement,
     scroll::Autoscroll,
-    Bias, DisplayPoint, Editor, ToOffset,
+    Bias, DisplayPoint, Editor, RowExt, ToOffset,
 };
 use gpui::{actions, ViewContext};
 use language::{Point, Selection, SelectionGoal};
@@ -16,7 +16,8 @@ use workspace::searchable::Direction;
 
 use crate::{
     motion::{start_of_line, Motion},
-    object::Object,
+    normal::case::CaseTarget,
+    object::{end_of_paragraph, start_of_paragraph, Object},
     state::{Mode, Operator},
     Vim,
 };
@@ -302,6 +303,7 @@ impl Vim {
     pub fn visual_object(&mut self, object: Object, cx: &mut ViewContext<Vim>) {
         if let Some(Operator::Object { around }) = self.active_operator() {
             self.pop_operator(cx);
+            let times = self.take_count(cx);
             let current_mode = self.mode;
             let target_mode = object.target_visual_mode(current_mode);
             if target_mode != current_mode {
@@ -316,16 +318,42 @@ impl Vim {
                         // all our motions assume that the current character is
                         // after the cursor; however in the case of a visual selection
                         // the current character is before the cursor.
-                        // But this will affect the judgment of the html tag
-                        // so the html tag needs to skip this logic.
-                        if !selection.reversed && object != Object::Tag {
+                        // But this will affect the judgment of the html tag and the syntax
+                        // based objects, so they need to skip this logic.
+                        if !selection.reversed
+                            && !matches!(object, Object::Tag | Object::Function | Object::Class)
+                        {
                             mut_selection.set_head(
                                 movement::left(map, mut_selection.head()),
                                 mut_selection.goal,
                             );
                         }
 
-                        if let Some(range) = object.range(map, mut_selection, around) {
+                        let mut range = object.range(map, mut_selection.clone(), around, times);
+                        // Repeating `ip`/`ap` grows the selection by the next paragraph,
+                        // once it already covers the one under the cursor.
+                        if object == Object::Paragraph
+                            && !selection.reversed
+                            && !selection.is_empty()
+                            && movement::right(map, selection.start) != selection.end
+                        {
+                            let head = mut_selection.head();
+                            let last_row = movement::left(map, selection.end).row();
+                            let covered = start_of_paragraph(map, head).row()
+                                >= selection.start.row()
+                                && end_of_paragraph(map, head).row() <= last_row;
+                            if covered && last_row < map.max_point().row() {
+                                mut_selection.set_head(
+                                    DisplayPoint::new(last_row.next_row(), 0),
+                                    mut_selection.goal,
+                                );
+                                range = object
+                                    .range(map, mut_selection, around, times)
+                                    .map(|range| selection.start..range.end);
+                            }
+                        }
+
+                        if let Some(range) = range {
                             if !range.is_empty() {
                                 let expand_both_ways = object.always_expands_both_ways()
                                     || selection.is_empty()
@@ -348,7 +376,9 @@ impl Vim {
                             //
                             // We don't do this adjustment for a one-line blank paragraph since the
                             // trailing newline is included in its selection from the beginning.
-                            if object == Object::Paragraph && range.start != range.end {
+                            if matches!(object, Object::Paragraph | Object::IndentObj { .. })
+                                && range.start != range.end
+                            {
                                 let row_of_selection_end_line = selection.end.to_point(map).row;
                                 let new_selection_end = if map
                                     .buffer_snapshot
@@ -607,13 +637,24 @@ impl Vim {
             editor.set_collapse_matches(true);
         });
 
-        match self.maybe_pop_operator() {
-            Some(Operator::Change) => self.substitute(None, false, cx),
-            Some(Operator::Delete) => {
+        if let Some(operator) = self.maybe_pop_operator() {
+            self.visual_operator(operator, cx);
+        }
+    }
+
+    /// Applies `operator` to the current visual selection.
+    pub(crate) fn visual_operator(&mut self, operator: Operator, cx: &mut ViewContext<Self>) {
+        match operator {
+            Operator::Change => self.substitute(None, false, cx),
+            Operator::Delete => {
                 self.stop_recording(cx);
                 self.visual_delete(false, cx)
             }
-            Some(Operator::Yank) => self.visual_yank(cx),
+            Operator::Yank => self.visual_yank(cx),
+            Operator::Lowercase => self.change_case_selections(CaseTarget::Lowercase, cx),
+            Operator::Uppercase => self.change_case_selections(CaseTarget::Uppercase, cx),
+            Operator::OppositeCase => self.change_case_selections(CaseTarget::OppositeCase, cx),
+            Operator::Rot13 => self.change_case_selections(CaseTarget::Rot13, cx),
             _ => {} // Ignoring other operators
         }
     }
@@ -1190,6 +1231,22 @@ mod test {
         cx.shared_state().await.assert_eq("«ˇaa aa» aa aa aa");
     }
 
+    #[gpui::test]
+    async fn test_case_gn(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("aaˇ aa aa aa", Mode::Normal);
+        cx.simulate_keystrokes("/ a a enter");
+        cx.assert_state("aa ˇaa aa aa", Mode::Normal);
+        cx.simulate_keystrokes("g shift-u g n");
+        cx.assert_state("aa ˇAA aa aa", Mode::Normal);
+
+        cx.set_state("aaˇ aa aa aa", Mode::Normal);
+        cx.simulate_keystrokes("/ a a enter");
+        cx.simulate_keystrokes("g ~ g n");
+        cx.assert_state("aa ˇAA aa aa", Mode::Normal);
+    }
+
     #[gpui::test]
     async fn test_gl(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -250,6 +250,8 @@ pub struct Pane {
     was_focused: bool,
     active_item_index: usize,
     preview_item_id: Option<EntityId>,
+    /// The item being followed in this pane, with the participant index of the leader.
+    followed_item: Option<(EntityId, u32)>,
     last_focus_handle_by_item: HashMap<EntityId, WeakFocusHandle>,
     nav_history: NavHistory,
     toolbar: View<Toolbar>,
@@ -361,6 +363,7 @@ impl Pane {
             zoomed: false,
             active_item_index: 0,
             preview_item_id: None,
+            followed_item: None,
             last_focus_handle_by_item: Default::default(),
             nav_history: NavHistory(Arc::new(Mutex::new(NavHistoryState {
                 mode: NavigationMode::Normal,
@@ -489,6 +492,11 @@ impl Pane {
         }
     }
 
+    /// The item that was active before the current one, which `pane::AlternateFile` activates.
+    pub fn alternate_file_item(&self) -> Option<Box<dyn ItemHandle>> {
+        self.alternate_file_items.1.as_ref()?.upgrade()
+    }
+
     pub fn track_alternate_file_items(&mut self) {
         if let Some(item) = self.active_item().map(|item| item.downgrade_item()) {
             let (current, _) = &self.alternate_file_items;
@@ -714,6 +722,19 @@ impl Pane {
         }
     }
 
+    /// Marks the item with the given ID as being followed, showing an indicator
+    /// in the leader's color on its tab.
+    pub(crate) fn set_followed_item(
+        &mut self,
+        followed_item: Option<(EntityId, u32)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.followed_item != followed_item {
+            self.followed_item = followed_item;
+            cx.notify();
+        }
+    }
+
     pub fn handle_item_edit(&mut self, item_id: EntityId, cx: &AppContext) {
         if let Some(preview_item) = self.preview_item() {
             if preview_item.item_id() == item_id && !preview_item.preserve_preview(cx) {
@@ -1765,6 +1786,10 @@ impl Pane {
         let is_first_item = ix == 0;
         let is_last_item = ix == self.items.len() - 1;
         let position_relative_to_active_item = ix.cmp(&self.active_item_index);
+        let followed_by_participant = self
+            .followed_item
+            .filter(|(followed_item_id, _)| *followed_item_id == item_id)
+            .map(|(_, participant_index)| participant_index);
 
         let tab = Tab::new(ix)
             .position(if is_first_item {
@@ -1850,7 +1875,12 @@ impl Pane {
                 h_flex()
                     .gap_1()
                     .children(icon.map(|icon| icon.size(IconSize::Small).color(icon_color)))
-                    .child(label),
+                    .child(label)
+                    .children(followed_by_participant.map(|participant_index| {
+                        Icon::new(IconName::Eye)
+                            .size(IconSize::XSmall)
+                            .color(Color::Player(participant_index))
+                    })),
             );
 
         let single_entry_to_resolve = {
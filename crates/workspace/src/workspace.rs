@@ -93,7 +93,8 @@ use ui::{
 use util::{maybe, ResultExt, TryFutureExt};
 use uuid::Uuid;
 pub use workspace_settings::{
-    AutosaveSetting, RestoreOnStartupBehavior, TabBarSettings, WorkspaceSettings,
+    AutosaveSetting, FollowMode, FollowSettings, RestoreOnStartupBehavior, TabBarSettings,
+    WorkspaceSettings,
 };
 
 use crate::notifications::NotificationId;
@@ -311,6 +312,7 @@ pub fn init_settings(cx: &mut AppContext) {
     ItemSettings::register(cx);
     PreviewTabsSettings::register(cx);
     TabBarSettings::register(cx);
+    FollowSettings::register(cx);
 }
 
 pub fn init(app_state: Arc<AppState>, cx: &mut AppContext) {
@@ -773,6 +775,7 @@ struct FollowerState {
     dock_pane: Option<View<Pane>>,
     active_view_id: Option<ViewId>,
     items_by_leader_view_id: HashMap<ViewId, FollowerView>,
+    mode: FollowMode,
 }
 
 struct FollowerView {
@@ -3225,6 +3228,7 @@ impl Workspace {
                 for item in state.items_by_leader_view_id.values() {
                     item.view.set_leader_peer_id(None, cx);
                 }
+                state.clear_followed_items(cx);
                 false
             } else {
                 true
@@ -3238,7 +3242,13 @@ impl Workspace {
         leader_id: PeerId,
         cx: &mut ViewContext<Self>,
     ) -> Option<Task<Result<()>>> {
-        let pane = self.active_pane().clone();
+        let &FollowSettings { mode, split } = FollowSettings::get_global(cx);
+        let mut pane = self.active_pane().clone();
+        // Panes that were used for following before are reused, so that following
+        // the next collaborator doesn't keep adding splits.
+        if split && !self.last_leaders_by_pane.contains_key(&pane.downgrade()) {
+            pane = self.split_pane(pane, SplitDirection::Right, cx);
+        }
 
         self.last_leaders_by_pane
             .insert(pane.downgrade(), leader_id);
@@ -3251,6 +3261,7 @@ impl Workspace {
                 dock_pane: None,
                 active_view_id: None,
                 items_by_leader_view_id: Default::default(),
+                mode,
             },
         );
         cx.notify();
@@ -3368,6 +3379,7 @@ impl Workspace {
     pub fn unfollow(&mut self, leader_id: PeerId, cx: &mut ViewContext<Self>) -> Option<()> {
         cx.notify();
         let state = self.follower_states.remove(&leader_id)?;
+        state.clear_followed_items(cx);
         for (_, item) in state.items_by_leader_view_id {
             item.view.set_leader_peer_id(None, cx);
         }
@@ -3818,6 +3830,7 @@ impl Workspace {
         let call = self.active_call()?;
         let room = call.read(cx).room()?.read(cx);
         let participant = room.remote_participant_for_peer_id(leader_id)?;
+        let participant_index = participant.participant_index.0;
 
         let leader_in_this_app;
         let leader_in_this_project;
@@ -3837,6 +3850,12 @@ impl Workspace {
         };
 
         let state = self.follower_states.get(&leader_id)?;
+        if state.mode == FollowMode::Viewport && state.is_showing_followed_item(cx) {
+            // Only the followed item's viewport is mirrored, so don't switch
+            // items when the leader does.
+            return None;
+        }
+
         let mut item_to_activate = None;
         if let (Some(active_view_id), true) = (state.active_view_id, leader_in_this_app) {
             if let Some(item) = state.items_by_leader_view_id.get(&active_view_id) {
@@ -3866,6 +3885,9 @@ impl Workspace {
             }
         }
 
+        if let Some(state) = self.follower_states.get(&leader_id) {
+            state.clear_followed_items(cx);
+        }
         pane.update(cx, |pane, cx| {
             let focus_active_item = pane.has_focus(cx) || transfer_focus;
             if let Some(index) = pane.index_for_item(item.as_ref()) {
@@ -3873,6 +3895,7 @@ impl Workspace {
             } else {
                 pane.add_item(item.boxed_clone(), false, false, None, cx)
             }
+            pane.set_followed_item(Some((item.item_id(), participant_index)), cx);
 
             if focus_active_item {
                 pane.focus_active_item(cx)
@@ -4973,6 +4996,22 @@ impl FollowerState {
     fn pane(&self) -> &View<Pane> {
         self.dock_pane.as_ref().unwrap_or(&self.center_pane)
     }
+
+    /// Whether the pane used for following shows one of the leader's items.
+    fn is_showing_followed_item(&self, cx: &AppContext) -> bool {
+        let Some(active_item) = self.pane().read(cx).active_item() else {
+            return false;
+        };
+        self.items_by_leader_view_id
+            .values()
+            .any(|item| item.view.item_id() == active_item.item_id())
+    }
+
+    fn clear_followed_items(&self, cx: &mut WindowContext) {
+        for pane in Some(&self.center_pane).into_iter().chain(&self.dock_pane) {
+            pane.update(cx, |pane, cx| pane.set_followed_item(None, cx));
+        }
+    }
 }
 
 pub trait WorkspaceHandle {
@@ -110,6 +110,36 @@ pub struct WorkspaceSettingsContent {
     pub command_aliases: Option<HashMap<String, String>>,
 }
 
+#[derive(Deserialize)]
+pub struct FollowSettings {
+    pub mode: FollowMode,
+    pub split: bool,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FollowSettingsContent {
+    /// What to mirror from the collaborator being followed.
+    ///
+    /// Default: full
+    pub mode: Option<FollowMode>,
+    /// Whether to follow a collaborator in a new split next to the active pane,
+    /// instead of taking over the active pane.
+    ///
+    /// Default: false
+    pub split: Option<bool>,
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FollowMode {
+    /// Follow the collaborator's active item, along with its selections and scroll position.
+    #[default]
+    Full,
+    /// Only mirror the selections and scroll position of the followed item,
+    /// without switching items when the collaborator does.
+    Viewport,
+}
+
 #[derive(Deserialize)]
 pub struct TabBarSettings {
     pub show: bool,
@@ -180,6 +210,16 @@ impl Settings for WorkspaceSettings {
     }
 }
 
+impl Settings for FollowSettings {
+    const KEY: Option<&'static str> = Some("follow");
+
+    type FileContent = FollowSettingsContent;
+
+    fn load(sources: SettingsSources<Self::FileContent>, _: &mut AppContext) -> Result<Self> {
+        sources.json_merge()
+    }
+}
+
 impl Settings for TabBarSettings {
     const KEY: Option<&'static str> = Some("tab_bar");
 
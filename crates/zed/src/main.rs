@@ -38,6 +38,7 @@ use simplelog::ConfigBuilder;
 use smol::process::Command;
 use std::{
     env,
+    ffi::OsStr,
     fs::OpenOptions,
     io::{IsTerminal, Write},
     path::Path,
@@ -55,7 +56,8 @@ use workspace::{
 };
 use zed::{
     app_menus, build_window_options, handle_cli_connection, handle_keymap_file_changes,
-    initialize_workspace, open_paths_with_positions, OpenListener, OpenRequest,
+    initialize_workspace, open_paths_with_positions, open_scratch_window, OpenListener,
+    OpenRequest,
 };
 
 use crate::zed::inline_completion_registry;
@@ -255,6 +257,7 @@ fn init_ui(
     channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
     search::init(cx);
     vim::init(cx);
+    vim::restore_persisted_state(cx);
     terminal_view::init(cx);
     journal::init(app_state.clone(), cx);
     language_selector::init(cx);
@@ -303,6 +306,7 @@ fn init_ui(
     let fs = app_state.fs.clone();
     load_user_themes_in_background(fs.clone(), cx);
     watch_themes(fs.clone(), cx);
+    watch_icon_themes(fs.clone(), cx);
     watch_languages(fs.clone(), app_state.languages.clone(), cx);
     watch_file_types(fs.clone(), cx);
 
@@ -647,6 +651,10 @@ fn handle_open_request(
         return;
     }
 
+    if request.open_scratch_window {
+        open_scratch_window(app_state.clone(), cx).detach_and_log_err(cx);
+    }
+
     let mut task = None;
     if !request.open_paths.is_empty() {
         let app_state = app_state.clone();
@@ -1179,6 +1187,60 @@ fn watch_themes(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
     .detach()
 }
 
+/// Spawns a background task to load the user's icon themes and watch the icon themes directory for changes.
+fn watch_icon_themes(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
+    use std::time::Duration;
+
+    use file_icons::FileIcons;
+    use gpui::UpdateGlobal;
+
+    async fn load_icon_theme(
+        path: &Path,
+        fs: &Arc<dyn fs::Fs>,
+        cx: &AsyncAppContext,
+    ) -> Result<()> {
+        if path.extension() != Some(OsStr::new("json")) {
+            return Ok(());
+        }
+        let content = fs.load(path).await?;
+        cx.update(|cx| {
+            FileIcons::update_global(cx, |file_icons, _| {
+                file_icons.register_icon_theme(path, &content)
+            })
+        })?
+        .with_context(|| format!("failed to load icon theme at path {path:?}"))?;
+        Ok(())
+    }
+
+    cx.spawn(|cx| async move {
+        let icon_themes_dir = paths::icon_themes_dir().as_path();
+        if let Some(mut entries) = fs.read_dir(icon_themes_dir).await.log_err() {
+            while let Some(path) = entries.next().await {
+                if let Some(path) = path.log_err() {
+                    load_icon_theme(&path, &fs, &cx).await.log_err();
+                }
+            }
+        }
+
+        let (mut events, _) = fs.watch(icon_themes_dir, Duration::from_millis(100)).await;
+        while let Some(paths) = events.next().await {
+            for path in paths {
+                if fs.metadata(&path).await.ok().flatten().is_some() {
+                    load_icon_theme(&path, &fs, &cx).await.log_err();
+                } else {
+                    cx.update(|cx| {
+                        FileIcons::update_global(cx, |file_icons, _| {
+                            file_icons.unregister_icon_theme(&path);
+                        })
+                    })
+                    .log_err();
+                }
+            }
+        }
+    })
+    .detach()
+}
+
 #[cfg(debug_assertions)]
 fn watch_languages(fs: Arc<dyn fs::Fs>, languages: Arc<LanguageRegistry>, cx: &mut AppContext) {
     use std::time::Duration;
@@ -1230,7 +1292,7 @@ fn watch_file_types(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
         while (events.next().await).is_some() {
             cx.update(|cx| {
                 FileIcons::update_global(cx, |file_types, _cx| {
-                    *file_types = file_icons::FileIcons::new(Assets);
+                    file_types.reload_default_theme(Assets);
                 });
             })
             .ok();
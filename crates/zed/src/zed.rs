@@ -14,11 +14,11 @@ use breadcrumbs::Breadcrumbs;
 use client::ZED_URL_SCHEME;
 use collections::VecDeque;
 use command_palette_hooks::CommandPaletteFilter;
-use editor::{scroll::Autoscroll, Editor, MultiBuffer};
+use editor::{scroll::Autoscroll, Editor, EditorEvent, MultiBuffer};
 use feature_flags::FeatureFlagAppExt;
 use gpui::{
     actions, point, px, AppContext, AsyncAppContext, Context, FocusableView, MenuItem, PromptLevel,
-    ReadGlobal, TitlebarOptions, View, ViewContext, VisualContext, WindowKind, WindowOptions,
+    ReadGlobal, Task, TitlebarOptions, View, ViewContext, VisualContext, WindowKind, WindowOptions,
 };
 pub use open_listener::*;
 
@@ -34,10 +34,10 @@ use rope::Rope;
 use search::project_search::ProjectSearchBar;
 use settings::{
     initial_local_settings_content, initial_tasks_content, watch_config_file, KeymapFile, Settings,
-    SettingsStore, DEFAULT_KEYMAP_PATH,
+    SettingsStore, VsCodeImport, DEFAULT_KEYMAP_PATH,
 };
 use std::any::TypeId;
-use std::{borrow::Cow, ops::Deref, path::Path, sync::Arc};
+use std::{borrow::Cow, ops::Deref, path::Path, sync::Arc, time::Duration};
 use task::static_source::{StaticSource, TrackedFile};
 use theme::ActiveTheme;
 use workspace::notifications::NotificationId;
@@ -50,18 +50,23 @@ use uuid::Uuid;
 use vim::VimModeSetting;
 use welcome::{BaseKeymap, MultibufferHint};
 use workspace::{
-    create_and_open_local_file, notifications::simple_message_notification::MessageNotification,
-    open_new, AppState, NewFile, NewWindow, OpenLog, Toast, Workspace, WorkspaceSettings,
+    create_and_open_local_file, item::Item,
+    notifications::simple_message_notification::MessageNotification, open_new, AppState, NewFile,
+    NewWindow, OpenLog, Toast, Workspace, WorkspaceSettings,
 };
 use workspace::{notifications::DetachAndPromptErr, Pane};
-use zed_actions::{OpenAccountSettings, OpenBrowser, OpenSettings, OpenZedUrl, Quit};
+use zed_actions::{
+    OpenAccountSettings, OpenBrowser, OpenScratchWindow, OpenSettings, OpenZedUrl, Quit,
+};
 
 actions!(
     zed,
     [
         DebugElements,
+        DebugKeyContexts,
         Hide,
         HideOthers,
+        ImportVsCodeSettings,
         Minimize,
         OpenDefaultKeymap,
         OpenDefaultSettings,
@@ -125,11 +130,107 @@ pub fn build_window_options(display_uuid: Option<Uuid>, cx: &mut AppContext) ->
     }
 }
 
+const SCRATCH_FILE_NAME: &str = "scratch";
+const SCRATCH_SAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Opens the scratch window, or activates it if it is already open.
+///
+/// The scratch window is a workspace for the scratch directory with a single buffer
+/// whose contents are kept across restarts: edits are saved shortly after they're
+/// made, whatever the `autosave` setting. Its language can be picked with the
+/// language selector.
+pub fn open_scratch_window(
+    app_state: Arc<AppState>,
+    cx: &mut AppContext,
+) -> Task<anyhow::Result<()>> {
+    let fs = app_state.fs.clone();
+    cx.spawn(|mut cx| async move {
+        let scratch_dir = paths::scratch_dir();
+        let scratch_file = scratch_dir.join(SCRATCH_FILE_NAME);
+        fs.create_dir(scratch_dir)
+            .await
+            .with_context(|| format!("creating scratch directory {scratch_dir:?}"))?;
+        fs.create_file(
+            &scratch_file,
+            fs::CreateOptions {
+                overwrite: false,
+                ignore_if_exists: true,
+            },
+        )
+        .await
+        .with_context(|| format!("creating scratch file {scratch_file:?}"))?;
+
+        let (_, items) = cx
+            .update(|cx| {
+                workspace::open_paths(
+                    &[scratch_dir.clone(), scratch_file],
+                    app_state,
+                    workspace::OpenOptions::default(),
+                    cx,
+                )
+            })?
+            .await?;
+        for item in items.into_iter().flatten() {
+            item?;
+        }
+        Ok(())
+    })
+}
+
+/// Saves the scratch buffer shortly after each edit made in `editor`, so that unsaved
+/// notes aren't lost when the scratch window is closed or Zed quits.
+fn save_scratch_buffer_on_edit(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let Some(buffer) = editor.buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let scratch_file = paths::scratch_dir().join(SCRATCH_FILE_NAME);
+    let is_scratch_file = buffer
+        .read(cx)
+        .file()
+        .and_then(|file| file.as_local())
+        .is_some_and(|file| file.abs_path(cx) == scratch_file);
+    if !is_scratch_file {
+        return;
+    }
+
+    // Replacing the pending save cancels it, so only the last of a burst of edits saves.
+    let mut pending_save = None;
+    cx.subscribe(
+        &cx.view().clone(),
+        move |_, editor, event: &EditorEvent, cx| {
+            if let EditorEvent::Edited { .. } = event {
+                pending_save.replace(cx.spawn(|_, mut cx| async move {
+                    cx.background_executor().timer(SCRATCH_SAVE_DELAY).await;
+                    let save = editor.update(&mut cx, |editor, cx| {
+                        let project = editor.workspace()?.read(cx).project().clone();
+                        editor.is_dirty(cx).then(|| editor.save(false, project, cx))
+                    });
+                    if let Ok(Some(save)) = save {
+                        save.await.log_err();
+                    }
+                }));
+            }
+        },
+    )
+    .detach();
+}
+
 pub fn initialize_workspace(
     app_state: Arc<AppState>,
     prompt_builder: Arc<PromptBuilder>,
     cx: &mut AppContext,
 ) {
+    cx.on_action({
+        let app_state = Arc::downgrade(&app_state);
+        move |_: &OpenScratchWindow, cx| {
+            if let Some(app_state) = app_state.upgrade() {
+                open_scratch_window(app_state, cx).detach_and_log_err(cx);
+            }
+        }
+    });
+
+    cx.observe_new_views(save_scratch_buffer_on_edit).detach();
+
     cx.observe_new_views(move |workspace: &mut Workspace, cx| {
         let workspace_handle = cx.view().clone();
         let center_pane = workspace.active_pane().clone();
@@ -400,6 +501,23 @@ pub fn initialize_workspace(
                     |_, _| None,
                 );
             })
+            .register_action(|workspace, _: &DebugKeyContexts, cx| {
+                struct KeyContexts;
+
+                let contexts = cx
+                    .context_stack()
+                    .iter()
+                    .map(|context| format!("{context:?}"))
+                    .collect::<Vec<_>>()
+                    .join(" > ");
+                workspace.show_toast(
+                    Toast::new(
+                        NotificationId::unique::<KeyContexts>(),
+                        format!("Active key contexts: {contexts}"),
+                    ),
+                    cx,
+                )
+            })
             .register_action(|workspace, _: &OpenLog, cx| {
                 open_log_file(workspace, cx);
             })
@@ -451,6 +569,7 @@ pub fn initialize_workspace(
                 },
             )
             .register_action(open_local_settings_file)
+            .register_action(import_vscode_settings)
             .register_action(open_local_tasks_file)
             .register_action(
                 move |workspace: &mut Workspace,
@@ -833,6 +952,67 @@ pub fn load_default_keymap(cx: &mut AppContext) {
     }
 }
 
+fn import_vscode_settings(
+    workspace: &mut Workspace,
+    _: &ImportVsCodeSettings,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let fs = workspace.app_state().fs.clone();
+    cx.spawn(|workspace, mut cx| async move {
+        let vscode_settings = fs
+            .load(paths::vscode_settings_file())
+            .await
+            .unwrap_or_default();
+        let vscode_keybindings = fs
+            .load(paths::vscode_keybindings_file())
+            .await
+            .unwrap_or_default();
+        if vscode_settings.trim().is_empty() && vscode_keybindings.trim().is_empty() {
+            anyhow::bail!(
+                "No VS Code settings were found in {:?}",
+                paths::vscode_user_dir()
+            );
+        }
+        let import = VsCodeImport::new(&vscode_settings, &vscode_keybindings)?;
+
+        let old_settings = fs.load(paths::settings_file()).await.unwrap_or_default();
+        let new_settings = cx.read_global(|store: &SettingsStore, _| {
+            store.new_text_for_json_update(old_settings, &import.settings)
+        })?;
+        fs.atomic_write(paths::settings_file().clone(), new_settings)
+            .await
+            .context("Failed to write settings")?;
+
+        if !import.keymap.is_empty() {
+            let old_keymap = fs.load(paths::keymap_file()).await.unwrap_or_default();
+            let new_keymap = cx.read_global(|store: &SettingsStore, _| {
+                store.new_text_for_json_array_append(old_keymap, &import.keymap)
+            })??;
+            fs.atomic_write(paths::keymap_file().clone(), new_keymap)
+                .await
+                .context("Failed to write keymap")?;
+        }
+
+        workspace.update(&mut cx, |workspace, cx| {
+            struct ImportedVsCodeSettings;
+
+            let mut message = "Imported settings and key bindings from VS Code.".to_string();
+            if !import.unmapped.is_empty() {
+                message.push_str(&format!(
+                    " The following have no Zed equivalent and were skipped: {}",
+                    import.unmapped.join(", ")
+                ));
+            }
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<ImportedVsCodeSettings>(), message),
+                cx,
+            )
+        })?;
+        Ok(())
+    })
+    .detach_and_prompt_err("Error importing VS Code settings", cx, |_, _| None);
+}
+
 fn open_local_settings_file(
     workspace: &mut Workspace,
     _: &OpenLocalSettings,
@@ -37,6 +37,7 @@ pub fn app_menus() -> Vec<Menu> {
             items: vec![
                 MenuItem::action("New", workspace::NewFile),
                 MenuItem::action("New Window", workspace::NewWindow),
+                MenuItem::action("New Scratch Window", zed_actions::OpenScratchWindow),
                 MenuItem::separator(),
                 MenuItem::action("Open…", workspace::Open),
                 MenuItem::action(
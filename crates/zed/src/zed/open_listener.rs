@@ -11,10 +11,11 @@ use editor::scroll::Autoscroll;
 use editor::Editor;
 use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use futures::channel::{mpsc, oneshot};
-use futures::{FutureExt, SinkExt, StreamExt};
-use gpui::{AppContext, AsyncAppContext, Global, WindowHandle};
+use futures::{Future, FutureExt, SinkExt, StreamExt};
+use gpui::{AppContext, AsyncAppContext, BackgroundExecutor, Global, WindowHandle};
 use language::{Bias, Point};
 use remote::SshConnectionOptions;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use std::{process, thread};
@@ -31,6 +32,7 @@ pub struct OpenRequest {
     pub open_channel_notes: Vec<(u64, Option<String>)>,
     pub join_channel: Option<u64>,
     pub ssh_connection: Option<SshConnectionOptions>,
+    pub open_scratch_window: bool,
 }
 
 impl OpenRequest {
@@ -91,6 +93,11 @@ impl OpenRequest {
     }
 
     fn parse_request_path(&mut self, request_path: &str) -> Result<()> {
+        if request_path == "scratch" {
+            self.open_scratch_window = true;
+            return Ok(());
+        }
+
         let mut parts = request_path.split('/');
         if parts.next() == Some("channel") {
             if let Some(slug) = parts.next() {
@@ -254,6 +261,7 @@ pub async fn handle_cli_connection(
             CliRequest::Open {
                 urls,
                 paths,
+                diff_paths,
                 wait,
                 open_new_workspace,
                 dev_server_token,
@@ -327,6 +335,35 @@ pub async fn handle_cli_connection(
                     return;
                 }
 
+                let mut errored = false;
+                if !diff_paths.is_empty() {
+                    if let Err(error) = open_diffs(
+                        diff_paths,
+                        wait,
+                        &responses,
+                        env.as_ref(),
+                        &app_state,
+                        &mut cx,
+                    )
+                    .await
+                    {
+                        responses
+                            .send(CliResponse::Stderr {
+                                message: format!("{error:#}"),
+                            })
+                            .log_err();
+                        errored = true;
+                    }
+                    if paths.is_empty() {
+                        responses
+                            .send(CliResponse::Exit {
+                                status: errored as i32,
+                            })
+                            .log_err();
+                        return;
+                    }
+                }
+
                 let open_workspace_result = open_workspaces(
                     paths,
                     open_new_workspace,
@@ -338,7 +375,11 @@ pub async fn handle_cli_connection(
                 )
                 .await;
 
-                let status = if open_workspace_result.is_err() { 1 } else { 0 };
+                let status = if errored || open_workspace_result.is_err() {
+                    1
+                } else {
+                    0
+                };
                 responses.send(CliResponse::Exit { status }).log_err();
             }
         }
@@ -508,24 +549,8 @@ async fn open_workspace(
                     } else {
                         let _ = futures::future::try_join_all(item_release_futures).await;
                     };
-                }
-                .fuse();
-
-                futures::pin_mut!(wait);
-
-                loop {
-                    // Repeatedly check if CLI is still open to avoid wasting resources
-                    // waiting for files or workspaces to close.
-                    let mut timer = background.timer(Duration::from_secs(1)).fuse();
-                    futures::select_biased! {
-                        _ = wait => break,
-                        _ = timer => {
-                            if responses.send(CliResponse::Ping).is_err() {
-                                break;
-                            }
-                        }
-                    }
-                }
+                };
+                wait_while_cli_is_open(wait, responses, &background).await;
             }
         }
         Err(error) => {
@@ -540,6 +565,89 @@ async fn open_workspace(
     errored
 }
 
+async fn open_diffs(
+    diff_paths: Vec<[String; 2]>,
+    wait: bool,
+    responses: &IpcSender<CliResponse>,
+    env: Option<&HashMap<String, String>>,
+    app_state: &Arc<AppState>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let mut diffs = Vec::new();
+    for [old_path, new_path] in diff_paths {
+        let old_text = app_state
+            .fs
+            .load(Path::new(&old_path))
+            .await
+            .with_context(|| format!("error opening {old_path:?}"))?;
+        let new_text = app_state
+            .fs
+            .load(Path::new(&new_path))
+            .await
+            .with_context(|| format!("error opening {new_path:?}"))?;
+        diffs.push(((old_path, old_text), (new_path, new_text)));
+    }
+
+    let (workspace, _) = cx
+        .update(|cx| Workspace::new_local(Vec::new(), app_state.clone(), None, env.cloned(), cx))?
+        .await?;
+
+    let mut item_release_futures = Vec::new();
+    workspace.update(cx, |workspace, cx| {
+        for ((old_path, old_text), (new_path, new_text)) in &diffs {
+            let editor = Editor::new_diff_in_workspace(
+                workspace,
+                (old_path, old_text),
+                (new_path, new_text),
+                cx,
+            );
+            let released = oneshot::channel();
+            editor
+                .on_release(
+                    cx,
+                    Box::new(move |_| {
+                        let _ = released.0.send(());
+                    }),
+                )
+                .detach();
+            item_release_futures.push(released.1);
+        }
+    })?;
+
+    if wait {
+        let background = cx.background_executor().clone();
+        let wait = async move {
+            let _ = futures::future::try_join_all(item_release_futures).await;
+        };
+        wait_while_cli_is_open(wait, responses, &background).await;
+    }
+
+    Ok(())
+}
+
+async fn wait_while_cli_is_open(
+    wait: impl Future<Output = ()>,
+    responses: &IpcSender<CliResponse>,
+    background: &BackgroundExecutor,
+) {
+    let wait = wait.fuse();
+    futures::pin_mut!(wait);
+
+    loop {
+        // Repeatedly check if CLI is still open to avoid wasting resources
+        // waiting for files or workspaces to close.
+        let mut timer = background.timer(Duration::from_secs(1)).fuse();
+        futures::select_biased! {
+            _ = wait => break,
+            _ = timer => {
+                if responses.send(CliResponse::Ping).is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{path::PathBuf, sync::Arc};
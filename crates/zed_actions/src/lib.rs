@@ -31,6 +31,7 @@ actions!(
         OpenKeymap,
         About,
         OpenLicenses,
+        OpenScratchWindow,
         OpenTelemetryLog,
         DecreaseBufferFontSize,
         IncreaseBufferFontSize,
@@ -0,0 +1,3 @@
+(element
+  (start_tag) @match
+  (end_tag) @match)
@@ -0,0 +1,26 @@
+(if
+  "if" @match
+  alternative: [
+    (elsif "elsif" @match)
+    (else "else" @match)
+  ]?
+  "end" @match)
+
+(elsif
+  "elsif" @match
+  alternative: [
+    (elsif "elsif" @match)
+    (else "else" @match)
+  ])
+
+(unless
+  "unless" @match
+  alternative: (else "else" @match)?
+  "end" @match)
+
+(begin
+  "begin" @match
+  (rescue "rescue" @match)*
+  (else "else" @match)?
+  (ensure "ensure" @match)?
+  "end" @match)